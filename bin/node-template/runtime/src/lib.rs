@@ -332,6 +332,10 @@ impl_runtime_apis! {
 		fn metadata() -> OpaqueMetadata {
 			Runtime::metadata().into()
 		}
+
+		fn metadata_at_version(version: u32) -> Option<OpaqueMetadata> {
+			Runtime::metadata_at_version(version).map(Into::into)
+		}
 	}
 
 	impl sp_block_builder::BlockBuilder<Block> for Runtime {
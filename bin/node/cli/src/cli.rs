@@ -47,6 +47,10 @@ pub enum Subcommand {
 	#[structopt(name = "benchmark", about = "Benchmark runtime pallets.")]
 	Benchmark(frame_benchmarking_cli::BenchmarkCmd),
 
+	/// The custom try-runtime subcommand testing runtime upgrades against a state snapshot.
+	#[structopt(name = "try-runtime", about = "Test runtime upgrades against a state snapshot.")]
+	TryRuntime(try_runtime_cli::TryRuntimeCmd),
+
 	/// Verify a signature for a message, provided on STDIN, with a given (public or secret) key.
 	Verify(VerifyCmd),
 
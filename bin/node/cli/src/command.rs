@@ -94,6 +94,16 @@ pub fn run() -> Result<()> {
 				You can enable it with `--features runtime-benchmarks`.".into())
 			}
 		}
+		Some(Subcommand::TryRuntime(cmd)) => {
+			if cfg!(feature = "try-runtime") {
+				let runner = cli.create_runner(cmd)?;
+
+				runner.sync_run(|config| cmd.run::<Block, Executor>(config))
+			} else {
+				Err("Try-runtime wasn't enabled when building the node. \
+				You can enable it with `--features try-runtime`.".into())
+			}
+		}
 		Some(Subcommand::Key(cmd)) => cmd.run(),
 		Some(Subcommand::Sign(cmd)) => cmd.run(),
 		Some(Subcommand::Verify(cmd)) => cmd.run(),
@@ -53,6 +53,12 @@ pub enum Error {
 	},
 	/// Call to an unsafe RPC was denied.
 	UnsafeRpcCalled(crate::policy::UnsafeRpcError),
+	/// The runtime doesn't support the requested version of the metadata.
+	#[display(fmt = "metadata version {} is not supported by the runtime", version)]
+	UnsupportedMetadataVersion {
+		/// Requested version.
+		version: u32,
+	},
 }
 
 impl std::error::Error for Error {
@@ -80,6 +86,11 @@ impl From<Error> for rpc::Error {
 				message: format!("{}", e),
 				data: None,
 			},
+			Error::UnsupportedMetadataVersion { .. } => rpc::Error {
+				code: rpc::ErrorCode::ServerError(BASE_ERROR + 3),
+				message: format!("{}", e),
+				data: None,
+			},
 			e => errors::internal(e),
 		}
 	}
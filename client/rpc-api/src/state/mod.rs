@@ -76,8 +76,11 @@ pub trait StateApi<Hash> {
 	fn storage_size(&self, key: StorageKey, hash: Option<Hash>) -> FutureResult<Option<u64>>;
 
 	/// Returns the runtime metadata as an opaque blob.
+	///
+	/// The metadata is returned in the default version of the runtime, unless another `version`
+	/// is requested.
 	#[rpc(name = "state_getMetadata")]
-	fn metadata(&self, hash: Option<Hash>) -> FutureResult<Bytes>;
+	fn metadata(&self, hash: Option<Hash>, version: Option<u32>) -> FutureResult<Bytes>;
 
 	/// Get the runtime version.
 	#[rpc(name = "state_getRuntimeVersion", alias("chain_getRuntimeVersion"))]
@@ -106,8 +106,8 @@ pub trait StateBackend<Block: BlockT, Client>: Send + Sync + 'static
 		key: StorageKey,
 	) -> FutureResult<Option<u64>>;
 
-	/// Returns the runtime metadata as an opaque blob.
-	fn metadata(&self, block: Option<Block::Hash>) -> FutureResult<Bytes>;
+	/// Returns the runtime metadata as an opaque blob, in the given version if any.
+	fn metadata(&self, block: Option<Block::Hash>, version: Option<u32>) -> FutureResult<Bytes>;
 
 	/// Get the runtime version.
 	fn runtime_version(&self, block: Option<Block::Hash>) -> FutureResult<RuntimeVersion>;
@@ -290,8 +290,8 @@ impl<Block, Client> StateApi<Block::Hash> for State<Block, Client>
 		self.backend.storage_size(block, key)
 	}
 
-	fn metadata(&self, block: Option<Block::Hash>) -> FutureResult<Bytes> {
-		self.backend.metadata(block)
+	fn metadata(&self, block: Option<Block::Hash>, version: Option<u32>) -> FutureResult<Bytes> {
+		self.backend.metadata(block, version)
 	}
 
 	fn query_storage(
@@ -37,7 +37,7 @@ use sp_runtime::{
 	generic::BlockId, traits::{Block as BlockT, NumberFor, SaturatedConversion, CheckedSub},
 };
 
-use sp_api::{Metadata, ProvideRuntimeApi, CallApiAt};
+use sp_api::{ApiExt, Metadata, ProvideRuntimeApi, CallApiAt};
 
 use super::{StateBackend, ChildStateBackend, error::{FutureResult, Error, Result}, client_err};
 use std::marker::PhantomData;
@@ -339,13 +339,27 @@ impl<BE, Block, Client> StateBackend<Block, Client> for FullState<BE, Block, Cli
 				.map_err(client_err)))
 	}
 
-	fn metadata(&self, block: Option<Block::Hash>) -> FutureResult<Bytes> {
+	fn metadata(&self, block: Option<Block::Hash>, version: Option<u32>) -> FutureResult<Bytes> {
 		Box::new(result(
 			self.block_or_best(block)
-				.and_then(|block|
-					self.client.runtime_api().metadata(&BlockId::Hash(block)).map(Into::into)
-				)
-				.map_err(client_err)))
+				.map_err(client_err)
+				.and_then(|block| {
+					let at = BlockId::Hash(block);
+					let api = self.client.runtime_api();
+					let version = match version {
+						Some(version) => version,
+						None => return api.metadata(&at).map(Into::into).map_err(client_err),
+					};
+					let has_versioned_metadata = api
+						.has_api_with::<dyn Metadata<Block, Error = ClientError>, _>(&at, |v| v >= 2)
+						.map_err(client_err)?;
+					let metadata = if has_versioned_metadata {
+						api.metadata_at_version(&at, version).map_err(client_err)?
+					} else {
+						None
+					};
+					metadata.map(Into::into).ok_or(Error::UnsupportedMetadataVersion { version })
+				})))
 	}
 
 	fn runtime_version(&self, block: Option<Block::Hash>) -> FutureResult<RuntimeVersion> {
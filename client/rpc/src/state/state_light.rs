@@ -20,7 +20,7 @@ use std::{
 	sync::Arc,
 	collections::{HashSet, HashMap, hash_map::Entry},
 };
-use codec::Decode;
+use codec::{Decode, Encode};
 use futures::{
 	future::{ready, Either},
 	channel::oneshot::{channel, Sender},
@@ -250,16 +250,30 @@ impl<Block, F, Client> StateBackend<Block, Client> for LightState<Block, F, Clie
 		)
 	}
 
-	fn metadata(&self, block: Option<Block::Hash>) -> FutureResult<Bytes> {
-		let metadata = self.call(block, "Metadata_metadata".into(), Bytes(Vec::new()))
-			.and_then(|metadata| OpaqueMetadata::decode(&mut &metadata.0[..])
-				.map(Into::into)
-				.map_err(|decode_err| client_err(ClientError::CallResultDecode(
-					"Unable to decode metadata",
-					decode_err,
-				))));
-
-		Box::new(metadata)
+	fn metadata(&self, block: Option<Block::Hash>, version: Option<u32>) -> FutureResult<Bytes> {
+		match version {
+			None => Box::new(self.call(block, "Metadata_metadata".into(), Bytes(Vec::new()))
+				.and_then(|metadata| OpaqueMetadata::decode(&mut &metadata.0[..])
+					.map(Into::into)
+					.map_err(|decode_err| client_err(ClientError::CallResultDecode(
+						"Unable to decode metadata",
+						decode_err,
+					))))),
+			Some(version) => Box::new(self.call(
+				block,
+				"Metadata_metadata_at_version".into(),
+				Bytes(version.encode()),
+			)
+				.and_then(move |metadata| Option::<OpaqueMetadata>::decode(&mut &metadata.0[..])
+					.map_err(|decode_err| client_err(ClientError::CallResultDecode(
+						"Unable to decode metadata",
+						decode_err,
+					)))
+					.and_then(|metadata| metadata
+						.map(Into::into)
+						.ok_or(Error::UnsupportedMetadataVersion { version })
+					))),
+		}
 	}
 
 	fn runtime_version(&self, block: Option<Block::Hash>) -> FutureResult<RuntimeVersion> {
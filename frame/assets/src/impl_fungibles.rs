@@ -0,0 +1,148 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Implementations of the `fungibles` traits for the assets module.
+
+use super::*;
+use frame_support::traits::tokens::fungibles;
+
+impl<T: Config> fungibles::Inspect<T::AccountId> for Module<T> {
+	type AssetId = T::AssetId;
+	type Balance = T::Balance;
+
+	fn total_issuance(asset: Self::AssetId) -> Self::Balance {
+		Asset::<T>::get(asset).map(|x| x.supply).unwrap_or_else(Zero::zero)
+	}
+
+	fn minimum_balance(asset: Self::AssetId) -> Self::Balance {
+		Asset::<T>::get(asset).map(|x| x.min_balance).unwrap_or_else(Zero::zero)
+	}
+
+	fn balance(asset: Self::AssetId, who: &T::AccountId) -> Self::Balance {
+		Account::<T>::get(asset, who).total()
+	}
+
+	fn reducible_balance(
+		asset: Self::AssetId,
+		who: &T::AccountId,
+		keep_alive: bool,
+	) -> Self::Balance {
+		Module::<T>::reducible_balance(asset, who, keep_alive)
+	}
+
+	fn can_deposit(
+		asset: Self::AssetId,
+		who: &T::AccountId,
+		amount: Self::Balance,
+	) -> DepositConsequence {
+		Module::<T>::can_increase(asset, who, amount)
+	}
+
+	fn can_withdraw(
+		asset: Self::AssetId,
+		who: &T::AccountId,
+		amount: Self::Balance,
+	) -> WithdrawConsequence<Self::Balance> {
+		Module::<T>::can_decrease(asset, who, amount, false)
+	}
+}
+
+impl<T: Config> fungibles::Mutate<T::AccountId> for Module<T> {
+	fn mint_into(
+		asset: Self::AssetId,
+		who: &T::AccountId,
+		amount: Self::Balance,
+	) -> DispatchResult {
+		Module::<T>::do_mint(asset, who, amount, None)
+	}
+
+	fn burn_from(
+		asset: Self::AssetId,
+		who: &T::AccountId,
+		amount: Self::Balance,
+	) -> Result<Self::Balance, DispatchError> {
+		let f = DebitFlags { keep_alive: false, best_effort: false };
+		Module::<T>::do_burn(asset, who, amount, None, f)
+	}
+}
+
+impl<T: Config> fungibles::Transfer<T::AccountId> for Module<T> {
+	fn transfer(
+		asset: Self::AssetId,
+		source: &T::AccountId,
+		dest: &T::AccountId,
+		amount: Self::Balance,
+		keep_alive: bool,
+	) -> Result<Self::Balance, DispatchError> {
+		let f = DebitFlags { keep_alive, best_effort: false };
+		Module::<T>::do_transfer(asset, source, dest, amount, None, f)
+	}
+}
+
+impl<T: Config> fungibles::Unbalanced<T::AccountId> for Module<T> {
+	fn set_balance(asset: Self::AssetId, who: &T::AccountId, amount: Self::Balance) -> DispatchResult {
+		Module::<T>::do_set_balance(asset, who, amount)
+	}
+
+	fn set_total_issuance(asset: Self::AssetId, amount: Self::Balance) {
+		Asset::<T>::mutate_exists(asset, |maybe_details| {
+			if let Some(details) = maybe_details {
+				details.supply = amount;
+			}
+		});
+	}
+}
+
+impl<T: Config> fungibles::InspectHold<T::AccountId> for Module<T> {
+	fn balance_on_hold(asset: Self::AssetId, who: &T::AccountId) -> Self::Balance {
+		Account::<T>::get(asset, who).reserved
+	}
+
+	fn can_hold(asset: Self::AssetId, who: &T::AccountId, amount: Self::Balance) -> bool {
+		if !Asset::<T>::contains_key(asset) {
+			return false
+		}
+		let account = Account::<T>::get(asset, who);
+		!account.is_frozen && account.balance >= amount
+	}
+}
+
+impl<T: Config> fungibles::MutateHold<T::AccountId> for Module<T> {
+	fn hold(asset: Self::AssetId, who: &T::AccountId, amount: Self::Balance) -> DispatchResult {
+		Module::<T>::do_hold(asset, who, amount)
+	}
+
+	fn release(
+		asset: Self::AssetId,
+		who: &T::AccountId,
+		amount: Self::Balance,
+		best_effort: bool,
+	) -> Result<Self::Balance, DispatchError> {
+		Module::<T>::do_release(asset, who, amount, best_effort)
+	}
+
+	fn transfer_held(
+		asset: Self::AssetId,
+		source: &T::AccountId,
+		dest: &T::AccountId,
+		amount: Self::Balance,
+		best_effort: bool,
+		on_hold: bool,
+	) -> Result<Self::Balance, DispatchError> {
+		Module::<T>::do_transfer_held(asset, source, dest, amount, best_effort, on_hold)
+	}
+}
@@ -101,6 +101,14 @@
 //!
 //! Please refer to the [`Module`](./struct.Module.html) struct for details on publicly available functions.
 //!
+//! ### Trait Implementations
+//!
+//! The module implements the [`fungibles`](../frame_support/traits/tokens/fungibles/index.html)
+//! traits `Inspect`, `Mutate`, `Transfer`, `Unbalanced`, `InspectHold` and `MutateHold`, so that
+//! other pallets can be generic over the asset that they use. Funds placed on hold through
+//! `MutateHold` cannot be transferred or burned, and keep the account alive; they are still counted
+//! towards the account's minimum balance.
+//!
 //! ## Related Modules
 //!
 //! * [`System`](../frame_system/index.html)
@@ -112,14 +120,16 @@
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarking;
 pub mod weights;
+mod impl_fungibles;
 
-use sp_std::{fmt::Debug};
-use sp_runtime::{RuntimeDebug, traits::{
-	Member, AtLeast32BitUnsigned, Zero, StaticLookup, Saturating, CheckedSub, CheckedAdd
+use sp_std::{fmt::Debug, marker::PhantomData};
+use sp_runtime::{RuntimeDebug, FixedU128, FixedPointNumber, FixedPointOperand, traits::{
+	Member, AtLeast32BitUnsigned, Zero, StaticLookup, Saturating, CheckedSub, CheckedAdd, Convert
 }};
 use codec::{Encode, Decode, HasCompact};
 use frame_support::{Parameter, decl_module, decl_event, decl_storage, decl_error, ensure,
 	traits::{Currency, ReservableCurrency, EnsureOrigin, Get, BalanceStatus::Reserved},
+	traits::tokens::{WithdrawConsequence, DepositConsequence, BalanceConversion, fungible},
 	dispatch::{DispatchResult, DispatchError},
 };
 use frame_system::ensure_signed;
@@ -190,12 +200,57 @@ pub struct AssetDetails<
 pub struct AssetBalance<
 	Balance: Encode + Decode + Clone + Debug + Eq + PartialEq,
 > {
-	/// The balance.
+	/// The balance which is free to be transferred or burned.
 	balance: Balance,
 	/// Whether the account is frozen.
 	is_frozen: bool,
 	/// Whether the account is a zombie. If not, then it has a reference.
 	is_zombie: bool,
+	/// The balance which is on hold. It can neither be transferred nor burned, but it does count
+	/// towards the minimum balance.
+	reserved: Balance,
+}
+
+impl<
+	Balance: Encode + Decode + Clone + Debug + Eq + PartialEq + Saturating + Copy,
+> AssetBalance<Balance> {
+	/// The total balance of the account, including any funds on hold.
+	fn total(&self) -> Balance {
+		self.balance.saturating_add(self.reserved)
+	}
+}
+
+/// The balance of an account before `reserved` was added, used to migrate `Account` to V1.
+#[derive(Decode)]
+struct OldAssetBalance<Balance> {
+	balance: Balance,
+	is_frozen: bool,
+	is_zombie: bool,
+}
+
+// A value placed in storage that represents the current version of the Assets storage.
+// This value is used by the `on_runtime_upgrade` logic to determine whether we run
+// storage migration logic.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+enum Releases {
+	V0,
+	V1,
+}
+
+impl Default for Releases {
+	fn default() -> Self {
+		Releases::V0
+	}
+}
+
+/// Options for how an account's free balance should be debited.
+#[derive(Copy, Clone, Eq, PartialEq, RuntimeDebug)]
+pub(crate) struct DebitFlags {
+	/// The debit may not take the account below the minimum balance.
+	keep_alive: bool,
+	/// Debit as much as possible up to the requested amount, rather than failing if it is not all
+	/// available.
+	best_effort: bool,
 }
 
 decl_storage! {
@@ -212,6 +267,11 @@ decl_storage! {
 			hasher(blake2_128_concat) T::AssetId,
 			hasher(blake2_128_concat) T::AccountId
 			=> AssetBalance<T::Balance>;
+
+		/// Storage version of the pallet.
+		///
+		/// New networks start with the latest version, as determined by the genesis build.
+		StorageVersion build(|_| Releases::V1): Releases;
 	}
 }
 
@@ -274,6 +334,8 @@ decl_error! {
 		MinBalanceZero,
 		/// A mint operation lead to an overflow.
 		Overflow,
+		/// The account would be destroyed by the operation, but it must be kept alive.
+		WouldDie,
 	}
 }
 
@@ -283,6 +345,14 @@ decl_module! {
 
 		fn deposit_event() = default;
 
+		fn on_runtime_upgrade() -> frame_support::weights::Weight {
+			if StorageVersion::get() == Releases::V0 {
+				Self::migrate_to_v1()
+			} else {
+				T::DbWeight::get().reads(1)
+			}
+		}
+
 		/// Issue a new class of fungible assets from a public origin.
 		///
 		/// This new asset class has no assets initially.
@@ -469,25 +539,7 @@ decl_module! {
 		) -> DispatchResult {
 			let origin = ensure_signed(origin)?;
 			let beneficiary = T::Lookup::lookup(beneficiary)?;
-
-			Asset::<T>::try_mutate(id, |maybe_details| {
-				let details = maybe_details.as_mut().ok_or(Error::<T>::Unknown)?;
-
-				ensure!(&origin == &details.issuer, Error::<T>::NoPermission);
-				details.supply = details.supply.checked_add(&amount).ok_or(Error::<T>::Overflow)?;
-
-				Account::<T>::try_mutate(id, &beneficiary, |t| -> DispatchResult {
-					let new_balance = t.balance.saturating_add(amount);
-					ensure!(new_balance >= details.min_balance, Error::<T>::BalanceLow);
-					if t.balance.is_zero() {
-						t.is_zombie = Self::new_account(&beneficiary, details)?;
-					}
-					t.balance = new_balance;
-					Ok(())
-				})?;
-				Self::deposit_event(RawEvent::Issued(id, beneficiary, amount));
-				Ok(())
-			})
+			Self::do_mint(id, &beneficiary, amount, Some(origin))
 		}
 
 		/// Reduce the balance of `who` by as much as possible up to `amount` assets of `id`.
@@ -513,34 +565,8 @@ decl_module! {
 		) -> DispatchResult {
 			let origin = ensure_signed(origin)?;
 			let who = T::Lookup::lookup(who)?;
-
-			Asset::<T>::try_mutate(id, |maybe_details| {
-				let d = maybe_details.as_mut().ok_or(Error::<T>::Unknown)?;
-				ensure!(&origin == &d.admin, Error::<T>::NoPermission);
-
-				let burned = Account::<T>::try_mutate_exists(
-					id,
-					&who,
-					|maybe_account| -> Result<T::Balance, DispatchError> {
-						let mut account = maybe_account.take().ok_or(Error::<T>::BalanceZero)?;
-						let mut burned = amount.min(account.balance);
-						account.balance -= burned;
-						*maybe_account = if account.balance < d.min_balance {
-							burned += account.balance;
-							Self::dead_account(&who, d, account.is_zombie);
-							None
-						} else {
-							Some(account)
-						};
-						Ok(burned)
-					}
-				)?;
-
-				d.supply = d.supply.saturating_sub(burned);
-
-				Self::deposit_event(RawEvent::Burned(id, who, burned));
-				Ok(())
-			})
+			let f = DebitFlags { keep_alive: false, best_effort: true };
+			Self::do_burn(id, &who, amount, Some(origin), f).map(|_| ())
 		}
 
 		/// Move some assets from the sender account to another.
@@ -569,50 +595,9 @@ decl_module! {
 		) -> DispatchResult {
 			let origin = ensure_signed(origin)?;
 			ensure!(!amount.is_zero(), Error::<T>::AmountZero);
-
-			let mut origin_account = Account::<T>::get(id, &origin);
-			ensure!(!origin_account.is_frozen, Error::<T>::Frozen);
-			origin_account.balance = origin_account.balance.checked_sub(&amount)
-				.ok_or(Error::<T>::BalanceLow)?;
-
 			let dest = T::Lookup::lookup(target)?;
-			Asset::<T>::try_mutate(id, |maybe_details| {
-				let details = maybe_details.as_mut().ok_or(Error::<T>::Unknown)?;
-
-				if dest == origin {
-					return Ok(())
-				}
-
-				let mut amount = amount;
-				if origin_account.balance < details.min_balance {
-					amount += origin_account.balance;
-					origin_account.balance = Zero::zero();
-				}
-
-				Account::<T>::try_mutate(id, &dest, |a| -> DispatchResult {
-					let new_balance = a.balance.saturating_add(amount);
-					ensure!(new_balance >= details.min_balance, Error::<T>::BalanceLow);
-					if a.balance.is_zero() {
-						a.is_zombie = Self::new_account(&dest, details)?;
-					}
-					a.balance = new_balance;
-					Ok(())
-				})?;
-
-				match origin_account.balance.is_zero() {
-					false => {
-						Self::dezombify(&origin, details, &mut origin_account.is_zombie);
-						Account::<T>::insert(id, &origin, &origin_account)
-					}
-					true => {
-						Self::dead_account(&origin, details, origin_account.is_zombie);
-						Account::<T>::remove(id, &origin);
-					}
-				}
-
-				Self::deposit_event(RawEvent::Transferred(id, origin, dest, amount));
-				Ok(())
-			})
+			let f = DebitFlags { keep_alive: false, best_effort: false };
+			Self::do_transfer(id, &origin, &dest, amount, None, f).map(|_| ())
 		}
 
 		/// Move some assets from one account to another.
@@ -642,51 +627,10 @@ decl_module! {
 			#[compact] amount: T::Balance,
 		) -> DispatchResult {
 			let origin = ensure_signed(origin)?;
-
 			let source = T::Lookup::lookup(source)?;
-			let mut source_account = Account::<T>::get(id, &source);
-			let mut amount = amount.min(source_account.balance);
-			ensure!(!amount.is_zero(), Error::<T>::AmountZero);
-
 			let dest = T::Lookup::lookup(dest)?;
-			if dest == source {
-				return Ok(())
-			}
-
-			Asset::<T>::try_mutate(id, |maybe_details| {
-				let details = maybe_details.as_mut().ok_or(Error::<T>::Unknown)?;
-				ensure!(&origin == &details.admin, Error::<T>::NoPermission);
-
-				source_account.balance -= amount;
-				if source_account.balance < details.min_balance {
-					amount += source_account.balance;
-					source_account.balance = Zero::zero();
-				}
-
-				Account::<T>::try_mutate(id, &dest, |a| -> DispatchResult {
-					let new_balance = a.balance.saturating_add(amount);
-					ensure!(new_balance >= details.min_balance, Error::<T>::BalanceLow);
-					if a.balance.is_zero() {
-						a.is_zombie = Self::new_account(&dest, details)?;
-					}
-					a.balance = new_balance;
-					Ok(())
-				})?;
-
-				match source_account.balance.is_zero() {
-					false => {
-						Self::dezombify(&source, details, &mut source_account.is_zombie);
-						Account::<T>::insert(id, &source, &source_account)
-					}
-					true => {
-						Self::dead_account(&source, details, source_account.is_zombie);
-						Account::<T>::remove(id, &source);
-					}
-				}
-
-				Self::deposit_event(RawEvent::ForceTransferred(id, source, dest, amount));
-				Ok(())
-			})
+			let f = DebitFlags { keep_alive: false, best_effort: true };
+			Self::do_transfer(id, &source, &dest, amount, Some(origin), f).map(|_| ())
 		}
 
 		/// Disallow further unprivileged transfers from an account.
@@ -840,6 +784,18 @@ decl_module! {
 
 // The main implementation block for the module.
 impl<T: Config> Module<T> {
+	/// Migrate `Account` to V1, giving every account an empty `reserved` balance.
+	pub fn migrate_to_v1() -> frame_support::weights::Weight {
+		Account::<T>::translate::<OldAssetBalance<T::Balance>, _>(|_, _, old| Some(AssetBalance {
+			balance: old.balance,
+			is_frozen: old.is_frozen,
+			is_zombie: old.is_zombie,
+			reserved: Zero::zero(),
+		}));
+		StorageVersion::put(Releases::V1);
+		T::BlockWeights::get().max_block
+	}
+
 	// Public immutables
 
 	/// Get the asset `id` balance of `who`.
@@ -857,6 +813,402 @@ impl<T: Config> Module<T> {
 		Asset::<T>::get(id).map(|x| x.max_zombies - x.zombies).unwrap_or_else(Zero::zero)
 	}
 
+	/// Get the maximum amount of asset `id` that `who` can withdraw or transfer.
+	///
+	/// If `keep_alive` is `true`, or the account has funds on hold, then what remains in the
+	/// account must be at least the minimum balance.
+	pub fn reducible_balance(id: T::AssetId, who: &T::AccountId, keep_alive: bool) -> T::Balance {
+		match Asset::<T>::get(id) {
+			Some(details) => {
+				let account = Account::<T>::get(id, who);
+				if account.is_frozen {
+					Zero::zero()
+				} else {
+					Self::reducible(&account, &details, keep_alive)
+				}
+			}
+			None => Zero::zero(),
+		}
+	}
+
+	/// The consequence of increasing the balance of asset `id` held by `who` by `amount`.
+	pub(crate) fn can_increase(
+		id: T::AssetId,
+		who: &T::AccountId,
+		amount: T::Balance,
+	) -> DepositConsequence {
+		let details = match Asset::<T>::get(id) {
+			Some(details) => details,
+			None => return DepositConsequence::UnknownAsset,
+		};
+		if details.supply.checked_add(&amount).is_none() {
+			return DepositConsequence::Overflow
+		}
+		let account = Account::<T>::get(id, who);
+		if account.balance.checked_add(&amount).is_none() {
+			return DepositConsequence::Overflow
+		}
+		if account.total().is_zero() {
+			if amount < details.min_balance {
+				return DepositConsequence::BelowMinimum
+			}
+			if !frame_system::Module::<T>::account_exists(who)
+				&& details.zombies >= details.max_zombies
+			{
+				return DepositConsequence::CannotCreate
+			}
+		}
+		DepositConsequence::Success
+	}
+
+	/// The consequence of decreasing the free balance of asset `id` held by `who` by `amount`.
+	pub(crate) fn can_decrease(
+		id: T::AssetId,
+		who: &T::AccountId,
+		amount: T::Balance,
+		keep_alive: bool,
+	) -> WithdrawConsequence<T::Balance> {
+		let details = match Asset::<T>::get(id) {
+			Some(details) => details,
+			None => return WithdrawConsequence::UnknownAsset,
+		};
+		if details.supply.checked_sub(&amount).is_none() {
+			return WithdrawConsequence::Underflow
+		}
+		let account = Account::<T>::get(id, who);
+		if account.is_frozen {
+			return WithdrawConsequence::Frozen
+		}
+		match account.balance.checked_sub(&amount) {
+			Some(rest) if rest.saturating_add(account.reserved) < details.min_balance => {
+				if keep_alive || !account.reserved.is_zero() {
+					WithdrawConsequence::WouldDie
+				} else {
+					WithdrawConsequence::ReducedToZero(rest)
+				}
+			}
+			Some(_) => WithdrawConsequence::Success,
+			None => WithdrawConsequence::NoFunds,
+		}
+	}
+
+	/// Increase the balance of `beneficiary` by `amount` of asset `id`, and the total supply with
+	/// it.
+	///
+	/// If `maybe_check_issuer` is `Some`, then it must be the Issuer of the asset.
+	pub(crate) fn do_mint(
+		id: T::AssetId,
+		beneficiary: &T::AccountId,
+		amount: T::Balance,
+		maybe_check_issuer: Option<T::AccountId>,
+	) -> DispatchResult {
+		Asset::<T>::try_mutate(id, |maybe_details| -> DispatchResult {
+			let details = maybe_details.as_mut().ok_or(Error::<T>::Unknown)?;
+
+			if let Some(check_issuer) = maybe_check_issuer {
+				ensure!(&check_issuer == &details.issuer, Error::<T>::NoPermission);
+			}
+			details.supply = details.supply.checked_add(&amount).ok_or(Error::<T>::Overflow)?;
+
+			Self::credit(id, beneficiary, details, amount)
+		})?;
+		Self::deposit_event(RawEvent::Issued(id, beneficiary.clone(), amount));
+		Ok(())
+	}
+
+	/// Reduce the free balance of `target` by `amount` of asset `id`, and the total supply with
+	/// it. Returns the amount actually burned, which may differ from `amount` according to `f`
+	/// and the asset's minimum balance.
+	///
+	/// If `maybe_check_admin` is `Some`, then it must be the Admin of the asset and the account
+	/// may be frozen. Otherwise, the account must not be frozen.
+	pub(crate) fn do_burn(
+		id: T::AssetId,
+		target: &T::AccountId,
+		amount: T::Balance,
+		maybe_check_admin: Option<T::AccountId>,
+		f: DebitFlags,
+	) -> Result<T::Balance, DispatchError> {
+		let burned = Asset::<T>::try_mutate(id, |maybe_details| {
+			let details = maybe_details.as_mut().ok_or(Error::<T>::Unknown)?;
+
+			let is_forced = maybe_check_admin.is_some();
+			if let Some(check_admin) = maybe_check_admin {
+				ensure!(&check_admin == &details.admin, Error::<T>::NoPermission);
+			}
+
+			let burned = Account::<T>::try_mutate_exists(
+				id,
+				target,
+				|maybe_account| -> Result<T::Balance, DispatchError> {
+					let mut account = maybe_account.take().ok_or(Error::<T>::BalanceZero)?;
+					ensure!(is_forced || !account.is_frozen, Error::<T>::Frozen);
+					let burned = Self::prep_debit(&mut account, details, amount, f)?;
+					*maybe_account = Self::settle(target, details, account);
+					Ok(burned)
+				}
+			)?;
+
+			details.supply = details.supply.saturating_sub(burned);
+			Ok::<_, DispatchError>(burned)
+		})?;
+		Self::deposit_event(RawEvent::Burned(id, target.clone(), burned));
+		Ok(burned)
+	}
+
+	/// Move `amount` of asset `id` from the free balance of `source` to `dest`. Returns the amount
+	/// actually transferred, which may differ from `amount` according to `f` and the asset's
+	/// minimum balance.
+	///
+	/// If `maybe_need_admin` is `Some`, then it must be the Admin of the asset and `source` may be
+	/// frozen. Otherwise, `source` must not be frozen.
+	pub(crate) fn do_transfer(
+		id: T::AssetId,
+		source: &T::AccountId,
+		dest: &T::AccountId,
+		amount: T::Balance,
+		maybe_need_admin: Option<T::AccountId>,
+		f: DebitFlags,
+	) -> Result<T::Balance, DispatchError> {
+		let mut source_account = Account::<T>::get(id, source);
+		let is_forced = maybe_need_admin.is_some();
+		ensure!(is_forced || !source_account.is_frozen, Error::<T>::Frozen);
+
+		let actual = Asset::<T>::try_mutate(id, |maybe_details| {
+			let details = maybe_details.as_mut().ok_or(Error::<T>::Unknown)?;
+
+			if let Some(need_admin) = maybe_need_admin {
+				ensure!(&need_admin == &details.admin, Error::<T>::NoPermission);
+			}
+
+			let actual = Self::prep_debit(&mut source_account, details, amount, f)?;
+			ensure!(!actual.is_zero(), Error::<T>::AmountZero);
+
+			if source == dest {
+				return Ok(actual)
+			}
+
+			Self::credit(id, dest, details, actual)?;
+
+			match Self::settle(source, details, source_account) {
+				Some(account) => Account::<T>::insert(id, source, &account),
+				None => Account::<T>::remove(id, source),
+			}
+			Ok::<_, DispatchError>(actual)
+		})?;
+
+		if source != dest {
+			let event = if is_forced {
+				RawEvent::ForceTransferred(id, source.clone(), dest.clone(), actual)
+			} else {
+				RawEvent::Transferred(id, source.clone(), dest.clone(), actual)
+			};
+			Self::deposit_event(event);
+		}
+		Ok(actual)
+	}
+
+	/// Place `amount` of the free balance of asset `id` held by `who` on hold.
+	pub(crate) fn do_hold(
+		id: T::AssetId,
+		who: &T::AccountId,
+		amount: T::Balance,
+	) -> DispatchResult {
+		ensure!(Asset::<T>::contains_key(id), Error::<T>::Unknown);
+		Account::<T>::try_mutate_exists(id, who, |maybe_account| -> DispatchResult {
+			let account = maybe_account.as_mut().ok_or(Error::<T>::BalanceZero)?;
+			ensure!(!account.is_frozen, Error::<T>::Frozen);
+			account.balance = account.balance.checked_sub(&amount).ok_or(Error::<T>::BalanceLow)?;
+			account.reserved = account.reserved.checked_add(&amount).ok_or(Error::<T>::Overflow)?;
+			Ok(())
+		})
+	}
+
+	/// Return `amount` of the balance of asset `id` which `who` has on hold to its free balance.
+	///
+	/// If `best_effort` is `true`, then as much as possible up to `amount` is released. Returns
+	/// the amount actually released.
+	pub(crate) fn do_release(
+		id: T::AssetId,
+		who: &T::AccountId,
+		amount: T::Balance,
+		best_effort: bool,
+	) -> Result<T::Balance, DispatchError> {
+		ensure!(Asset::<T>::contains_key(id), Error::<T>::Unknown);
+		Account::<T>::try_mutate_exists(id, who, |maybe_account| {
+			let account = maybe_account.as_mut().ok_or(Error::<T>::BalanceZero)?;
+			let actual = if best_effort { amount.min(account.reserved) } else { amount };
+			account.reserved = account.reserved.checked_sub(&actual).ok_or(Error::<T>::BalanceLow)?;
+			account.balance = account.balance.saturating_add(actual);
+			Ok(actual)
+		})
+	}
+
+	/// Move `amount` of asset `id` which `source` has on hold to `dest`.
+	///
+	/// If `on_hold` is `true`, then the funds remain on hold in `dest`, which must already exist.
+	/// If `best_effort` is `true`, then as much as possible up to `amount` is moved. Should what
+	/// remains in `source` fall below the minimum balance, it is moved too. Returns the amount
+	/// actually moved.
+	pub(crate) fn do_transfer_held(
+		id: T::AssetId,
+		source: &T::AccountId,
+		dest: &T::AccountId,
+		amount: T::Balance,
+		best_effort: bool,
+		on_hold: bool,
+	) -> Result<T::Balance, DispatchError> {
+		if source == dest {
+			return if on_hold {
+				let held = Account::<T>::get(id, source).reserved;
+				let actual = if best_effort { amount.min(held) } else { amount };
+				ensure!(actual <= held, Error::<T>::BalanceLow);
+				Ok(actual)
+			} else {
+				Self::do_release(id, source, amount, best_effort)
+			}
+		}
+
+		let actual = Asset::<T>::try_mutate(id, |maybe_details| {
+			let details = maybe_details.as_mut().ok_or(Error::<T>::Unknown)?;
+
+			let mut source_account = Account::<T>::get(id, source);
+			let held = source_account.reserved;
+			let mut actual = if best_effort { amount.min(held) } else { amount };
+			ensure!(!actual.is_zero(), Error::<T>::AmountZero);
+			source_account.reserved = held.checked_sub(&actual).ok_or(Error::<T>::BalanceLow)?;
+			if source_account.total() < details.min_balance {
+				actual = actual.saturating_add(source_account.balance);
+				source_account.balance = Zero::zero();
+			}
+
+			if on_hold {
+				Account::<T>::try_mutate_exists(id, dest, |maybe_account| -> DispatchResult {
+					let account = maybe_account.as_mut().ok_or(Error::<T>::BalanceZero)?;
+					account.reserved = account.reserved.checked_add(&actual)
+						.ok_or(Error::<T>::Overflow)?;
+					Ok(())
+				})?;
+			} else {
+				Self::credit(id, dest, details, actual)?;
+			}
+
+			match Self::settle(source, details, source_account) {
+				Some(account) => Account::<T>::insert(id, source, &account),
+				None => Account::<T>::remove(id, source),
+			}
+			Ok::<_, DispatchError>(actual)
+		})?;
+
+		Self::deposit_event(RawEvent::Transferred(id, source.clone(), dest.clone(), actual));
+		Ok(actual)
+	}
+
+	/// Set the total balance of asset `id` held by `who`, including any funds on hold, to
+	/// `amount`, without altering the total supply.
+	pub(crate) fn do_set_balance(
+		id: T::AssetId,
+		who: &T::AccountId,
+		amount: T::Balance,
+	) -> DispatchResult {
+		Asset::<T>::try_mutate(id, |maybe_details| -> DispatchResult {
+			let details = maybe_details.as_mut().ok_or(Error::<T>::Unknown)?;
+			Account::<T>::try_mutate_exists(id, who, |maybe_account| -> DispatchResult {
+				let mut account = maybe_account.take().unwrap_or_default();
+				let was_alive = !account.total().is_zero();
+				account.balance = amount.checked_sub(&account.reserved)
+					.ok_or(Error::<T>::WouldDie)?;
+				if amount.is_zero() {
+					if was_alive {
+						Self::dead_account(who, details, account.is_zombie);
+					}
+					return Ok(())
+				}
+				ensure!(amount >= details.min_balance, Error::<T>::BalanceLow);
+				if !was_alive {
+					account.is_zombie = Self::new_account(who, details)?;
+				} else {
+					Self::dezombify(who, details, &mut account.is_zombie);
+				}
+				*maybe_account = Some(account);
+				Ok(())
+			})
+		})
+	}
+
+	/// The amount of the free balance of `account` which can be debited without going against
+	/// `keep_alive` or leaving funds on hold in an account below the minimum balance.
+	fn reducible(
+		account: &AssetBalance<T::Balance>,
+		details: &AssetDetails<T::Balance, T::AccountId, BalanceOf<T>>,
+		keep_alive: bool,
+	) -> T::Balance {
+		if keep_alive || !account.reserved.is_zero() {
+			let required = details.min_balance.saturating_sub(account.reserved);
+			account.balance.saturating_sub(required)
+		} else {
+			account.balance
+		}
+	}
+
+	/// Debit `amount` from the free balance of `account`, returning the amount actually debited.
+	///
+	/// Should what remains fall below the minimum balance, then it is debited too, unless this
+	/// would go against `f.keep_alive` or leave funds on hold, in which case it fails.
+	fn prep_debit(
+		account: &mut AssetBalance<T::Balance>,
+		details: &AssetDetails<T::Balance, T::AccountId, BalanceOf<T>>,
+		amount: T::Balance,
+		f: DebitFlags,
+	) -> Result<T::Balance, DispatchError> {
+		let mut amount = if f.best_effort {
+			amount.min(Self::reducible(account, details, f.keep_alive))
+		} else {
+			amount
+		};
+		account.balance = account.balance.checked_sub(&amount).ok_or(Error::<T>::BalanceLow)?;
+		if account.total() < details.min_balance {
+			ensure!(!f.keep_alive && account.reserved.is_zero(), Error::<T>::WouldDie);
+			amount += account.balance;
+			account.balance = Zero::zero();
+		}
+		Ok(amount)
+	}
+
+	/// Credit `amount` to the free balance of `who`, creating the account if needed.
+	fn credit(
+		id: T::AssetId,
+		who: &T::AccountId,
+		details: &mut AssetDetails<T::Balance, T::AccountId, BalanceOf<T>>,
+		amount: T::Balance,
+	) -> DispatchResult {
+		Account::<T>::try_mutate(id, who, |a| -> DispatchResult {
+			let new_balance = a.balance.saturating_add(amount);
+			let new_total = new_balance.saturating_add(a.reserved);
+			ensure!(new_total >= details.min_balance, Error::<T>::BalanceLow);
+			if a.total().is_zero() {
+				a.is_zombie = Self::new_account(who, details)?;
+			}
+			a.balance = new_balance;
+			Ok(())
+		})
+	}
+
+	/// Reap `account` of `who` if it is now empty, or return it to be stored otherwise.
+	fn settle(
+		who: &T::AccountId,
+		details: &mut AssetDetails<T::Balance, T::AccountId, BalanceOf<T>>,
+		mut account: AssetBalance<T::Balance>,
+	) -> Option<AssetBalance<T::Balance>> {
+		if account.total().is_zero() {
+			Self::dead_account(who, details, account.is_zombie);
+			None
+		} else {
+			Self::dezombify(who, details, &mut account.is_zombie);
+			Some(account)
+		}
+	}
+
 	fn new_account(
 		who: &T::AccountId,
 		d: &mut AssetDetails<T::Balance, T::AccountId, BalanceOf<T>>,
@@ -901,11 +1253,60 @@ impl<T: Config> Module<T> {
 	}
 }
 
+/// Possible errors when converting between external and asset balances.
+#[derive(Copy, Clone, Eq, PartialEq, RuntimeDebug)]
+pub enum ConversionError {
+	/// The external minimum balance must not be zero.
+	MinBalanceZero,
+	/// The asset is not present in storage.
+	AssetMissing,
+}
+
+/// Converts a balance value into an asset balance based on the ratio between the asset's minimum
+/// balance and the minimum balance of the fungible `F` (usually the native currency).
+///
+/// `CON` converts between the balance types of `F` and of the assets.
+pub struct BalanceToAssetBalance<F, T, CON>(PhantomData<(F, T, CON)>);
+
+type FungibleBalanceOf<F, T> =
+	<F as fungible::Inspect<<T as frame_system::Config>::AccountId>>::Balance;
+
+impl<F, T, CON> BalanceConversion<FungibleBalanceOf<F, T>, T::AssetId, T::Balance>
+	for BalanceToAssetBalance<F, T, CON>
+where
+	F: fungible::Inspect<T::AccountId>,
+	T: Config,
+	CON: Convert<FungibleBalanceOf<F, T>, T::Balance>,
+	T::Balance: FixedPointOperand,
+{
+	type Error = ConversionError;
+
+	/// Convert the given balance value into an asset balance.
+	///
+	/// Will return `Err` if the asset is not found or the minimum balance of `F` is zero.
+	///
+	/// The conversion uses the ratio `asset.min_balance / F::minimum_balance()`, so that a balance
+	/// of exactly the external minimum converts to exactly the asset's minimum balance.
+	fn to_asset_balance(
+		balance: FungibleBalanceOf<F, T>,
+		asset_id: T::AssetId,
+	) -> Result<T::Balance, ConversionError> {
+		let asset = Asset::<T>::get(asset_id).ok_or(ConversionError::AssetMissing)?;
+		let min_balance = CON::convert(F::minimum_balance());
+		ensure!(!min_balance.is_zero(), ConversionError::MinBalanceZero);
+		let balance = CON::convert(balance);
+		Ok(FixedU128::saturating_from_rational(asset.min_balance, min_balance)
+			.saturating_mul_int(balance))
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
 
 	use frame_support::{impl_outer_origin, assert_ok, assert_noop, parameter_types, impl_outer_event};
+	use frame_support::traits::tokens::{fungible, fungibles::{self, Inspect, InspectHold}};
+	use sp_runtime::TokenError;
 	use sp_core::H256;
 	use sp_runtime::{traits::{BlakeTwo256, IdentityLookup}, testing::Header};
 
@@ -1301,4 +1702,309 @@ mod tests {
 			assert_noop!(Assets::burn(Origin::signed(1), 0, 2, u64::max_value()), Error::<Test>::BalanceZero);
 		});
 	}
+
+	#[test]
+	fn fungibles_inspect_should_work() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(Assets::force_create(Origin::root(), 0, 1, 10, 10));
+			assert_ok!(Assets::mint(Origin::signed(1), 0, 1, 100));
+
+			assert_eq!(<Assets as Inspect<u64>>::total_issuance(0), 100);
+			assert_eq!(<Assets as Inspect<u64>>::minimum_balance(0), 10);
+			assert_eq!(<Assets as Inspect<u64>>::balance(0, &1), 100);
+			assert_eq!(<Assets as Inspect<u64>>::reducible_balance(0, &1, false), 100);
+			assert_eq!(<Assets as Inspect<u64>>::reducible_balance(0, &1, true), 90);
+
+			assert_eq!(Assets::can_deposit(0, &2, 10), DepositConsequence::Success);
+			assert_eq!(Assets::can_deposit(0, &2, 9), DepositConsequence::BelowMinimum);
+			assert_eq!(Assets::can_deposit(0, &1, 9), DepositConsequence::Success);
+			assert_eq!(Assets::can_deposit(1, &1, 10), DepositConsequence::UnknownAsset);
+			assert_eq!(Assets::can_deposit(0, &1, u64::max_value()), DepositConsequence::Overflow);
+
+			assert_eq!(Assets::can_withdraw(0, &1, 90), WithdrawConsequence::Success);
+			assert_eq!(Assets::can_withdraw(0, &1, 91), WithdrawConsequence::ReducedToZero(9));
+			assert_eq!(Assets::can_withdraw(0, &1, 101), WithdrawConsequence::Underflow);
+			assert_eq!(Assets::can_withdraw(0, &2, 1), WithdrawConsequence::NoFunds);
+			assert_eq!(Assets::can_withdraw(1, &1, 1), WithdrawConsequence::UnknownAsset);
+
+			assert_ok!(Assets::freeze(Origin::signed(1), 0, 1));
+			assert_eq!(Assets::can_withdraw(0, &1, 1), WithdrawConsequence::Frozen);
+			assert_eq!(<Assets as Inspect<u64>>::reducible_balance(0, &1, false), 0);
+		});
+	}
+
+	#[test]
+	fn fungibles_deposit_into_zombie_should_respect_max_zombies() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(Assets::force_create(Origin::root(), 0, 1, 1, 1));
+			assert_ok!(Assets::mint(Origin::signed(1), 0, 1, 100));
+			assert_eq!(Assets::can_deposit(0, &2, 10), DepositConsequence::CannotCreate);
+			assert_noop!(
+				<Assets as fungibles::Mutate<u64>>::mint_into(0, &2, 10),
+				Error::<Test>::TooManyZombies,
+			);
+
+			Balances::make_free_balance_be(&2, 100);
+			assert_eq!(Assets::can_deposit(0, &2, 10), DepositConsequence::Success);
+			assert_ok!(<Assets as fungibles::Mutate<u64>>::mint_into(0, &2, 10));
+		});
+	}
+
+	#[test]
+	fn fungibles_mutate_should_work() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(Assets::force_create(Origin::root(), 0, 1, 10, 10));
+			assert_ok!(<Assets as fungibles::Mutate<u64>>::mint_into(0, &1, 100));
+			assert_eq!(Assets::balance(0, 1), 100);
+			assert_eq!(Assets::total_supply(0), 100);
+			assert_noop!(
+				<Assets as fungibles::Mutate<u64>>::mint_into(0, &2, 9),
+				Error::<Test>::BalanceLow,
+			);
+
+			assert_eq!(<Assets as fungibles::Mutate<u64>>::burn_from(0, &1, 50), Ok(50));
+			assert_noop!(
+				<Assets as fungibles::Mutate<u64>>::burn_from(0, &1, 51),
+				Error::<Test>::BalanceLow,
+			);
+			// burning into the dust takes the rest too.
+			assert_eq!(<Assets as fungibles::Mutate<u64>>::burn_from(0, &1, 45), Ok(50));
+			assert_eq!(Assets::balance(0, 1), 0);
+			assert_eq!(Assets::total_supply(0), 0);
+			assert_eq!(Asset::<Test>::get(0).unwrap().accounts, 0);
+
+			assert_ok!(<Assets as fungibles::Mutate<u64>>::mint_into(0, &1, 100));
+			assert_ok!(Assets::freeze(Origin::signed(1), 0, 1));
+			assert_noop!(
+				<Assets as fungibles::Mutate<u64>>::burn_from(0, &1, 10),
+				Error::<Test>::Frozen,
+			);
+			assert_noop!(
+				<Assets as fungibles::Mutate<u64>>::slash(0, &1, 10),
+				TokenError::NoFunds,
+			);
+			assert_ok!(Assets::thaw(Origin::signed(1), 0, 1));
+			assert_eq!(<Assets as fungibles::Mutate<u64>>::slash(0, &1, 200), Ok(100));
+			assert_eq!(Assets::total_supply(0), 0);
+		});
+	}
+
+	#[test]
+	fn fungibles_transfer_should_work() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(Assets::force_create(Origin::root(), 0, 1, 10, 10));
+			assert_ok!(Assets::mint(Origin::signed(1), 0, 1, 100));
+
+			assert_eq!(<Assets as fungibles::Transfer<u64>>::transfer(0, &1, &2, 50, true), Ok(50));
+			assert_noop!(
+				<Assets as fungibles::Transfer<u64>>::transfer(0, &1, &2, 45, true),
+				Error::<Test>::WouldDie,
+			);
+			assert_eq!(<Assets as fungibles::Transfer<u64>>::transfer(0, &1, &2, 45, false), Ok(50));
+			assert_eq!(Assets::balance(0, 1), 0);
+			assert_eq!(Assets::balance(0, 2), 100);
+			assert_eq!(Asset::<Test>::get(0).unwrap().accounts, 1);
+
+			assert_ok!(Assets::freeze(Origin::signed(1), 0, 2));
+			assert_noop!(
+				<Assets as fungibles::Transfer<u64>>::transfer(0, &2, &1, 50, false),
+				Error::<Test>::Frozen,
+			);
+		});
+	}
+
+	#[test]
+	fn fungibles_teleport_should_work() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(Assets::force_create(Origin::root(), 0, 1, 10, 10));
+			assert_ok!(Assets::mint(Origin::signed(1), 0, 1, 100));
+
+			assert_noop!(
+				<Assets as fungibles::Mutate<u64>>::teleport(0, &1, &2, 5),
+				TokenError::BelowMinimum,
+			);
+			assert_eq!(<Assets as fungibles::Mutate<u64>>::teleport(0, &1, &2, 95), Ok(100));
+			assert_eq!(Assets::balance(0, 1), 0);
+			assert_eq!(Assets::balance(0, 2), 100);
+			assert_eq!(Assets::total_supply(0), 100);
+		});
+	}
+
+	#[test]
+	fn fungibles_hold_should_work() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(Assets::force_create(Origin::root(), 0, 1, 10, 10));
+			assert_ok!(Assets::mint(Origin::signed(1), 0, 1, 100));
+			assert_ok!(Assets::mint(Origin::signed(1), 0, 2, 100));
+
+			assert!(Assets::can_hold(0, &1, 100));
+			assert!(!Assets::can_hold(0, &1, 101));
+			assert_ok!(<Assets as fungibles::MutateHold<u64>>::hold(0, &1, 95));
+			assert_eq!(Assets::balance_on_hold(0, &1), 95);
+			assert_eq!(<Assets as Inspect<u64>>::balance(0, &1), 100);
+			assert_eq!(Assets::balance(0, 1), 5);
+			assert_noop!(
+				<Assets as fungibles::MutateHold<u64>>::hold(0, &1, 6),
+				Error::<Test>::BalanceLow,
+			);
+
+			// funds on hold keep the account alive, so all free funds may be spent...
+			assert_eq!(<Assets as Inspect<u64>>::reducible_balance(0, &1, false), 5);
+			assert_noop!(Assets::transfer(Origin::signed(1), 0, 2, 6), Error::<Test>::BalanceLow);
+			assert_ok!(Assets::transfer(Origin::signed(1), 0, 2, 5));
+			assert_eq!(Assets::balance(0, 2), 105);
+			// ...but none of the funds on hold may be burned.
+			assert_ok!(Assets::burn(Origin::signed(1), 0, 1, 100));
+			assert_eq!(Assets::balance_on_hold(0, &1), 95);
+			assert_eq!(Asset::<Test>::get(0).unwrap().accounts, 2);
+
+			assert_noop!(
+				<Assets as fungibles::MutateHold<u64>>::release(0, &1, 96, false),
+				Error::<Test>::BalanceLow,
+			);
+			assert_eq!(<Assets as fungibles::MutateHold<u64>>::release(0, &1, 96, true), Ok(95));
+			assert_eq!(Assets::balance_on_hold(0, &1), 0);
+			assert_eq!(Assets::balance(0, 1), 95);
+		});
+	}
+
+	#[test]
+	fn fungibles_hold_below_minimum_balance_should_not_die() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(Assets::force_create(Origin::root(), 0, 1, 10, 10));
+			assert_ok!(Assets::mint(Origin::signed(1), 0, 1, 100));
+			assert_ok!(<Assets as fungibles::MutateHold<u64>>::hold(0, &1, 5));
+
+			// at least 5 free funds must stay to keep the total at the minimum balance.
+			assert_eq!(<Assets as Inspect<u64>>::reducible_balance(0, &1, false), 90);
+			assert_eq!(Assets::can_withdraw(0, &1, 91), WithdrawConsequence::WouldDie);
+			assert_noop!(
+				<Assets as fungibles::Transfer<u64>>::transfer(0, &1, &2, 91, false),
+				Error::<Test>::WouldDie,
+			);
+			assert_ok!(Assets::force_transfer(Origin::signed(1), 0, 1, 2, 100));
+			assert_eq!(Assets::balance(0, 1), 5);
+			assert_eq!(Assets::balance(0, 2), 90);
+		});
+	}
+
+	#[test]
+	fn fungibles_transfer_held_should_work() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(Assets::force_create(Origin::root(), 0, 1, 10, 10));
+			assert_ok!(Assets::mint(Origin::signed(1), 0, 1, 100));
+			assert_ok!(<Assets as fungibles::MutateHold<u64>>::hold(0, &1, 50));
+
+			// destination must exist for the funds to stay on hold.
+			assert_noop!(
+				<Assets as fungibles::MutateHold<u64>>::transfer_held(0, &1, &2, 20, false, true),
+				Error::<Test>::BalanceZero,
+			);
+			assert_eq!(
+				<Assets as fungibles::MutateHold<u64>>::transfer_held(0, &1, &2, 20, false, false),
+				Ok(20),
+			);
+			assert_eq!(Assets::balance(0, 2), 20);
+			assert_eq!(
+				<Assets as fungibles::MutateHold<u64>>::transfer_held(0, &1, &2, 40, true, true),
+				Ok(30),
+			);
+			assert_eq!(Assets::balance_on_hold(0, &1), 0);
+			assert_eq!(Assets::balance_on_hold(0, &2), 30);
+			assert_eq!(<Assets as Inspect<u64>>::balance(0, &1), 50);
+			assert_eq!(<Assets as Inspect<u64>>::balance(0, &2), 50);
+		});
+	}
+
+	#[test]
+	fn fungibles_unbalanced_should_work() {
+		use fungibles::Unbalanced;
+		new_test_ext().execute_with(|| {
+			assert_ok!(Assets::force_create(Origin::root(), 0, 1, 10, 10));
+
+			assert_ok!(Assets::set_balance(0, &1, 100));
+			assert_eq!(Assets::balance(0, 1), 100);
+			assert_eq!(Asset::<Test>::get(0).unwrap().accounts, 1);
+			// the supply is left untouched.
+			assert_eq!(Assets::total_supply(0), 0);
+			Assets::set_total_issuance(0, 100);
+			assert_eq!(Assets::total_supply(0), 100);
+
+			assert_noop!(Assets::set_balance(0, &2, 9), Error::<Test>::BalanceLow);
+			assert_eq!(Assets::increase_balance(0, &2, 10), Ok(10));
+			assert_noop!(Assets::decrease_balance(0, &2, 11), TokenError::NoFunds);
+			assert_eq!(Assets::decrease_balance(0, &2, 5), Ok(10));
+			assert_eq!(Asset::<Test>::get(0).unwrap().accounts, 1);
+
+			assert_ok!(<Assets as fungibles::MutateHold<u64>>::hold(0, &1, 5));
+			assert_noop!(Assets::set_balance(0, &1, 0), Error::<Test>::WouldDie);
+			// the account cannot be destroyed, so only down to the minimum balance is taken.
+			assert_eq!(Assets::decrease_balance_at_most(0, &1, 100), 90);
+			assert_eq!(<Assets as Inspect<u64>>::balance(0, &1), 10);
+			assert_eq!(Assets::increase_balance_at_most(0, &1, 5), 5);
+			assert_eq!(<Assets as Inspect<u64>>::balance(0, &1), 15);
+		});
+	}
+
+	#[test]
+	fn item_of_should_work() {
+		use fungible::{ItemOf, Inspect as _, Mutate as _, Transfer as _};
+		parameter_types! {
+			pub const AssetZero: u32 = 0;
+		}
+		type AssetZeroOf = ItemOf<Assets, AssetZero, u64>;
+
+		new_test_ext().execute_with(|| {
+			assert_ok!(Assets::force_create(Origin::root(), 0, 1, 10, 10));
+			assert_ok!(AssetZeroOf::mint_into(&1, 100));
+			assert_eq!(AssetZeroOf::balance(&1), 100);
+			assert_eq!(AssetZeroOf::minimum_balance(), 10);
+			assert_eq!(AssetZeroOf::transfer(&1, &2, 30, true), Ok(30));
+			assert_eq!(Assets::balance(0, 2), 30);
+			assert_eq!(AssetZeroOf::total_issuance(), 100);
+		});
+	}
+
+	#[test]
+	fn balance_conversion_should_work() {
+		use sp_runtime::traits::Identity;
+		type Conversion = BalanceToAssetBalance<Balances, Test, Identity>;
+
+		new_test_ext().execute_with(|| {
+			assert_eq!(Conversion::to_asset_balance(100, 0), Err(ConversionError::AssetMissing));
+			assert_ok!(Assets::force_create(Origin::root(), 0, 1, 10, 3));
+			// the existential deposit is 1, so every unit is worth 3 units of the asset.
+			assert_eq!(Conversion::to_asset_balance(100, 0), Ok(300));
+			assert_eq!(Conversion::to_asset_balance(0, 0), Ok(0));
+		});
+	}
+
+	#[test]
+	fn migrate_to_v1_works() {
+		use frame_support::{Blake2_128Concat, StorageHasher};
+
+		new_test_ext().execute_with(|| {
+			let key = [
+				Blake2_128Concat::hash(&0u32.encode()),
+				Blake2_128Concat::hash(&1u64.encode()),
+			].concat();
+			frame_support::migration::put_storage_value(
+				b"Assets",
+				b"Account",
+				&key,
+				(100u64, true, false),
+			);
+			assert_eq!(StorageVersion::get(), Releases::V0);
+
+			Assets::migrate_to_v1();
+
+			assert_eq!(StorageVersion::get(), Releases::V1);
+			assert_eq!(Account::<Test>::get(0, 1), AssetBalance {
+				balance: 100,
+				is_frozen: true,
+				is_zombie: false,
+				reserved: 0,
+			});
+		});
+	}
 }
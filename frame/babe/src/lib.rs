@@ -699,6 +699,10 @@ impl<T: Config> frame_support::traits::EstimateNextSessionRotation<T::BlockNumbe
 		Self::next_expected_epoch_change(now)
 	}
 
+	fn average_session_length() -> T::BlockNumber {
+		T::EpochDuration::get().saturated_into()
+	}
+
 	// The validity of this weight depends on the implementation of `estimate_next_session_rotation`
 	fn weight(_now: T::BlockNumber) -> Weight {
 		// Read: Current Slot, Epoch Index, Genesis Slot
@@ -81,6 +81,10 @@
 //! creates new funds (e.g. a reward) or destroys some funds (e.g. a system fee).
 //! - [`IsDeadAccount`](../frame_support/traits/trait.IsDeadAccount.html): Determiner to say whether a
 //! given account is unused.
+//! - [`fungible`](../frame_support/traits/tokens/fungible/index.html) traits `Inspect`, `Mutate`,
+//! `Transfer`, `Unbalanced`, `InspectHold` and `MutateHold`: Functions for dealing with a single
+//! fungible token class, where funds on hold are the reserved balance. Pallets which are generic
+//! over these may also be given an asset of a multi-asset pallet through `fungible::ItemOf`.
 //!
 //! ## Interface
 //!
@@ -166,6 +170,7 @@ use frame_support::{
 		WithdrawReasons, LockIdentifier, LockableCurrency, ExistenceRequirement,
 		Imbalance, SignedImbalance, ReservableCurrency, Get, ExistenceRequirement::KeepAlive,
 		ExistenceRequirement::AllowDeath, IsDeadAccount, BalanceStatus as Status,
+		tokens::{fungible, WithdrawConsequence, DepositConsequence},
 	}
 };
 use sp_runtime::{
@@ -1219,6 +1224,220 @@ impl<T: Config<I>, I: Instance> ReservableCurrency<T::AccountId> for Module<T, I
 	}
 }
 
+impl<T: Config<I>, I: Instance> fungible::Inspect<T::AccountId> for Module<T, I> {
+	type Balance = T::Balance;
+
+	fn total_issuance() -> Self::Balance {
+		TotalIssuance::<T, I>::get()
+	}
+
+	fn minimum_balance() -> Self::Balance {
+		T::ExistentialDeposit::get()
+	}
+
+	fn balance(who: &T::AccountId) -> Self::Balance {
+		Self::account(who).total()
+	}
+
+	fn reducible_balance(who: &T::AccountId, keep_alive: bool) -> Self::Balance {
+		let a = Self::account(who);
+		// Liquid balance is what is neither reserved nor locked.
+		let liquid = a.free.saturating_sub(a.fee_frozen.max(a.misc_frozen));
+		if system::Module::<T>::allow_death(who) && !keep_alive && a.reserved.is_zero() {
+			liquid
+		} else {
+			// `must_remain_to_exist` is the part of liquid balance which must remain to keep the
+			// total over ED.
+			let must_remain_to_exist = T::ExistentialDeposit::get()
+				.saturating_sub(a.total() - liquid);
+			liquid.saturating_sub(must_remain_to_exist)
+		}
+	}
+
+	fn can_deposit(who: &T::AccountId, amount: Self::Balance) -> DepositConsequence {
+		if amount.is_zero() { return DepositConsequence::Success }
+
+		if TotalIssuance::<T, I>::get().checked_add(&amount).is_none() {
+			return DepositConsequence::Overflow
+		}
+
+		match Self::account(who).total().checked_add(&amount) {
+			None => DepositConsequence::Overflow,
+			Some(new_total) if new_total < T::ExistentialDeposit::get() =>
+				DepositConsequence::BelowMinimum,
+			Some(_) => DepositConsequence::Success,
+		}
+	}
+
+	fn can_withdraw(
+		who: &T::AccountId,
+		amount: Self::Balance,
+	) -> WithdrawConsequence<Self::Balance> {
+		if amount.is_zero() { return WithdrawConsequence::Success }
+
+		if TotalIssuance::<T, I>::get().checked_sub(&amount).is_none() {
+			return WithdrawConsequence::Underflow
+		}
+
+		let account = Self::account(who);
+		let new_free = match account.free.checked_sub(&amount) {
+			Some(x) => x,
+			None => return WithdrawConsequence::NoFunds,
+		};
+
+		// Enough free funds must remain to satisfy any locks.
+		if new_free < account.frozen(Reasons::All) {
+			return WithdrawConsequence::Frozen
+		}
+
+		// The account would be reaped; this is only fine if it holds nothing else and nothing
+		// depends upon it.
+		if new_free.saturating_add(account.reserved) < T::ExistentialDeposit::get() {
+			if !account.reserved.is_zero() || !system::Module::<T>::allow_death(who) {
+				return WithdrawConsequence::WouldDie
+			}
+			return WithdrawConsequence::ReducedToZero(new_free)
+		}
+
+		WithdrawConsequence::Success
+	}
+}
+
+impl<T: Config<I>, I: Instance> fungible::Mutate<T::AccountId> for Module<T, I> {
+	fn mint_into(who: &T::AccountId, amount: Self::Balance) -> DispatchResult {
+		if amount.is_zero() { return Ok(()) }
+		<Self as fungible::Inspect<_>>::can_deposit(who, amount).into_result()?;
+		Self::try_mutate_account(who, |account, _| -> DispatchResult {
+			account.free = account.free.checked_add(&amount).ok_or(Error::<T, I>::Overflow)?;
+			Ok(())
+		})?;
+		TotalIssuance::<T, I>::mutate(|t| *t = t.saturating_add(amount));
+		Ok(())
+	}
+
+	fn burn_from(who: &T::AccountId, amount: Self::Balance) -> Result<Self::Balance, DispatchError> {
+		if amount.is_zero() { return Ok(Zero::zero()) }
+		let extra = <Self as fungible::Inspect<_>>::can_withdraw(who, amount).into_result()?;
+		let actual = amount.saturating_add(extra);
+		Self::try_mutate_account(who, |account, _| -> DispatchResult {
+			account.free = account.free.checked_sub(&actual)
+				.ok_or(Error::<T, I>::InsufficientBalance)?;
+			Ok(())
+		})?;
+		TotalIssuance::<T, I>::mutate(|t| *t = t.saturating_sub(actual));
+		Ok(actual)
+	}
+}
+
+impl<T: Config<I>, I: Instance> fungible::Transfer<T::AccountId> for Module<T, I> {
+	fn transfer(
+		source: &T::AccountId,
+		dest: &T::AccountId,
+		amount: T::Balance,
+		keep_alive: bool,
+	) -> Result<T::Balance, DispatchError> {
+		let er = if keep_alive { KeepAlive } else { AllowDeath };
+		<Self as Currency<T::AccountId>>::transfer(source, dest, amount, er).map(|_| amount)
+	}
+}
+
+impl<T: Config<I>, I: Instance> fungible::Unbalanced<T::AccountId> for Module<T, I> {
+	fn set_balance(who: &T::AccountId, amount: Self::Balance) -> DispatchResult {
+		let ed = T::ExistentialDeposit::get();
+		ensure!(amount.is_zero() || amount >= ed, Error::<T, I>::ExistentialDeposit);
+		ensure!(
+			!amount.is_zero() || system::Module::<T>::allow_death(who),
+			Error::<T, I>::KeepAlive,
+		);
+		Self::try_mutate_account(who, |account, _| -> DispatchResult {
+			// Funds on hold cannot be touched here.
+			account.free = amount.checked_sub(&account.reserved)
+				.ok_or(Error::<T, I>::InsufficientBalance)?;
+			Ok(())
+		})
+	}
+
+	fn set_total_issuance(amount: Self::Balance) {
+		TotalIssuance::<T, I>::mutate(|t| *t = amount);
+	}
+}
+
+impl<T: Config<I>, I: Instance> fungible::InspectHold<T::AccountId> for Module<T, I> {
+	fn balance_on_hold(who: &T::AccountId) -> T::Balance {
+		Self::account(who).reserved
+	}
+
+	fn can_hold(who: &T::AccountId, amount: T::Balance) -> bool {
+		<Self as ReservableCurrency<T::AccountId>>::can_reserve(who, amount)
+	}
+}
+
+impl<T: Config<I>, I: Instance> fungible::MutateHold<T::AccountId> for Module<T, I> {
+	fn hold(who: &T::AccountId, amount: Self::Balance) -> DispatchResult {
+		<Self as ReservableCurrency<T::AccountId>>::reserve(who, amount)
+	}
+
+	fn release(
+		who: &T::AccountId,
+		amount: Self::Balance,
+		best_effort: bool,
+	) -> Result<T::Balance, DispatchError> {
+		let actual = amount.min(Self::account(who).reserved);
+		ensure!(best_effort || actual == amount, Error::<T, I>::InsufficientBalance);
+		let leftover = <Self as ReservableCurrency<T::AccountId>>::unreserve(who, actual);
+		debug_assert!(leftover.is_zero(), "no more than what is reserved is unreserved; qed");
+		Ok(actual)
+	}
+
+	fn transfer_held(
+		source: &T::AccountId,
+		dest: &T::AccountId,
+		amount: Self::Balance,
+		best_effort: bool,
+		on_hold: bool,
+	) -> Result<Self::Balance, DispatchError> {
+		let status = if on_hold { Status::Reserved } else { Status::Free };
+		if source == dest {
+			return if on_hold {
+				let actual = amount.min(Self::account(source).reserved);
+				ensure!(best_effort || actual == amount, Error::<T, I>::InsufficientBalance);
+				Ok(actual)
+			} else {
+				<Self as fungible::MutateHold<_>>::release(source, amount, best_effort)
+			}
+		}
+
+		let actual = Self::try_mutate_account(dest, |to_account, is_new|
+			-> Result<T::Balance, DispatchError>
+		{
+			// Funds may only remain on hold in an account which already exists.
+			ensure!(!is_new || !on_hold, Error::<T, I>::DeadAccount);
+			Self::try_mutate_account(source, |from_account, _|
+				-> Result<T::Balance, DispatchError>
+			{
+				let actual = if best_effort { amount.min(from_account.reserved) } else { amount };
+				from_account.reserved = from_account.reserved.checked_sub(&actual)
+					.ok_or(Error::<T, I>::InsufficientBalance)?;
+				if on_hold {
+					to_account.reserved = to_account.reserved.checked_add(&actual)
+						.ok_or(Error::<T, I>::Overflow)?;
+				} else {
+					to_account.free = to_account.free.checked_add(&actual)
+						.ok_or(Error::<T, I>::Overflow)?;
+				}
+				ensure!(
+					to_account.total() >= T::ExistentialDeposit::get(),
+					Error::<T, I>::ExistentialDeposit,
+				);
+				Ok(actual)
+			})
+		})?;
+
+		Self::deposit_event(RawEvent::ReserveRepatriated(source.clone(), dest.clone(), actual, status));
+		Ok(actual)
+	}
+}
+
 /// Implement `OnKilledAccount` to remove the local account, if using local account storage.
 ///
 /// NOTE: You probably won't need to use this! This only needs to be "wired in" to System module
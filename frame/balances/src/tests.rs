@@ -796,5 +796,174 @@ macro_rules! decl_tests {
 					);
 				});
 		}
+
+		#[test]
+		fn fungible_inspect_should_work() {
+			use frame_support::traits::tokens::{fungible, DepositConsequence, WithdrawConsequence};
+			<$ext_builder>::default().existential_deposit(10).monied(true).build().execute_with(|| {
+				assert_eq!(<Balances as fungible::Inspect<_>>::minimum_balance(), 10);
+				assert_eq!(<Balances as fungible::Inspect<_>>::total_issuance(), 1100);
+				assert_eq!(<Balances as fungible::Inspect<_>>::balance(&1), 100);
+				assert_eq!(<Balances as fungible::Inspect<_>>::reducible_balance(&1, false), 100);
+				assert_eq!(<Balances as fungible::Inspect<_>>::reducible_balance(&1, true), 90);
+
+				assert_eq!(
+					<Balances as fungible::Inspect<_>>::can_deposit(&5, 9),
+					DepositConsequence::BelowMinimum,
+				);
+				assert_eq!(
+					<Balances as fungible::Inspect<_>>::can_deposit(&5, 10),
+					DepositConsequence::Success,
+				);
+				assert_eq!(
+					<Balances as fungible::Inspect<_>>::can_deposit(&1, u64::max_value()),
+					DepositConsequence::Overflow,
+				);
+
+				assert_eq!(
+					<Balances as fungible::Inspect<_>>::can_withdraw(&1, 90),
+					WithdrawConsequence::Success,
+				);
+				assert_eq!(
+					<Balances as fungible::Inspect<_>>::can_withdraw(&1, 91),
+					WithdrawConsequence::ReducedToZero(9),
+				);
+				assert_eq!(
+					<Balances as fungible::Inspect<_>>::can_withdraw(&1, 101),
+					WithdrawConsequence::NoFunds,
+				);
+
+				// funds on hold keep the account alive.
+				assert_ok!(Balances::reserve(&1, 50));
+				assert_eq!(<Balances as fungible::Inspect<_>>::balance(&1), 100);
+				assert_eq!(<Balances as fungible::Inspect<_>>::reducible_balance(&1, false), 50);
+				assert_eq!(
+					<Balances as fungible::Inspect<_>>::can_withdraw(&1, 50),
+					WithdrawConsequence::Success,
+				);
+
+				// locked funds may not be withdrawn.
+				Balances::set_lock(ID_1, &2, 150, WithdrawReasons::all());
+				assert_eq!(<Balances as fungible::Inspect<_>>::reducible_balance(&2, false), 50);
+				assert_eq!(
+					<Balances as fungible::Inspect<_>>::can_withdraw(&2, 60),
+					WithdrawConsequence::Frozen,
+				);
+			});
+		}
+
+		#[test]
+		fn fungible_mutate_should_work() {
+			use frame_support::traits::tokens::fungible;
+			<$ext_builder>::default().existential_deposit(10).monied(true).build().execute_with(|| {
+				assert_noop!(
+					<Balances as fungible::Mutate<_>>::mint_into(&5, 9),
+					sp_runtime::TokenError::BelowMinimum,
+				);
+				assert_ok!(<Balances as fungible::Mutate<_>>::mint_into(&5, 50));
+				assert_eq!(Balances::free_balance(5), 50);
+				assert_eq!(Balances::total_issuance(), 1150);
+
+				assert_noop!(
+					<Balances as fungible::Mutate<_>>::burn_from(&5, 51),
+					sp_runtime::TokenError::NoFunds,
+				);
+				// burning into the dust takes the rest too.
+				assert_eq!(<Balances as fungible::Mutate<_>>::burn_from(&5, 45), Ok(50));
+				assert_eq!(Balances::total_balance(&5), 0);
+				assert_eq!(Balances::total_issuance(), 1100);
+			});
+		}
+
+		#[test]
+		fn fungible_transfer_should_work() {
+			use frame_support::traits::tokens::fungible;
+			<$ext_builder>::default().existential_deposit(10).monied(true).build().execute_with(|| {
+				assert_noop!(
+					<Balances as fungible::Transfer<_>>::transfer(&1, &5, 95, true),
+					Error::<$test, _>::KeepAlive,
+				);
+				assert_eq!(<Balances as fungible::Transfer<_>>::transfer(&1, &5, 90, true), Ok(90));
+				assert_eq!(<Balances as fungible::Transfer<_>>::transfer(&1, &5, 10, false), Ok(10));
+				assert_eq!(Balances::total_balance(&1), 0);
+				assert_eq!(Balances::free_balance(5), 100);
+			});
+		}
+
+		#[test]
+		fn fungible_hold_should_work() {
+			use frame_support::traits::tokens::fungible;
+			<$ext_builder>::default().existential_deposit(10).monied(true).build().execute_with(|| {
+				assert_ok!(<Balances as fungible::MutateHold<_>>::hold(&1, 60));
+				assert_eq!(<Balances as fungible::InspectHold<_>>::balance_on_hold(&1), 60);
+				assert!(<Balances as fungible::InspectHold<_>>::can_hold(&1, 40));
+				assert!(!<Balances as fungible::InspectHold<_>>::can_hold(&1, 41));
+
+				assert_noop!(
+					<Balances as fungible::MutateHold<_>>::release(&1, 70, false),
+					Error::<$test, _>::InsufficientBalance,
+				);
+				assert_eq!(<Balances as fungible::MutateHold<_>>::release(&1, 70, true), Ok(60));
+				assert_eq!(Balances::reserved_balance(1), 0);
+				assert_eq!(Balances::free_balance(1), 100);
+			});
+		}
+
+		#[test]
+		fn fungible_transfer_held_should_work() {
+			use frame_support::traits::tokens::fungible;
+			<$ext_builder>::default().existential_deposit(10).monied(true).build().execute_with(|| {
+				assert_ok!(<Balances as fungible::MutateHold<_>>::hold(&1, 60));
+
+				// funds may only stay on hold in an existing account.
+				assert_noop!(
+					<Balances as fungible::MutateHold<_>>::transfer_held(&1, &5, 20, false, true),
+					Error::<$test, _>::DeadAccount,
+				);
+				assert_noop!(
+					<Balances as fungible::MutateHold<_>>::transfer_held(&1, &5, 5, false, false),
+					Error::<$test, _>::ExistentialDeposit,
+				);
+				assert_eq!(
+					<Balances as fungible::MutateHold<_>>::transfer_held(&1, &5, 20, false, false),
+					Ok(20),
+				);
+				assert_eq!(Balances::free_balance(5), 20);
+				assert_eq!(
+					<Balances as fungible::MutateHold<_>>::transfer_held(&1, &2, 100, true, true),
+					Ok(40),
+				);
+				assert_eq!(Balances::reserved_balance(1), 0);
+				assert_eq!(Balances::free_balance(1), 40);
+				assert_eq!(Balances::reserved_balance(2), 40);
+				assert_eq!(Balances::free_balance(2), 200);
+			});
+		}
+
+		#[test]
+		fn fungible_unbalanced_should_work() {
+			use frame_support::traits::tokens::fungible;
+			<$ext_builder>::default().existential_deposit(10).monied(true).build().execute_with(|| {
+				assert_noop!(
+					<Balances as fungible::Unbalanced<_>>::set_balance(&1, 5),
+					Error::<$test, _>::ExistentialDeposit,
+				);
+				assert_ok!(<Balances as fungible::Unbalanced<_>>::set_balance(&1, 50));
+				assert_eq!(Balances::free_balance(1), 50);
+				// the total issuance is left untouched.
+				assert_eq!(Balances::total_issuance(), 1100);
+				<Balances as fungible::Unbalanced<_>>::set_total_issuance(1050);
+				assert_eq!(Balances::total_issuance(), 1050);
+
+				assert_ok!(Balances::reserve(&1, 20));
+				assert_noop!(
+					<Balances as fungible::Unbalanced<_>>::set_balance(&1, 10),
+					Error::<$test, _>::InsufficientBalance,
+				);
+				assert_eq!(<Balances as fungible::Unbalanced<_>>::increase_balance(&1, 10), Ok(10));
+				assert_eq!(Balances::free_balance(1), 40);
+				assert_eq!(Balances::reserved_balance(1), 20);
+			});
+		}
 	}
 }
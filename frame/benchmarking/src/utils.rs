@@ -127,6 +127,11 @@ pub trait Benchmarking {
 		self.reset_read_write_count()
 	}
 
+	/// Get the (read, write) count of each storage transaction layer.
+	fn storage_layer_read_write_count(&self) -> Vec<(u32, u32)> {
+		self.storage_layer_read_write_count()
+	}
+
 	/// Get the DB whitelist.
 	fn get_whitelist(&self) -> Vec<TrackedStorageKey> {
 		self.get_whitelist()
@@ -77,6 +77,53 @@ fn create_bounty<T: Config>() -> Result<(
 	Ok((curator_lookup, bounty_id))
 }
 
+// The value of a child bounty of the bounty `bounty_id`.
+fn child_bounty_value<T: Config>(bounty_id: BountyIndex) -> BalanceOf<T> {
+	Bounties::<T>::bounties(bounty_id).map_or_else(Zero::zero, |bounty| bounty.value / 4u32.into())
+}
+
+// Add a child bounty with a description of `d` bytes to the active bounty `bounty_id`.
+fn create_child_bounty<T: Config>(
+	curator: &T::AccountId,
+	bounty_id: BountyIndex,
+	d: u32,
+) -> Result<BountyIndex, &'static str> {
+	let value = child_bounty_value::<T>(bounty_id);
+	let description = vec![0; d as usize];
+	Bounties::<T>::propose_child_bounty(
+		RawOrigin::Signed(curator.clone()).into(),
+		bounty_id,
+		value,
+		description,
+	)?;
+	Ok(ChildBountyCount::get() - 1)
+}
+
+// Assign a curator to the child bounty `child_bounty_id` and have them accept it.
+fn create_child_curator<T: Config>(
+	curator: &T::AccountId,
+	bounty_id: BountyIndex,
+	child_bounty_id: BountyIndex,
+) -> Result<T::AccountId, &'static str> {
+	let child_curator: T::AccountId = account("child_curator", 0, SEED);
+	let fee = child_bounty_value::<T>(bounty_id) / 2u32.into();
+	let _ = T::Currency::make_free_balance_be(&child_curator, fee);
+	let child_curator_lookup = T::Lookup::unlookup(child_curator.clone());
+	Bounties::<T>::propose_child_curator(
+		RawOrigin::Signed(curator.clone()).into(),
+		bounty_id,
+		child_bounty_id,
+		child_curator_lookup,
+		fee,
+	)?;
+	Bounties::<T>::accept_child_curator(
+		RawOrigin::Signed(child_curator.clone()).into(),
+		bounty_id,
+		child_bounty_id,
+	)?;
+	Ok(child_curator)
+}
+
 fn setup_pod_account<T: Config>() {
 	let pot_account = Bounties::<T>::account_id();
 	let value = T::Currency::minimum_balance().saturating_mul(1_000_000_000u32.into());
@@ -220,6 +267,94 @@ benchmarks! {
 		ensure!(missed_any == false, "Missed some");
 		assert_last_event::<T>(RawEvent::BountyBecameActive(b - 1).into())
 	}
+
+	propose_child_bounty {
+		let d in 0 .. MAX_BYTES;
+		setup_pod_account::<T>();
+		let (curator_lookup, bounty_id) = create_bounty::<T>()?;
+		let curator = T::Lookup::lookup(curator_lookup)?;
+		let value = child_bounty_value::<T>(bounty_id);
+		let description = vec![0; d as usize];
+	}: _(RawOrigin::Signed(curator), bounty_id, value, description)
+	verify {
+		assert_last_event::<T>(RawEvent::ChildBountyAdded(bounty_id, ChildBountyCount::get() - 1).into())
+	}
+
+	propose_child_curator {
+		setup_pod_account::<T>();
+		let (curator_lookup, bounty_id) = create_bounty::<T>()?;
+		let curator = T::Lookup::lookup(curator_lookup)?;
+		let child_bounty_id = create_child_bounty::<T>(&curator, bounty_id, MAX_BYTES)?;
+		let child_curator = T::Lookup::unlookup(account("child_curator", 0, SEED));
+		let fee = child_bounty_value::<T>(bounty_id) / 2u32.into();
+	}: _(RawOrigin::Signed(curator), bounty_id, child_bounty_id, child_curator, fee)
+
+	accept_child_curator {
+		setup_pod_account::<T>();
+		let (curator_lookup, bounty_id) = create_bounty::<T>()?;
+		let curator = T::Lookup::lookup(curator_lookup)?;
+		let child_bounty_id = create_child_bounty::<T>(&curator, bounty_id, MAX_BYTES)?;
+		let child_curator: T::AccountId = account("child_curator", 0, SEED);
+		let fee = child_bounty_value::<T>(bounty_id) / 2u32.into();
+		let _ = T::Currency::make_free_balance_be(&child_curator, fee);
+		Bounties::<T>::propose_child_curator(
+			RawOrigin::Signed(curator).into(),
+			bounty_id,
+			child_bounty_id,
+			T::Lookup::unlookup(child_curator.clone()),
+			fee,
+		)?;
+	}: _(RawOrigin::Signed(child_curator), bounty_id, child_bounty_id)
+
+	award_child_bounty {
+		setup_pod_account::<T>();
+		let (curator_lookup, bounty_id) = create_bounty::<T>()?;
+		let curator = T::Lookup::lookup(curator_lookup)?;
+		let child_bounty_id = create_child_bounty::<T>(&curator, bounty_id, MAX_BYTES)?;
+		let child_curator = create_child_curator::<T>(&curator, bounty_id, child_bounty_id)?;
+		let beneficiary_account: T::AccountId = account("beneficiary", 0, SEED);
+		let beneficiary = T::Lookup::unlookup(beneficiary_account.clone());
+	}: _(RawOrigin::Signed(child_curator), bounty_id, child_bounty_id, beneficiary)
+	verify {
+		assert_last_event::<T>(
+			RawEvent::ChildBountyAwarded(bounty_id, child_bounty_id, beneficiary_account).into()
+		)
+	}
+
+	claim_child_bounty {
+		setup_pod_account::<T>();
+		let (curator_lookup, bounty_id) = create_bounty::<T>()?;
+		let curator = T::Lookup::lookup(curator_lookup)?;
+		let child_bounty_id = create_child_bounty::<T>(&curator, bounty_id, MAX_BYTES)?;
+		let child_curator = create_child_curator::<T>(&curator, bounty_id, child_bounty_id)?;
+
+		let beneficiary_account: T::AccountId = account("beneficiary", 0, SEED);
+		let beneficiary = T::Lookup::unlookup(beneficiary_account.clone());
+		Bounties::<T>::award_child_bounty(
+			RawOrigin::Signed(child_curator.clone()).into(),
+			bounty_id,
+			child_bounty_id,
+			beneficiary,
+		)?;
+
+		frame_system::Module::<T>::set_block_number(T::BountyDepositPayoutDelay::get());
+		ensure!(T::Currency::free_balance(&beneficiary_account).is_zero(), "Beneficiary already has balance");
+
+	}: _(RawOrigin::Signed(child_curator), bounty_id, child_bounty_id)
+	verify {
+		ensure!(!T::Currency::free_balance(&beneficiary_account).is_zero(), "Beneficiary didn't get paid");
+	}
+
+	close_child_bounty {
+		setup_pod_account::<T>();
+		let (curator_lookup, bounty_id) = create_bounty::<T>()?;
+		let curator = T::Lookup::lookup(curator_lookup)?;
+		let child_bounty_id = create_child_bounty::<T>(&curator, bounty_id, MAX_BYTES)?;
+		create_child_curator::<T>(&curator, bounty_id, child_bounty_id)?;
+	}: _(RawOrigin::Signed(curator), bounty_id, child_bounty_id)
+	verify {
+		assert_last_event::<T>(RawEvent::ChildBountyCanceled(bounty_id, child_bounty_id).into())
+	}
 }
 
 #[cfg(test)]
@@ -242,6 +377,12 @@ mod tests {
 			assert_ok!(test_benchmark_close_bounty_active::<Test>());
 			assert_ok!(test_benchmark_extend_bounty_expiry::<Test>());
 			assert_ok!(test_benchmark_spend_funds::<Test>());
+			assert_ok!(test_benchmark_propose_child_bounty::<Test>());
+			assert_ok!(test_benchmark_propose_child_curator::<Test>());
+			assert_ok!(test_benchmark_accept_child_curator::<Test>());
+			assert_ok!(test_benchmark_award_child_bounty::<Test>());
+			assert_ok!(test_benchmark_claim_child_bounty::<Test>());
+			assert_ok!(test_benchmark_close_child_bounty::<Test>());
 		});
 	}
 }
@@ -35,6 +35,13 @@
 //! curator or once the bounty is active or payout is pending, resulting in the slash of the
 //! curator's deposit.
 //!
+//! ## Child Bounty
+//!
+//! A large bounty can be split into smaller tasks by the curator of the active bounty, the parent
+//! bounty. Each child bounty is funded from the account of the parent bounty, has a curator and a
+//! fee of its own, and follows the same award and claim lifecycle as a bounty. The parent bounty
+//! cannot be awarded or closed until all of its child bounties have been claimed or closed.
+//!
 //!
 //! ### Terminology
 //!
@@ -55,6 +62,11 @@
 //!   claiming.
 //! - **Curator fee:** The reserved upfront payment for a curator for work related to the bounty.
 //!
+//! Child Bounty:
+//! - **Parent bounty:** The active bounty from whose account a child bounty is funded.
+//! - **Child bounty curator:** An account proposed by the curator of the parent bounty to manage a
+//!   child bounty, in exchange of a deposit.
+//!
 //! ## Interface
 //!
 //! ### Dispatchable Functions
@@ -71,6 +83,14 @@
 //! - `claim_bounty` - Claim a specific bounty amount from the Payout Address.
 //! - `unassign_curator` - Unassign an accepted curator from a specific earmark.
 //! - `close_bounty` - Cancel the earmark for a specific treasury amount and close the bounty.
+//!
+//! Child bounty protocol:
+//! - `propose_child_bounty` - Earmark part of the funds of an active bounty for a child bounty.
+//! - `propose_child_curator` - Assign an account to a child bounty as candidate curator.
+//! - `accept_child_curator` - Accept a child bounty assignment, setting a curator deposit.
+//! - `award_child_bounty` - Close and pay out the child bounty for the completed work.
+//! - `claim_child_bounty` - Claim a child bounty payout from the Payout Address.
+//! - `close_child_bounty` - Cancel a child bounty and return its funds to the parent bounty.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
@@ -86,7 +106,7 @@ use frame_support::traits::{
 	Currency, Get, Imbalance, OnUnbalanced, ExistenceRequirement::{AllowDeath},
 	ReservableCurrency};
 
-use sp_runtime::{Permill, RuntimeDebug, DispatchResult, traits::{
+use sp_runtime::{Permill, RuntimeDebug, DispatchResult, DispatchError, traits::{
 	Zero, StaticLookup, AccountIdConversion, Saturating, BadOrigin
 }};
 
@@ -129,6 +149,12 @@ pub trait Config: frame_system::Config + pallet_treasury::Config {
 	/// Maximum acceptable reason length.
 	type MaximumReasonLength: Get<u32>;
 
+	/// Maximum number of child bounties that a bounty can have at the same time.
+	type MaxActiveChildBountyCount: Get<u32>;
+
+	/// Minimum value for a child bounty.
+	type ChildBountyValueMinimum: Get<BalanceOf<Self>>;
+
 	/// Weight information for extrinsics in this pallet.
 	type WeightInfo: WeightInfo;
 }
@@ -185,6 +211,48 @@ pub enum BountyStatus<AccountId, BlockNumber> {
 	},
 }
 
+/// A child bounty, funded from the account of its parent bounty.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct ChildBounty<AccountId, Balance, BlockNumber> {
+	/// The parent of this child bounty.
+	parent_bounty: BountyIndex,
+	/// The (total) amount that should be paid if the child bounty is rewarded.
+	value: Balance,
+	/// The curator fee. Included in value.
+	fee: Balance,
+	/// The deposit of curator.
+	curator_deposit: Balance,
+	/// The status of this child bounty.
+	status: ChildBountyStatus<AccountId, BlockNumber>,
+}
+
+/// The status of a child bounty.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub enum ChildBountyStatus<AccountId, BlockNumber> {
+	/// The child bounty is funded and waiting for curator assignment.
+	Added,
+	/// A curator has been proposed by the curator of the parent bounty. Waiting for acceptance
+	/// from the child bounty curator.
+	CuratorProposed {
+		/// The assigned curator of this child bounty.
+		curator: AccountId,
+	},
+	/// The child bounty is active and waiting to be awarded.
+	Active {
+		/// The curator of this child bounty.
+		curator: AccountId,
+	},
+	/// The child bounty is awarded and waiting to released after a delay.
+	PendingPayout {
+		/// The curator of this child bounty.
+		curator: AccountId,
+		/// The beneficiary of the child bounty.
+		beneficiary: AccountId,
+		/// When the child bounty can be claimed.
+		unlock_at: BlockNumber,
+	},
+}
+
 // Note :: For backward compatability reasons,
 // pallet-bounties uses Treasury for storage.
 // This is temporary solution, soon will get replaced with
@@ -205,6 +273,22 @@ decl_storage! {
 
 		/// Bounty indices that have been approved but not yet funded.
 		pub BountyApprovals get(fn bounty_approvals): Vec<BountyIndex>;
+
+		/// Number of child bounties that have been made.
+		pub ChildBountyCount get(fn child_bounty_count): BountyIndex;
+
+		/// Child bounties that have been added, by parent bounty and child bounty index.
+		pub ChildBounties get(fn child_bounties):
+		double_map hasher(twox_64_concat) BountyIndex, hasher(twox_64_concat) BountyIndex
+		=> Option<ChildBounty<T::AccountId, BalanceOf<T>, T::BlockNumber>>;
+
+		/// The description of each child bounty.
+		pub ChildBountyDescriptions get(fn child_bounty_descriptions):
+		map hasher(twox_64_concat) BountyIndex => Option<Vec<u8>>;
+
+		/// Number of child bounties of each bounty which are yet to be claimed or closed.
+		pub ParentChildBounties get(fn parent_child_bounties):
+		map hasher(twox_64_concat) BountyIndex => u32;
 	}
 }
 
@@ -228,6 +312,15 @@ decl_event!(
 		BountyCanceled(BountyIndex),
 		/// A bounty expiry is extended. \[index\]
 		BountyExtended(BountyIndex),
+		/// A child bounty is added. \[parent index, child index\]
+		ChildBountyAdded(BountyIndex, BountyIndex),
+		/// A child bounty is awarded to a beneficiary. \[parent index, child index, beneficiary\]
+		ChildBountyAwarded(BountyIndex, BountyIndex, AccountId),
+		/// A child bounty is claimed by beneficiary.
+		/// \[parent index, child index, payout, beneficiary\]
+		ChildBountyClaimed(BountyIndex, BountyIndex, Balance, AccountId),
+		/// A child bounty is cancelled. \[parent index, child index\]
+		ChildBountyCanceled(BountyIndex, BountyIndex),
 	}
 );
 
@@ -253,6 +346,13 @@ decl_error! {
 		PendingPayout,
 		/// The bounties cannot be claimed/closed because it's still in the countdown period.
 		Premature,
+		/// The bounty cannot be awarded or closed because it has child bounties which are yet to
+		/// be claimed or closed.
+		HasActiveChildBounty,
+		/// The bounty already has the maximum number of active child bounties.
+		TooManyChildBounties,
+		/// The bounty account does not hold enough funds for the child bounty and the curator fee.
+		InsufficientBountyBalance,
 	}
 }
 
@@ -279,6 +379,12 @@ decl_module! {
 		/// Maximum acceptable reason length.
 		const MaximumReasonLength: u32 = T::MaximumReasonLength::get();
 
+		/// Maximum number of child bounties that a bounty can have at the same time.
+		const MaxActiveChildBountyCount: u32 = T::MaxActiveChildBountyCount::get();
+
+		/// Minimum value for a child bounty.
+		const ChildBountyValueMinimum: BalanceOf<T> = T::ChildBountyValueMinimum::get();
+
 		type Error = Error<T>;
 
 		fn deposit_event() = default;
@@ -488,7 +594,8 @@ decl_module! {
 
 		/// Award bounty to a beneficiary account. The beneficiary will be able to claim the funds after a delay.
 		///
-		/// The dispatch origin for this call must be the curator of this bounty.
+		/// The dispatch origin for this call must be the curator of this bounty. All the child
+		/// bounties of this bounty must have been claimed or closed.
 		///
 		/// - `bounty_id`: Bounty ID to award.
 		/// - `beneficiary`: The beneficiary account whom will receive the payout.
@@ -500,6 +607,7 @@ decl_module! {
 		fn award_bounty(origin, #[compact] bounty_id: BountyIndex, beneficiary: <T::Lookup as StaticLookup>::Source) {
 			let signer = ensure_signed(origin)?;
 			let beneficiary = T::Lookup::lookup(beneficiary)?;
+			ensure!(Self::parent_child_bounties(bounty_id) == 0, Error::<T>::HasActiveChildBounty);
 
 			Bounties::<T>::try_mutate_exists(bounty_id, |maybe_bounty| -> DispatchResult {
 				let mut bounty = maybe_bounty.as_mut().ok_or(Error::<T>::InvalidIndex)?;
@@ -563,7 +671,8 @@ decl_module! {
 		/// Cancel a proposed or active bounty. All the funds will be sent to treasury and
 		/// the curator deposit will be unreserved if possible.
 		///
-		/// Only `T::RejectOrigin` is able to cancel a bounty.
+		/// Only `T::RejectOrigin` is able to cancel a bounty, and only once all of its child
+		/// bounties have been claimed or closed.
 		///
 		/// - `bounty_id`: Bounty ID to cancel.
 		///
@@ -573,6 +682,7 @@ decl_module! {
 		#[weight = <T as Config>::WeightInfo::close_bounty_proposed().max(<T as Config>::WeightInfo::close_bounty_active())]
 		fn close_bounty(origin, #[compact] bounty_id: BountyIndex) -> DispatchResultWithPostInfo {
 			T::RejectOrigin::ensure_origin(origin)?;
+			ensure!(Self::parent_child_bounties(bounty_id) == 0, Error::<T>::HasActiveChildBounty);
 
 			Bounties::<T>::try_mutate_exists(bounty_id, |maybe_bounty| -> DispatchResultWithPostInfo {
 				let bounty = maybe_bounty.as_ref().ok_or(Error::<T>::InvalidIndex)?;
@@ -656,6 +766,321 @@ decl_module! {
 
 			Self::deposit_event(Event::<T>::BountyExtended(bounty_id));
 		}
+
+		/// Add a new child bounty, funded with `value` from the account of the parent bounty.
+		///
+		/// The dispatch origin for this call must be the curator of the parent bounty, which must
+		/// be active. The parent bounty account must keep enough funds to pay the curator fee.
+		///
+		/// - `parent_bounty_id`: Index of the parent bounty.
+		/// - `value`: The total payment amount of this child bounty, curator fee included.
+		/// - `description`: The description of this child bounty.
+		///
+		/// # <weight>
+		/// - O(1).
+		/// # </weight>
+		#[weight = <T as Config>::WeightInfo::propose_child_bounty(description.len() as u32)]
+		fn propose_child_bounty(
+			origin,
+			#[compact] parent_bounty_id: BountyIndex,
+			#[compact] value: BalanceOf<T>,
+			description: Vec<u8>,
+		) {
+			let signer = ensure_signed(origin)?;
+			ensure!(description.len() <= T::MaximumReasonLength::get() as usize, Error::<T>::ReasonTooBig);
+			ensure!(value >= T::ChildBountyValueMinimum::get(), Error::<T>::InvalidValue);
+			ensure!(
+				Self::parent_child_bounties(parent_bounty_id) < T::MaxActiveChildBountyCount::get(),
+				Error::<T>::TooManyChildBounties,
+			);
+
+			let parent_bounty = Self::ensure_bounty_curator(parent_bounty_id, &signer)?;
+			let parent_bounty_account = Self::bounty_account_id(parent_bounty_id);
+			let balance = T::Currency::free_balance(&parent_bounty_account);
+			ensure!(
+				balance.saturating_sub(value) >= parent_bounty.fee,
+				Error::<T>::InsufficientBountyBalance,
+			);
+
+			let index = Self::child_bounty_count();
+			T::Currency::transfer(
+				&parent_bounty_account,
+				&Self::child_bounty_account_id(index),
+				value,
+				AllowDeath,
+			)?;
+
+			ChildBountyCount::put(index + 1);
+			ParentChildBounties::mutate(parent_bounty_id, |count| *count += 1);
+
+			let child_bounty = ChildBounty {
+				parent_bounty: parent_bounty_id,
+				value,
+				fee: 0u32.into(),
+				curator_deposit: 0u32.into(),
+				status: ChildBountyStatus::Added,
+			};
+			ChildBounties::<T>::insert(parent_bounty_id, index, &child_bounty);
+			ChildBountyDescriptions::insert(index, description);
+
+			Self::deposit_event(Event::<T>::ChildBountyAdded(parent_bounty_id, index));
+		}
+
+		/// Assign a curator to a child bounty.
+		///
+		/// The dispatch origin for this call must be the curator of the parent bounty.
+		///
+		/// - `parent_bounty_id`: Index of the parent bounty.
+		/// - `child_bounty_id`: Index of the child bounty.
+		/// - `curator`: The curator account whom will manage this child bounty.
+		/// - `fee`: The curator fee.
+		///
+		/// # <weight>
+		/// - O(1).
+		/// # </weight>
+		#[weight = <T as Config>::WeightInfo::propose_child_curator()]
+		fn propose_child_curator(
+			origin,
+			#[compact] parent_bounty_id: BountyIndex,
+			#[compact] child_bounty_id: BountyIndex,
+			curator: <T::Lookup as StaticLookup>::Source,
+			#[compact] fee: BalanceOf<T>,
+		) {
+			let signer = ensure_signed(origin)?;
+			let curator = T::Lookup::lookup(curator)?;
+			Self::ensure_bounty_curator(parent_bounty_id, &signer)?;
+
+			ChildBounties::<T>::try_mutate_exists(
+				parent_bounty_id,
+				child_bounty_id,
+				|maybe_child_bounty| -> DispatchResult {
+					let child_bounty = maybe_child_bounty.as_mut().ok_or(Error::<T>::InvalidIndex)?;
+					match child_bounty.status {
+						ChildBountyStatus::Added | ChildBountyStatus::CuratorProposed { .. } => {},
+						_ => return Err(Error::<T>::UnexpectedStatus.into()),
+					};
+
+					ensure!(fee < child_bounty.value, Error::<T>::InvalidFee);
+
+					child_bounty.status = ChildBountyStatus::CuratorProposed { curator };
+					child_bounty.fee = fee;
+
+					Ok(())
+				},
+			)?;
+		}
+
+		/// Accept the curator role for a child bounty.
+		/// A deposit will be reserved from curator and refund upon successful payout.
+		///
+		/// May only be called from the proposed curator of the child bounty.
+		///
+		/// - `parent_bounty_id`: Index of the parent bounty.
+		/// - `child_bounty_id`: Index of the child bounty.
+		///
+		/// # <weight>
+		/// - O(1).
+		/// # </weight>
+		#[weight = <T as Config>::WeightInfo::accept_child_curator()]
+		fn accept_child_curator(
+			origin,
+			#[compact] parent_bounty_id: BountyIndex,
+			#[compact] child_bounty_id: BountyIndex,
+		) {
+			let signer = ensure_signed(origin)?;
+
+			ChildBounties::<T>::try_mutate_exists(
+				parent_bounty_id,
+				child_bounty_id,
+				|maybe_child_bounty| -> DispatchResult {
+					let child_bounty = maybe_child_bounty.as_mut().ok_or(Error::<T>::InvalidIndex)?;
+
+					match child_bounty.status {
+						ChildBountyStatus::CuratorProposed { ref curator } => {
+							ensure!(signer == *curator, Error::<T>::RequireCurator);
+
+							let deposit = T::BountyCuratorDeposit::get() * child_bounty.fee;
+							T::Currency::reserve(curator, deposit)?;
+							child_bounty.curator_deposit = deposit;
+							child_bounty.status = ChildBountyStatus::Active { curator: curator.clone() };
+
+							Ok(())
+						},
+						_ => Err(Error::<T>::UnexpectedStatus.into()),
+					}
+				},
+			)?;
+		}
+
+		/// Award child bounty to a beneficiary account. The beneficiary will be able to claim the
+		/// funds after a delay.
+		///
+		/// The dispatch origin for this call must be the curator of this child bounty.
+		///
+		/// - `parent_bounty_id`: Index of the parent bounty.
+		/// - `child_bounty_id`: Index of the child bounty to award.
+		/// - `beneficiary`: The beneficiary account whom will receive the payout.
+		///
+		/// # <weight>
+		/// - O(1).
+		/// # </weight>
+		#[weight = <T as Config>::WeightInfo::award_child_bounty()]
+		fn award_child_bounty(
+			origin,
+			#[compact] parent_bounty_id: BountyIndex,
+			#[compact] child_bounty_id: BountyIndex,
+			beneficiary: <T::Lookup as StaticLookup>::Source,
+		) {
+			let signer = ensure_signed(origin)?;
+			let beneficiary = T::Lookup::lookup(beneficiary)?;
+
+			ChildBounties::<T>::try_mutate_exists(
+				parent_bounty_id,
+				child_bounty_id,
+				|maybe_child_bounty| -> DispatchResult {
+					let child_bounty = maybe_child_bounty.as_mut().ok_or(Error::<T>::InvalidIndex)?;
+					match &child_bounty.status {
+						ChildBountyStatus::Active { curator } => {
+							ensure!(signer == *curator, Error::<T>::RequireCurator);
+						},
+						_ => return Err(Error::<T>::UnexpectedStatus.into()),
+					}
+					child_bounty.status = ChildBountyStatus::PendingPayout {
+						curator: signer,
+						beneficiary: beneficiary.clone(),
+						unlock_at: system::Module::<T>::block_number() + T::BountyDepositPayoutDelay::get(),
+					};
+
+					Ok(())
+				},
+			)?;
+
+			Self::deposit_event(
+				Event::<T>::ChildBountyAwarded(parent_bounty_id, child_bounty_id, beneficiary)
+			);
+		}
+
+		/// Claim the payout from an awarded child bounty after payout delay.
+		///
+		/// The dispatch origin for this call must be _Signed_.
+		///
+		/// - `parent_bounty_id`: Index of the parent bounty.
+		/// - `child_bounty_id`: Index of the child bounty to claim.
+		///
+		/// # <weight>
+		/// - O(1).
+		/// # </weight>
+		#[weight = <T as Config>::WeightInfo::claim_child_bounty()]
+		fn claim_child_bounty(
+			origin,
+			#[compact] parent_bounty_id: BountyIndex,
+			#[compact] child_bounty_id: BountyIndex,
+		) {
+			let _ = ensure_signed(origin)?; // anyone can trigger claim
+
+			ChildBounties::<T>::try_mutate_exists(
+				parent_bounty_id,
+				child_bounty_id,
+				|maybe_child_bounty| -> DispatchResult {
+					let child_bounty = maybe_child_bounty.take().ok_or(Error::<T>::InvalidIndex)?;
+					let (curator, beneficiary, unlock_at) = match child_bounty.status {
+						ChildBountyStatus::PendingPayout { curator, beneficiary, unlock_at } =>
+							(curator, beneficiary, unlock_at),
+						_ => return Err(Error::<T>::UnexpectedStatus.into()),
+					};
+					ensure!(system::Module::<T>::block_number() >= unlock_at, Error::<T>::Premature);
+
+					let child_bounty_account = Self::child_bounty_account_id(child_bounty_id);
+					let balance = T::Currency::free_balance(&child_bounty_account);
+					let fee = child_bounty.fee.min(balance); // just to be safe
+					let payout = balance.saturating_sub(fee);
+					let _ = T::Currency::unreserve(&curator, child_bounty.curator_deposit);
+					// should not fail
+					let _ = T::Currency::transfer(&child_bounty_account, &curator, fee, AllowDeath);
+					let _ = T::Currency::transfer(&child_bounty_account, &beneficiary, payout, AllowDeath);
+					*maybe_child_bounty = None;
+
+					ChildBountyDescriptions::remove(child_bounty_id);
+					ParentChildBounties::mutate(parent_bounty_id, |count| *count = count.saturating_sub(1));
+
+					Self::deposit_event(Event::<T>::ChildBountyClaimed(
+						parent_bounty_id,
+						child_bounty_id,
+						payout,
+						beneficiary,
+					));
+					Ok(())
+				},
+			)?;
+		}
+
+		/// Cancel a child bounty which is not pending payout. All the funds will be sent back to
+		/// the parent bounty and the curator deposit will be unreserved if possible.
+		///
+		/// The dispatch origin for this call must be either `T::RejectOrigin` or the curator of the
+		/// parent bounty.
+		///
+		/// - `parent_bounty_id`: Index of the parent bounty.
+		/// - `child_bounty_id`: Index of the child bounty to cancel.
+		///
+		/// # <weight>
+		/// - O(1).
+		/// # </weight>
+		#[weight = <T as Config>::WeightInfo::close_child_bounty()]
+		fn close_child_bounty(
+			origin,
+			#[compact] parent_bounty_id: BountyIndex,
+			#[compact] child_bounty_id: BountyIndex,
+		) {
+			let maybe_sender = ensure_signed(origin.clone())
+				.map(Some)
+				.or_else(|_| T::RejectOrigin::ensure_origin(origin).map(|_| None))?;
+			if let Some(sender) = maybe_sender {
+				Self::ensure_bounty_curator(parent_bounty_id, &sender)?;
+			}
+
+			ChildBounties::<T>::try_mutate_exists(
+				parent_bounty_id,
+				child_bounty_id,
+				|maybe_child_bounty| -> DispatchResult {
+					let child_bounty = maybe_child_bounty.as_ref().ok_or(Error::<T>::InvalidIndex)?;
+
+					match &child_bounty.status {
+						ChildBountyStatus::Added |
+						ChildBountyStatus::CuratorProposed { .. } => {
+							// Nothing extra to do besides the removal of the child bounty below.
+						},
+						ChildBountyStatus::Active { curator } => {
+							// Cancelled by the parent curator or council, refund deposit of the
+							// working curator.
+							let _ = T::Currency::unreserve(&curator, child_bounty.curator_deposit);
+							// Then execute removal of the child bounty below.
+						},
+						ChildBountyStatus::PendingPayout { .. } => {
+							// Child bounty is already pending payout, there is nothing left to
+							// cancel.
+							return Err(Error::<T>::PendingPayout.into())
+						},
+					}
+
+					let child_bounty_account = Self::child_bounty_account_id(child_bounty_id);
+					let balance = T::Currency::free_balance(&child_bounty_account);
+					let _ = T::Currency::transfer(
+						&child_bounty_account,
+						&Self::bounty_account_id(parent_bounty_id),
+						balance,
+						AllowDeath,
+					); // should not fail
+					*maybe_child_bounty = None;
+
+					ChildBountyDescriptions::remove(child_bounty_id);
+					ParentChildBounties::mutate(parent_bounty_id, |count| *count = count.saturating_sub(1));
+
+					Self::deposit_event(Event::<T>::ChildBountyCanceled(parent_bounty_id, child_bounty_id));
+					Ok(())
+				},
+			)?;
+		}
 	}
 }
 
@@ -677,6 +1102,28 @@ impl<T: Config> Module<T> {
 		T::ModuleId::get().into_sub_account(("bt", id))
 	}
 
+	/// The account ID of a child bounty account
+	pub fn child_bounty_account_id(id: BountyIndex) -> T::AccountId {
+		// only use two byte prefix to support 16 byte account id (used by test)
+		// "modl" ++ "py/trsry" ++ "cb" is 14 bytes, and two bytes remaining for child bounty index
+		T::ModuleId::get().into_sub_account(("cb", id))
+	}
+
+	/// Ensure `who` is the curator of the active bounty `bounty_id` and return the bounty.
+	fn ensure_bounty_curator(
+		bounty_id: BountyIndex,
+		who: &T::AccountId,
+	) -> Result<Bounty<T::AccountId, BalanceOf<T>, T::BlockNumber>, DispatchError> {
+		let bounty = Self::bounties(bounty_id).ok_or(Error::<T>::InvalidIndex)?;
+		match bounty.status {
+			BountyStatus::Active { ref curator, .. } => {
+				ensure!(curator == who, Error::<T>::RequireCurator);
+			},
+			_ => return Err(Error::<T>::UnexpectedStatus.into()),
+		}
+		Ok(bounty)
+	}
+
 	fn create_bounty(
 		proposer: T::AccountId,
 		description: Vec<u8>,
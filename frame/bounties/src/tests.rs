@@ -129,6 +129,8 @@ parameter_types! {
 	pub const BountyCuratorDeposit: Permill = Permill::from_percent(50);
 	pub const BountyValueMinimum: u64 = 1;
 	pub const MaximumReasonLength: u32 = 16384;
+	pub const MaxActiveChildBountyCount: u32 = 2;
+	pub const ChildBountyValueMinimum: u64 = 2;
 }
 impl Config for Test {
 	type Event = Event;
@@ -139,6 +141,8 @@ impl Config for Test {
 	type BountyValueMinimum = BountyValueMinimum;
 	type DataDepositPerByte = DataDepositPerByte;
 	type MaximumReasonLength = MaximumReasonLength;
+	type MaxActiveChildBountyCount = MaxActiveChildBountyCount;
+	type ChildBountyValueMinimum = ChildBountyValueMinimum;
 	type WeightInfo = ();
 }
 type System = frame_system::Module<Test>;
@@ -901,3 +905,165 @@ fn genesis_funding_works() {
 		assert_eq!(Treasury::pot(), initial_funding - Balances::minimum_balance());
 	});
 }
+
+// Create bounty 0 with a value of 50, curated by account 4 for a fee of 4.
+fn setup_active_bounty() {
+	System::set_block_number(1);
+	Balances::make_free_balance_be(&Treasury::account_id(), 101);
+	Balances::make_free_balance_be(&4, 10);
+	assert_ok!(Bounties::propose_bounty(Origin::signed(0), 50, b"12345".to_vec()));
+
+	assert_ok!(Bounties::approve_bounty(Origin::root(), 0));
+
+	System::set_block_number(2);
+	<Treasury as OnInitialize<u64>>::on_initialize(2);
+
+	assert_ok!(Bounties::propose_curator(Origin::root(), 0, 4, 4));
+	assert_ok!(Bounties::accept_curator(Origin::signed(4), 0));
+}
+
+#[test]
+fn propose_child_bounty_works() {
+	new_test_ext().execute_with(|| {
+		setup_active_bounty();
+
+		assert_noop!(
+			Bounties::propose_child_bounty(Origin::signed(1), 0, 10, b"12345".to_vec()),
+			Error::<Test>::RequireCurator,
+		);
+		assert_noop!(
+			Bounties::propose_child_bounty(Origin::signed(4), 1, 10, b"12345".to_vec()),
+			Error::<Test>::InvalidIndex,
+		);
+		assert_noop!(
+			Bounties::propose_child_bounty(Origin::signed(4), 0, 1, b"12345".to_vec()),
+			Error::<Test>::InvalidValue,
+		);
+		// The parent bounty account must keep enough to pay the curator fee.
+		assert_noop!(
+			Bounties::propose_child_bounty(Origin::signed(4), 0, 47, b"12345".to_vec()),
+			Error::<Test>::InsufficientBountyBalance,
+		);
+
+		assert_ok!(Bounties::propose_child_bounty(Origin::signed(4), 0, 10, b"12345".to_vec()));
+
+		assert_eq!(last_event(), RawEvent::ChildBountyAdded(0, 0));
+
+		assert_eq!(Bounties::child_bounties(0, 0).unwrap(), ChildBounty {
+			parent_bounty: 0,
+			value: 10,
+			fee: 0,
+			curator_deposit: 0,
+			status: ChildBountyStatus::Added,
+		});
+		assert_eq!(Bounties::child_bounty_descriptions(0).unwrap(), b"12345".to_vec());
+		assert_eq!(Bounties::parent_child_bounties(0), 1);
+
+		assert_eq!(Balances::free_balance(Bounties::bounty_account_id(0)), 40);
+		assert_eq!(Balances::free_balance(Bounties::child_bounty_account_id(0)), 10);
+
+		assert_ok!(Bounties::propose_child_bounty(Origin::signed(4), 0, 10, Vec::new()));
+		assert_noop!(
+			Bounties::propose_child_bounty(Origin::signed(4), 0, 10, Vec::new()),
+			Error::<Test>::TooManyChildBounties,
+		);
+	});
+}
+
+#[test]
+fn award_and_claim_child_bounty_works() {
+	new_test_ext().execute_with(|| {
+		setup_active_bounty();
+		Balances::make_free_balance_be(&8, 10);
+		assert_ok!(Bounties::propose_child_bounty(Origin::signed(4), 0, 10, b"12345".to_vec()));
+
+		assert_noop!(
+			Bounties::propose_child_curator(Origin::signed(1), 0, 0, 8, 2),
+			Error::<Test>::RequireCurator,
+		);
+		assert_noop!(
+			Bounties::propose_child_curator(Origin::signed(4), 0, 0, 8, 10),
+			Error::<Test>::InvalidFee,
+		);
+		assert_ok!(Bounties::propose_child_curator(Origin::signed(4), 0, 0, 8, 2));
+
+		assert_noop!(Bounties::accept_child_curator(Origin::signed(9), 0, 0), Error::<Test>::RequireCurator);
+		assert_ok!(Bounties::accept_child_curator(Origin::signed(8), 0, 0));
+
+		assert_eq!(Bounties::child_bounties(0, 0).unwrap(), ChildBounty {
+			parent_bounty: 0,
+			value: 10,
+			fee: 2,
+			curator_deposit: 1,
+			status: ChildBountyStatus::Active { curator: 8 },
+		});
+		assert_eq!(Balances::reserved_balance(8), 1);
+
+		assert_noop!(Bounties::award_child_bounty(Origin::signed(4), 0, 0, 9), Error::<Test>::RequireCurator);
+		assert_ok!(Bounties::award_child_bounty(Origin::signed(8), 0, 0, 9));
+
+		assert_eq!(last_event(), RawEvent::ChildBountyAwarded(0, 0, 9));
+		assert_eq!(Bounties::child_bounties(0, 0).unwrap().status, ChildBountyStatus::PendingPayout {
+			curator: 8,
+			beneficiary: 9,
+			unlock_at: 5,
+		});
+
+		assert_noop!(Bounties::close_child_bounty(Origin::root(), 0, 0), Error::<Test>::PendingPayout);
+		assert_noop!(Bounties::claim_child_bounty(Origin::signed(1), 0, 0), Error::<Test>::Premature);
+
+		System::set_block_number(5);
+		<Treasury as OnInitialize<u64>>::on_initialize(5);
+
+		assert_ok!(Bounties::claim_child_bounty(Origin::signed(1), 0, 0));
+
+		assert_eq!(last_event(), RawEvent::ChildBountyClaimed(0, 0, 8, 9));
+
+		assert_eq!(Balances::free_balance(9), 8);
+		assert_eq!(Balances::free_balance(8), 12); // initial 10 + 2 fee
+		assert_eq!(Balances::reserved_balance(8), 0);
+		assert_eq!(Balances::free_balance(Bounties::child_bounty_account_id(0)), 0);
+
+		assert_eq!(Bounties::child_bounties(0, 0), None);
+		assert_eq!(Bounties::child_bounty_descriptions(0), None);
+		assert_eq!(Bounties::parent_child_bounties(0), 0);
+
+		// The parent bounty can be awarded once its child bounties are settled.
+		assert_ok!(Bounties::award_bounty(Origin::signed(4), 0, 3));
+	});
+}
+
+#[test]
+fn close_bounty_waits_for_child_bounties() {
+	new_test_ext().execute_with(|| {
+		setup_active_bounty();
+		Balances::make_free_balance_be(&8, 10);
+		assert_ok!(Bounties::propose_child_bounty(Origin::signed(4), 0, 10, b"12345".to_vec()));
+		assert_ok!(Bounties::propose_child_bounty(Origin::signed(4), 0, 5, Vec::new()));
+		assert_ok!(Bounties::propose_child_curator(Origin::signed(4), 0, 0, 8, 2));
+		assert_ok!(Bounties::accept_child_curator(Origin::signed(8), 0, 0));
+
+		assert_noop!(Bounties::award_bounty(Origin::signed(4), 0, 3), Error::<Test>::HasActiveChildBounty);
+		assert_noop!(Bounties::close_bounty(Origin::root(), 0), Error::<Test>::HasActiveChildBounty);
+
+		// Only the parent curator or `RejectOrigin` can close a child bounty.
+		assert_noop!(Bounties::close_child_bounty(Origin::signed(8), 0, 0), Error::<Test>::RequireCurator);
+		assert_ok!(Bounties::close_child_bounty(Origin::signed(4), 0, 0));
+
+		assert_eq!(last_event(), RawEvent::ChildBountyCanceled(0, 0));
+
+		// Refund the deposit of the child bounty curator.
+		assert_eq!(Balances::free_balance(8), 10);
+		assert_eq!(Balances::reserved_balance(8), 0);
+		assert_eq!(Balances::free_balance(Bounties::child_bounty_account_id(0)), 0);
+		assert_eq!(Balances::free_balance(Bounties::bounty_account_id(0)), 45);
+
+		assert_ok!(Bounties::close_child_bounty(Origin::root(), 0, 1));
+		assert_eq!(Balances::free_balance(Bounties::bounty_account_id(0)), 50);
+		assert_eq!(Bounties::parent_child_bounties(0), 0);
+
+		assert_ok!(Bounties::close_bounty(Origin::root(), 0));
+
+		assert_eq!(last_event(), RawEvent::BountyCanceled(0));
+	});
+}
@@ -55,6 +55,12 @@ pub trait WeightInfo {
 	fn close_bounty_active() -> Weight;
 	fn extend_bounty_expiry() -> Weight;
 	fn spend_funds(b: u32, ) -> Weight;
+	fn propose_child_bounty(d: u32, ) -> Weight;
+	fn propose_child_curator() -> Weight;
+	fn accept_child_curator() -> Weight;
+	fn award_child_bounty() -> Weight;
+	fn claim_child_bounty() -> Weight;
+	fn close_child_bounty() -> Weight;
 }
 
 /// Weights for pallet_bounties using the Substrate node and recommended hardware.
@@ -121,6 +127,38 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			.saturating_add(T::DbWeight::get().writes(1 as Weight))
 			.saturating_add(T::DbWeight::get().writes((3 as Weight).saturating_mul(b as Weight)))
 	}
+	fn propose_child_bounty(d: u32, ) -> Weight {
+		(87_412_000 as Weight)
+			// Standard Error: 0
+			.saturating_add((1_000 as Weight).saturating_mul(d as Weight))
+			.saturating_add(T::DbWeight::get().reads(4 as Weight))
+			.saturating_add(T::DbWeight::get().writes(6 as Weight))
+	}
+	fn propose_child_curator() -> Weight {
+		(24_108_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn accept_child_curator() -> Weight {
+		(53_977_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	fn award_child_bounty() -> Weight {
+		(38_265_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn claim_child_bounty() -> Weight {
+		(181_433_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(4 as Weight))
+			.saturating_add(T::DbWeight::get().writes(6 as Weight))
+	}
+	fn close_child_bounty() -> Weight {
+		(121_760_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(4 as Weight))
+			.saturating_add(T::DbWeight::get().writes(6 as Weight))
+	}
 }
 
 // For backwards compatibility and tests
@@ -186,4 +224,36 @@ impl WeightInfo for () {
 			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
 			.saturating_add(RocksDbWeight::get().writes((3 as Weight).saturating_mul(b as Weight)))
 	}
+	fn propose_child_bounty(d: u32, ) -> Weight {
+		(87_412_000 as Weight)
+			// Standard Error: 0
+			.saturating_add((1_000 as Weight).saturating_mul(d as Weight))
+			.saturating_add(RocksDbWeight::get().reads(4 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(6 as Weight))
+	}
+	fn propose_child_curator() -> Weight {
+		(24_108_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	fn accept_child_curator() -> Weight {
+		(53_977_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
+	fn award_child_bounty() -> Weight {
+		(38_265_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	fn claim_child_bounty() -> Weight {
+		(181_433_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(4 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(6 as Weight))
+	}
+	fn close_child_bounty() -> Weight {
+		(121_760_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(4 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(6 as Weight))
+	}
 }
@@ -30,8 +30,39 @@ use sp_std::prelude::*;
 pub struct ContractExecResult {
 	pub exec_result: ExecResult,
 	pub gas_consumed: u64,
+	/// The UTF-8 encoded debug messages emitted by the contracts during the execution.
+	///
+	/// Empty unless debug message recording was enabled.
+	pub debug_message: Vec<u8>,
 }
 
+/// Result type of a `bare_instantiate` call.
+///
+/// The result of a contract instantiation along with the gas consumed, the storage deposit
+/// and the events that were deposited during the instantiation.
+#[derive(Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub struct ContractInstantiateResult<AccountId, Balance, BlockNumber> {
+	pub exec_result: Result<InstantiateReturnValue<AccountId, BlockNumber>, ExecError>,
+	pub gas_consumed: u64,
+	/// The deposit that was reserved from the origin in order to store the code of the
+	/// new contract.
+	///
+	/// Zero if the code was already stored.
+	pub storage_deposit: Balance,
+	/// The SCALE encoded event records deposited during the instantiation.
+	///
+	/// Empty unless the events were requested to be collected.
+	pub events: Vec<Vec<u8>>,
+	/// The UTF-8 encoded debug messages emitted by the contracts during the instantiation.
+	///
+	/// Empty unless debug message recording was enabled.
+	pub debug_message: Vec<u8>,
+}
+
+/// Result type of a `bare_upload_code` call.
+pub type CodeUploadResult<CodeHash, Balance> =
+	Result<CodeUploadReturnValue<CodeHash, Balance>, DispatchError>;
+
 /// Result type of a `get_storage` call.
 pub type GetStorageResult = Result<Option<Vec<u8>>, ContractAccessError>;
 
@@ -46,6 +77,9 @@ pub enum ContractAccessError {
 	DoesntExist,
 	/// The specified contract is a tombstone and thus cannot have any storage.
 	IsTombstone,
+	/// The specified contract would be evicted but its storage can't be queued for deletion
+	/// because the deletion queue is full.
+	DeletionQueueFull,
 }
 
 #[derive(Eq, PartialEq, Encode, Decode, RuntimeDebug)]
@@ -83,6 +117,40 @@ impl ExecReturnValue {
 	}
 }
 
+/// The result of a successful contract instantiation.
+#[derive(PartialEq, Eq, Encode, Decode, RuntimeDebug)]
+pub struct InstantiateReturnValue<AccountId, BlockNumber> {
+	/// The output of the called constructor.
+	pub result: ExecReturnValue,
+	/// The account id of the new contract.
+	pub account_id: AccountId,
+	/// Information about when and if the new contract will be evicted.
+	///
+	/// `None` if the projection wasn't requested or if the constructor reverted
+	/// and therefore no contract was created.
+	pub rent_projection: Option<RentProjection<BlockNumber>>,
+}
+
+/// The result of a successful code upload.
+#[derive(PartialEq, Eq, Encode, Decode, RuntimeDebug)]
+pub struct CodeUploadReturnValue<CodeHash, Balance> {
+	/// The key under which the new code is stored.
+	pub code_hash: CodeHash,
+	/// The deposit that was reserved from the uploader in order to store the code.
+	///
+	/// Zero if the code was already stored.
+	pub deposit: Balance,
+}
+
+/// Reference to an existing code hash or a new wasm module.
+#[derive(PartialEq, Eq, Encode, Decode, RuntimeDebug)]
+pub enum Code<Hash> {
+	/// A wasm module as raw bytes.
+	Upload(Vec<u8>),
+	/// The code hash of an on-chain wasm blob.
+	Existing(Hash),
+}
+
 /// Origin of the error.
 ///
 /// Call or instantiate both called into other contracts and pass through errors happening
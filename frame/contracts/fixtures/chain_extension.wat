@@ -0,0 +1,52 @@
+;; Call the chain extension by passing through the input and output of this contract.
+;; The first byte of the input selects the function of the chain extension.
+(module
+	(import "seal0" "seal_call_chain_extension"
+		(func $seal_call_chain_extension (param i32 i32 i32 i32 i32) (result i32))
+	)
+	(import "seal0" "seal_input" (func $seal_input (param i32 i32)))
+	(import "seal0" "seal_return" (func $seal_return (param i32 i32 i32)))
+	(import "env" "memory" (memory 1 1))
+
+	(func $assert (param i32)
+		(block $ok
+			(br_if $ok
+				(get_local 0)
+			)
+			(unreachable)
+		)
+	)
+
+	;; [0, 4) size of the input buffer
+	(data (i32.const 0) "\08")
+
+	;; [4, 12) here we store the input data
+
+	;; [12, 16) size of the output buffer
+	(data (i32.const 12) "\20")
+
+	;; [16, 48) here we store the output data
+
+	(func (export "deploy"))
+
+	(func (export "call")
+		(call $seal_input (i32.const 4) (i32.const 0))
+
+		;; the chain extension returns the func_id it was called with
+		(call $assert
+			(i32.eq
+				(call $seal_call_chain_extension
+					(i32.load8_u (i32.const 4)) ;; func_id
+					(i32.const 4) ;; input_ptr
+					(i32.load (i32.const 0)) ;; input_len
+					(i32.const 16) ;; output_ptr
+					(i32.const 12) ;; output_len_ptr
+				)
+				(i32.load8_u (i32.const 4))
+			)
+		)
+
+		;; exit with success and take the output of the chain extension as output buffer
+		(call $seal_return (i32.const 0) (i32.const 16) (i32.load (i32.const 12)))
+	)
+)
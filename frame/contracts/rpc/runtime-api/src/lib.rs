@@ -25,14 +25,18 @@
 
 use codec::Codec;
 use sp_std::vec::Vec;
-use pallet_contracts_primitives::{ContractExecResult, GetStorageResult, RentProjectionResult};
+use pallet_contracts_primitives::{
+	ContractExecResult, GetStorageResult, RentProjectionResult, ContractInstantiateResult,
+	CodeUploadResult, Code,
+};
 
 sp_api::decl_runtime_apis! {
 	/// The API to interact with contracts without using executive.
-	pub trait ContractsApi<AccountId, Balance, BlockNumber> where
+	pub trait ContractsApi<AccountId, Balance, BlockNumber, Hash> where
 		AccountId: Codec,
 		Balance: Codec,
 		BlockNumber: Codec,
+		Hash: Codec,
 	{
 		/// Perform a call from a specified account to a given contract.
 		///
@@ -45,6 +49,24 @@ sp_api::decl_runtime_apis! {
 			input_data: Vec<u8>,
 		) -> ContractExecResult;
 
+		/// Instantiate a new contract.
+		///
+		/// See the contracts' `instantiate` and `instantiate_with_code` dispatchable functions
+		/// for more details.
+		fn instantiate(
+			origin: AccountId,
+			endowment: Balance,
+			gas_limit: u64,
+			code: Code<Hash>,
+			data: Vec<u8>,
+			salt: Vec<u8>,
+		) -> ContractInstantiateResult<AccountId, Balance, BlockNumber>;
+
+		/// Upload new code without instantiating a contract from it.
+		///
+		/// See the contracts' `put_code` dispatchable function for more details.
+		fn upload_code(origin: AccountId, code: Vec<u8>) -> CodeUploadResult<Hash, Balance>;
+
 		/// Query a given storage key in a given contract.
 		///
 		/// Returns `Ok(Some(Vec<u8>))` if the storage value exists under the given key in the
@@ -61,7 +83,8 @@ sp_api::decl_runtime_apis! {
 		/// The returned projection is relevant for the current block, i.e. it is as if the contract
 		/// was accessed at the current block.
 		///
-		/// Returns `Err` if the contract is in a tombstone state or doesn't exist.
+		/// Returns `Err` if the contract is in a tombstone state or doesn't exist, or if it would
+		/// be evicted while the deletion queue is full.
 		fn rent_projection(address: AccountId) -> RentProjectionResult<BlockNumber>;
 	}
 }
@@ -22,7 +22,9 @@ use std::sync::Arc;
 use codec::Codec;
 use jsonrpc_core::{Error, ErrorCode, Result};
 use jsonrpc_derive::rpc;
-use pallet_contracts_primitives::RentProjection;
+use pallet_contracts_primitives::{
+	RentProjection, ContractExecResult, ContractInstantiateResult, CodeUploadResult,
+};
 use serde::{Deserialize, Serialize};
 use sp_api::ProvideRuntimeApi;
 use sp_blockchain::HeaderBackend;
@@ -34,13 +36,13 @@ use sp_runtime::{
 	DispatchError,
 };
 use std::convert::TryInto;
-use pallet_contracts_primitives::ContractExecResult;
 
 pub use pallet_contracts_rpc_runtime_api::ContractsApi as ContractsRuntimeApi;
 
 const RUNTIME_ERROR: i64 = 1;
 const CONTRACT_DOESNT_EXIST: i64 = 2;
 const CONTRACT_IS_A_TOMBSTONE: i64 = 3;
+const DELETION_QUEUE_FULL: i64 = 4;
 
 /// A rough estimate of how much gas a decent hardware consumes per second,
 /// using native execution.
@@ -68,6 +70,11 @@ impl From<ContractAccessError> for Error {
 				message: "The contract is a tombstone and doesn't have any storage.".into(),
 				data: None,
 			},
+			DeletionQueueFull => Error {
+				code: ErrorCode::ServerError(DELETION_QUEUE_FULL),
+				message: "The contract would be evicted but the deletion queue is full.".into(),
+				data: None,
+			},
 		}
 	}
 }
@@ -84,6 +91,48 @@ pub struct CallRequest<AccountId, Balance> {
 	input_data: Bytes,
 }
 
+/// A struct that encodes RPC parameters required to instantiate a new smart-contract.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[serde(deny_unknown_fields)]
+pub struct InstantiateRequest<AccountId, Balance, Hash> {
+	origin: AccountId,
+	endowment: Balance,
+	gas_limit: number::NumberOrHex,
+	code: Code<Hash>,
+	data: Bytes,
+	salt: Bytes,
+}
+
+/// A struct that encodes RPC parameters required to upload new code.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[serde(deny_unknown_fields)]
+pub struct CodeUploadRequest<AccountId> {
+	origin: AccountId,
+	code: Bytes,
+}
+
+/// Reference to an existing code hash or a new wasm module.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[serde(deny_unknown_fields)]
+pub enum Code<Hash> {
+	/// A wasm module as raw bytes.
+	Upload(Bytes),
+	/// The code hash of an on-chain wasm blob.
+	Existing(Hash),
+}
+
+impl<Hash> From<Code<Hash>> for pallet_contracts_primitives::Code<Hash> {
+	fn from(code: Code<Hash>) -> Self {
+		match code {
+			Code::Upload(binary) => pallet_contracts_primitives::Code::Upload(binary.to_vec()),
+			Code::Existing(hash) => pallet_contracts_primitives::Code::Existing(hash),
+		}
+	}
+}
+
 #[derive(Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 #[serde(rename_all = "camelCase")]
@@ -102,8 +151,8 @@ pub struct RpcContractExecResult {
 	/// How much gas was consumed by the call. In case of an error this is the amount
 	/// that was used up until the error occurred.
 	gas_consumed: u64,
-	/// Additional dynamic human readable error information for debugging. An empty string
-	/// indicates that no additional information is available.
+	/// The debug messages emitted by the contracts during the execution. An empty string
+	/// indicates that no debug messages were emitted.
 	debug_message: String,
 	/// Indicates whether the contract execution was successful or not.
 	result: std::result::Result<RpcContractExecSuccess, DispatchError>,
@@ -111,10 +160,11 @@ pub struct RpcContractExecResult {
 
 impl From<ContractExecResult> for RpcContractExecResult {
 	fn from(r: ContractExecResult) -> Self {
+		let debug_message = String::from_utf8_lossy(&r.debug_message).into_owned();
 		match r.exec_result {
 			Ok(val) => RpcContractExecResult {
 				gas_consumed: r.gas_consumed,
-				debug_message: String::new(),
+				debug_message,
 				result: Ok(RpcContractExecSuccess {
 					flags: val.flags.bits(),
 					data: val.data.into(),
@@ -122,16 +172,105 @@ impl From<ContractExecResult> for RpcContractExecResult {
 			},
 			Err(err) => RpcContractExecResult {
 				gas_consumed: r.gas_consumed,
-				debug_message: String::new(),
+				debug_message,
 				result: Err(err.error),
 			},
 		}
 	}
 }
 
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+#[serde(rename_all = "camelCase")]
+struct RpcContractInstantiateSuccess<AccountId, BlockNumber> {
+	/// The return flags of the constructor. See `pallet_contracts_primitives::ReturnFlags`.
+	flags: u32,
+	/// Data as returned by the constructor.
+	data: Bytes,
+	/// The account id of the new contract.
+	account_id: AccountId,
+	/// The block number at which the new contract will be evicted. `None` if the contract
+	/// is exempted from rent or if the constructor reverted.
+	rent_projection: Option<BlockNumber>,
+}
+
+/// An RPC serializable result of contract instantiation.
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcContractInstantiateResult<AccountId, Balance, BlockNumber> {
+	/// How much gas was consumed by the instantiation. In case of an error this is the
+	/// amount that was used up until the error occurred.
+	gas_consumed: u64,
+	/// The deposit reserved from the origin in order to store the code of the new contract.
+	storage_deposit: Balance,
+	/// The debug messages emitted by the contracts during the instantiation. An empty string
+	/// indicates that no debug messages were emitted.
+	debug_message: String,
+	/// The SCALE encoded event records deposited during the instantiation.
+	events: Vec<Bytes>,
+	/// Indicates whether the instantiation was successful or not.
+	result: std::result::Result<RpcContractInstantiateSuccess<AccountId, BlockNumber>, DispatchError>,
+}
+
+impl<AccountId, Balance, BlockNumber>
+	From<ContractInstantiateResult<AccountId, Balance, BlockNumber>>
+	for RpcContractInstantiateResult<AccountId, Balance, BlockNumber>
+{
+	fn from(r: ContractInstantiateResult<AccountId, Balance, BlockNumber>) -> Self {
+		RpcContractInstantiateResult {
+			gas_consumed: r.gas_consumed,
+			storage_deposit: r.storage_deposit,
+			debug_message: String::from_utf8_lossy(&r.debug_message).into_owned(),
+			events: r.events.into_iter().map(Bytes).collect(),
+			result: r.exec_result
+				.map(|val| RpcContractInstantiateSuccess {
+					flags: val.result.flags.bits(),
+					data: val.result.data.into(),
+					account_id: val.account_id,
+					rent_projection: match val.rent_projection {
+						Some(RentProjection::EvictionAt(block_num)) => Some(block_num),
+						Some(RentProjection::NoEviction) | None => None,
+					},
+				})
+				.map_err(|err| err.error),
+		}
+	}
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+#[serde(rename_all = "camelCase")]
+struct RpcCodeUploadSuccess<Hash, Balance> {
+	/// The key under which the new code is stored.
+	code_hash: Hash,
+	/// The deposit reserved from the origin in order to store the code.
+	deposit: Balance,
+}
+
+/// An RPC serializable result of a code upload.
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcCodeUploadResult<Hash, Balance> {
+	/// Indicates whether the upload was successful or not.
+	result: std::result::Result<RpcCodeUploadSuccess<Hash, Balance>, DispatchError>,
+}
+
+impl<Hash, Balance> From<CodeUploadResult<Hash, Balance>> for RpcCodeUploadResult<Hash, Balance> {
+	fn from(r: CodeUploadResult<Hash, Balance>) -> Self {
+		RpcCodeUploadResult {
+			result: r.map(|val| RpcCodeUploadSuccess {
+				code_hash: val.code_hash,
+				deposit: val.deposit,
+			}),
+		}
+	}
+}
+
 /// Contracts RPC methods.
 #[rpc]
-pub trait ContractsApi<BlockHash, BlockNumber, AccountId, Balance> {
+pub trait ContractsApi<BlockHash, BlockNumber, AccountId, Balance, Hash> {
 	/// Executes a call to a contract.
 	///
 	/// This call is performed locally without submitting any transactions. Thus executing this
@@ -145,6 +284,34 @@ pub trait ContractsApi<BlockHash, BlockNumber, AccountId, Balance> {
 		at: Option<BlockHash>,
 	) -> Result<RpcContractExecResult>;
 
+	/// Instantiate a new contract.
+	///
+	/// This instantiate is performed locally without submitting any transactions. Thus the
+	/// contract is not actually created.
+	///
+	/// This method is useful for UIs to dry-run contract instantiations in order to estimate
+	/// their costs, the address of the new contract and the events deposited.
+	#[rpc(name = "contracts_instantiate")]
+	fn instantiate(
+		&self,
+		instantiate_request: InstantiateRequest<AccountId, Balance, Hash>,
+		at: Option<BlockHash>,
+	) -> Result<RpcContractInstantiateResult<AccountId, Balance, BlockNumber>>;
+
+	/// Upload new code without instantiating a contract from it.
+	///
+	/// This upload is performed locally without submitting any transactions. Thus the code
+	/// is not actually stored.
+	///
+	/// This method is useful for UIs to check whether some code would be accepted by the
+	/// chain and to determine its code hash and the deposit required to store it.
+	#[rpc(name = "contracts_uploadCode")]
+	fn upload_code(
+		&self,
+		upload_request: CodeUploadRequest<AccountId>,
+		at: Option<BlockHash>,
+	) -> Result<RpcCodeUploadResult<Hash, Balance>>;
+
 	/// Returns the value under a specified storage `key` in a contract given by `address` param,
 	/// or `None` if it is not set.
 	#[rpc(name = "contracts_getStorage")]
@@ -184,12 +351,13 @@ impl<C, B> Contracts<C, B> {
 		}
 	}
 }
-impl<C, Block, AccountId, Balance>
+impl<C, Block, AccountId, Balance, Hash>
 	ContractsApi<
 		<Block as BlockT>::Hash,
 		<<Block as BlockT>::Header as HeaderT>::Number,
 		AccountId,
 		Balance,
+		Hash,
 	> for Contracts<C, Block>
 where
 	Block: BlockT,
@@ -199,9 +367,11 @@ where
 		AccountId,
 		Balance,
 		<<Block as BlockT>::Header as HeaderT>::Number,
+		Hash,
 	>,
 	AccountId: Codec,
 	Balance: Codec,
+	Hash: Codec,
 {
 	fn call(
 		&self,
@@ -221,24 +391,7 @@ where
 			input_data,
 		} = call_request;
 
-		// Make sure that gas_limit fits into 64 bits.
-		let gas_limit: u64 = gas_limit.try_into().map_err(|_| Error {
-			code: ErrorCode::InvalidParams,
-			message: format!("{:?} doesn't fit in 64 bit unsigned value", gas_limit),
-			data: None,
-		})?;
-
-		let max_gas_limit = 5 * GAS_PER_SECOND;
-		if gas_limit > max_gas_limit {
-			return Err(Error {
-				code: ErrorCode::InvalidParams,
-				message: format!(
-					"Requested gas limit is greater than maximum allowed: {} > {}",
-					gas_limit, max_gas_limit
-				),
-				data: None,
-			});
-		}
+		let gas_limit = limit_gas(gas_limit)?;
 
 		let exec_result = api
 			.call(&at, origin, dest, value, gas_limit, input_data.to_vec())
@@ -247,6 +400,57 @@ where
 		Ok(exec_result.into())
 	}
 
+	fn instantiate(
+		&self,
+		instantiate_request: InstantiateRequest<AccountId, Balance, Hash>,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<RpcContractInstantiateResult<
+		AccountId,
+		Balance,
+		<<Block as BlockT>::Header as HeaderT>::Number,
+	>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash));
+
+		let InstantiateRequest {
+			origin,
+			endowment,
+			gas_limit,
+			code,
+			data,
+			salt,
+		} = instantiate_request;
+
+		let gas_limit = limit_gas(gas_limit)?;
+
+		let result = api
+			.instantiate(&at, origin, endowment, gas_limit, code.into(), data.to_vec(), salt.to_vec())
+			.map_err(runtime_error_into_rpc_err)?;
+
+		Ok(result.into())
+	}
+
+	fn upload_code(
+		&self,
+		upload_request: CodeUploadRequest<AccountId>,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<RpcCodeUploadResult<Hash, Balance>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash));
+
+		let CodeUploadRequest { origin, code } = upload_request;
+
+		let result = api
+			.upload_code(&at, origin, code.to_vec())
+			.map_err(runtime_error_into_rpc_err)?;
+
+		Ok(result.into())
+	}
+
 	fn get_storage(
 		&self,
 		address: AccountId,
@@ -289,6 +493,30 @@ where
 	}
 }
 
+/// Makes sure that the supplied `gas_limit` fits into 64 bits and doesn't exceed
+/// the maximum allowed for a single dry-run.
+fn limit_gas(gas_limit: number::NumberOrHex) -> Result<u64> {
+	let gas_limit: u64 = gas_limit.try_into().map_err(|_| Error {
+		code: ErrorCode::InvalidParams,
+		message: format!("{:?} doesn't fit in 64 bit unsigned value", gas_limit),
+		data: None,
+	})?;
+
+	let max_gas_limit = 5 * GAS_PER_SECOND;
+	if gas_limit > max_gas_limit {
+		return Err(Error {
+			code: ErrorCode::InvalidParams,
+			message: format!(
+				"Requested gas limit is greater than maximum allowed: {} > {}",
+				gas_limit, max_gas_limit
+			),
+			data: None,
+		});
+	}
+
+	Ok(gas_limit)
+}
+
 /// Converts a runtime trap into an RPC error.
 fn runtime_error_into_rpc_err(err: impl std::fmt::Debug) -> Error {
 	Error {
@@ -318,6 +546,47 @@ mod tests {
 		assert_eq!(req.gas_limit.into_u256(), U256::from(0xe8d4a51000u64));
 	}
 
+	#[test]
+	fn instantiate_request_should_serialize_deserialize_properly() {
+		type Req = InstantiateRequest<String, u128, String>;
+		let req: Req = serde_json::from_str(r#"
+		{
+			"origin": "5CiPPseXPECbkjWCa6MnjNokrgYjMqmKndv2rSnekmSK2DjL",
+			"endowment": 0,
+			"gasLimit": 1000000000000,
+			"code": {
+				"existing": "0x1122"
+			},
+			"data": "0x4299",
+			"salt": "0x9988"
+		}
+		"#).unwrap();
+
+		assert_eq!(req.origin, "5CiPPseXPECbkjWCa6MnjNokrgYjMqmKndv2rSnekmSK2DjL");
+		assert_eq!(req.endowment, 0);
+		assert_eq!(req.gas_limit.into_u256(), U256::from(0xe8d4a51000u64));
+		assert_eq!(req.data.0, vec![0x42, 0x99]);
+		assert_eq!(req.salt.0, vec![0x99, 0x88]);
+		let code = match req.code {
+			Code::Existing(hash) => hash,
+			_ => panic!("json encoded an existing hash"),
+		};
+		assert_eq!(&code, "0x1122");
+	}
+
+	#[test]
+	fn upload_request_should_serialize_deserialize_properly() {
+		type Req = CodeUploadRequest<String>;
+		let req: Req = serde_json::from_str(r#"
+		{
+			"origin": "5CiPPseXPECbkjWCa6MnjNokrgYjMqmKndv2rSnekmSK2DjL",
+			"code": "0x8c97db39"
+		}
+		"#).unwrap();
+		assert_eq!(req.origin, "5CiPPseXPECbkjWCa6MnjNokrgYjMqmKndv2rSnekmSK2DjL");
+		assert_eq!(req.code.0, vec![0x8c, 0x97, 0xdb, 0x39]);
+	}
+
 	#[test]
 	fn result_should_serialize_deserialize_properly() {
 		fn test(expected: &str) {
@@ -328,4 +597,27 @@ mod tests {
 		test(r#"{"gasConsumed":5000,"debugMessage":"helpOk","result":{"Ok":{"flags":5,"data":"0x1234"}}}"#);
 		test(r#"{"gasConsumed":3400,"debugMessage":"helpErr","result":{"Err":"BadOrigin"}}"#);
 	}
+
+	#[test]
+	fn instantiate_result_should_serialize_deserialize_properly() {
+		fn test(expected: &str) {
+			let res: RpcContractInstantiateResult<String, u64, u64> =
+				serde_json::from_str(expected).unwrap();
+			let actual = serde_json::to_string(&res).unwrap();
+			assert_eq!(actual, expected);
+		}
+		test(r#"{"gasConsumed":5000,"storageDeposit":500,"debugMessage":"","events":["0x0102"],"result":{"Ok":{"flags":0,"data":"0x1234","accountId":"5CiPP","rentProjection":100}}}"#);
+		test(r#"{"gasConsumed":3400,"storageDeposit":0,"debugMessage":"","events":[],"result":{"Err":"BadOrigin"}}"#);
+	}
+
+	#[test]
+	fn upload_result_should_serialize_deserialize_properly() {
+		fn test(expected: &str) {
+			let res: RpcCodeUploadResult<String, u64> = serde_json::from_str(expected).unwrap();
+			let actual = serde_json::to_string(&res).unwrap();
+			assert_eq!(actual, expected);
+		}
+		test(r#"{"result":{"Ok":{"codeHash":"0x1122","deposit":500}}}"#);
+		test(r#"{"result":{"Err":"BadOrigin"}}"#);
+	}
 }
@@ -37,6 +37,7 @@ use self::{
 	sandbox::Sandbox,
 };
 use frame_benchmarking::{benchmarks, account, whitelisted_caller};
+use frame_support::{StorageMap, StorageValue};
 use frame_system::{Module as System, RawOrigin};
 use parity_wasm::elements::{Instruction, ValueType, BlockType};
 use sp_runtime::traits::{Hash, Bounded};
@@ -232,7 +233,7 @@ where
 		System::<T>::set_block_number(
 			contract.eviction_at()? + T::SignedClaimHandicap::get() + 5u32.into()
 		);
-		Rent::<T>::collect(&contract.account_id);
+		Rent::<T>::collect(&contract.account_id)?;
 		contract.ensure_tombstone()?;
 
 		Ok(Tombstone {
@@ -270,6 +271,40 @@ benchmarks! {
 	_ {
 	}
 
+	// The base weight of the lazy deletion without any queued tries.
+	on_initialize {}: {
+		Storage::<T>::process_deletion_queue_batch(Weight::max_value())
+	}
+
+	// The cost of deleting one key from a queued trie.
+	// `k`: Number of keys in the trie.
+	on_initialize_per_trie_item {
+		let k in 0 .. 1024;
+		let instance = Contract::<T>::new(WasmModule::dummy(), vec![], Endow::Max)?;
+		instance.store(&create_storage::<T>(k, T::MaxValueSize::get())?)?;
+		Storage::<T>::destroy_contract(&instance.account_id)?;
+	}: {
+		Storage::<T>::process_deletion_queue_batch(Weight::max_value())
+	}
+	verify {
+		assert_eq!(<DeletionQueue>::decode_len().unwrap_or(0), 0);
+	}
+
+	// The cost of decoding the deletion queue. All tries are empty.
+	// `q`: Number of tries in the queue.
+	on_initialize_per_queue_item {
+		let q in 0 .. 1024.min(T::DeletionQueueDepth::get());
+		for i in 0 .. q {
+			let instance = Contract::<T>::with_index(i, WasmModule::dummy(), vec![], Endow::Max)?;
+			Storage::<T>::destroy_contract(&instance.account_id)?;
+		}
+	}: {
+		Storage::<T>::process_deletion_queue_batch(Weight::max_value())
+	}
+	verify {
+		assert_eq!(<DeletionQueue>::decode_len().unwrap_or(0), 0);
+	}
+
 	// This extrinsic is pretty much constant as it is only a simple setter.
 	update_schedule {
 		let schedule = Schedule {
@@ -316,6 +351,34 @@ benchmarks! {
 		Contract::<T>::address_alive_info(&addr)?;
 	}
 
+	// This stores the code and instantiates a contract in a single transaction. We use the
+	// maximum sized module from `put_code` as the code and a dummy constructor.
+	// `c`: Size of the code in kilobytes.
+	// `s`: Size of the salt in kilobytes.
+	instantiate_with_code {
+		let c in 0 .. Contracts::<T>::current_schedule().limits.code_size / 1024;
+		let s in 0 .. code::max_pages::<T>() * 64;
+		let salt = vec![42u8; (s * 1024) as usize];
+		let endowment = ConfigCache::<T>::subsistence_threshold_uncached();
+		let caller = whitelisted_caller();
+		T::Currency::make_free_balance_be(&caller, caller_funding::<T>());
+		let WasmModule { code, hash, .. } = WasmModule::<T>::sized(c * 1024);
+		let deposit = T::CodeDepositPerByte::get().saturating_mul((code.len() as u32).into());
+		let origin = RawOrigin::Signed(caller.clone());
+		let addr = Contracts::<T>::contract_address(&caller, &hash, &salt);
+	}: _(origin, endowment, Weight::max_value(), code, vec![], salt)
+	verify {
+		// endowment and code deposit were removed from the caller
+		assert_eq!(
+			T::Currency::free_balance(&caller),
+			caller_funding::<T>() - endowment - deposit,
+		);
+		// contract has the full endowment because no rent collection happended
+		assert_eq!(T::Currency::free_balance(&addr), endowment);
+		// instantiate should leave a alive contract
+		Contract::<T>::address_alive_info(&addr)?;
+	}
+
 	// We just call a dummy contract to measure to overhead of the call extrinsic.
 	// The size of the data has no influence on the costs of this extrinsic as long as the contract
 	// won't call `seal_input` in its constructor to copy the data to contract memory.
@@ -378,6 +441,21 @@ benchmarks! {
 		);
 	}
 
+	// Removing code doesn't depend on its size because the storage items are deleted
+	// without being read.
+	remove_code {
+		let caller = whitelisted_caller();
+		T::Currency::make_free_balance_be(&caller, caller_funding::<T>());
+		let WasmModule { code, hash, .. } = WasmModule::<T>::dummy();
+		let origin = RawOrigin::Signed(caller.clone());
+		Contracts::<T>::put_code(origin.clone().into(), code)?;
+	}: _(origin, hash)
+	verify {
+		// the code was removed and the deposit refunded
+		assert!(<CodeStorage<T>>::get(&hash).is_none());
+		assert_eq!(T::Currency::free_balance(&caller), caller_funding::<T>());
+	}
+
 	seal_caller {
 		let r in 0 .. API_BENCHMARK_BATCHES;
 		let instance = Contract::<T>::new(WasmModule::getter(
@@ -2394,11 +2472,16 @@ mod tests {
 		}
 	}
 
+	create_test!(on_initialize);
+	create_test!(on_initialize_per_trie_item);
+	create_test!(on_initialize_per_queue_item);
 	create_test!(update_schedule);
 	create_test!(put_code);
 	create_test!(instantiate);
+	create_test!(instantiate_with_code);
 	create_test!(call);
 	create_test!(claim_surcharge);
+	create_test!(remove_code);
 
 	create_test!(seal_caller);
 	create_test!(seal_address);
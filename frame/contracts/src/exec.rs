@@ -20,11 +20,11 @@ use crate::{
 	Error, ContractInfoOf
 };
 use sp_core::crypto::UncheckedFrom;
-use sp_std::prelude::*;
+use sp_std::{prelude::*, cell::RefCell};
 use sp_runtime::traits::{Bounded, Zero, Convert, Saturating};
 use frame_support::{
 	dispatch::DispatchError,
-	traits::{ExistenceRequirement, Currency, Time, Randomness},
+	traits::{ExistenceRequirement, Currency, Time, Randomness, Get},
 	weights::Weight,
 	ensure, StorageMap,
 };
@@ -167,6 +167,17 @@ pub trait Ext {
 
 	/// Returns the price for the specified amount of weight.
 	fn get_weight_price(&self, weight: Weight) -> BalanceOf<Self::T>;
+
+	/// Append a string to the debug buffer.
+	///
+	/// It is added as-is without any additional new line. Messages which would make the
+	/// buffer exceed `MaxDebugBufferLen` are dropped.
+	///
+	/// This is a no-op if debug message recording is disabled which is always the case
+	/// when the code is executing on-chain.
+	///
+	/// Returns `true` if debug message recording is enabled. Otherwise `false` is returned.
+	fn append_debug_buffer(&mut self, msg: &str) -> bool;
 }
 
 /// Loader is a companion of the `Vm` trait. It loads an appropriate abstract
@@ -212,6 +223,10 @@ pub struct ExecutionContext<'a, T: Config + 'a, V, L> {
 	pub loader: &'a L,
 	pub timestamp: MomentOf<T>,
 	pub block_number: T::BlockNumber,
+	/// The buffer that collects the debug messages of all contracts on the call stack.
+	///
+	/// `None` if debug message recording is disabled.
+	pub debug_message: Option<&'a RefCell<Vec<u8>>>,
 }
 
 impl<'a, T, E, V, L> ExecutionContext<'a, T, V, L>
@@ -236,6 +251,7 @@ where
 			loader: &loader,
 			timestamp: T::Time::now(),
 			block_number: <frame_system::Module<T>>::block_number(),
+			debug_message: None,
 		}
 	}
 
@@ -252,6 +268,7 @@ where
 			loader: self.loader,
 			timestamp: self.timestamp.clone(),
 			block_number: self.block_number.clone(),
+			debug_message: self.debug_message,
 		}
 	}
 
@@ -272,7 +289,7 @@ where
 		// cannot be changed before the first call
 		// We do not allow 'calling' plain accounts. For transfering value
 		// `seal_transfer` must be used.
-		let contract = if let Some(ContractInfo::Alive(info)) = Rent::<T>::collect(&dest) {
+		let contract = if let Some(ContractInfo::Alive(info)) = Rent::<T>::collect(&dest)? {
 			info
 		} else {
 			Err(Error::<T>::NotCallable)?
@@ -574,13 +591,7 @@ where
 			value,
 			self.ctx,
 		)?;
-		let self_trie_id = self.ctx.self_trie_id.as_ref().expect(
-			"this function is only invoked by in the context of a contract;\
-				a contract has a trie id;\
-				this can't be None; qed",
-		);
-		Storage::<T>::destroy_contract(&self_id, self_trie_id);
-		Ok(())
+		Storage::<T>::destroy_contract(&self_id)
 	}
 
 	fn call(
@@ -691,6 +702,18 @@ where
 	fn get_weight_price(&self, weight: Weight) -> BalanceOf<Self::T> {
 		T::WeightPrice::convert(weight)
 	}
+
+	fn append_debug_buffer(&mut self, msg: &str) -> bool {
+		if let Some(buffer) = self.ctx.debug_message {
+			let mut buffer = buffer.borrow_mut();
+			if buffer.len().saturating_add(msg.len()) <= T::MaxDebugBufferLen::get() as usize {
+				buffer.extend(msg.as_bytes());
+			}
+			true
+		} else {
+			false
+		}
+	}
 }
 
 fn deposit_event<T: Config>(
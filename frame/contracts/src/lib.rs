@@ -48,6 +48,14 @@
 //! then all of B's calls are reverted. Assuming correct error handling by contract A, A's other calls and state
 //! changes still persist.
 //!
+//! ### Chain Extensions
+//!
+//! Contracts can only call the host functions which are defined by this module. In order to
+//! give contracts access to functionality of other modules of the runtime, a runtime can
+//! implement a [`ChainExtension`](./chain_extension/trait.ChainExtension.html) which contracts
+//! call into through `seal_call_chain_extension`. See the
+//! [`chain_extension`](./chain_extension/index.html) module for details.
+//!
 //! ### Notable Scenarios
 //!
 //! Contract call failures are not always cascading. When failures occur in a sub-call, they do not "bubble up",
@@ -62,7 +70,11 @@
 //! * `instantiate` - Deploys a new contract from the given `code_hash`, optionally transferring some balance.
 //! This instantiates a new smart contract account and calls its contract deploy handler to
 //! initialize the contract.
+//! * `instantiate_with_code` - Stores the given binary Wasm code and deploys a new contract from it
+//! within a single transaction. This is the same as calling `put_code` followed by `instantiate`.
 //! * `call` - Makes a call to an account, optionally transferring some balance.
+//! * `remove_code` - Removes the stored code of the given `code_hash` when it is no longer used
+//! by any contract and refunds the deposit paid for storing it.
 //!
 //! ## Usage
 //!
@@ -89,6 +101,7 @@ mod rent;
 mod benchmarking;
 mod schedule;
 pub mod weights;
+pub mod chain_extension;
 
 #[cfg(test)]
 mod tests;
@@ -106,23 +119,24 @@ use crate::{
 	storage::Storage,
 };
 use sp_core::crypto::UncheckedFrom;
-use sp_std::{prelude::*, marker::PhantomData, fmt::Debug};
+use sp_std::{prelude::*, marker::PhantomData, fmt::Debug, cell::RefCell};
 use codec::{Codec, Encode, Decode};
 use sp_runtime::{
 	traits::{
 		Hash, StaticLookup, Zero, MaybeSerializeDeserialize, Member, Convert, Saturating,
 	},
-	RuntimeDebug,
+	RuntimeDebug, DispatchError,
 };
 use frame_support::{
 	decl_module, decl_event, decl_storage, decl_error, ensure,
 	storage::child::ChildInfo,
 	dispatch::{DispatchResult, DispatchResultWithPostInfo},
-	traits::{OnUnbalanced, Currency, Get, Time, Randomness},
+	traits::{OnUnbalanced, Currency, ReservableCurrency, Get, Time, Randomness},
 };
 use frame_system::{ensure_signed, ensure_root};
 use pallet_contracts_primitives::{
-	RentProjectionResult, GetStorageResult, ContractAccessError, ContractExecResult, ExecResult,
+	RentProjectionResult, GetStorageResult, ContractAccessError, ContractExecResult, ExecError,
+	ContractInstantiateResult, InstantiateReturnValue, CodeUploadResult, CodeUploadReturnValue, Code,
 };
 use frame_support::weights::Weight;
 
@@ -250,6 +264,20 @@ where
 	}
 }
 
+pub type CodeInfo<T> =
+	RawCodeInfo<<T as frame_system::Config>::AccountId, BalanceOf<T>>;
+
+/// Information about the owner and the users of some stored code.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct RawCodeInfo<AccountId, Balance> {
+	/// The account that stored the code and paid the deposit for it.
+	pub owner: AccountId,
+	/// The deposit reserved from `owner` which is refunded when the code is removed.
+	pub deposit: Balance,
+	/// The number of contracts (alive or tombstone) that use the code.
+	pub refcount: u64,
+}
+
 impl<T: Config> From<AliveContractInfo<T>> for ContractInfo<T> {
 	fn from(alive_info: AliveContractInfo<T>) -> Self {
 		Self::Alive(alive_info)
@@ -266,7 +294,7 @@ pub trait Config: frame_system::Config {
 	type Randomness: Randomness<Self::Hash>;
 
 	/// The currency in which fees are paid and contract balances are held.
-	type Currency: Currency<Self::AccountId>;
+	type Currency: ReservableCurrency<Self::AccountId>;
 
 	/// The overarching event type.
 	type Event: From<Event<Self>> + Into<<Self as frame_system::Config>::Event>;
@@ -306,12 +334,32 @@ pub trait Config: frame_system::Config {
 	/// to removal of a contract.
 	type SurchargeReward: Get<BalanceOf<Self>>;
 
+	/// The deposit reserved from the uploader of some code per byte of that code.
+	///
+	/// The deposit is refunded when the code is removed.
+	type CodeDepositPerByte: Get<BalanceOf<Self>>;
+
 	/// The maximum nesting level of a call/instantiate stack.
 	type MaxDepth: Get<u32>;
 
 	/// The maximum size of a storage value and event payload in bytes.
 	type MaxValueSize: Get<u32>;
 
+	/// The maximum length of the debug buffer in bytes.
+	///
+	/// The buffer is only populated when executing off-chain, e.g. from an RPC. Messages
+	/// which would make it exceed this length are dropped.
+	type MaxDebugBufferLen: Get<u32>;
+
+	/// The maximum number of storage tries that can be queued for deletion.
+	///
+	/// Removing a contract fails when the queue is full. The runtime should pick a depth
+	/// which can be processed within a few blocks using `DeletionWeightLimit`.
+	type DeletionQueueDepth: Get<u32>;
+
+	/// The maximum amount of weight that can be consumed per block for lazy trie removal.
+	type DeletionWeightLimit: Get<Weight>;
+
 	/// Used to answer contracts's queries regarding the current weight price. This is **not**
 	/// used to calculate the actual fee and is only for informational purposes.
 	type WeightPrice: Convert<Weight, BalanceOf<Self>>;
@@ -319,6 +367,9 @@ pub trait Config: frame_system::Config {
 	/// Describes the weights of the dispatchables of this module and is also used to
 	/// construct a default cost schedule.
 	type WeightInfo: WeightInfo;
+
+	/// Type that allows the runtime authors to add new host functions for a contract to call.
+	type ChainExtension: chain_extension::ChainExtension<Self>;
 }
 
 decl_error! {
@@ -378,6 +429,20 @@ decl_error! {
 		/// on the call stack. Those actions are contract self destruction and restoration
 		/// of a tombstone.
 		ReentranceDenied,
+		/// The contract called into the chain extension but the chain does not provide one.
+		NoChainExtension,
+		/// The contract tried to emit a debug message which is not valid UTF-8.
+		DebugMessageInvalidUTF8,
+		/// The code can't be removed because it is used by at least one contract.
+		CodeInUse,
+		/// Only the account that stored the code is allowed to remove it.
+		NotCodeOwner,
+		/// Removal of a contract failed because the deletion queue is full.
+		///
+		/// This can happen when either calling `seal_terminate` or when a contract is evicted.
+		/// The queue is emptied by lazily deleting the storage of removed contracts in the
+		/// following blocks. Retrying later will therefore succeed.
+		DeletionQueueFull,
 	}
 }
 
@@ -424,6 +489,9 @@ decl_module! {
 		/// to removal of a contract.
 		const SurchargeReward: BalanceOf<T> = T::SurchargeReward::get();
 
+		/// The deposit reserved from the uploader of some code per byte of that code.
+		const CodeDepositPerByte: BalanceOf<T> = T::CodeDepositPerByte::get();
+
 		/// The maximum nesting level of a call/instantiate stack. A reasonable default
 		/// value is 100.
 		const MaxDepth: u32 = T::MaxDepth::get();
@@ -431,8 +499,26 @@ decl_module! {
 		/// The maximum size of a storage value in bytes. A reasonable default is 16 KiB.
 		const MaxValueSize: u32 = T::MaxValueSize::get();
 
+		/// The maximum length of the debug buffer in bytes. A reasonable default is 2 MiB.
+		const MaxDebugBufferLen: u32 = T::MaxDebugBufferLen::get();
+
+		/// The maximum number of storage tries that can be queued for deletion.
+		const DeletionQueueDepth: u32 = T::DeletionQueueDepth::get();
+
+		/// The maximum amount of weight that can be consumed per block for lazy trie removal.
+		const DeletionWeightLimit: Weight = T::DeletionWeightLimit::get();
+
 		fn deposit_event() = default;
 
+		fn on_initialize() -> Weight {
+			// Lazy deletion must never push the block over its limit, e.g. after a runtime
+			// upgrade consumed most of it.
+			let weight_limit = T::BlockWeights::get().max_block
+				.saturating_sub(<frame_system::Module<T>>::block_weight().total())
+				.min(T::DeletionWeightLimit::get());
+			Storage::<T>::process_deletion_queue_batch(weight_limit)
+		}
+
 		/// Updates the schedule for metering contracts.
 		///
 		/// The schedule must have a greater version than the stored schedule.
@@ -451,19 +537,17 @@ decl_module! {
 
 		/// Stores the given binary Wasm code into the chain's storage and returns its `codehash`.
 		/// You can instantiate contracts only with stored code.
+		///
+		/// A deposit of `CodeDepositPerByte` for every byte of the code is reserved from the
+		/// sender. Storing code which is already stored is a no-op and reserves no deposit.
 		#[weight = T::WeightInfo::put_code(code.len() as u32 / 1024)]
 		pub fn put_code(
 			origin,
 			code: Vec<u8>
 		) -> DispatchResult {
-			ensure_signed(origin)?;
+			let origin = ensure_signed(origin)?;
 			let schedule = <Module<T>>::current_schedule();
-			ensure!(code.len() as u32 <= schedule.limits.code_size, Error::<T>::CodeTooLarge);
-			let result = wasm::save_code::<T>(code, &schedule);
-			if let Ok(code_hash) = result {
-				Self::deposit_event(RawEvent::CodeStored(code_hash));
-			}
-			result.map(|_| ()).map_err(Into::into)
+			Self::store_code(code, &origin, &schedule).map(|_| ())
 		}
 
 		/// Makes a call to an account, optionally transferring some balance.
@@ -485,7 +569,7 @@ decl_module! {
 			let dest = T::Lookup::lookup(dest)?;
 			let mut gas_meter = GasMeter::new(gas_limit);
 
-			let result = Self::execute_wasm(origin, &mut gas_meter, |ctx, gas_meter| {
+			let result = Self::execute_wasm(origin, &mut gas_meter, None, |ctx, gas_meter| {
 				ctx.call(dest, value, gas_meter, data)
 			});
 			gas_meter.into_dispatch_result(result)
@@ -521,7 +605,7 @@ decl_module! {
 			let origin = ensure_signed(origin)?;
 			let mut gas_meter = GasMeter::new(gas_limit);
 
-			let result = Self::execute_wasm(origin, &mut gas_meter, |ctx, gas_meter| {
+			let result = Self::execute_wasm(origin, &mut gas_meter, None, |ctx, gas_meter| {
 				ctx.instantiate(endowment, gas_meter, &code_hash, data, &salt)
 					.map(|(_address, output)| output)
 			});
@@ -556,10 +640,67 @@ decl_module! {
 			};
 
 			// If poking the contract has lead to eviction of the contract, give out the rewards.
-			if Rent::<T>::snitch_contract_should_be_evicted(&dest, handicap) {
+			if Rent::<T>::snitch_contract_should_be_evicted(&dest, handicap)? {
 				T::Currency::deposit_into_existing(&rewarded, T::SurchargeReward::get())?;
 			}
 		}
+
+		/// Stores the given binary Wasm code and instantiates a new contract from it,
+		/// optionally transferring some balance.
+		///
+		/// This is the same as calling `put_code` followed by `instantiate` with the hash of
+		/// the supplied `code` but only requires a single transaction. See `instantiate` for
+		/// the meaning of the remaining arguments.
+		///
+		/// The code is stored and the `CodeStored` event deposited regardless of whether the
+		/// instantiation succeeds.
+		#[weight =
+			T::WeightInfo::instantiate_with_code(
+				code.len() as u32 / 1024,
+				salt.len() as u32 / 1024,
+			).saturating_add(*gas_limit)
+		]
+		pub fn instantiate_with_code(
+			origin,
+			#[compact] endowment: BalanceOf<T>,
+			#[compact] gas_limit: Gas,
+			code: Vec<u8>,
+			data: Vec<u8>,
+			salt: Vec<u8>,
+		) -> DispatchResultWithPostInfo {
+			let origin = ensure_signed(origin)?;
+			let code_len = code.len() as u32;
+			let schedule = <Module<T>>::current_schedule();
+			let code_hash = Self::store_code(code, &origin, &schedule)?;
+			let mut gas_meter = GasMeter::new(gas_limit);
+
+			let result = Self::execute_wasm(origin, &mut gas_meter, None, |ctx, gas_meter| {
+				ctx.instantiate(endowment, gas_meter, &code_hash, data, &salt)
+					.map(|(_address, output)| output)
+			});
+			let mut result = gas_meter.into_dispatch_result(result);
+
+			// Storing the code isn't metered by the gas meter. We need to add its weight
+			// on top of the gas spent so that it isn't refunded.
+			let post_info = match &mut result {
+				Ok(post_info) => post_info,
+				Err(err) => &mut err.post_info,
+			};
+			post_info.actual_weight = post_info.actual_weight
+				.map(|weight| weight.saturating_add(T::WeightInfo::put_code(code_len / 1024)));
+			result
+		}
+
+		/// Removes the code stored under `code_hash` and refunds the deposit to its owner.
+		///
+		/// Only the account that stored the code can remove it and only as long as no contract
+		/// uses it. Code is removed automatically when the last contract using it is removed.
+		/// Code stored before the introduction of deposits has no owner and can't be removed.
+		#[weight = T::WeightInfo::remove_code()]
+		pub fn remove_code(origin, code_hash: CodeHash<T>) -> DispatchResult {
+			let origin = ensure_signed(origin)?;
+			wasm::remove_code::<T>(&origin, &code_hash)
+		}
 	}
 }
 
@@ -574,24 +715,121 @@ where
 	/// suitable for calling directly from Rust.
 	///
 	/// It returns the exection result and the amount of used weight.
+	///
+	/// If `debug` is set to `true` the debug messages emitted by the contracts are collected
+	/// and returned. This must only be enabled when executing off-chain, e.g. from an RPC.
 	pub fn bare_call(
 		origin: T::AccountId,
 		dest: T::AccountId,
 		value: BalanceOf<T>,
 		gas_limit: Gas,
 		input_data: Vec<u8>,
+		debug: bool,
 	) -> ContractExecResult {
 		let mut gas_meter = GasMeter::new(gas_limit);
-		let exec_result = Self::execute_wasm(origin, &mut gas_meter, |ctx, gas_meter| {
-			ctx.call(dest, value, gas_meter, input_data)
-		});
+		let debug_message = if debug { Some(RefCell::new(Vec::new())) } else { None };
+		let exec_result = Self::execute_wasm(
+			origin,
+			&mut gas_meter,
+			debug_message.as_ref(),
+			|ctx, gas_meter| ctx.call(dest, value, gas_meter, input_data),
+		);
 		let gas_consumed = gas_meter.gas_spent();
 		ContractExecResult {
 			exec_result,
 			gas_consumed,
+			debug_message: debug_message.map(RefCell::into_inner).unwrap_or_default(),
+		}
+	}
+
+	/// Instantiate a new contract.
+	///
+	/// This function is similar to `Self::instantiate` but is better suited for calling
+	/// directly from Rust. In contrast to the dispatchable it accepts either the hash of
+	/// already stored code or a new wasm blob which is stored before the instantiation.
+	///
+	/// If `compute_projection` is set to `true` the result also contains the rent projection
+	/// of the new contract. If `collect_events` is set to `true` the result contains all
+	/// events deposited during the execution of this function. If `debug` is set to `true`
+	/// the debug messages emitted by the contracts are collected and returned. All of them
+	/// are meant to be used by off-chain callers only, e.g. an RPC dry-run.
+	pub fn bare_instantiate(
+		origin: T::AccountId,
+		endowment: BalanceOf<T>,
+		gas_limit: Gas,
+		code: Code<CodeHash<T>>,
+		data: Vec<u8>,
+		salt: Vec<u8>,
+		compute_projection: bool,
+		collect_events: bool,
+		debug: bool,
+	) -> ContractInstantiateResult<T::AccountId, BalanceOf<T>, T::BlockNumber> {
+		let event_count = <frame_system::Module<T>>::event_count();
+		let reserved_before = T::Currency::reserved_balance(&origin);
+		let mut gas_meter = GasMeter::new(gas_limit);
+		let debug_message = if debug { Some(RefCell::new(Vec::new())) } else { None };
+		let code_hash: Result<CodeHash<T>, ExecError> = match code {
+			Code::Upload(binary) => {
+				let schedule = <Module<T>>::current_schedule();
+				Self::store_code(binary, &origin, &schedule).map_err(Into::into)
+			},
+			Code::Existing(hash) => Ok(hash),
+		};
+		let storage_deposit = T::Currency::reserved_balance(&origin).saturating_sub(reserved_before);
+		let exec_result = code_hash
+			.and_then(|code_hash| Self::execute_wasm(
+				origin,
+				&mut gas_meter,
+				debug_message.as_ref(),
+				|ctx, gas_meter| ctx.instantiate(endowment, gas_meter, &code_hash, data, &salt),
+			))
+			.map(|(account_id, result)| {
+				// A reverted constructor leaves no contract behind to project the rent for.
+				let rent_projection = if compute_projection && result.is_success() {
+					Rent::<T>::compute_projection(&account_id).ok()
+				} else {
+					None
+				};
+				InstantiateReturnValue {
+					result,
+					account_id,
+					rent_projection,
+				}
+			});
+		let events = if collect_events {
+			<frame_system::Module<T>>::events()
+				.into_iter()
+				.skip(event_count as usize)
+				.map(|record| record.encode())
+				.collect()
+		} else {
+			Vec::new()
+		};
+		ContractInstantiateResult {
+			exec_result,
+			gas_consumed: gas_meter.gas_spent(),
+			storage_deposit,
+			events,
+			debug_message: debug_message.map(RefCell::into_inner).unwrap_or_default(),
 		}
 	}
 
+	/// Store the given binary Wasm code without instantiating a contract from it.
+	///
+	/// This function is similar to `Self::put_code` but is better suited for calling
+	/// directly from Rust. It returns the hash of the stored code and the deposit reserved
+	/// from `origin` for storing it.
+	pub fn bare_upload_code(
+		origin: T::AccountId,
+		code: Vec<u8>,
+	) -> CodeUploadResult<CodeHash<T>, BalanceOf<T>> {
+		let schedule = <Module<T>>::current_schedule();
+		let reserved_before = T::Currency::reserved_balance(&origin);
+		let code_hash = Self::store_code(code, &origin, &schedule)?;
+		let deposit = T::Currency::reserved_balance(&origin).saturating_sub(reserved_before);
+		Ok(CodeUploadReturnValue { code_hash, deposit })
+	}
+
 	/// Query storage of a specified contract under a specified key.
 	pub fn get_storage(address: T::AccountId, key: [u8; 32]) -> GetStorageResult {
 		let contract_info = ContractInfoOf::<T>::get(&address)
@@ -642,17 +880,29 @@ impl<T: Config> Module<T>
 where
 	T::AccountId: UncheckedFrom<T::Hash> + AsRef<[u8]>,
 {
-	fn execute_wasm(
+	fn execute_wasm<R>(
 		origin: T::AccountId,
 		gas_meter: &mut GasMeter<T>,
-		func: impl FnOnce(&mut ExecutionContext<T, WasmVm<T>, WasmLoader<T>>, &mut GasMeter<T>) -> ExecResult,
-	) -> ExecResult {
+		debug_message: Option<&RefCell<Vec<u8>>>,
+		func: impl FnOnce(&mut ExecutionContext<T, WasmVm<T>, WasmLoader<T>>, &mut GasMeter<T>) -> R,
+	) -> R {
 		let cfg = ConfigCache::preload();
 		let vm = WasmVm::new(&cfg.schedule);
 		let loader = WasmLoader::new(&cfg.schedule);
 		let mut ctx = ExecutionContext::top_level(origin, &cfg, &vm, &loader);
+		ctx.debug_message = debug_message;
 		func(&mut ctx, gas_meter)
 	}
+
+	/// Instrument and store the given binary Wasm code on behalf of `owner`.
+	fn store_code(
+		code: Vec<u8>,
+		owner: &T::AccountId,
+		schedule: &Schedule<T>,
+	) -> Result<CodeHash<T>, DispatchError> {
+		ensure!(code.len() as u32 <= schedule.limits.code_size, Error::<T>::CodeTooLarge);
+		wasm::save_code::<T>(code, owner, schedule)
+	}
 }
 
 decl_event! {
@@ -695,6 +945,10 @@ decl_event! {
 		/// An event deposited upon execution of a contract from the account.
 		/// \[account, data\]
 		ContractExecution(AccountId, Vec<u8>),
+
+		/// Code with the specified hash has been removed and its deposit refunded.
+		/// \[code_hash\]
+		CodeRemoved(Hash),
 	}
 }
 
@@ -709,8 +963,16 @@ decl_storage! {
 		pub PristineCode: map hasher(identity) CodeHash<T> => Option<Vec<u8>>;
 		/// A mapping between an original code hash and instrumented wasm code, ready for execution.
 		pub CodeStorage: map hasher(identity) CodeHash<T> => Option<wasm::PrefabWasmModule>;
+		/// The owner, deposit and reference count of the code stored under a code hash.
+		///
+		/// Code stored before the introduction of deposits has no entry here.
+		pub CodeInfoOf: map hasher(identity) CodeHash<T> => Option<CodeInfo<T>>;
 		/// The subtrie counter.
 		pub AccountCounter: u64 = 0;
+		/// Storage tries of removed contracts which are deleted lazily in `on_initialize`.
+		///
+		/// The length is bounded by `DeletionQueueDepth`.
+		DeletionQueue: Vec<storage::DeletedContract>;
 		/// The code associated with a given account.
 		///
 		/// TWOX-NOTE: SAFE since `AccountId` is a secure hash.
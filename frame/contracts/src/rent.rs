@@ -18,7 +18,7 @@
 
 use crate::{
 	AliveContractInfo, BalanceOf, ContractInfo, ContractInfoOf, Module, RawEvent,
-	TombstoneContractInfo, Config, CodeHash, ConfigCache, Error,
+	TombstoneContractInfo, Config, CodeHash, ConfigCache, Error, wasm, storage::Storage,
 };
 use sp_std::prelude::*;
 use sp_io::hashing::blake2_256;
@@ -228,24 +228,26 @@ where
 	/// Enacts the given verdict and returns the updated `ContractInfo`.
 	///
 	/// `alive_contract_info` should be from the same address as `account`.
+	///
+	/// Removing the contract fails without any side effects when the storage of the contract
+	/// can't be queued for deletion.
 	fn enact_verdict(
 		account: &T::AccountId,
 		alive_contract_info: AliveContractInfo<T>,
 		current_block_number: T::BlockNumber,
 		verdict: Verdict<T>,
-	) -> Option<ContractInfo<T>> {
+	) -> Result<Option<ContractInfo<T>>, DispatchError> {
 		match verdict {
-			Verdict::Exempt => return Some(ContractInfo::Alive(alive_contract_info)),
+			Verdict::Exempt => return Ok(Some(ContractInfo::Alive(alive_contract_info))),
 			Verdict::Kill => {
+				Storage::<T>::queue_trie_for_deletion(&alive_contract_info)?;
 				<ContractInfoOf<T>>::remove(account);
-				child::kill_storage(
-					&alive_contract_info.child_trie_info(),
-					None,
-				);
+				wasm::decrement_refcount::<T>(&alive_contract_info.code_hash);
 				<Module<T>>::deposit_event(RawEvent::Evicted(account.clone(), false));
-				None
+				Ok(None)
 			}
 			Verdict::Evict { amount } => {
+				Storage::<T>::queue_trie_for_deletion(&alive_contract_info)?;
 				if let Some(amount) = amount {
 					amount.withdraw(account);
 				}
@@ -262,13 +264,8 @@ where
 				let tombstone_info = ContractInfo::Tombstone(tombstone);
 				<ContractInfoOf<T>>::insert(account, &tombstone_info);
 
-				child::kill_storage(
-					&alive_contract_info.child_trie_info(),
-					None,
-				);
-
 				<Module<T>>::deposit_event(RawEvent::Evicted(account.clone(), true));
-				Some(tombstone_info)
+				Ok(Some(tombstone_info))
 			}
 			Verdict::Charge { amount } => {
 				let contract_info = ContractInfo::Alive(AliveContractInfo::<T> {
@@ -279,7 +276,7 @@ where
 				<ContractInfoOf<T>>::insert(account, &contract_info);
 
 				amount.withdraw(account);
-				Some(contract_info)
+				Ok(Some(contract_info))
 			}
 		}
 	}
@@ -288,10 +285,10 @@ where
 	///
 	/// NOTE this function performs eviction eagerly. All changes are read and written directly to
 	/// storage.
-	pub fn collect(account: &T::AccountId) -> Option<ContractInfo<T>> {
+	pub fn collect(account: &T::AccountId) -> Result<Option<ContractInfo<T>>, DispatchError> {
 		let contract_info = <ContractInfoOf<T>>::get(account);
 		let alive_contract_info = match contract_info {
-			None | Some(ContractInfo::Tombstone(_)) => return contract_info,
+			None | Some(ContractInfo::Tombstone(_)) => return Ok(contract_info),
 			Some(ContractInfo::Alive(contract)) => contract,
 		};
 
@@ -308,7 +305,8 @@ where
 	/// Process a report that a contract under the given address should be evicted.
 	///
 	/// Enact the eviction right away if the contract should be evicted and return true.
-	/// Otherwise, **do nothing** and return false.
+	/// Otherwise, **do nothing** and return false. An error is returned when the contract
+	/// should be evicted but its storage can't be queued for deletion.
 	///
 	/// The `handicap` parameter gives a way to check the rent to a moment in the past instead
 	/// of current block. E.g. if the contract is going to be evicted at the current block,
@@ -320,10 +318,10 @@ where
 	pub fn snitch_contract_should_be_evicted(
 		account: &T::AccountId,
 		handicap: T::BlockNumber,
-	) -> bool {
+	) -> Result<bool, DispatchError> {
 		let contract_info = <ContractInfoOf<T>>::get(account);
 		let alive_contract_info = match contract_info {
-			None | Some(ContractInfo::Tombstone(_)) => return false,
+			None | Some(ContractInfo::Tombstone(_)) => return Ok(false),
 			Some(ContractInfo::Alive(contract)) => contract,
 		};
 		let current_block_number = <frame_system::Module<T>>::block_number();
@@ -337,10 +335,10 @@ where
 		// Enact the verdict only if the contract gets removed.
 		match verdict {
 			Verdict::Kill | Verdict::Evict { .. } => {
-				Self::enact_verdict(account, alive_contract_info, current_block_number, verdict);
-				true
+				Self::enact_verdict(account, alive_contract_info, current_block_number, verdict)?;
+				Ok(true)
 			}
-			_ => false,
+			_ => Ok(false),
 		}
 	}
 
@@ -370,8 +368,11 @@ where
 			Zero::zero(),
 			&alive_contract_info,
 		);
+		// Enacting the verdict only fails when the contract is to be removed while the deletion
+		// queue is full.
 		let new_contract_info =
-			Self::enact_verdict(account, alive_contract_info, current_block_number, verdict);
+			Self::enact_verdict(account, alive_contract_info, current_block_number, verdict)
+				.map_err(|_| ContractAccessError::DeletionQueueFull)?;
 
 		// Check what happened after enaction of the verdict.
 		let alive_contract_info = match new_contract_info {
@@ -478,7 +479,10 @@ where
 			.map(|(_, value)| value.len() as u32)
 			.sum::<u32>();
 
+		// The tombstone keeps using the code of the restored contract while the origin
+		// contract goes away.
 		<ContractInfoOf<T>>::remove(&origin);
+		wasm::decrement_refcount::<T>(&origin_contract.code_hash);
 		<ContractInfoOf<T>>::insert(&dest, ContractInfo::Alive(AliveContractInfo::<T> {
 			trie_id: origin_contract.trie_id,
 			storage_size: origin_contract.storage_size,
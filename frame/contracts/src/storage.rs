@@ -19,20 +19,35 @@
 use crate::{
 	exec::{AccountIdOf, StorageKey},
 	AliveContractInfo, BalanceOf, CodeHash, ContractInfo, ContractInfoOf, Config, TrieId,
-	AccountCounter,
+	AccountCounter, DeletionQueue, Error, WeightInfo, wasm,
 };
+use codec::{Encode, Decode};
 use sp_std::prelude::*;
 use sp_std::marker::PhantomData;
 use sp_io::hashing::blake2_256;
-use sp_runtime::traits::Bounded;
+use sp_runtime::{DispatchResult, traits::Bounded};
 use sp_core::crypto::UncheckedFrom;
-use frame_support::{storage::child, StorageMap};
+use frame_support::{
+	StorageMap, StorageValue,
+	storage::child::{self, KillOutcome},
+	traits::Get,
+	weights::Weight,
+};
 
 /// An error that means that the account requested either doesn't exist or represents a tombstone
 /// account.
 #[cfg_attr(test, derive(PartialEq, Eq, Debug))]
 pub struct ContractAbsentError;
 
+/// The storage trie of a removed contract which still needs to be deleted.
+#[derive(Encode, Decode)]
+pub struct DeletedContract {
+	/// The number of key-value pairs which are left in the trie.
+	pair_count: u32,
+	/// The trie which is to be deleted.
+	trie_id: TrieId,
+}
+
 pub struct Storage<T>(PhantomData<T>);
 
 impl<T> Storage<T>
@@ -162,6 +177,7 @@ where
 	/// Creates a new contract descriptor in the storage with the given code hash at the given address.
 	///
 	/// Returns `Err` if there is already a contract (or a tombstone) exists at the given address.
+	/// Otherwise the new contract is counted as a user of the code.
 	pub fn place_contract(
 		account: &AccountIdOf<T>,
 		trie_id: TrieId,
@@ -185,23 +201,112 @@ where
 				}
 				.into(),
 			);
+			wasm::increment_refcount::<T>(&ch);
 
 			Ok(())
 		})
 	}
 
-	/// Removes the contract and all the storage associated with it.
+	/// Removes the contract and queues the storage associated with it for deletion.
+	///
+	/// This function doesn't affect the account. The code of the contract is removed when
+	/// this was the last contract using it. Nothing is changed if the deletion queue is full.
+	pub fn destroy_contract(address: &AccountIdOf<T>) -> DispatchResult {
+		if let Some(ContractInfo::Alive(info)) = <ContractInfoOf<T>>::get(address) {
+			Self::queue_trie_for_deletion(&info)?;
+			<ContractInfoOf<T>>::remove(address);
+			wasm::decrement_refcount::<T>(&info.code_hash);
+		}
+		Ok(())
+	}
+
+	/// Pushes the storage trie of the given contract to the deletion queue.
 	///
-	/// This function doesn't affect the account.
-	pub fn destroy_contract(address: &AccountIdOf<T>, trie_id: &TrieId) {
-		<ContractInfoOf<T>>::remove(address);
-		child::kill_storage(&crate::child_trie_info(&trie_id), None);
+	/// The trie is deleted lazily by `process_deletion_queue_batch` in later blocks. Fails with
+	/// `DeletionQueueFull` if the queue already holds `DeletionQueueDepth` tries.
+	pub fn queue_trie_for_deletion(contract: &AliveContractInfo<T>) -> DispatchResult {
+		let queue_len = <DeletionQueue>::decode_len().unwrap_or(0);
+		if queue_len >= T::DeletionQueueDepth::get() as usize {
+			return Err(Error::<T>::DeletionQueueFull.into());
+		}
+		<DeletionQueue>::append(DeletedContract {
+			pair_count: contract.total_pair_count,
+			trie_id: contract.trie_id.clone(),
+		});
+		Ok(())
+	}
+
+	/// Calculates the weight needed to delete one key and how many keys can be deleted from
+	/// a deletion queue of `queue_len` tries within `weight_limit`.
+	pub fn deletion_budget(queue_len: usize, weight_limit: Weight) -> (Weight, u32) {
+		let base_weight = T::WeightInfo::on_initialize();
+		let weight_per_queue_item = T::WeightInfo::on_initialize_per_queue_item(1)
+			.saturating_sub(T::WeightInfo::on_initialize_per_queue_item(0));
+		let weight_per_key = T::WeightInfo::on_initialize_per_trie_item(1)
+			.saturating_sub(T::WeightInfo::on_initialize_per_trie_item(0));
+		let decoding_weight = weight_per_queue_item.saturating_mul(queue_len as Weight);
+
+		// A `weight_per_key` of zero would mean that the benchmarks are broken. We rather
+		// delete no keys at all in this case than an unbounded amount.
+		let key_budget = weight_limit
+			.saturating_sub(base_weight)
+			.saturating_sub(decoding_weight)
+			.checked_div(weight_per_key)
+			.unwrap_or(0)
+			.min(u32::max_value() as Weight) as u32;
+
+		(weight_per_key, key_budget)
+	}
+
+	/// Deletes as many keys of the queued tries as fit into `weight_limit` and returns the
+	/// weight that was actually used.
+	pub fn process_deletion_queue_batch(weight_limit: Weight) -> Weight {
+		let queue_len = <DeletionQueue>::decode_len().unwrap_or(0);
+		if queue_len == 0 {
+			return T::WeightInfo::on_initialize();
+		}
+
+		let (weight_per_key, mut remaining_key_budget) = Self::deletion_budget(
+			queue_len,
+			weight_limit,
+		);
+
+		// There might not even be enough weight to decode the queue, e.g. when a runtime
+		// upgrade used up the whole block. The queue is then processed in a later block.
+		if remaining_key_budget == 0 {
+			return T::WeightInfo::on_initialize();
+		}
+
+		let mut queue = <DeletionQueue>::get();
+
+		while !queue.is_empty() && remaining_key_budget > 0 {
+			let trie = &mut queue[0];
+			let outcome = child::kill_storage(
+				&crate::child_trie_info(&trie.trie_id),
+				Some(remaining_key_budget),
+			);
+			match outcome {
+				KillOutcome::AllRemoved => {
+					remaining_key_budget = remaining_key_budget.saturating_sub(trie.pair_count);
+					// The order doesn't matter because nobody waits for the deletion of a
+					// specific trie.
+					queue.swap_remove(0);
+				}
+				KillOutcome::SomeRemaining => {
+					// The whole budget was spent on this trie.
+					trie.pair_count = trie.pair_count.saturating_sub(remaining_key_budget);
+					remaining_key_budget = 0;
+				}
+			}
+		}
+
+		<DeletionQueue>::put(queue);
+		weight_limit.saturating_sub(weight_per_key.saturating_mul(remaining_key_budget as Weight))
 	}
 
 	/// This generator uses inner counter for account id and applies the hash over `AccountId +
 	/// accountid_counter`.
 	pub fn generate_trie_id(account_id: &AccountIdOf<T>) -> TrieId {
-		use frame_support::StorageValue;
 		use sp_runtime::traits::Hash;
 		// Note that skipping a value due to error is not an issue here.
 		// We only need uniqueness, not sequence.
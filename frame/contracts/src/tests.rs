@@ -17,21 +17,28 @@
 use crate::{
 	BalanceOf, ContractInfo, ContractInfoOf, GenesisConfig, Module,
 	RawAliveContractInfo, RawEvent, Config, Schedule, gas::Gas,
-	Error, ConfigCache, RuntimeReturnCode, storage::Storage,
-	exec::AccountIdOf,
+	Error, ConfigCache, RuntimeReturnCode, storage::Storage, CodeStorage, PristineCode,
+	CodeInfoOf, DeletionQueue, exec::AccountIdOf,
+	chain_extension::{
+		ChainExtension, Environment, Ext, SysConfig, RetVal,
+		UncheckedFrom, Result as ExtensionResult, ReturnFlags,
+	},
 };
+use std::cell::RefCell;
 use assert_matches::assert_matches;
-use codec::Encode;
+use codec::{Encode, Decode};
+use pallet_contracts_primitives::{Code, ContractAccessError};
 use sp_runtime::{
 	traits::{BlakeTwo256, Hash, IdentityLookup, Convert},
 	testing::{Header, H256},
 	AccountId32,
 };
 use frame_support::{
-	assert_ok, assert_err_ignore_postinfo, impl_outer_dispatch, impl_outer_event,
-	impl_outer_origin, parameter_types, StorageMap,
-	traits::{Currency, ReservableCurrency},
-	weights::{Weight, PostDispatchInfo},
+	assert_ok, assert_noop, assert_err_ignore_postinfo, impl_outer_dispatch, impl_outer_event,
+	impl_outer_origin, parameter_types, StorageMap, StorageValue,
+	storage::child,
+	traits::{Currency, ReservableCurrency, OnInitialize},
+	weights::{Weight, PostDispatchInfo, DispatchClass},
 	dispatch::DispatchErrorWithPostInfo,
 };
 use frame_system::{self as system, EventRecord, Phase};
@@ -158,6 +165,10 @@ parameter_types! {
 	pub const SurchargeReward: u64 = 150;
 	pub const MaxDepth: u32 = 100;
 	pub const MaxValueSize: u32 = 16_384;
+	pub const MaxDebugBufferLen: u32 = 16;
+	pub static CodeDepositPerByte: u64 = 0;
+	pub const DeletionQueueDepth: u32 = 1024;
+	pub const DeletionWeightLimit: Weight = 500_000_000_000;
 }
 
 parameter_types! {
@@ -170,6 +181,77 @@ impl Convert<Weight, BalanceOf<Self>> for Test {
 	}
 }
 
+thread_local! {
+	static TEST_EXTENSION: RefCell<TestExtension> = Default::default();
+}
+
+/// The chain extension of the test runtime.
+///
+/// The first byte of the input selects the function:
+///
+/// - `0`: Write the input to the output.
+/// - `1`: Charge the weight given by the second byte of the input.
+/// - `2`: Revert the execution of the contract with the output `[42, 99]`.
+/// - `3`: Write the caller of the contract to the output.
+pub struct TestExtension {
+	enabled: bool,
+	last_seen_buffer: Vec<u8>,
+}
+
+impl TestExtension {
+	fn disable() {
+		TEST_EXTENSION.with(|e| e.borrow_mut().enabled = false)
+	}
+
+	fn last_seen_buffer() -> Vec<u8> {
+		TEST_EXTENSION.with(|e| e.borrow().last_seen_buffer.clone())
+	}
+}
+
+impl Default for TestExtension {
+	fn default() -> Self {
+		Self {
+			enabled: true,
+			last_seen_buffer: vec![],
+		}
+	}
+}
+
+impl ChainExtension<Test> for TestExtension {
+	fn call<E>(func_id: u32, mut env: Environment<E>) -> ExtensionResult<RetVal>
+	where
+		E: Ext<T = Test>,
+		<E::T as SysConfig>::AccountId: UncheckedFrom<<E::T as SysConfig>::Hash> + AsRef<[u8]>,
+	{
+		match func_id {
+			0 => {
+				let input = env.read(env.in_len())?;
+				env.write(&input, false, None)?;
+				TEST_EXTENSION.with(|e| e.borrow_mut().last_seen_buffer = input);
+			},
+			1 => {
+				let weight = env.read(2)?[1].into();
+				env.charge_weight(weight)?;
+			},
+			2 => {
+				return Ok(RetVal::Diverging { flags: ReturnFlags::REVERT, data: vec![42, 99] })
+			},
+			3 => {
+				let caller = env.caller().encode();
+				env.write(&caller, false, None)?;
+			},
+			_ => {
+				panic!("Passed unknown func_id to test chain extension: {}", func_id);
+			}
+		}
+		Ok(RetVal::Converging(func_id))
+	}
+
+	fn enabled() -> bool {
+		TEST_EXTENSION.with(|e| e.borrow().enabled)
+	}
+}
+
 impl Config for Test {
 	type Time = Timestamp;
 	type Randomness = Randomness;
@@ -182,10 +264,15 @@ impl Config for Test {
 	type RentByteFee = RentByteFee;
 	type RentDepositOffset = RentDepositOffset;
 	type SurchargeReward = SurchargeReward;
+	type CodeDepositPerByte = CodeDepositPerByte;
 	type MaxDepth = MaxDepth;
 	type MaxValueSize = MaxValueSize;
+	type MaxDebugBufferLen = MaxDebugBufferLen;
+	type DeletionQueueDepth = DeletionQueueDepth;
+	type DeletionWeightLimit = DeletionWeightLimit;
 	type WeightPrice = Self;
 	type WeightInfo = ();
+	type ChainExtension = TestExtension;
 }
 
 type Balances = pallet_balances::Module<Test>;
@@ -203,11 +290,13 @@ const GAS_LIMIT: Gas = 10_000_000_000;
 
 pub struct ExtBuilder {
 	existential_deposit: u64,
+	code_deposit_per_byte: u64,
 }
 impl Default for ExtBuilder {
 	fn default() -> Self {
 		Self {
 			existential_deposit: 1,
+			code_deposit_per_byte: 0,
 		}
 	}
 }
@@ -216,8 +305,13 @@ impl ExtBuilder {
 		self.existential_deposit = existential_deposit;
 		self
 	}
+	pub fn code_deposit_per_byte(mut self, code_deposit_per_byte: u64) -> Self {
+		self.code_deposit_per_byte = code_deposit_per_byte;
+		self
+	}
 	pub fn set_associated_consts(&self) {
 		EXISTENTIAL_DEPOSIT.with(|v| *v.borrow_mut() = self.existential_deposit);
+		CODE_DEPOSIT_PER_BYTE.with(|v| *v.borrow_mut() = self.code_deposit_per_byte);
 	}
 	pub fn build(self) -> sp_io::TestExternalities {
 		self.set_associated_consts();
@@ -1329,7 +1423,14 @@ fn restoration(test_different_storage: bool, test_restore_to_with_dirty_storage:
 				assert_eq!(bob_contract.trie_id, django_trie_id);
 				assert_eq!(bob_contract.deduct_block, System::block_number());
 				assert!(ContractInfoOf::<Test>::get(&addr_django).is_none());
+				// `DJANGO` was the last contract using the restoration code.
+				assert!(!CodeStorage::<Test>::contains_key(&restoration_code_hash));
 				assert_eq!(System::events(), vec![
+					EventRecord {
+						phase: Phase::Initialization,
+						event: MetaEvent::contracts(RawEvent::CodeRemoved(restoration_code_hash)),
+						topics: vec![],
+					},
 					EventRecord {
 						phase: Phase::Initialization,
 						event: MetaEvent::system(system::RawEvent::KilledAccount(addr_django.clone())),
@@ -1691,6 +1792,7 @@ fn crypto_hashes() {
 					0,
 					GAS_LIMIT,
 					params,
+					false,
 				).exec_result.unwrap();
 				assert!(result.is_success());
 				let expected = hash_fn(input.as_ref());
@@ -1726,6 +1828,7 @@ fn transfer_return_code() {
 			0,
 			GAS_LIMIT,
 			vec![],
+			false,
 		).exec_result.unwrap();
 		assert_return_code!(result, RuntimeReturnCode::BelowSubsistenceThreshold);
 
@@ -1740,6 +1843,7 @@ fn transfer_return_code() {
 			0,
 			GAS_LIMIT,
 			vec![],
+			false,
 		).exec_result.unwrap();
 		assert_return_code!(result, RuntimeReturnCode::TransferFailed);
 	});
@@ -1775,6 +1879,7 @@ fn call_return_code() {
 			0,
 			GAS_LIMIT,
 			AsRef::<[u8]>::as_ref(&DJANGO).to_vec(),
+			false,
 		).exec_result.unwrap();
 		assert_return_code!(result, RuntimeReturnCode::NotCallable);
 
@@ -1797,6 +1902,7 @@ fn call_return_code() {
 			0,
 			GAS_LIMIT,
 			AsRef::<[u8]>::as_ref(&addr_django).iter().chain(&0u32.to_le_bytes()).cloned().collect(),
+			false,
 		).exec_result.unwrap();
 		assert_return_code!(result, RuntimeReturnCode::BelowSubsistenceThreshold);
 
@@ -1811,6 +1917,7 @@ fn call_return_code() {
 			0,
 			GAS_LIMIT,
 			AsRef::<[u8]>::as_ref(&addr_django).iter().chain(&0u32.to_le_bytes()).cloned().collect(),
+			false,
 		).exec_result.unwrap();
 		assert_return_code!(result, RuntimeReturnCode::TransferFailed);
 
@@ -1822,6 +1929,7 @@ fn call_return_code() {
 			0,
 			GAS_LIMIT,
 			AsRef::<[u8]>::as_ref(&addr_django).iter().chain(&1u32.to_le_bytes()).cloned().collect(),
+			false,
 		).exec_result.unwrap();
 		assert_return_code!(result, RuntimeReturnCode::CalleeReverted);
 
@@ -1832,6 +1940,7 @@ fn call_return_code() {
 			0,
 			GAS_LIMIT,
 			AsRef::<[u8]>::as_ref(&addr_django).iter().chain(&2u32.to_le_bytes()).cloned().collect(),
+			false,
 		).exec_result.unwrap();
 		assert_return_code!(result, RuntimeReturnCode::CalleeTrapped);
 
@@ -1869,6 +1978,7 @@ fn instantiate_return_code() {
 			0,
 			GAS_LIMIT,
 			vec![0; 33],
+			false,
 		).exec_result.unwrap();
 		assert_return_code!(result, RuntimeReturnCode::BelowSubsistenceThreshold);
 
@@ -1883,6 +1993,7 @@ fn instantiate_return_code() {
 			0,
 			GAS_LIMIT,
 			vec![0; 33],
+			false,
 		).exec_result.unwrap();
 		assert_return_code!(result, RuntimeReturnCode::TransferFailed);
 
@@ -1894,6 +2005,7 @@ fn instantiate_return_code() {
 			0,
 			GAS_LIMIT,
 			vec![0; 33],
+			false,
 		).exec_result.unwrap();
 		assert_return_code!(result, RuntimeReturnCode::CodeNotFound);
 
@@ -1904,6 +2016,7 @@ fn instantiate_return_code() {
 			0,
 			GAS_LIMIT,
 			callee_hash.iter().chain(&1u32.to_le_bytes()).cloned().collect(),
+			false,
 		).exec_result.unwrap();
 		assert_return_code!(result, RuntimeReturnCode::CalleeReverted);
 
@@ -1914,8 +2027,543 @@ fn instantiate_return_code() {
 			0,
 			GAS_LIMIT,
 			callee_hash.iter().chain(&2u32.to_le_bytes()).cloned().collect(),
+			false,
 		).exec_result.unwrap();
 		assert_return_code!(result, RuntimeReturnCode::CalleeTrapped);
 
 	});
 }
+
+#[test]
+fn disabled_chain_extension_wont_deploy() {
+	let (code, _hash) = compile_module::<Test>("chain_extension").unwrap();
+	ExtBuilder::default().existential_deposit(50).build().execute_with(|| {
+		let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+		TestExtension::disable();
+		assert_eq!(
+			Contracts::put_code(Origin::signed(ALICE), code),
+			Err("module uses chain extensions but chain extensions are disabled".into()),
+		);
+	});
+}
+
+#[test]
+fn disabled_chain_extension_errors_on_call() {
+	let (code, hash) = compile_module::<Test>("chain_extension").unwrap();
+	ExtBuilder::default().existential_deposit(50).build().execute_with(|| {
+		let subsistence = ConfigCache::<Test>::subsistence_threshold_uncached();
+		let _ = Balances::deposit_creating(&ALICE, 10 * subsistence);
+		assert_ok!(Contracts::put_code(Origin::signed(ALICE), code));
+		assert_ok!(Contracts::instantiate(
+			Origin::signed(ALICE),
+			subsistence,
+			GAS_LIMIT,
+			hash.into(),
+			vec![],
+			vec![],
+		));
+		let addr = Contracts::contract_address(&ALICE, &hash, &[]);
+		TestExtension::disable();
+		let result = Contracts::bare_call(ALICE, addr, 0, GAS_LIMIT, vec![0], false);
+		assert_eq!(result.exec_result.unwrap_err().error, Error::<Test>::NoChainExtension.into());
+	});
+}
+
+#[test]
+fn chain_extension_works() {
+	let (code, hash) = compile_module::<Test>("chain_extension").unwrap();
+	ExtBuilder::default().existential_deposit(50).build().execute_with(|| {
+		let subsistence = ConfigCache::<Test>::subsistence_threshold_uncached();
+		let _ = Balances::deposit_creating(&ALICE, 10 * subsistence);
+		assert_ok!(Contracts::put_code(Origin::signed(ALICE), code));
+		assert_ok!(Contracts::instantiate(
+			Origin::signed(ALICE),
+			subsistence,
+			GAS_LIMIT,
+			hash.into(),
+			vec![],
+			vec![],
+		));
+		let addr = Contracts::contract_address(&ALICE, &hash, &[]);
+
+		// The extension reads the input and writes it to the output.
+		let input: Vec<u8> = vec![0, 1, 2, 3];
+		let result = Contracts::bare_call(ALICE, addr.clone(), 0, GAS_LIMIT, input.clone(), false);
+		assert_eq!(TestExtension::last_seen_buffer(), input);
+		assert_eq!(result.exec_result.unwrap().data, input);
+
+		// The extension charges the weight given by the second byte of the input.
+		let gas_consumed = Contracts::bare_call(
+			ALICE,
+			addr.clone(),
+			0,
+			GAS_LIMIT,
+			vec![1, 0],
+			false,
+		).gas_consumed;
+		let result = Contracts::bare_call(ALICE, addr.clone(), 0, GAS_LIMIT, vec![1, 42], false);
+		assert_ok!(result.exec_result);
+		assert_eq!(result.gas_consumed, gas_consumed + 42);
+
+		// The extension diverges and reverts the execution of the contract.
+		let result = Contracts::bare_call(
+			ALICE,
+			addr.clone(),
+			0,
+			GAS_LIMIT,
+			vec![2],
+			false,
+		).exec_result.unwrap();
+		assert_eq!(result.flags, ReturnFlags::REVERT);
+		assert_eq!(result.data, vec![42, 99]);
+
+		// The extension has access to the caller of the contract.
+		let result = Contracts::bare_call(
+			ALICE,
+			addr.clone(),
+			0,
+			GAS_LIMIT,
+			vec![3],
+			false,
+		).exec_result.unwrap();
+		assert_eq!(result.data, ALICE.encode());
+	});
+}
+
+#[test]
+fn instantiate_with_code_works() {
+	let (wasm, code_hash) = compile_module::<Test>("return_from_start_fn").unwrap();
+	ExtBuilder::default().existential_deposit(50).build().execute_with(|| {
+		let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+		let subsistence = ConfigCache::<Test>::subsistence_threshold_uncached();
+
+		assert_ok!(Contracts::instantiate_with_code(
+			Origin::signed(ALICE),
+			subsistence,
+			GAS_LIMIT,
+			wasm,
+			vec![],
+			vec![],
+		));
+		let addr = Contracts::contract_address(&ALICE, &code_hash, &[]);
+
+		assert!(CodeStorage::<Test>::contains_key(&code_hash));
+		assert!(ContractInfoOf::<Test>::contains_key(&addr));
+		let events = System::events();
+		assert!(events.iter().any(|record|
+			record.event == MetaEvent::contracts(RawEvent::CodeStored(code_hash))
+		));
+		assert_eq!(
+			events.last().unwrap().event,
+			MetaEvent::contracts(RawEvent::Instantiated(ALICE, addr)),
+		);
+	});
+}
+
+#[test]
+fn bare_instantiate_works() {
+	let (wasm, code_hash) = compile_module::<Test>("return_from_start_fn").unwrap();
+	ExtBuilder::default().existential_deposit(50).code_deposit_per_byte(2).build().execute_with(|| {
+		let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+		let subsistence = ConfigCache::<Test>::subsistence_threshold_uncached();
+		let deposit = 2 * wasm.len() as u64;
+
+		// The code is stored as part of the instantiation.
+		let result = Contracts::bare_instantiate(
+			ALICE,
+			subsistence,
+			GAS_LIMIT,
+			Code::Upload(wasm),
+			vec![],
+			vec![],
+			true,
+			true,
+			false,
+		);
+		let addr = Contracts::contract_address(&ALICE, &code_hash, &[]);
+		assert!(result.gas_consumed > 0);
+		assert_eq!(result.storage_deposit, deposit);
+		let value = result.exec_result.unwrap();
+		assert!(value.result.is_success());
+		assert_eq!(value.account_id, addr);
+		assert!(value.rent_projection.is_some());
+		assert!(CodeStorage::<Test>::contains_key(&code_hash));
+
+		// Only the events deposited during the instantiation are collected.
+		let events = result.events
+			.iter()
+			.map(|record| EventRecord::<MetaEvent, H256>::decode(&mut &record[..]).unwrap().event)
+			.collect::<Vec<_>>();
+		assert_eq!(events.first(), Some(&MetaEvent::contracts(RawEvent::CodeStored(code_hash))));
+		assert_eq!(
+			events.last(),
+			Some(&MetaEvent::contracts(RawEvent::Instantiated(ALICE, addr))),
+		);
+		assert_eq!(events.len() + 2, System::events().len());
+
+		// Instantiate from the already stored code without collecting events or projection.
+		let result = Contracts::bare_instantiate(
+			ALICE,
+			subsistence,
+			GAS_LIMIT,
+			Code::Existing(code_hash),
+			vec![],
+			vec![1],
+			false,
+			false,
+			false,
+		);
+		assert_eq!(result.storage_deposit, 0);
+		let value = result.exec_result.unwrap();
+		assert_eq!(value.account_id, Contracts::contract_address(&ALICE, &code_hash, &[1]));
+		assert_eq!(value.rent_projection, None);
+		assert!(result.events.is_empty());
+	});
+}
+
+#[test]
+fn bare_upload_code_works() {
+	let (wasm, code_hash) = compile_module::<Test>("return_from_start_fn").unwrap();
+	ExtBuilder::default()
+		.existential_deposit(50)
+		.code_deposit_per_byte(2)
+		.build()
+		.execute_with(|| {
+			let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+			let deposit = 2 * wasm.len() as u64;
+
+			let result = Contracts::bare_upload_code(ALICE, wasm.clone()).unwrap();
+			assert_eq!(result.code_hash, code_hash);
+			assert_eq!(result.deposit, deposit);
+			assert!(CodeStorage::<Test>::contains_key(&code_hash));
+
+			// Uploading the same code again is free.
+			assert_eq!(Contracts::bare_upload_code(ALICE, wasm).unwrap().deposit, 0);
+			assert_eq!(Balances::reserved_balance(&ALICE), deposit);
+			assert_eq!(
+				Contracts::bare_upload_code(ALICE, vec![1, 2, 3]),
+				Err("Can't decode wasm code".into()),
+			);
+		});
+}
+
+#[test]
+fn remove_code_works() {
+	let (wasm, code_hash) = compile_module::<Test>("return_from_start_fn").unwrap();
+	ExtBuilder::default()
+		.existential_deposit(50)
+		.code_deposit_per_byte(2)
+		.build()
+		.execute_with(|| {
+			let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+			let _ = Balances::deposit_creating(&BOB, 1_000_000);
+			let deposit = 2 * wasm.len() as u64;
+
+			assert_ok!(Contracts::put_code(Origin::signed(ALICE), wasm.clone()));
+			assert_eq!(Balances::reserved_balance(&ALICE), deposit);
+			assert_eq!(CodeInfoOf::<Test>::get(&code_hash).unwrap().refcount, 0);
+
+			// Storing the same code again neither reserves a deposit nor changes the owner.
+			assert_ok!(Contracts::put_code(Origin::signed(BOB), wasm));
+			assert_eq!(Balances::reserved_balance(&BOB), 0);
+			assert_eq!(CodeInfoOf::<Test>::get(&code_hash).unwrap().owner, ALICE);
+
+			assert_noop!(
+				Contracts::remove_code(Origin::signed(BOB), code_hash),
+				Error::<Test>::NotCodeOwner,
+			);
+			assert_ok!(Contracts::remove_code(Origin::signed(ALICE), code_hash));
+			assert_eq!(Balances::reserved_balance(&ALICE), 0);
+			assert_eq!(Balances::free_balance(&ALICE), 1_000_000);
+			assert!(!CodeStorage::<Test>::contains_key(&code_hash));
+			assert!(!PristineCode::<Test>::contains_key(&code_hash));
+			assert!(!CodeInfoOf::<Test>::contains_key(&code_hash));
+			assert_eq!(
+				System::events().last().unwrap().event,
+				MetaEvent::contracts(RawEvent::CodeRemoved(code_hash)),
+			);
+			assert_noop!(
+				Contracts::remove_code(Origin::signed(ALICE), code_hash),
+				Error::<Test>::CodeNotFound,
+			);
+		});
+}
+
+#[test]
+fn remove_code_in_use_fails() {
+	let (wasm, code_hash) = compile_module::<Test>("return_from_start_fn").unwrap();
+	ExtBuilder::default()
+		.existential_deposit(50)
+		.code_deposit_per_byte(2)
+		.build()
+		.execute_with(|| {
+			let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+			let subsistence = ConfigCache::<Test>::subsistence_threshold_uncached();
+
+			assert_ok!(Contracts::instantiate_with_code(
+				Origin::signed(ALICE),
+				subsistence,
+				GAS_LIMIT,
+				wasm,
+				vec![],
+				vec![],
+			));
+			assert_eq!(CodeInfoOf::<Test>::get(&code_hash).unwrap().refcount, 1);
+			assert_noop!(
+				Contracts::remove_code(Origin::signed(ALICE), code_hash),
+				Error::<Test>::CodeInUse,
+			);
+		});
+}
+
+#[test]
+fn code_is_removed_with_last_contract() {
+	let (wasm, code_hash) = compile_module::<Test>("self_destruct").unwrap();
+	ExtBuilder::default()
+		.existential_deposit(50)
+		.code_deposit_per_byte(2)
+		.build()
+		.execute_with(|| {
+			let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+			assert_ok!(Contracts::put_code(Origin::signed(ALICE), wasm.clone()));
+			assert_eq!(Balances::reserved_balance(&ALICE), 2 * wasm.len() as u64);
+
+			// Instantiate two contracts from the same code.
+			for salt in &[vec![0], vec![1]] {
+				assert_ok!(Contracts::instantiate(
+					Origin::signed(ALICE),
+					100_000,
+					GAS_LIMIT,
+					code_hash.into(),
+					vec![],
+					salt.clone(),
+				));
+			}
+			let addr_a = Contracts::contract_address(&ALICE, &code_hash, &[0]);
+			let addr_b = Contracts::contract_address(&ALICE, &code_hash, &[1]);
+			assert_eq!(CodeInfoOf::<Test>::get(&code_hash).unwrap().refcount, 2);
+
+			// Terminating the first contract keeps the code around.
+			assert_ok!(Contracts::call(Origin::signed(ALICE), addr_a, 0, GAS_LIMIT, vec![]));
+			assert_eq!(CodeInfoOf::<Test>::get(&code_hash).unwrap().refcount, 1);
+			assert!(CodeStorage::<Test>::contains_key(&code_hash));
+
+			// Terminating the last contract removes the code and refunds the deposit.
+			assert_ok!(Contracts::call(Origin::signed(ALICE), addr_b, 0, GAS_LIMIT, vec![]));
+			assert!(!CodeStorage::<Test>::contains_key(&code_hash));
+			assert!(!PristineCode::<Test>::contains_key(&code_hash));
+			assert!(!CodeInfoOf::<Test>::contains_key(&code_hash));
+			assert_eq!(Balances::reserved_balance(&ALICE), 0);
+		});
+}
+
+#[test]
+fn debug_message_works() {
+	let (wasm, code_hash) = compile_module::<Test>("debug_message").unwrap();
+	ExtBuilder::default().existential_deposit(50).build().execute_with(|| {
+		let subsistence = ConfigCache::<Test>::subsistence_threshold_uncached();
+		let _ = Balances::deposit_creating(&ALICE, 10 * subsistence);
+		assert_ok!(Contracts::put_code(Origin::signed(ALICE), wasm));
+		assert_ok!(Contracts::instantiate(
+			Origin::signed(ALICE),
+			subsistence,
+			GAS_LIMIT,
+			code_hash.into(),
+			vec![],
+			vec![],
+		));
+		let addr = Contracts::contract_address(&ALICE, &code_hash, &[]);
+
+		// Debug message recording is disabled.
+		let result = Contracts::bare_call(
+			ALICE,
+			addr.clone(),
+			0,
+			GAS_LIMIT,
+			b"Hello World!".to_vec(),
+			false,
+		);
+		assert_return_code!(result.exec_result.unwrap(), RuntimeReturnCode::LoggingDisabled);
+		assert!(result.debug_message.is_empty());
+
+		// Debug message recording is enabled.
+		let result = Contracts::bare_call(
+			ALICE,
+			addr.clone(),
+			0,
+			GAS_LIMIT,
+			b"Hello World!".to_vec(),
+			true,
+		);
+		assert_return_code!(result.exec_result.unwrap(), RuntimeReturnCode::Success);
+		assert_eq!(std::str::from_utf8(&result.debug_message).unwrap(), "Hello World!");
+
+		// Messages exceeding `MaxDebugBufferLen` are dropped.
+		let result = Contracts::bare_call(
+			ALICE,
+			addr.clone(),
+			0,
+			GAS_LIMIT,
+			vec![b'a'; MaxDebugBufferLen::get() as usize + 1],
+			true,
+		);
+		assert_return_code!(result.exec_result.unwrap(), RuntimeReturnCode::Success);
+		assert!(result.debug_message.is_empty());
+	});
+}
+
+#[test]
+fn debug_message_invalid_utf8() {
+	let (wasm, code_hash) = compile_module::<Test>("debug_message").unwrap();
+	ExtBuilder::default().existential_deposit(50).build().execute_with(|| {
+		let subsistence = ConfigCache::<Test>::subsistence_threshold_uncached();
+		let _ = Balances::deposit_creating(&ALICE, 10 * subsistence);
+		assert_ok!(Contracts::put_code(Origin::signed(ALICE), wasm));
+		assert_ok!(Contracts::instantiate(
+			Origin::signed(ALICE),
+			subsistence,
+			GAS_LIMIT,
+			code_hash.into(),
+			vec![],
+			vec![],
+		));
+		let addr = Contracts::contract_address(&ALICE, &code_hash, &[]);
+
+		let result = Contracts::bare_call(ALICE, addr, 0, GAS_LIMIT, vec![0xfc], true);
+		assert_eq!(
+			result.exec_result.unwrap_err().error,
+			Error::<Test>::DebugMessageInvalidUTF8.into(),
+		);
+	});
+}
+
+#[test]
+fn lazy_removal_works() {
+	let (wasm, code_hash) = compile_module::<Test>("self_destruct").unwrap();
+	ExtBuilder::default().existential_deposit(50).build().execute_with(|| {
+		let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+		assert_ok!(Contracts::instantiate_with_code(
+			Origin::signed(ALICE),
+			100_000,
+			GAS_LIMIT,
+			wasm,
+			vec![],
+			vec![],
+		));
+		let addr = Contracts::contract_address(&ALICE, &code_hash, &[]);
+		let info = ContractInfoOf::<Test>::get(&addr).unwrap().get_alive().unwrap();
+		let trie = &info.child_trie_info();
+
+		// Put a value into the child trie of the contract.
+		child::put(trie, &[99], &42);
+
+		// Terminate the contract.
+		assert_ok!(Contracts::call(Origin::signed(ALICE), addr.clone(), 0, GAS_LIMIT, vec![]));
+
+		// The contract is gone but its storage is still there until the queue is processed.
+		assert!(!ContractInfoOf::<Test>::contains_key(&addr));
+		assert_eq!(DeletionQueue::decode_len(), Some(1));
+		assert_matches!(child::get::<i32>(trie, &[99]), Some(42));
+
+		// Run the lazy removal.
+		Contracts::on_initialize(System::block_number());
+
+		assert_matches!(child::get::<i32>(trie, &[99]), None);
+		assert_eq!(DeletionQueue::decode_len(), Some(0));
+	});
+}
+
+#[test]
+fn lazy_removal_does_not_run_on_full_block() {
+	let (wasm, code_hash) = compile_module::<Test>("self_destruct").unwrap();
+	ExtBuilder::default().existential_deposit(50).build().execute_with(|| {
+		let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+		assert_ok!(Contracts::instantiate_with_code(
+			Origin::signed(ALICE),
+			100_000,
+			GAS_LIMIT,
+			wasm,
+			vec![],
+			vec![],
+		));
+		let addr = Contracts::contract_address(&ALICE, &code_hash, &[]);
+		let info = ContractInfoOf::<Test>::get(&addr).unwrap().get_alive().unwrap();
+		let trie = &info.child_trie_info();
+		child::put(trie, &[99], &42);
+
+		assert_ok!(Contracts::call(Origin::signed(ALICE), addr.clone(), 0, GAS_LIMIT, vec![]));
+
+		// Fill up the block so that no weight is left for the lazy removal.
+		let max_block = <Test as frame_system::Config>::BlockWeights::get().max_block;
+		System::register_extra_weight_unchecked(max_block, DispatchClass::Mandatory);
+
+		Contracts::on_initialize(System::block_number());
+
+		// The value is still there because there was no weight left.
+		assert_matches!(child::get::<i32>(trie, &[99]), Some(42));
+		assert_eq!(DeletionQueue::decode_len(), Some(1));
+	});
+}
+
+#[test]
+fn deletion_queue_full() {
+	let (wasm, code_hash) = compile_module::<Test>("self_destruct").unwrap();
+	ExtBuilder::default().existential_deposit(50).build().execute_with(|| {
+		let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+		assert_ok!(Contracts::instantiate_with_code(
+			Origin::signed(ALICE),
+			100_000,
+			GAS_LIMIT,
+			wasm,
+			vec![],
+			vec![],
+		));
+		let addr = Contracts::contract_address(&ALICE, &code_hash, &[]);
+		let info = ContractInfoOf::<Test>::get(&addr).unwrap().get_alive().unwrap();
+
+		// Fill the deletion queue up to its limit.
+		for _ in 0..DeletionQueueDepth::get() {
+			assert_ok!(Storage::<Test>::queue_trie_for_deletion(&info));
+		}
+		assert_noop!(
+			Storage::<Test>::queue_trie_for_deletion(&info),
+			Error::<Test>::DeletionQueueFull,
+		);
+
+		// Terminating the contract fails and leaves it alive.
+		assert_err_ignore_postinfo!(
+			Contracts::call(Origin::signed(ALICE), addr.clone(), 0, GAS_LIMIT, vec![]),
+			Error::<Test>::DeletionQueueFull,
+		);
+		assert!(ContractInfoOf::<Test>::get(&addr).unwrap().get_alive().is_some());
+	});
+}
+
+#[test]
+fn rent_projection_with_full_deletion_queue() {
+	let (wasm, code_hash) = compile_module::<Test>("set_rent").unwrap();
+	ExtBuilder::default().existential_deposit(50).build().execute_with(|| {
+		let _ = Balances::deposit_creating(&ALICE, 1_000_000);
+		assert_ok!(Contracts::instantiate_with_code(
+			Origin::signed(ALICE),
+			1_000,
+			GAS_LIMIT,
+			wasm,
+			<Test as pallet_balances::Config>::Balance::from(100u32).encode(), // rent allowance
+			vec![],
+		));
+		let addr = Contracts::contract_address(&ALICE, &code_hash, &[]);
+		let info = ContractInfoOf::<Test>::get(&addr).unwrap().get_alive().unwrap();
+		for _ in 0..DeletionQueueDepth::get() {
+			assert_ok!(Storage::<Test>::queue_trie_for_deletion(&info));
+		}
+
+		// The contract exceeds its rent allowance but can't be evicted.
+		initialize_block(10);
+		assert_eq!(
+			Contracts::rent_projection(addr.clone()),
+			Err(ContractAccessError::DeletionQueueFull),
+		);
+		assert!(ContractInfoOf::<Test>::get(&addr).unwrap().get_alive().is_some());
+	});
+}
@@ -25,27 +25,49 @@
 //! - When we update the schedule we want it to have strictly greater version than the current saved one:
 //! this guarantees that every instrumented contract code in cache cannot have the version equal to the current one.
 //! Thus, before executing a contract it should be reinstrument with new schedule.
+//! - Every stored code records its owner, the deposit reserved from the owner and the number
+//! of contracts using it. The code is removed and the deposit refunded when the last contract
+//! using it is removed or when the owner removes it while it is unused.
 
 use crate::wasm::{prepare, runtime::Env, PrefabWasmModule};
-use crate::{CodeHash, CodeStorage, PristineCode, Schedule, Config};
+use crate::{
+	CodeHash, CodeStorage, PristineCode, CodeInfoOf, CodeInfo, Schedule, Config, Error, Module,
+	RawEvent,
+};
 use sp_std::prelude::*;
-use sp_runtime::traits::Hash;
+use sp_runtime::{DispatchError, DispatchResult, traits::{Hash, Saturating}};
 use sp_core::crypto::UncheckedFrom;
-use frame_support::StorageMap;
+use frame_support::{StorageMap, ensure, traits::{Get, ReservableCurrency}};
 
 /// Put code in the storage. The hash of code is used as a key and is returned
 /// as a result of this function.
 ///
-/// This function instruments the given code and caches it in the storage.
+/// This function instruments the given code and caches it in the storage. A deposit
+/// proportional to the size of the code is reserved from `owner`. Saving code which is
+/// already stored leaves the existing code and its owner untouched.
 pub fn save<T: Config>(
 	original_code: Vec<u8>,
+	owner: &T::AccountId,
 	schedule: &Schedule<T>,
-) -> Result<CodeHash<T>, &'static str> where T::AccountId: UncheckedFrom<T::Hash> + AsRef<[u8]> {
-	let prefab_module = prepare::prepare_contract::<Env, T>(&original_code, schedule)?;
+) -> Result<CodeHash<T>, DispatchError> where T::AccountId: UncheckedFrom<T::Hash> + AsRef<[u8]> {
 	let code_hash = T::Hashing::hash(&original_code);
+	if <CodeStorage<T>>::contains_key(&code_hash) {
+		return Ok(code_hash);
+	}
+
+	let prefab_module = prepare::prepare_contract::<Env, T>(&original_code, schedule)?;
+	let deposit = T::CodeDepositPerByte::get()
+		.saturating_mul((original_code.len() as u32).into());
+	T::Currency::reserve(owner, deposit)?;
 
 	<CodeStorage<T>>::insert(code_hash, prefab_module);
 	<PristineCode<T>>::insert(code_hash, original_code);
+	<CodeInfoOf<T>>::insert(code_hash, CodeInfo::<T> {
+		owner: owner.clone(),
+		deposit,
+		refcount: 0,
+	});
+	<Module<T>>::deposit_event(RawEvent::CodeStored(code_hash));
 
 	Ok(code_hash)
 }
@@ -92,3 +114,57 @@ pub fn load<T: Config>(
 	}
 	Ok(prefab_module)
 }
+
+/// Increment the number of contracts using the code stored under `code_hash`.
+///
+/// Code stored before the introduction of deposits isn't reference counted.
+pub fn increment_refcount<T: Config>(code_hash: &CodeHash<T>)
+where
+	T::AccountId: UncheckedFrom<T::Hash> + AsRef<[u8]>
+{
+	<CodeInfoOf<T>>::mutate(code_hash, |info| if let Some(info) = info {
+		info.refcount = info.refcount.saturating_add(1);
+	});
+}
+
+/// Decrement the number of contracts using the code stored under `code_hash`.
+///
+/// The code is removed and the deposit refunded to its owner when the last contract using
+/// it is removed.
+pub fn decrement_refcount<T: Config>(code_hash: &CodeHash<T>)
+where
+	T::AccountId: UncheckedFrom<T::Hash> + AsRef<[u8]>
+{
+	if let Some(mut info) = <CodeInfoOf<T>>::get(code_hash) {
+		info.refcount = info.refcount.saturating_sub(1);
+		if info.refcount == 0 {
+			remove::<T>(code_hash, info);
+		} else {
+			<CodeInfoOf<T>>::insert(code_hash, info);
+		}
+	}
+}
+
+/// Remove the unused code stored under `code_hash` on behalf of its owner `origin`.
+pub fn try_remove<T: Config>(origin: &T::AccountId, code_hash: &CodeHash<T>) -> DispatchResult
+where
+	T::AccountId: UncheckedFrom<T::Hash> + AsRef<[u8]>
+{
+	let info = <CodeInfoOf<T>>::get(code_hash).ok_or(Error::<T>::CodeNotFound)?;
+	ensure!(&info.owner == origin, Error::<T>::NotCodeOwner);
+	ensure!(info.refcount == 0, Error::<T>::CodeInUse);
+	remove::<T>(code_hash, info);
+	Ok(())
+}
+
+/// Remove the code stored under `code_hash` and refund the deposit to its owner.
+fn remove<T: Config>(code_hash: &CodeHash<T>, info: CodeInfo<T>)
+where
+	T::AccountId: UncheckedFrom<T::Hash> + AsRef<[u8]>
+{
+	T::Currency::unreserve(&info.owner, info.deposit);
+	<CodeStorage<T>>::remove(code_hash);
+	<PristineCode<T>>::remove(code_hash);
+	<CodeInfoOf<T>>::remove(code_hash);
+	<Module<T>>::deposit_event(RawEvent::CodeRemoved(*code_hash));
+}
@@ -33,14 +33,15 @@ mod code_cache;
 mod prepare;
 mod runtime;
 
-use self::runtime::Runtime;
 use self::code_cache::load as load_code;
 use pallet_contracts_primitives::ExecResult;
 
-pub use self::code_cache::save as save_code;
+pub use self::code_cache::{
+	save as save_code, try_remove as remove_code, increment_refcount, decrement_refcount,
+};
 #[cfg(feature = "runtime-benchmarks")]
 pub use self::code_cache::save_raw as save_code_raw;
-pub use self::runtime::ReturnCode;
+pub use self::runtime::{ReturnCode, Runtime, RuntimeToken};
 
 /// A prepared wasm module ready for execution.
 #[derive(Clone, Encode, Decode)]
@@ -220,6 +221,7 @@ mod tests {
 		restores: Vec<RestoreEntry>,
 		// (topics, data)
 		events: Vec<(Vec<H256>, Vec<u8>)>,
+		debug_buffer: Vec<u8>,
 	}
 
 	impl Ext for MockExt {
@@ -354,6 +356,11 @@ mod tests {
 		fn get_weight_price(&self, weight: Weight) -> BalanceOf<Self::T> {
 			BalanceOf::<Self::T>::from(1312_u32).saturating_mul(weight.into())
 		}
+
+		fn append_debug_buffer(&mut self, msg: &str) -> bool {
+			self.debug_buffer.extend(msg.as_bytes());
+			true
+		}
 	}
 
 	impl Ext for &mut MockExt {
@@ -453,6 +460,9 @@ mod tests {
 		fn get_weight_price(&self, weight: Weight) -> BalanceOf<Self::T> {
 			(**self).get_weight_price(weight)
 		}
+		fn append_debug_buffer(&mut self, msg: &str) -> bool {
+			(**self).append_debug_buffer(msg)
+		}
 	}
 
 	fn execute<E: Ext>(
@@ -1769,4 +1779,72 @@ mod tests {
 		);
 	}
 
+	const CODE_DEBUG_MESSAGE: &str = r#"
+(module
+	(import "seal0" "seal_debug_message" (func $seal_debug_message (param i32 i32) (result i32)))
+	(import "env" "memory" (memory 1 1))
+
+	(data (i32.const 0) "Hello World!")
+
+	(func (export "call")
+		(call $seal_debug_message
+			(i32.const 0)	;; Pointer to the text buffer
+			(i32.const 12)	;; The size of the buffer
+		)
+		drop
+	)
+
+	(func (export "deploy"))
+)
+"#;
+
+	#[test]
+	fn debug_message_works() {
+		let mut ext = MockExt::default();
+		execute(
+			CODE_DEBUG_MESSAGE,
+			vec![],
+			&mut ext,
+			&mut GasMeter::new(GAS_LIMIT),
+		).unwrap();
+
+		assert_eq!(std::str::from_utf8(&ext.debug_buffer).unwrap(), "Hello World!");
+	}
+
+	const CODE_DEBUG_MESSAGE_FAIL: &str = r#"
+(module
+	(import "seal0" "seal_debug_message" (func $seal_debug_message (param i32 i32) (result i32)))
+	(import "env" "memory" (memory 1 1))
+
+	(data (i32.const 0) "\fc")
+
+	(func (export "call")
+		(call $seal_debug_message
+			(i32.const 0)	;; Pointer to the text buffer
+			(i32.const 1)	;; The size of the buffer
+		)
+		drop
+	)
+
+	(func (export "deploy"))
+)
+"#;
+
+	#[test]
+	fn debug_message_invalid_utf8_fails() {
+		let mut ext = MockExt::default();
+		let result = execute(
+			CODE_DEBUG_MESSAGE_FAIL,
+			vec![],
+			&mut ext,
+			&mut GasMeter::new(GAS_LIMIT),
+		);
+		assert_eq!(
+			result,
+			Err(ExecError {
+				error: Error::<Test>::DebugMessageInvalidUTF8.into(),
+				origin: ErrorOrigin::Caller,
+			})
+		);
+	}
 }
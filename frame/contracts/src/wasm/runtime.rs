@@ -65,6 +65,9 @@ pub enum ReturnCode {
 	/// The contract that was called is either no contract at all (a plain account)
 	/// or is a tombstone.
 	NotCallable = 8,
+	/// The call to `seal_debug_message` had no effect because debug message
+	/// recording was disabled.
+	LoggingDisabled = 9,
 }
 
 impl ConvertibleToWasm for ReturnCode {
@@ -191,6 +194,8 @@ pub enum RuntimeToken {
 	HashBlake256(u32),
 	/// Weight of calling `seal_hash_blake2_128` for the given input size.
 	HashBlake128(u32),
+	/// Weight charged by a chain extension through its `Environment`.
+	ChainExtension(u64),
 }
 
 impl<T: Config> Token<T> for RuntimeToken
@@ -249,6 +254,7 @@ where
 				.saturating_add(s.hash_blake2_256_per_byte.saturating_mul(len.into())),
 			HashBlake128(len) => s.hash_blake2_128
 				.saturating_add(s.hash_blake2_128_per_byte.saturating_mul(len.into())),
+			ChainExtension(amount) => amount,
 		}
 	}
 }
@@ -311,6 +317,19 @@ where
 		}
 	}
 
+	/// Get a mutable reference to the inner `Ext`.
+	///
+	/// This is mainly for the chain extension to have access to the environment the
+	/// contract is executing in.
+	pub(crate) fn ext(&mut self) -> &mut E {
+		self.ext
+	}
+
+	/// Get a reference to the inner `Ext`.
+	pub(crate) fn ext_ref(&self) -> &E {
+		self.ext
+	}
+
 	/// Converts the sandbox result and the runtime state into the execution outcome.
 	///
 	/// It evaluates information stored in the `trap_reason` variable of the runtime and
@@ -372,7 +391,7 @@ where
 	/// Charge the gas meter with the specified token.
 	///
 	/// Returns `Err(HostError)` if there is not enough gas.
-	fn charge_gas<Tok>(&mut self, token: Tok) -> Result<(), sp_sandbox::HostError>
+	pub(crate) fn charge_gas<Tok>(&mut self, token: Tok) -> Result<(), sp_sandbox::HostError>
 	where
 		Tok: Token<E::T, Metadata=HostFnWeights<E::T>>,
 	{
@@ -392,7 +411,7 @@ where
 	/// Returns `Err` if one of the following conditions occurs:
 	///
 	/// - requested buffer is not within the bounds of the sandbox memory.
-	fn read_sandbox_memory(&mut self, ptr: u32, len: u32)
+	pub(crate) fn read_sandbox_memory(&mut self, ptr: u32, len: u32)
 	-> Result<Vec<u8>, sp_sandbox::HostError>
 	{
 		let mut buf = vec![0u8; len as usize];
@@ -453,7 +472,7 @@ where
 	///
 	/// In addition to the error conditions of `write_sandbox_memory` this functions returns
 	/// `Err` if the size of the buffer located at `out_ptr` is too small to fit `buf`.
-	fn write_sandbox_output(
+	pub(crate) fn write_sandbox_output(
 		&mut self,
 		out_ptr: u32,
 		out_len_ptr: u32,
@@ -528,6 +547,19 @@ where
 		sp_sandbox::HostError
 	}
 
+	/// Takes back the error which was stored by `store_err` when a helper of this
+	/// type failed with the supplied `HostError`.
+	///
+	/// This is used by the chain extension which hands errors to its implementation
+	/// instead of trapping right away.
+	pub(crate) fn take_err(&mut self, _: sp_sandbox::HostError) -> DispatchError {
+		match self.trap_reason.take() {
+			Some(TrapReason::SupervisorError(err)) => err,
+			// The helpers used by the chain extension only ever store a supervisor error.
+			_ => Error::<E::T>::ContractTrapped.into(),
+		}
+	}
+
 	/// Used by Runtime API that calls into other contracts.
 	///
 	/// Those need to transform the the `ExecResult` returned from the execution into
@@ -916,6 +948,7 @@ define_env!(Env, <E: Ext>,
 	// # Traps
 	//
 	// - The contract is live i.e is already on the call stack.
+	// - The deletion queue is full.
 	seal_terminate(
 		ctx,
 		beneficiary_ptr: u32,
@@ -1280,14 +1313,50 @@ define_env!(Env, <E: Ext>,
 	// Prints utf8 encoded string from the data buffer.
 	// Only available on `--dev` chains.
 	// This function may be removed at any time, superseded by a more general contract debugging feature.
+	//
+	// The string is also appended to the debug buffer if debug message recording is enabled.
+	// See `seal_debug_message`.
 	seal_println(ctx, str_ptr: u32, str_len: u32) => {
 		let data = ctx.read_sandbox_memory(str_ptr, str_len)?;
 		if let Ok(utf8) = core::str::from_utf8(&data) {
 			sp_runtime::print(utf8);
+			if ctx.ext.append_debug_buffer(utf8) {
+				ctx.ext.append_debug_buffer("\n");
+			}
 		}
 		Ok(())
 	},
 
+	// Emit a custom debug message.
+	//
+	// No newlines are added to the supplied message.
+	// Specifying invalid UTF-8 triggers a trap.
+	//
+	// This is a no-op if debug message recording is disabled which is always the case
+	// when the code is executing on-chain. The message is interpreted as UTF-8 and
+	// appended to the debug buffer which is then supplied to the calling RPC client.
+	// Messages which would exceed the maximum size of the debug buffer are dropped.
+	//
+	// This function is free of charge. It is safe to do so because the contract is only
+	// able to consume resources when debug message recording is enabled which never
+	// happens on-chain. This keeps the gas consumption of a dry-run equal to the one of
+	// the on-chain execution.
+	//
+	// # Return Value
+	//
+	// Returns `ReturnCode::LoggingDisabled` if debug message recording is disabled.
+	// The contract can cache this value in order to prevent further calls.
+	seal_debug_message(ctx, str_ptr: u32, str_len: u32) -> ReturnCode => {
+		if ctx.ext.append_debug_buffer("") {
+			let data = ctx.read_sandbox_memory(str_ptr, str_len)?;
+			let msg = core::str::from_utf8(&data)
+				.map_err(|_| ctx.store_err(Error::<E::T>::DebugMessageInvalidUTF8))?;
+			ctx.ext.append_debug_buffer(msg);
+			return Ok(ReturnCode::Success);
+		}
+		Ok(ReturnCode::LoggingDisabled)
+	},
+
 	// Stores the current block number of the current contract into the supplied buffer.
 	//
 	// The value is stored to linear memory at the address pointed to by `out_ptr`.
@@ -1400,4 +1469,50 @@ define_env!(Env, <E: Ext>,
 		ctx.charge_gas(RuntimeToken::HashBlake128(input_len))?;
 		ctx.compute_hash_on_intermediate_buffer(blake2_128, input_ptr, input_len, output_ptr)
 	},
+
+	// Call into the chain extension provided by the chain if any.
+	//
+	// Handling of the input values is up to the specific chain extension and so is the
+	// return value. The extension can decide to use the inputs as primitive inputs or as
+	// in/out arguments by interpreting them as pointers. Any caller of this function
+	// must therefore coordinate with the chain that it targets.
+	//
+	// # Parameters
+	//
+	// - `func_id`: selects the function of the chain extension to call.
+	// - `input_ptr`: the pointer into the linear memory where the input data is placed.
+	// - `input_len`: the length of the input data in bytes.
+	// - `output_ptr`: the pointer into the linear memory where the output data is written to.
+	// - `output_len_ptr`: in-out pointer into linear memory where the buffer length
+	//   is read from and the output length is written to.
+	//
+	// # Note
+	//
+	// If no chain extension exists the contract will trap with the `NoChainExtension`
+	// module error.
+	seal_call_chain_extension(
+		ctx,
+		func_id: u32,
+		input_ptr: u32,
+		input_len: u32,
+		output_ptr: u32,
+		output_len_ptr: u32
+	) -> u32 => {
+		use crate::chain_extension::{ChainExtension, Environment, RetVal};
+		if !<E::T as Config>::ChainExtension::enabled() {
+			Err(ctx.store_err(Error::<E::T>::NoChainExtension))?;
+		}
+		let env = Environment::new(ctx, input_ptr, input_len, output_ptr, output_len_ptr);
+		match <E::T as Config>::ChainExtension::call(func_id, env) {
+			Ok(RetVal::Converging(val)) => Ok(val),
+			Ok(RetVal::Diverging { flags, data }) => {
+				ctx.trap_reason = Some(TrapReason::Return(ReturnData {
+					flags: flags.bits(),
+					data,
+				}));
+				Err(sp_sandbox::HostError)
+			},
+			Err(err) => Err(ctx.store_err(err)),
+		}
+	},
 );
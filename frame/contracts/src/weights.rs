@@ -43,11 +43,16 @@ use sp_std::marker::PhantomData;
 
 /// Weight functions needed for pallet_contracts.
 pub trait WeightInfo {
+	fn on_initialize() -> Weight;
+	fn on_initialize_per_trie_item(k: u32, ) -> Weight;
+	fn on_initialize_per_queue_item(q: u32, ) -> Weight;
 	fn update_schedule() -> Weight;
 	fn put_code(n: u32, ) -> Weight;
 	fn instantiate(n: u32, s: u32, ) -> Weight;
+	fn instantiate_with_code(c: u32, s: u32, ) -> Weight;
 	fn call() -> Weight;
 	fn claim_surcharge() -> Weight;
+	fn remove_code() -> Weight;
 	fn seal_caller(r: u32, ) -> Weight;
 	fn seal_address(r: u32, ) -> Weight;
 	fn seal_gas_left(r: u32, ) -> Weight;
@@ -145,6 +150,23 @@ pub trait WeightInfo {
 /// Weights for pallet_contracts using the Substrate node and recommended hardware.
 pub struct SubstrateWeight<T>(PhantomData<T>);
 impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	fn on_initialize() -> Weight {
+		(3_947_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+	}
+	fn on_initialize_per_trie_item(k: u32, ) -> Weight {
+		(48_805_000 as Weight)
+			.saturating_add((2_301_000 as Weight).saturating_mul(k as Weight))
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes((1 as Weight).saturating_mul(k as Weight)))
+	}
+	fn on_initialize_per_queue_item(q: u32, ) -> Weight {
+		(0 as Weight)
+			.saturating_add((35_577_000 as Weight).saturating_mul(q as Weight))
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
 	fn update_schedule() -> Weight {
 		(35_214_000 as Weight)
 			.saturating_add(T::DbWeight::get().reads(1 as Weight))
@@ -153,15 +175,22 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 	fn put_code(n: u32, ) -> Weight {
 		(0 as Weight)
 			.saturating_add((109_242_000 as Weight).saturating_mul(n as Weight))
-			.saturating_add(T::DbWeight::get().reads(1 as Weight))
-			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(4 as Weight))
 	}
 	fn instantiate(n: u32, s: u32, ) -> Weight {
 		(195_276_000 as Weight)
 			.saturating_add((35_000 as Weight).saturating_mul(n as Weight))
 			.saturating_add((2_244_000 as Weight).saturating_mul(s as Weight))
-			.saturating_add(T::DbWeight::get().reads(6 as Weight))
-			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+			.saturating_add(T::DbWeight::get().reads(7 as Weight))
+			.saturating_add(T::DbWeight::get().writes(4 as Weight))
+	}
+	fn instantiate_with_code(c: u32, s: u32, ) -> Weight {
+		(196_829_000 as Weight)
+			.saturating_add((109_318_000 as Weight).saturating_mul(c as Weight))
+			.saturating_add((2_251_000 as Weight).saturating_mul(s as Weight))
+			.saturating_add(T::DbWeight::get().reads(9 as Weight))
+			.saturating_add(T::DbWeight::get().writes(8 as Weight))
 	}
 	fn call() -> Weight {
 		(207_142_000 as Weight)
@@ -173,6 +202,11 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			.saturating_add(T::DbWeight::get().reads(3 as Weight))
 			.saturating_add(T::DbWeight::get().writes(2 as Weight))
 	}
+	fn remove_code() -> Weight {
+		(86_455_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(4 as Weight))
+	}
 	fn seal_caller(r: u32, ) -> Weight {
 		(136_550_000 as Weight)
 			.saturating_add((373_182_000 as Weight).saturating_mul(r as Weight))
@@ -257,8 +291,8 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 		(130_607_000 as Weight)
 			.saturating_add((358_370_000 as Weight).saturating_mul(r as Weight))
 			.saturating_add(T::DbWeight::get().reads(4 as Weight))
-			.saturating_add(T::DbWeight::get().reads((2 as Weight).saturating_mul(r as Weight)))
-			.saturating_add(T::DbWeight::get().writes((3 as Weight).saturating_mul(r as Weight)))
+			.saturating_add(T::DbWeight::get().reads((3 as Weight).saturating_mul(r as Weight)))
+			.saturating_add(T::DbWeight::get().writes((4 as Weight).saturating_mul(r as Weight)))
 	}
 	fn seal_restore_to(r: u32, ) -> Weight {
 		(233_645_000 as Weight)
@@ -619,6 +653,23 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 
 // For backwards compatibility and tests
 impl WeightInfo for () {
+	fn on_initialize() -> Weight {
+		(3_947_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+	}
+	fn on_initialize_per_trie_item(k: u32, ) -> Weight {
+		(48_805_000 as Weight)
+			.saturating_add((2_301_000 as Weight).saturating_mul(k as Weight))
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes((1 as Weight).saturating_mul(k as Weight)))
+	}
+	fn on_initialize_per_queue_item(q: u32, ) -> Weight {
+		(0 as Weight)
+			.saturating_add((35_577_000 as Weight).saturating_mul(q as Weight))
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
 	fn update_schedule() -> Weight {
 		(35_214_000 as Weight)
 			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
@@ -627,15 +678,22 @@ impl WeightInfo for () {
 	fn put_code(n: u32, ) -> Weight {
 		(0 as Weight)
 			.saturating_add((109_242_000 as Weight).saturating_mul(n as Weight))
-			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
-			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(4 as Weight))
 	}
 	fn instantiate(n: u32, s: u32, ) -> Weight {
 		(195_276_000 as Weight)
 			.saturating_add((35_000 as Weight).saturating_mul(n as Weight))
 			.saturating_add((2_244_000 as Weight).saturating_mul(s as Weight))
-			.saturating_add(RocksDbWeight::get().reads(6 as Weight))
-			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+			.saturating_add(RocksDbWeight::get().reads(7 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(4 as Weight))
+	}
+	fn instantiate_with_code(c: u32, s: u32, ) -> Weight {
+		(196_829_000 as Weight)
+			.saturating_add((109_318_000 as Weight).saturating_mul(c as Weight))
+			.saturating_add((2_251_000 as Weight).saturating_mul(s as Weight))
+			.saturating_add(RocksDbWeight::get().reads(9 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(8 as Weight))
 	}
 	fn call() -> Weight {
 		(207_142_000 as Weight)
@@ -647,6 +705,11 @@ impl WeightInfo for () {
 			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
 			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
 	}
+	fn remove_code() -> Weight {
+		(86_455_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(4 as Weight))
+	}
 	fn seal_caller(r: u32, ) -> Weight {
 		(136_550_000 as Weight)
 			.saturating_add((373_182_000 as Weight).saturating_mul(r as Weight))
@@ -731,8 +794,8 @@ impl WeightInfo for () {
 		(130_607_000 as Weight)
 			.saturating_add((358_370_000 as Weight).saturating_mul(r as Weight))
 			.saturating_add(RocksDbWeight::get().reads(4 as Weight))
-			.saturating_add(RocksDbWeight::get().reads((2 as Weight).saturating_mul(r as Weight)))
-			.saturating_add(RocksDbWeight::get().writes((3 as Weight).saturating_mul(r as Weight)))
+			.saturating_add(RocksDbWeight::get().reads((3 as Weight).saturating_mul(r as Weight)))
+			.saturating_add(RocksDbWeight::get().writes((4 as Weight).saturating_mul(r as Weight)))
 	}
 	fn seal_restore_to(r: u32, ) -> Weight {
 		(233_645_000 as Weight)
@@ -781,6 +781,36 @@ benchmarks! {
 			Err(Error::<T>::PreimageInvalid.into())
 		);
 	}
+
+	submit {
+		let caller = funded_account::<T>("caller", 0);
+		let proposal_origin: T::PalletsOrigin = RawOrigin::Root.into();
+		let track = T::Tracks::track_for(&proposal_origin).map_err(|_| "no track for root")?;
+		let info = T::Tracks::info(track).ok_or("no track info")?;
+		let delay = info.min_enactment_period;
+		let proposal_hash: T::Hash = T::Hashing::hash_of(&0);
+
+		// Worst case: all the deciding slots are taken and the queue is one short of full.
+		let queued = info.max_queued.checked_sub(1).ok_or("track has no queue")?;
+		for i in 0 .. info.max_deciding + queued {
+			let submitter = funded_account::<T>("submitter", i);
+			Democracy::<T>::submit(
+				RawOrigin::Signed(submitter).into(),
+				Box::new(proposal_origin.clone()),
+				T::Hashing::hash_of(&(i + 1)),
+				delay,
+			)?;
+		}
+		assert_eq!(Democracy::<T>::track_queue(track).len() as u32, queued);
+		whitelist_account!(caller);
+	}: _(RawOrigin::Signed(caller), Box::new(proposal_origin), proposal_hash, delay)
+	verify {
+		assert_eq!(
+			Democracy::<T>::track_queue(track).len() as u32,
+			info.max_queued,
+			"Referendum not queued",
+		);
+	}
 }
 
 #[cfg(test)]
@@ -821,6 +851,7 @@ mod tests {
 			assert_ok!(test_benchmark_enact_proposal_slash::<Test>());
 			assert_ok!(test_benchmark_blacklist::<Test>());
 			assert_ok!(test_benchmark_cancel_proposal::<Test>());
+			assert_ok!(test_benchmark_submit::<Test>());
 		});
 	}
 }
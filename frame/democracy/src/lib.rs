@@ -37,6 +37,9 @@
 //! behind a vote. The conviction will dictate the length of time the tokens
 //! will be locked, as well as the multiplier that scales the vote power.
 //!
+//! Alongside these queues, referenda may be submitted on a _track_. See
+//! [Referendum Tracks](#referendum-tracks).
+//!
 //! ### Terminology
 //!
 //! - **Enactment Period:** The minimum period of locking and the period between a proposal being
@@ -68,6 +71,22 @@
 //! quorum biasing is that _positive bias_ referendums will be rejected by
 //! default and _negative bias_ referendums get passed by default.
 //!
+//! ### Referendum Tracks
+//!
+//! A referendum may also be submitted directly on a _track_, along with the origin with which its
+//! proposal is dispatched once approved. The runtime defines the tracks and which origin
+//! corresponds to which track through `Config::Tracks`. Each track has its own decision period,
+//! minimum enactment period and maximum number of referenda being decided at once; further
+//! referenda wait in the queue of the track until a deciding slot becomes free.
+//!
+//! A referendum on a track is approved as soon as both its _approval_ (the proportion of aye
+//! votes, post-conviction) and its _support_ (the proportion of the total issuance voting,
+//! pre-conviction) pass the curves of the track for the elapsed part of the decision period. It
+//! is rejected if it does not pass them by the end of the decision period.
+//!
+//! Votes and delegations are kept separately for each track, so that an account may delegate its
+//! voting power to different accounts for different tracks.
+//!
 //! ## Interface
 //!
 //! ### Dispatchable Functions
@@ -86,6 +105,10 @@
 //! - `unvote` - Cancel a previous vote, this must be done by the voter before the vote ends.
 //! - `delegate` - Delegates the voting power (tokens * conviction) to another account.
 //! - `undelegate` - Stops the delegation of voting power to another account.
+//! - `submit` - Submits a referendum on the track of the origin of its proposal. Requires a
+//!   deposit.
+//! - `delegate_track` - Delegates the voting power for the referenda of a track.
+//! - `undelegate_track` - Stops the delegation of voting power for the referenda of a track.
 //!
 //! Administration actions that can be done to any account:
 //! - `reap_vote` - Remove some account's expired votes.
@@ -154,12 +177,13 @@
 
 use sp_std::prelude::*;
 use sp_runtime::{
-	DispatchResult, DispatchError, RuntimeDebug,
+	DispatchResult, DispatchError, RuntimeDebug, Perbill,
 	traits::{Zero, Hash, Dispatchable, Saturating, Bounded},
 };
 use codec::{Encode, Decode, Input};
 use frame_support::{
 	decl_module, decl_storage, decl_event, decl_error, ensure, Parameter,
+	storage::IterableStorageDoubleMap,
 	weights::{Weight, DispatchClass, Pays},
 	traits::{
 		Currency, ReservableCurrency, LockableCurrency, WithdrawReasons, LockIdentifier, Get,
@@ -173,12 +197,16 @@ mod vote_threshold;
 mod vote;
 mod conviction;
 mod types;
+mod tracks;
 pub mod weights;
 pub use weights::WeightInfo;
 pub use vote_threshold::{Approved, VoteThreshold};
 pub use vote::{Vote, AccountVote, Voting};
 pub use conviction::Conviction;
-pub use types::{ReferendumInfo, ReferendumStatus, Tally, UnvoteScope, Delegations};
+pub use types::{
+	ReferendumInfo, ReferendumStatus, Tally, UnvoteScope, Delegations, TrackReferendum,
+};
+pub use tracks::{TrackId, Curve, TrackInfo, TracksInfo};
 
 #[cfg(test)]
 mod tests;
@@ -202,6 +230,18 @@ pub type ReferendumIndex = u32;
 type BalanceOf<T> = <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
 type NegativeImbalanceOf<T> =
 	<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::NegativeImbalance;
+type VotingFor<T> = Voting<
+	BalanceOf<T>,
+	<T as frame_system::Config>::AccountId,
+	<T as frame_system::Config>::BlockNumber,
+>;
+type TrackReferendumOf<T> = TrackReferendum<
+	<T as frame_system::Config>::AccountId,
+	BalanceOf<T>,
+	<T as frame_system::Config>::BlockNumber,
+	<T as frame_system::Config>::Hash,
+	<T as Config>::PalletsOrigin,
+>;
 
 pub trait Config: frame_system::Config + Sized {
 	type Proposal: Parameter + Dispatchable<Origin=Self::Origin> + From<Call<Self>>;
@@ -290,7 +330,12 @@ pub trait Config: frame_system::Config + Sized {
 	type Scheduler: ScheduleNamed<Self::BlockNumber, Self::Proposal, Self::PalletsOrigin>;
 
 	/// Overarching type of all pallets origins.
-	type PalletsOrigin: From<system::RawOrigin<Self::AccountId>>;
+	type PalletsOrigin: From<system::RawOrigin<Self::AccountId>> + Parameter
+		+ Into<<Self as frame_system::Config>::Origin>;
+
+	/// The tracks on which referenda may be submitted, keyed by the origin with which their
+	/// proposal is dispatched.
+	type Tracks: TracksInfo<BalanceOf<Self>, Self::BlockNumber, Origin=Self::PalletsOrigin>;
 
 	/// The maximum number of votes for an account.
 	///
@@ -403,6 +448,31 @@ decl_storage! {
 		/// Record of all proposals that have been subject to emergency cancellation.
 		pub Cancellations: map hasher(identity) T::Hash => bool;
 
+		/// Information concerning any given referendum submitted on a track. Kept alongside
+		/// `ReferendumInfoOf`, which only exists once the referendum started to be decided, until
+		/// the referendum is enacted, rejected or cancelled.
+		///
+		/// TWOX-NOTE: SAFE as indexes are not under an attacker’s control.
+		pub TrackReferendumInfoOf get(fn track_referendum_info):
+			map hasher(twox_64_concat) ReferendumIndex => Option<TrackReferendumOf<T>>;
+
+		/// The referenda of each track which are currently being decided.
+		pub DecidingOf get(fn deciding_of):
+			map hasher(twox_64_concat) TrackId => Vec<ReferendumIndex>;
+
+		/// The referenda of each track which wait for a free deciding slot, oldest first. At most
+		/// `max_queued` of the track.
+		pub TrackQueue get(fn track_queue):
+			map hasher(twox_64_concat) TrackId => Vec<ReferendumIndex>;
+
+		/// All votes for a particular voter on the referenda of a track, like `VotingOf` is for
+		/// the referenda launched from the public and external queues.
+		///
+		/// TWOX-NOTE: SAFE as `AccountId`s are crypto hashes anyway.
+		pub TrackVotingOf:
+			double_map hasher(twox_64_concat) T::AccountId, hasher(twox_64_concat) TrackId
+			=> VotingFor<T>;
+
 		/// Storage version of the pallet.
 		///
 		/// New networks start with last version.
@@ -457,6 +527,10 @@ decl_event! {
 		Unlocked(AccountId),
 		/// A proposal \[hash\] has been blacklisted permanently.
 		Blacklisted(Hash),
+		/// A referendum has been submitted on a track. \[ref_index, track\]
+		Submitted(ReferendumIndex, TrackId),
+		/// A referendum on a track has started to be decided. \[ref_index, track\]
+		DecisionStarted(ReferendumIndex, TrackId),
 	}
 }
 
@@ -533,6 +607,14 @@ decl_error! {
 		InvalidWitness,
 		/// Maximum number of proposals reached.
 		TooManyProposals,
+		/// There is no track for the origin of the proposal.
+		NoTrack,
+		/// The track does not exist.
+		BadTrack,
+		/// The enactment delay is shorter than the minimum enactment period of the track.
+		DelayTooShort,
+		/// The track has no free deciding slot and its queue is full.
+		QueueFull,
 	}
 }
 
@@ -833,11 +915,15 @@ decl_module! {
 		/// - `which`: The index of the referendum to cancel.
 		///
 		/// Weight: `O(D)` where `D` is the items in the dispatch queue. Weighted as `D = 10`.
-		#[weight = (T::WeightInfo::cancel_queued(10), DispatchClass::Operational)]
+		#[weight = (
+			T::WeightInfo::cancel_queued(10).saturating_add(T::DbWeight::get().writes(1)),
+			DispatchClass::Operational,
+		)]
 		fn cancel_queued(origin, which: ReferendumIndex) {
 			ensure_root(origin)?;
 			T::Scheduler::cancel_named((DEMOCRACY_ID, which).encode())
 				.map_err(|_| Error::<T>::ProposalMissing)?;
+			<TrackReferendumInfoOf<T>>::remove(which);
 		}
 
 		/// Weight: see `begin_block`
@@ -878,7 +964,7 @@ decl_module! {
 			balance: BalanceOf<T>
 		) -> DispatchResultWithPostInfo {
 			let who = ensure_signed(origin)?;
-			let votes = Self::try_delegate(who, to, conviction, balance)?;
+			let votes = Self::try_delegate(who, None, to, conviction, balance)?;
 
 			Ok(Some(T::WeightInfo::delegate(votes)).into())
 		}
@@ -900,7 +986,7 @@ decl_module! {
 		#[weight = T::WeightInfo::undelegate(T::MaxVotes::get().into())]
 		fn undelegate(origin) -> DispatchResultWithPostInfo {
 			let who = ensure_signed(origin)?;
-			let votes = Self::try_undelegate(who)?;
+			let votes = Self::try_undelegate(who, None)?;
 			Ok(Some(T::WeightInfo::undelegate(votes)).into())
 		}
 
@@ -1021,9 +1107,14 @@ decl_module! {
 		///
 		/// - `target`: The account to remove the lock on.
 		///
-		/// Weight: `O(R)` with R number of vote of target.
+		/// Weight: `O(R + T)` with R number of vote of target and T number of tracks, as the
+		///   voting of target on each track is read and written.
 		#[weight = T::WeightInfo::unlock_set(T::MaxVotes::get())
-			.max(T::WeightInfo::unlock_remove(T::MaxVotes::get()))]
+			.max(T::WeightInfo::unlock_remove(T::MaxVotes::get()))
+			.saturating_add(T::DbWeight::get().reads_writes(
+				T::Tracks::tracks().len() as Weight,
+				T::Tracks::tracks().len() as Weight,
+			))]
 		fn unlock(origin, target: T::AccountId) {
 			ensure_signed(origin)?;
 			Self::update_lock(&target);
@@ -1055,8 +1146,10 @@ decl_module! {
 		/// - `index`: The index of referendum of the vote to be removed.
 		///
 		/// Weight: `O(R + log R)` where R is the number of referenda that `target` has voted on.
-		///   Weight is calculated for the maximum number of vote.
-		#[weight = T::WeightInfo::remove_vote(T::MaxVotes::get())]
+		///   Weight is calculated for the maximum number of vote, plus a read of the votes of
+		///   `target` on each track.
+		#[weight = T::WeightInfo::remove_vote(T::MaxVotes::get())
+			.saturating_add(T::DbWeight::get().reads(T::Tracks::tracks().len() as Weight))]
 		fn remove_vote(origin, index: ReferendumIndex) -> DispatchResult {
 			let who = ensure_signed(origin)?;
 			Self::try_remove_vote(&who, index, UnvoteScope::Any)
@@ -1076,8 +1169,10 @@ decl_module! {
 		/// - `index`: The index of referendum of the vote to be removed.
 		///
 		/// Weight: `O(R + log R)` where R is the number of referenda that `target` has voted on.
-		///   Weight is calculated for the maximum number of vote.
-		#[weight = T::WeightInfo::remove_other_vote(T::MaxVotes::get())]
+		///   Weight is calculated for the maximum number of vote, plus a read of the votes of
+		///   `target` on each track.
+		#[weight = T::WeightInfo::remove_other_vote(T::MaxVotes::get())
+			.saturating_add(T::DbWeight::get().reads(T::Tracks::tracks().len() as Weight))]
 		fn remove_other_vote(origin, target: T::AccountId, index: ReferendumIndex) -> DispatchResult {
 			let who = ensure_signed(origin)?;
 			let scope = if target == who { UnvoteScope::Any } else { UnvoteScope::OnlyExpired };
@@ -1165,6 +1260,114 @@ decl_module! {
 				}
 			}
 		}
+
+		/// Submit a referendum on the track of `proposal_origin`.
+		///
+		/// The referendum starts to be decided as soon as its track has a free deciding slot. Once
+		/// approved, the proposal is dispatched with `proposal_origin`.
+		///
+		/// The dispatch origin of this call must be _Signed_ and the sender must have funds to
+		/// cover the decision deposit of the track, which is returned once the referendum is
+		/// decided or cancelled. If the track has no free deciding slot, its queue must not be full.
+		///
+		/// - `proposal_origin`: The origin with which the proposal is dispatched.
+		/// - `proposal_hash`: The hash of the proposal preimage.
+		/// - `delay`: The number of blocks after approval before the proposal is enacted. This
+		///   must be at least the minimum enactment period of the track.
+		///
+		/// Emits `Submitted`, and `DecisionStarted` if the referendum starts to be decided.
+		///
+		/// Weight: `O(1)`
+		#[weight = T::WeightInfo::submit()]
+		fn submit(origin,
+			proposal_origin: Box<T::PalletsOrigin>,
+			proposal_hash: T::Hash,
+			delay: T::BlockNumber,
+		) {
+			let who = ensure_signed(origin)?;
+			let track = T::Tracks::track_for(&proposal_origin).map_err(|_| Error::<T>::NoTrack)?;
+			let info = T::Tracks::info(track).ok_or(Error::<T>::NoTrack)?;
+			ensure!(delay >= info.min_enactment_period, Error::<T>::DelayTooShort);
+
+			let now = <frame_system::Module<T>>::block_number();
+			if let Some((until, _)) = <Blacklist<T>>::get(proposal_hash) {
+				ensure!(now >= until, Error::<T>::ProposalBlacklisted);
+			}
+
+			let deciding = DecidingOf::decode_len(track).unwrap_or(0) as u32;
+			let queued = TrackQueue::decode_len(track).unwrap_or(0) as u32;
+			ensure!(deciding < info.max_deciding || queued < info.max_queued, Error::<T>::QueueFull);
+
+			T::Currency::reserve(&who, info.decision_deposit)?;
+			let ref_index = Self::referendum_count();
+			ReferendumCount::put(ref_index + 1);
+			let referendum = TrackReferendum {
+				track,
+				origin: *proposal_origin,
+				proposal_hash,
+				delay,
+				submitter: who,
+				deposit: info.decision_deposit,
+				deciding: None,
+			};
+			Self::deposit_event(RawEvent::Submitted(ref_index, track));
+
+			if deciding < info.max_deciding {
+				Self::begin_deciding(now, ref_index, referendum);
+			} else {
+				<TrackReferendumInfoOf<T>>::insert(ref_index, referendum);
+				TrackQueue::append(track, ref_index);
+			}
+		}
+
+		/// Delegate the voting power (with some given conviction) of the sending account for the
+		/// referenda of `track`.
+		///
+		/// This is the same as `delegate`, except that it only applies to the referenda of
+		/// `track`. The votes and delegations of each track are independent from each other and
+		/// from those of the referenda launched from the public and external queues.
+		///
+		/// - `track`: The track whose referenda the delegation applies to.
+		/// - `to`: The account whose voting the `target` account's voting power will follow.
+		/// - `conviction`: The conviction that will be attached to the delegated votes. When the
+		///   account is undelegated, the funds will be locked for the corresponding period.
+		/// - `balance`: The amount of the account's balance to be used in delegating. This must
+		///   not be more than the account's current balance.
+		///
+		/// Emits `Delegated`.
+		///
+		/// Weight: `O(R)` where R is the number of referendums the voter delegating to has
+		///   voted on. Weight is charged as if maximum votes.
+		#[weight = T::WeightInfo::delegate(T::MaxVotes::get())]
+		fn delegate_track(
+			origin,
+			track: TrackId,
+			to: T::AccountId,
+			conviction: Conviction,
+			balance: BalanceOf<T>
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			ensure!(T::Tracks::info(track).is_some(), Error::<T>::BadTrack);
+			let votes = Self::try_delegate(who, Some(track), to, conviction, balance)?;
+
+			Ok(Some(T::WeightInfo::delegate(votes)).into())
+		}
+
+		/// Undelegate the voting power of the sending account for the referenda of `track`.
+		///
+		/// This is the same as `undelegate`, except that it only applies to the delegation for
+		/// the referenda of `track`.
+		///
+		/// Emits `Undelegated`.
+		///
+		/// Weight: `O(R)` where R is the number of referendums the voter delegating to has
+		///   voted on. Weight is charged as if maximum votes.
+		#[weight = T::WeightInfo::undelegate(T::MaxVotes::get().into())]
+		fn undelegate_track(origin, track: TrackId) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let votes = Self::try_undelegate(who, Some(track))?;
+			Ok(Some(T::WeightInfo::undelegate(votes)).into())
+		}
 	}
 }
 
@@ -1220,6 +1423,11 @@ impl<T: Config> Module<T> {
 	pub fn internal_cancel_referendum(ref_index: ReferendumIndex) {
 		Self::deposit_event(RawEvent::Cancelled(ref_index));
 		ReferendumInfoOf::<T>::remove(ref_index);
+		Self::conclude_track_referendum(<frame_system::Module<T>>::block_number(), ref_index);
+		if <TrackReferendumInfoOf<T>>::take(ref_index).is_some() {
+			// An approved referendum on a track may not be enacted once its origin is gone.
+			let _ = T::Scheduler::cancel_named((DEMOCRACY_ID, ref_index).encode());
+		}
 	}
 
 	// private.
@@ -1246,7 +1454,8 @@ impl<T: Config> Module<T> {
 	fn try_vote(who: &T::AccountId, ref_index: ReferendumIndex, vote: AccountVote<BalanceOf<T>>) -> DispatchResult {
 		let mut status = Self::referendum_status(ref_index)?;
 		ensure!(vote.balance() <= T::Currency::free_balance(who), Error::<T>::InsufficientFunds);
-		VotingOf::<T>::try_mutate(who, |voting| -> DispatchResult {
+		let track = Self::voting_class(ref_index);
+		Self::try_mutate_voting(who, track, |voting| -> DispatchResult {
 			if let Voting::Direct { ref mut votes, delegations, .. } = voting {
 				match votes.binary_search_by_key(&ref_index, |i| i.0) {
 					Ok(i) => {
@@ -1292,7 +1501,8 @@ impl<T: Config> Module<T> {
 	/// This will generally be combined with a call to `unlock`.
 	fn try_remove_vote(who: &T::AccountId, ref_index: ReferendumIndex, scope: UnvoteScope) -> DispatchResult {
 		let info = ReferendumInfoOf::<T>::get(ref_index);
-		VotingOf::<T>::try_mutate(who, |voting| -> DispatchResult {
+		let track = Self::voting_class_of(who, ref_index);
+		Self::try_mutate_voting(who, track, |voting| -> DispatchResult {
 			if let Voting::Direct { ref mut votes, delegations, ref mut prior } = voting {
 				let i = votes.binary_search_by_key(&ref_index, |i| i.0).map_err(|_| Error::<T>::NotVoter)?;
 				match info {
@@ -1307,7 +1517,7 @@ impl<T: Config> Module<T> {
 					}
 					Some(ReferendumInfo::Finished{end, approved}) =>
 						if let Some((lock_periods, balance)) = votes[i].1.locked_if(approved) {
-							let unlock_at = end + T::EnactmentPeriod::get() * lock_periods.into();
+							let unlock_at = end + Self::lock_period(track) * lock_periods.into();
 							let now = system::Module::<T>::block_number();
 							if now < unlock_at {
 								ensure!(matches!(scope, UnvoteScope::Any), Error::<T>::NoPermission);
@@ -1324,8 +1534,12 @@ impl<T: Config> Module<T> {
 	}
 
 	/// Return the number of votes for `who`
-	fn increase_upstream_delegation(who: &T::AccountId, amount: Delegations<BalanceOf<T>>) -> u32 {
-		VotingOf::<T>::mutate(who, |voting| match voting {
+	fn increase_upstream_delegation(
+		who: &T::AccountId,
+		track: Option<TrackId>,
+		amount: Delegations<BalanceOf<T>>,
+	) -> u32 {
+		Self::mutate_voting(who, track, |voting| match voting {
 			Voting::Delegating { delegations, .. } => {
 				// We don't support second level delegating, so we don't need to do anything more.
 				*delegations = delegations.saturating_add(amount);
@@ -1348,8 +1562,12 @@ impl<T: Config> Module<T> {
 	}
 
 	/// Return the number of votes for `who`
-	fn reduce_upstream_delegation(who: &T::AccountId, amount: Delegations<BalanceOf<T>>) -> u32 {
-		VotingOf::<T>::mutate(who, |voting| match voting {
+	fn reduce_upstream_delegation(
+		who: &T::AccountId,
+		track: Option<TrackId>,
+		amount: Delegations<BalanceOf<T>>,
+	) -> u32 {
+		Self::mutate_voting(who, track, |voting| match voting {
 			Voting::Delegating { delegations, .. } => {
 				// We don't support second level delegating, so we don't need to do anything more.
 				*delegations = delegations.saturating_sub(amount);
@@ -1376,13 +1594,14 @@ impl<T: Config> Module<T> {
 	/// Return the upstream number of votes.
 	fn try_delegate(
 		who: T::AccountId,
+		track: Option<TrackId>,
 		target: T::AccountId,
 		conviction: Conviction,
 		balance: BalanceOf<T>,
 	) -> Result<u32, DispatchError> {
 		ensure!(who != target, Error::<T>::Nonsense);
 		ensure!(balance <= T::Currency::free_balance(&who), Error::<T>::InsufficientFunds);
-		let votes = VotingOf::<T>::try_mutate(&who, |voting| -> Result<u32, DispatchError> {
+		let votes = Self::try_mutate_voting(&who, track, |voting| -> Result<u32, DispatchError> {
 			let mut old = Voting::Delegating {
 				balance,
 				target: target.clone(),
@@ -1394,7 +1613,7 @@ impl<T: Config> Module<T> {
 			match old {
 				Voting::Delegating { balance, target, conviction, delegations, prior, .. } => {
 					// remove any delegation votes to our current target.
-					Self::reduce_upstream_delegation(&target, conviction.votes(balance));
+					Self::reduce_upstream_delegation(&target, track, conviction.votes(balance));
 					voting.set_common(delegations, prior);
 				}
 				Voting::Direct { votes, delegations, prior } => {
@@ -1403,7 +1622,8 @@ impl<T: Config> Module<T> {
 					voting.set_common(delegations, prior);
 				}
 			}
-			let votes = Self::increase_upstream_delegation(&target, conviction.votes(balance));
+			let votes =
+				Self::increase_upstream_delegation(&target, track, conviction.votes(balance));
 			// Extend the lock to `balance` (rather than setting it) since we don't know what other
 			// votes are in place.
 			T::Currency::extend_lock(
@@ -1421,8 +1641,8 @@ impl<T: Config> Module<T> {
 	/// Attempt to end the current delegation.
 	///
 	/// Return the number of votes of upstream.
-	fn try_undelegate(who: T::AccountId) -> Result<u32, DispatchError> {
-		let votes = VotingOf::<T>::try_mutate(&who, |voting| -> Result<u32, DispatchError> {
+	fn try_undelegate(who: T::AccountId, track: Option<TrackId>) -> Result<u32, DispatchError> {
+		let votes = Self::try_mutate_voting(&who, track, |voting| -> Result<u32, DispatchError> {
 			let mut old = Voting::default();
 			sp_std::mem::swap(&mut old, voting);
 			match old {
@@ -1434,10 +1654,11 @@ impl<T: Config> Module<T> {
 					mut prior,
 				} => {
 					// remove any delegation votes to our current target.
-					let votes = Self::reduce_upstream_delegation(&target, conviction.votes(balance));
+					let votes =
+						Self::reduce_upstream_delegation(&target, track, conviction.votes(balance));
 					let now = system::Module::<T>::block_number();
 					let lock_periods = conviction.lock_periods().into();
-					prior.accumulate(now + T::EnactmentPeriod::get() * lock_periods, balance);
+					prior.accumulate(now + Self::lock_period(track) * lock_periods, balance);
 					voting.set_common(delegations, prior);
 
 					Ok(votes)
@@ -1454,10 +1675,17 @@ impl<T: Config> Module<T> {
 	/// Rejig the lock on an account. It will never get more stringent (since that would indicate
 	/// a security hole) but may be reduced from what they are currently.
 	fn update_lock(who: &T::AccountId) {
-		let lock_needed = VotingOf::<T>::mutate(who, |voting| {
-			voting.rejig(system::Module::<T>::block_number());
+		let now = system::Module::<T>::block_number();
+		let mut lock_needed = VotingOf::<T>::mutate(who, |voting| {
+			voting.rejig(now);
 			voting.locked_balance()
 		});
+		// The votes of all tracks share the same lock.
+		for (track, mut voting) in TrackVotingOf::<T>::iter_prefix(who).collect::<Vec<_>>() {
+			voting.rejig(now);
+			lock_needed = lock_needed.max(voting.locked_balance());
+			TrackVotingOf::<T>::insert(who, track, voting);
+		}
 		if lock_needed.is_zero() {
 			T::Currency::remove_lock(DEMOCRACY_ID, who);
 		} else {
@@ -1544,16 +1772,22 @@ impl<T: Config> Module<T> {
 				let _ = T::Currency::unreserve(&provider, deposit);
 				Self::deposit_event(RawEvent::PreimageUsed(proposal_hash, provider, deposit));
 
-				let ok = proposal.dispatch(frame_system::RawOrigin::Root.into()).is_ok();
+				let origin: T::Origin = match <TrackReferendumInfoOf<T>>::take(index) {
+					Some(referendum) => referendum.origin.into(),
+					None => frame_system::RawOrigin::Root.into(),
+				};
+				let ok = proposal.dispatch(origin).is_ok();
 				Self::deposit_event(RawEvent::Executed(index, ok));
 
 				Ok(())
 			} else {
+				<TrackReferendumInfoOf<T>>::remove(index);
 				T::Slash::on_unbalanced(T::Currency::slash_reserved(&provider, deposit).0);
 				Self::deposit_event(RawEvent::PreimageInvalid(proposal_hash, index));
 				Err(Error::<T>::PreimageInvalid.into())
 			}
 		} else {
+			<TrackReferendumInfoOf<T>>::remove(index);
 			Self::deposit_event(RawEvent::PreimageMissing(proposal_hash, index));
 			Err(Error::<T>::PreimageMissing.into())
 		}
@@ -1564,8 +1798,11 @@ impl<T: Config> Module<T> {
 		index: ReferendumIndex,
 		status: ReferendumStatus<T::BlockNumber, T::Hash, BalanceOf<T>>,
 	) -> Result<bool, DispatchError> {
-		let total_issuance = T::Currency::total_issuance();
-		let approved = status.threshold.approved(status.tally, total_issuance);
+		let approved = if <TrackReferendumInfoOf<T>>::contains_key(index) {
+			Self::track_passing(now, index, &status.tally)
+		} else {
+			status.threshold.approved(status.tally, T::Currency::total_issuance())
+		};
 
 		if approved {
 			Self::deposit_event(RawEvent::Passed(index));
@@ -1588,10 +1825,12 @@ impl<T: Config> Module<T> {
 					Call::enact_proposal(status.proposal_hash, index).into(),
 				).is_err() {
 					frame_support::print("LOGIC ERROR: bake_referendum/schedule_named failed");
+					<TrackReferendumInfoOf<T>>::remove(index);
 				}
 			}
 		} else {
 			Self::deposit_event(RawEvent::NotPassed(index));
+			<TrackReferendumInfoOf<T>>::remove(index);
 		}
 
 		Ok(approved)
@@ -1607,6 +1846,9 @@ impl<T: Config> Module<T> {
 	///   `ReferendumCount`, `LowestUnbaked`
 	/// - Db writes: `PublicProps`, `account`, `ReferendumCount`, `DepositOf`, `ReferendumInfoOf`
 	/// - Db reads per R: `DepositOf`, `ReferendumInfoOf`
+	/// - Db reads per track: `DecidingOf`
+	/// - Db reads per deciding referendum: `ReferendumInfoOf`, `TrackReferendumInfoOf`,
+	///   `TotalIssuance`
 	/// # </weight>
 	fn begin_block(now: T::BlockNumber) -> Result<Weight, DispatchError> {
 		let max_block_weight = T::BlockWeights::get().max_block;
@@ -1624,16 +1866,175 @@ impl<T: Config> Module<T> {
 		let last = Self::referendum_count();
 		let r = last.saturating_sub(next);
 		weight = weight.saturating_add(T::WeightInfo::on_initialize_base(r));
+		// approve any referenda on a track which already pass the curves of their track.
+		let tracks = T::Tracks::tracks();
+		weight = weight.saturating_add(T::DbWeight::get().reads(tracks.len() as Weight));
+		for (track, _) in tracks {
+			let deciding = DecidingOf::get(track);
+			weight = weight.saturating_add(T::DbWeight::get().reads(3 * deciding.len() as Weight));
+			for index in deciding.into_iter() {
+				if let Ok(status) = Self::referendum_status(index) {
+					if status.end > now && Self::track_passing(now, index, &status.tally) {
+						Self::finish_referendum(now, index, status)?;
+						weight = max_block_weight;
+					}
+				}
+			}
+		}
 		// tally up votes for any expiring referenda.
 		for (index, info) in Self::maturing_referenda_at_inner(now, next..last).into_iter() {
-			let approved = Self::bake_referendum(now, index, info)?;
-			ReferendumInfoOf::<T>::insert(index, ReferendumInfo::Finished { end: now, approved });
+			Self::finish_referendum(now, index, info)?;
 			weight = max_block_weight;
 		}
 
 		Ok(weight)
 	}
 
+	/// Free the deciding slot of the referendum `index` if it is on a track, then bake it and
+	/// record its result.
+	fn finish_referendum(
+		now: T::BlockNumber,
+		index: ReferendumIndex,
+		status: ReferendumStatus<T::BlockNumber, T::Hash, BalanceOf<T>>,
+	) -> DispatchResult {
+		Self::conclude_track_referendum(now, index);
+		let approved = Self::bake_referendum(now, index, status)?;
+		ReferendumInfoOf::<T>::insert(index, ReferendumInfo::Finished { end: now, approved });
+		Ok(())
+	}
+
+	/// Start deciding the referendum `ref_index` on its track.
+	fn begin_deciding(
+		now: T::BlockNumber,
+		ref_index: ReferendumIndex,
+		mut referendum: TrackReferendumOf<T>,
+	) {
+		let decision_period = T::Tracks::info(referendum.track)
+			.map_or_else(Zero::zero, |info| info.decision_period);
+		referendum.deciding = Some(now);
+		DecidingOf::append(referendum.track, ref_index);
+		let item = ReferendumInfo::new(
+			now + decision_period,
+			referendum.proposal_hash,
+			VoteThreshold::SimpleMajority,
+			referendum.delay,
+		);
+		<ReferendumInfoOf<T>>::insert(ref_index, item);
+		Self::deposit_event(RawEvent::DecisionStarted(ref_index, referendum.track));
+		<TrackReferendumInfoOf<T>>::insert(ref_index, referendum);
+	}
+
+	/// Remove the referendum `ref_index` from the deciding referenda or the queue of its track,
+	/// if it is a referendum on a track which is still there, and return the deposit of its
+	/// submitter.
+	///
+	/// If it was being decided, the oldest referendum queued on the track starts to be decided.
+	fn conclude_track_referendum(now: T::BlockNumber, ref_index: ReferendumIndex) {
+		let referendum = match <TrackReferendumInfoOf<T>>::get(ref_index) {
+			Some(referendum) => referendum,
+			None => return,
+		};
+		let track = referendum.track;
+		let remove = |indices: &mut Vec<ReferendumIndex>| {
+			indices.iter().position(|&i| i == ref_index).map(|p| indices.remove(p)).is_some()
+		};
+		let was_deciding = referendum.deciding.is_some();
+		let removed = if was_deciding {
+			DecidingOf::mutate(track, remove)
+		} else {
+			TrackQueue::mutate(track, remove)
+		};
+		if !removed {
+			return;
+		}
+
+		T::Currency::unreserve(&referendum.submitter, referendum.deposit);
+		if was_deciding {
+			let next = TrackQueue::mutate(track, |queue| {
+				if queue.is_empty() { None } else { Some(queue.remove(0)) }
+			});
+			if let Some((next, referendum)) = next
+				.and_then(|next| Self::track_referendum_info(next).map(|r| (next, r)))
+			{
+				Self::begin_deciding(now, next, referendum);
+			}
+		}
+	}
+
+	/// Whether the referendum `ref_index` on a track, with the given `tally`, passes the curves of
+	/// its track at block `now`.
+	fn track_passing(
+		now: T::BlockNumber,
+		ref_index: ReferendumIndex,
+		tally: &Tally<BalanceOf<T>>,
+	) -> bool {
+		let referendum = match Self::track_referendum_info(ref_index) {
+			Some(referendum) => referendum,
+			None => return false,
+		};
+		let info = match T::Tracks::info(referendum.track) {
+			Some(info) => info,
+			None => return false,
+		};
+		let elapsed = now.saturating_sub(referendum.deciding.unwrap_or(now));
+		let x = Perbill::from_rational_approximation(elapsed, info.decision_period);
+		let total_issuance = T::Currency::total_issuance();
+		info.min_approval.passing(x, tally.approval()) &&
+			info.min_support.passing(x, tally.support(total_issuance))
+	}
+
+	/// The voting class of the referendum `ref_index`: its track if it was submitted on one, or
+	/// `None` if it was launched from the public or external queues.
+	fn voting_class(ref_index: ReferendumIndex) -> Option<TrackId> {
+		Self::track_referendum_info(ref_index).map(|referendum| referendum.track)
+	}
+
+	/// The voting class in which `who` voted on the referendum `ref_index`.
+	///
+	/// Unlike `voting_class`, this also finds the track of a referendum which is already over,
+	/// from the votes of `who` on each track.
+	fn voting_class_of(who: &T::AccountId, ref_index: ReferendumIndex) -> Option<TrackId> {
+		Self::voting_class(ref_index).or_else(|| T::Tracks::tracks().iter()
+			.map(|&(track, _)| track)
+			.find(|&track| match TrackVotingOf::<T>::get(who, track) {
+				Voting::Direct { votes, .. } =>
+					votes.binary_search_by_key(&ref_index, |i| i.0).is_ok(),
+				Voting::Delegating { .. } => false,
+			})
+		)
+	}
+
+	/// The period the votes and delegations of the voting class `track` are locked for, per
+	/// lock period of their conviction.
+	fn lock_period(track: Option<TrackId>) -> T::BlockNumber {
+		track.and_then(T::Tracks::info)
+			.map_or_else(T::EnactmentPeriod::get, |info| info.min_enactment_period)
+	}
+
+	/// Mutate the voting of `who` for the voting class `track`.
+	fn mutate_voting<R>(
+		who: &T::AccountId,
+		track: Option<TrackId>,
+		f: impl FnOnce(&mut VotingFor<T>) -> R,
+	) -> R {
+		match track {
+			None => VotingOf::<T>::mutate(who, f),
+			Some(track) => TrackVotingOf::<T>::mutate(who, track, f),
+		}
+	}
+
+	/// Try to mutate the voting of `who` for the voting class `track`.
+	fn try_mutate_voting<R, E>(
+		who: &T::AccountId,
+		track: Option<TrackId>,
+		f: impl FnOnce(&mut VotingFor<T>) -> Result<R, E>,
+	) -> Result<R, E> {
+		match track {
+			None => VotingOf::<T>::try_mutate(who, f),
+			Some(track) => TrackVotingOf::<T>::try_mutate(who, track, f),
+		}
+	}
+
 	/// Reads the length of account in DepositOf without getting the complete value in the runtime.
 	///
 	/// Return 0 if no deposit for this proposal.
@@ -42,6 +42,7 @@ mod public_proposals;
 mod scheduling;
 mod voting;
 mod decoders;
+mod tracks;
 
 const AYE: Vote = Vote { aye: true, conviction: Conviction::None };
 const NAY: Vote = Vote { aye: false, conviction: Conviction::None };
@@ -168,6 +169,55 @@ impl Contains<u64> for OneToFive {
 	fn add(_m: &u64) {}
 }
 
+pub struct TestTracksInfo;
+impl TracksInfo<u64, u64> for TestTracksInfo {
+	type Origin = OriginCaller;
+	fn tracks() -> &'static [(TrackId, TrackInfo<u64, u64>)] {
+		static DATA: [(TrackId, TrackInfo<u64, u64>); 2] = [
+			(0, TrackInfo {
+				name: "root",
+				max_deciding: 1,
+				max_queued: 2,
+				decision_deposit: 10,
+				decision_period: 4,
+				min_enactment_period: 2,
+				min_approval: Curve::LinearDecreasing {
+					begin: Perbill::from_percent(100),
+					end: Perbill::from_percent(50),
+				},
+				min_support: Curve::LinearDecreasing {
+					begin: Perbill::from_percent(50),
+					end: Perbill::from_percent(10),
+				},
+			}),
+			(1, TrackInfo {
+				name: "signed",
+				max_deciding: 2,
+				max_queued: 2,
+				decision_deposit: 1,
+				decision_period: 4,
+				min_enactment_period: 1,
+				min_approval: Curve::LinearDecreasing {
+					begin: Perbill::from_percent(100),
+					end: Perbill::from_percent(50),
+				},
+				min_support: Curve::LinearDecreasing {
+					begin: Perbill::from_percent(50),
+					end: Perbill::from_percent(10),
+				},
+			}),
+		];
+		&DATA[..]
+	}
+	fn track_for(origin: &OriginCaller) -> Result<TrackId, ()> {
+		match origin {
+			OriginCaller::system(frame_system::RawOrigin::Root) => Ok(0),
+			OriginCaller::system(frame_system::RawOrigin::Signed(_)) => Ok(1),
+			_ => Err(()),
+		}
+	}
+}
+
 impl super::Config for Test {
 	type Proposal = Call;
 	type Event = Event;
@@ -194,6 +244,7 @@ impl super::Config for Test {
 	type MaxVotes = MaxVotes;
 	type OperationalPreimageOrigin = EnsureSignedBy<Six, u64>;
 	type PalletsOrigin = OriginCaller;
+	type Tracks = TestTracksInfo;
 	type WeightInfo = ();
 	type MaxProposals = MaxProposals;
 }
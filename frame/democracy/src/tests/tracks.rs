@@ -0,0 +1,248 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The tests for referendum tracks.
+
+use super::*;
+use frame_support::storage::{StorageMap, StorageDoubleMap};
+
+fn root() -> Box<OriginCaller> {
+	Box::new(frame_system::RawOrigin::Root.into())
+}
+
+fn submit_set_balance(who: u64, value: u64) -> DispatchResult {
+	Democracy::submit(Origin::signed(who), root(), set_balance_proposal_hash_and_note(value), 2)
+}
+
+fn the_lock(amount: u64) -> BalanceLock<u64> {
+	BalanceLock {
+		id: DEMOCRACY_ID,
+		amount,
+		reasons: pallet_balances::Reasons::Misc,
+	}
+}
+
+#[test]
+fn submit_should_check_track_and_delay() {
+	new_test_ext().execute_with(|| {
+		let h = set_balance_proposal_hash_and_note(2);
+		let none: Box<OriginCaller> = Box::new(frame_system::RawOrigin::None.into());
+		assert_noop!(Democracy::submit(Origin::signed(6), none, h, 2), Error::<Test>::NoTrack);
+		assert_noop!(
+			Democracy::submit(Origin::signed(6), root(), h, 1),
+			Error::<Test>::DelayTooShort,
+		);
+		assert_noop!(
+			Democracy::submit(Origin::signed(7), root(), h, 2),
+			BalancesError::<Test, _>::InsufficientBalance,
+		);
+	});
+}
+
+#[test]
+fn submit_should_start_deciding() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(submit_set_balance(6, 2));
+		assert_eq!(Democracy::referendum_count(), 1);
+		assert_eq!(Balances::reserved_balance(6), 10);
+		assert_eq!(Democracy::deciding_of(0), vec![0]);
+		assert_eq!(Democracy::track_referendum_info(0).unwrap().deciding, Some(1));
+		assert_eq!(Democracy::referendum_status(0).unwrap().end, 5);
+	});
+}
+
+#[test]
+fn submit_should_check_queue_full() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(submit_set_balance(6, 2));
+		assert_ok!(submit_set_balance(5, 3));
+		assert_ok!(submit_set_balance(4, 4));
+		assert_eq!(Democracy::track_queue(0), vec![1, 2]);
+		assert_noop!(submit_set_balance(3, 5), Error::<Test>::QueueFull);
+	});
+}
+
+#[test]
+fn track_queue_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(submit_set_balance(6, 2));
+		assert_ok!(submit_set_balance(5, 3));
+		// Only one referendum may be decided at a time on the root track.
+		assert_eq!(Democracy::deciding_of(0), vec![0]);
+		assert_eq!(Democracy::track_queue(0), vec![1]);
+		assert_noop!(
+			Democracy::vote(Origin::signed(1), 1, aye(1)),
+			Error::<Test>::ReferendumInvalid,
+		);
+
+		// The first referendum is rejected at the end of its decision period.
+		fast_forward_to(5);
+		assert_eq!(Democracy::referendum_info(0), Some(ReferendumInfo::Finished {
+			end: 5,
+			approved: false,
+		}));
+		assert_eq!(Balances::reserved_balance(6), 0);
+		assert!(Democracy::track_referendum_info(0).is_none());
+		assert_eq!(Democracy::deciding_of(0), vec![1]);
+		assert!(Democracy::track_queue(0).is_empty());
+		assert_eq!(Democracy::referendum_status(1).unwrap().end, 9);
+
+		// Cancelling a queued or deciding referendum returns the deposit.
+		assert_ok!(submit_set_balance(4, 4));
+		assert_eq!(Democracy::track_queue(0), vec![2]);
+		assert_ok!(Democracy::cancel_referendum(Origin::root(), 2));
+		assert!(Democracy::track_queue(0).is_empty());
+		assert_eq!(Balances::reserved_balance(4), 0);
+		assert_ok!(Democracy::cancel_referendum(Origin::root(), 1));
+		assert!(Democracy::deciding_of(0).is_empty());
+		assert_eq!(Balances::reserved_balance(5), 0);
+		assert!(Democracy::track_referendum_info(1).is_none());
+		assert!(Democracy::track_referendum_info(2).is_none());
+	});
+}
+
+#[test]
+fn referendum_should_pass_early_with_approval_and_support() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(submit_set_balance(6, 2));
+		assert_ok!(Democracy::vote(Origin::signed(5), 0, aye(5)));
+		assert_ok!(Democracy::vote(Origin::signed(6), 0, aye(6)));
+
+		// 48% support passes the 40% needed after a quarter of the decision period.
+		next_block();
+		assert_eq!(Democracy::referendum_info(0), Some(ReferendumInfo::Finished {
+			end: 2,
+			approved: true,
+		}));
+		assert_eq!(Balances::reserved_balance(6), 0);
+		assert!(Democracy::deciding_of(0).is_empty());
+
+		fast_forward_to(4);
+		assert_eq!(Balances::free_balance(42), 2);
+		assert!(Democracy::track_referendum_info(0).is_none());
+	});
+}
+
+#[test]
+fn referendum_should_be_rejected_without_support() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(submit_set_balance(6, 2));
+		assert_ok!(Democracy::vote(Origin::signed(1), 0, aye(1)));
+
+		// 5% support never passes the 10% needed at the end of the decision period.
+		fast_forward_to(4);
+		assert!(Democracy::referendum_status(0).is_ok());
+		next_block();
+		assert_eq!(Democracy::referendum_info(0), Some(ReferendumInfo::Finished {
+			end: 5,
+			approved: false,
+		}));
+
+		fast_forward_to(8);
+		assert_eq!(Balances::free_balance(42), 0);
+	});
+}
+
+#[test]
+fn proposal_should_be_dispatched_with_track_origin() {
+	new_test_ext().execute_with(|| {
+		let p = Call::Balances(pallet_balances::Call::transfer(42, 2)).encode();
+		let h = BlakeTwo256::hash(&p[..]);
+		assert_ok!(Democracy::note_preimage(Origin::signed(6), p));
+		let signed: Box<OriginCaller> = Box::new(frame_system::RawOrigin::Signed(3).into());
+		assert_noop!(
+			Democracy::submit(Origin::signed(6), signed.clone(), h, 0),
+			Error::<Test>::DelayTooShort,
+		);
+		assert_ok!(Democracy::submit(Origin::signed(6), signed, h, 1));
+		assert_eq!(Democracy::deciding_of(1), vec![0]);
+		assert_ok!(Democracy::vote(Origin::signed(5), 0, aye(5)));
+		assert_ok!(Democracy::vote(Origin::signed(6), 0, aye(6)));
+
+		fast_forward_to(3);
+		assert_eq!(Balances::free_balance(42), 2);
+		assert_eq!(Balances::free_balance(3), 28);
+	});
+}
+
+#[test]
+fn delegation_should_be_per_track() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(submit_set_balance(6, 2));
+		assert_noop!(
+			Democracy::delegate_track(Origin::signed(2), 7, 1, Conviction::None, 20),
+			Error::<Test>::BadTrack,
+		);
+		assert_ok!(Democracy::delegate_track(Origin::signed(2), 0, 1, Conviction::None, 20));
+		assert_ok!(Democracy::vote(Origin::signed(1), 0, aye(1)));
+		assert_eq!(tally(0), Tally { ayes: 3, nays: 0, turnout: 30 });
+
+		// A delegation for another track or for the public and external queues doesn't count.
+		assert_ok!(Democracy::delegate_track(Origin::signed(3), 1, 1, Conviction::None, 30));
+		assert_ok!(Democracy::delegate(Origin::signed(4), 1, Conviction::None, 40));
+		assert_eq!(tally(0), Tally { ayes: 3, nays: 0, turnout: 30 });
+
+		// The vote of 1 is only recorded for the track.
+		assert!(TrackVotingOf::<Test>::contains_key(1, 0));
+		assert!(matches!(
+			VotingOf::<Test>::get(1),
+			Voting::Direct { votes, .. } if votes.is_empty()
+		));
+		assert_noop!(
+			Democracy::vote(Origin::signed(2), 0, aye(2)),
+			Error::<Test>::AlreadyDelegating,
+		);
+
+		assert_noop!(
+			Democracy::undelegate_track(Origin::signed(2), 1),
+			Error::<Test>::NotDelegating,
+		);
+		assert_ok!(Democracy::undelegate_track(Origin::signed(2), 0));
+		assert_eq!(tally(0), Tally { ayes: 1, nays: 0, turnout: 10 });
+	});
+}
+
+#[test]
+fn track_votes_should_lock_for_track_enactment_period() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(submit_set_balance(6, 2));
+		assert_ok!(Democracy::vote(Origin::signed(5), 0, big_aye(5)));
+		assert_ok!(Democracy::vote(Origin::signed(4), 0, aye(4)));
+		assert_ok!(Democracy::vote(Origin::signed(3), 0, aye(3)));
+		assert_eq!(Balances::locks(5), vec![the_lock(50)]);
+
+		// Passes at block 2, so the vote of 5 is locked until block 2 + 2 * 1.
+		next_block();
+		assert_ok!(Democracy::remove_vote(Origin::signed(5), 0));
+		assert_ok!(Democracy::unlock(Origin::signed(5), 5));
+		assert_eq!(Balances::locks(5), vec![the_lock(50)]);
+
+		assert_ok!(Democracy::remove_vote(Origin::signed(4), 0));
+		assert_ok!(Democracy::unlock(Origin::signed(4), 4));
+		assert_eq!(Balances::locks(4), vec![]);
+
+		fast_forward_to(4);
+		assert_ok!(Democracy::unlock(Origin::signed(5), 5));
+		assert_eq!(Balances::locks(5), vec![]);
+
+		// The vote is still found on its track once the referendum is enacted.
+		assert!(Democracy::track_referendum_info(0).is_none());
+		assert_ok!(Democracy::remove_vote(Origin::signed(3), 0));
+		assert_ok!(Democracy::unlock(Origin::signed(3), 3));
+		assert_eq!(Balances::locks(3), vec![]);
+	});
+}
@@ -0,0 +1,107 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Referendum tracks and the curves used to decide their referenda.
+
+use codec::{Encode, Decode};
+use sp_runtime::{RuntimeDebug, Perbill, traits::Saturating};
+
+/// A track index.
+pub type TrackId = u16;
+
+/// A curve describing how a threshold evolves over the decision period of a referendum.
+#[derive(Encode, Decode, Copy, Clone, PartialEq, Eq, RuntimeDebug)]
+pub enum Curve {
+	/// Decreases linearly from `begin` at the start of the decision period to `end` at its end.
+	LinearDecreasing { begin: Perbill, end: Perbill },
+}
+
+impl Curve {
+	/// The threshold after the proportion `x` of the decision period has elapsed.
+	pub fn threshold(&self, x: Perbill) -> Perbill {
+		match self {
+			Curve::LinearDecreasing { begin, end } => {
+				let delta = begin.saturating_sub(*end);
+				begin.saturating_sub(Perbill::from_parts(x * delta.deconstruct()))
+			}
+		}
+	}
+
+	/// Whether `y` meets the threshold after the proportion `x` of the decision period has
+	/// elapsed.
+	pub fn passing(&self, x: Perbill, y: Perbill) -> bool {
+		y >= self.threshold(x)
+	}
+}
+
+/// Info regarding a referendum track.
+#[derive(Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct TrackInfo<Balance, BlockNumber> {
+	/// Name of the track.
+	pub name: &'static str,
+	/// The maximum number of referenda which may be decided on this track at the same time.
+	pub max_deciding: u32,
+	/// The maximum number of referenda which may wait for a free deciding slot on this track.
+	pub max_queued: u32,
+	/// The amount reserved from the submitter of a referendum until it is decided.
+	pub decision_deposit: Balance,
+	/// The number of blocks a referendum is decided for, at most.
+	pub decision_period: BlockNumber,
+	/// The minimum number of blocks between the approval of a referendum and its enactment.
+	///
+	/// This is also the lock period of the votes and delegations on this track.
+	pub min_enactment_period: BlockNumber,
+	/// The minimum proportion of aye votes, post-conviction, needed for approval.
+	pub min_approval: Curve,
+	/// The minimum proportion of the total issuance voting, pre-conviction, needed for approval.
+	pub min_support: Curve,
+}
+
+/// Information on the referendum tracks of a runtime.
+pub trait TracksInfo<Balance, BlockNumber> {
+	/// The origin from which the track of a referendum is implied.
+	type Origin;
+
+	/// The known tracks and their information.
+	fn tracks() -> &'static [(TrackId, TrackInfo<Balance, BlockNumber>)];
+
+	/// Determine the track on which a referendum dispatching as `origin` is decided.
+	fn track_for(origin: &Self::Origin) -> Result<TrackId, ()>;
+
+	/// Return the info of the track `id`, by default this just looks it up in `Self::tracks()`.
+	fn info(id: TrackId) -> Option<&'static TrackInfo<Balance, BlockNumber>> {
+		Self::tracks().iter().find(|x| x.0 == id).map(|x| &x.1)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn linear_decreasing_curve_works() {
+		let c = Curve::LinearDecreasing {
+			begin: Perbill::from_percent(100),
+			end: Perbill::from_percent(50),
+		};
+		assert_eq!(c.threshold(Perbill::zero()), Perbill::from_percent(100));
+		assert_eq!(c.threshold(Perbill::from_percent(50)), Perbill::from_percent(75));
+		assert_eq!(c.threshold(Perbill::one()), Perbill::from_percent(50));
+		assert!(c.passing(Perbill::one(), Perbill::from_percent(50)));
+		assert!(!c.passing(Perbill::from_percent(50), Perbill::from_percent(70)));
+	}
+}
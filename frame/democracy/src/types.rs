@@ -18,9 +18,11 @@
 //! Miscellaneous additional datatypes.
 
 use codec::{Encode, Decode};
-use sp_runtime::RuntimeDebug;
-use sp_runtime::traits::{Zero, Bounded, CheckedAdd, CheckedSub, CheckedMul, CheckedDiv, Saturating};
-use crate::{Vote, VoteThreshold, AccountVote, Conviction};
+use sp_runtime::{RuntimeDebug, Perbill};
+use sp_runtime::traits::{
+	Zero, Bounded, CheckedAdd, CheckedSub, CheckedMul, CheckedDiv, Saturating, AtLeast32BitUnsigned,
+};
+use crate::{Vote, VoteThreshold, AccountVote, Conviction, TrackId};
 
 /// Info regarding an ongoing referendum.
 #[derive(Encode, Decode, Default, Clone, PartialEq, Eq, RuntimeDebug)]
@@ -160,6 +162,18 @@ impl<
 	}
 }
 
+impl<Balance: AtLeast32BitUnsigned + Copy> Tally<Balance> {
+	/// The proportion of aye votes, expressed in terms of post-conviction lock-vote.
+	pub fn approval(&self) -> Perbill {
+		Perbill::from_rational_approximation(self.ayes, self.ayes.saturating_add(self.nays))
+	}
+
+	/// The proportion of the `total_issuance` currently expressing its opinion.
+	pub fn support(&self, total_issuance: Balance) -> Perbill {
+		Perbill::from_rational_approximation(self.turnout, total_issuance)
+	}
+}
+
 /// Info regarding an ongoing referendum.
 #[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
 pub struct ReferendumStatus<BlockNumber, Hash, Balance> {
@@ -167,7 +181,8 @@ pub struct ReferendumStatus<BlockNumber, Hash, Balance> {
 	pub (crate) end: BlockNumber,
 	/// The hash of the proposal being voted on.
 	pub (crate) proposal_hash: Hash,
-	/// The thresholding mechanism to determine whether it passed.
+	/// The thresholding mechanism to determine whether it passed. Unused for a referendum on a
+	/// track, which is decided by the curves of its track instead.
 	pub (crate) threshold: VoteThreshold,
 	/// The delay (in blocks) to wait after a successful referendum before deploying.
 	pub (crate) delay: BlockNumber,
@@ -197,6 +212,25 @@ impl<BlockNumber, Hash, Balance: Default> ReferendumInfo<BlockNumber, Hash, Bala
 	}
 }
 
+/// Info regarding a referendum submitted on a track.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct TrackReferendum<AccountId, Balance, BlockNumber, Hash, Origin> {
+	/// The track on which the referendum is decided.
+	pub (crate) track: TrackId,
+	/// The origin with which the proposal is dispatched once approved.
+	pub (crate) origin: Origin,
+	/// The hash of the proposal being voted on.
+	pub (crate) proposal_hash: Hash,
+	/// The delay (in blocks) to wait after a successful referendum before deploying.
+	pub (crate) delay: BlockNumber,
+	/// The account which submitted the referendum.
+	pub (crate) submitter: AccountId,
+	/// The amount reserved from the submitter until the referendum is decided.
+	pub (crate) deposit: Balance,
+	/// The block at which the referendum started to be decided, if it did.
+	pub (crate) deciding: Option<BlockNumber>,
+}
+
 /// Whether an `unvote` operation is able to make actions that are not strictly always in the
 /// interest of an account.
 pub enum UnvoteScope {
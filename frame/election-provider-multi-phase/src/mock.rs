@@ -0,0 +1,446 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Test utilities
+
+use super::*;
+use crate as multi_phase;
+pub use frame_support::{assert_noop, assert_ok};
+use frame_support::{
+	impl_outer_dispatch, impl_outer_event, impl_outer_origin, parameter_types,
+	traits::{OffchainWorker, OnInitialize},
+	weights::Weight,
+};
+use parking_lot::RwLock;
+use sp_core::{
+	offchain::{
+		testing::{PoolState, TestOffchainExt, TestTransactionPoolExt},
+		OffchainExt, TransactionPoolExt,
+	},
+	H256,
+};
+use sp_election_providers::ElectionDataProvider;
+use sp_npos_elections::{
+	assignment_ratio_to_staked_normalized, build_support_map, evaluate_support, seq_phragmen,
+	to_without_backing, CompactSolution, ElectionResult,
+};
+use sp_runtime::{
+	testing::{Header, TestXt},
+	traits::{BlakeTwo256, IdentityLookup},
+	PerU16,
+};
+use std::sync::Arc;
+
+impl_outer_origin! {
+	pub enum Origin for Runtime where system = frame_system {}
+}
+
+impl_outer_dispatch! {
+	pub enum OuterCall for Runtime where origin: Origin {
+		multi_phase::MultiPhase,
+	}
+}
+
+use frame_system as system;
+impl_outer_event! {
+	pub enum MetaEvent for Runtime {
+		system<T>,
+		pallet_balances<T>,
+		multi_phase<T>,
+	}
+}
+
+// Workaround for https://github.com/rust-lang/rust/issues/26925 . Remove when sorted.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct Runtime;
+
+pub(crate) type System = frame_system::Module<Runtime>;
+pub(crate) type Balances = pallet_balances::Module<Runtime>;
+pub(crate) type MultiPhase = multi_phase::Module<Runtime>;
+
+pub(crate) type Balance = u64;
+pub(crate) type AccountId = u64;
+pub(crate) type BlockNumber = u64;
+
+sp_npos_elections::generate_solution_type!(
+	#[compact]
+	pub struct TestCompact::<u32, u16, PerU16>(16)
+);
+
+/// All events of this pallet.
+pub(crate) fn multi_phase_events() -> Vec<super::Event<Runtime>> {
+	System::events()
+		.into_iter()
+		.map(|r| r.event)
+		.filter_map(|e| if let MetaEvent::multi_phase(inner) = e { Some(inner) } else { None })
+		.collect::<Vec<_>>()
+}
+
+/// To from `now` to block `n`.
+pub fn roll_to(n: u64) {
+	let now = System::block_number();
+	for i in now + 1..=n {
+		System::set_block_number(i);
+		MultiPhase::on_initialize(i);
+	}
+}
+
+pub fn roll_to_with_ocw(n: u64) {
+	let now = System::block_number();
+	for i in now + 1..=n {
+		System::set_block_number(i);
+		MultiPhase::on_initialize(i);
+		MultiPhase::offchain_worker(i);
+	}
+}
+
+/// Spit out a verifiable raw solution.
+///
+/// This is a good example of what an offchain miner would do.
+pub fn raw_solution() -> RawSolution<CompactOf<Runtime>> {
+	let RoundSnapshot { voters, targets } = MultiPhase::snapshot().unwrap();
+	let desired_targets = MultiPhase::desired_targets().unwrap();
+
+	// closures
+	let cache = helpers::generate_voter_cache::<Runtime>(&voters);
+	let voter_index = helpers::voter_index_fn_linear::<Runtime>(&voters);
+	let target_index = helpers::target_index_fn_linear::<Runtime>(&targets);
+	let stake_of = helpers::stake_of_fn::<Runtime>(&voters, &cache);
+
+	let ElectionResult { winners, assignments } = seq_phragmen::<_, CompactAccuracyOf<Runtime>>(
+		desired_targets as usize,
+		targets.clone(),
+		voters.clone(),
+		None,
+	)
+	.unwrap();
+
+	let winners = to_without_backing(winners);
+
+	let score = {
+		let staked = assignment_ratio_to_staked_normalized(assignments.clone(), &stake_of).unwrap();
+		let supports = build_support_map(&winners, &staked).unwrap();
+		evaluate_support(&supports)
+	};
+	let compact =
+		<CompactOf<Runtime>>::from_assignment(assignments, &voter_index, &target_index).unwrap();
+
+	let round = MultiPhase::round();
+	RawSolution { compact, score, round }
+}
+
+/// Free and reserved balance of `who`.
+pub fn balances(who: &AccountId) -> (Balance, Balance) {
+	(Balances::free_balance(who), Balances::reserved_balance(who))
+}
+
+pub fn witness() -> SolutionOrSnapshotSize {
+	MultiPhase::snapshot()
+		.map(|snap| SolutionOrSnapshotSize {
+			voters: snap.voters.len() as u32,
+			targets: snap.targets.len() as u32,
+		})
+		.unwrap_or_default()
+}
+
+impl frame_system::Config for Runtime {
+	type BaseCallFilter = ();
+	type BlockWeights = BlockWeights;
+	type BlockLength = ();
+	type DbWeight = ();
+	type Origin = Origin;
+	type Index = u64;
+	type BlockNumber = BlockNumber;
+	type Call = OuterCall;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = AccountId;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = MetaEvent;
+	type BlockHashCount = BlockHashCount;
+	type Version = ();
+	type PalletInfo = ();
+	type AccountData = pallet_balances::AccountData<u64>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+}
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub BlockWeights: frame_system::limits::BlockWeights =
+		frame_system::limits::BlockWeights::simple_max(
+			frame_support::weights::constants::WEIGHT_PER_SECOND * 2
+		);
+}
+
+parameter_types! {
+	pub const ExistentialDeposit: u64 = 1;
+}
+
+impl pallet_balances::Config for Runtime {
+	type Balance = Balance;
+	type Event = MetaEvent;
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+	type MaxLocks = ();
+	type WeightInfo = ();
+}
+
+parameter_types! {
+	pub static Targets: Vec<AccountId> = vec![10, 20, 30, 40];
+	pub static Voters: Vec<(AccountId, VoteWeight, Vec<AccountId>)> = vec![
+		(1, 10, vec![10, 20]),
+		(2, 10, vec![30, 40]),
+		(3, 10, vec![40]),
+		(4, 10, vec![10, 20, 30, 40]),
+		// self votes.
+		(10, 10, vec![10]),
+		(20, 20, vec![20]),
+		(30, 30, vec![30]),
+		(40, 40, vec![40]),
+	];
+
+	pub static Fallback: FallbackStrategy = FallbackStrategy::OnChain;
+	pub static DesiredTargets: u32 = 2;
+	pub static SignedPhase: u64 = 10;
+	pub static UnsignedPhase: u64 = 5;
+	pub static SignedMaxSubmissions: u32 = 5;
+	pub static SignedDepositBase: Balance = 5;
+	pub static SignedDepositByte: Balance = 0;
+	pub static SignedDepositWeight: Balance = 0;
+	pub static SignedRewardBase: Balance = 7;
+	pub static MinerMaxIterations: u32 = 5;
+	pub static MinerTxPriority: u64 = 100;
+	pub static SolutionImprovementThreshold: Perbill = Perbill::zero();
+	pub static MinerMaxWeight: Weight = BlockWeights::get().max_block;
+	pub static MockWeightInfo: bool = false;
+
+	pub static EpochLength: u64 = 30;
+}
+
+// Hopefully this won't be too much of a hassle to maintain.
+pub struct DualMockWeightInfo;
+impl multi_phase::weights::WeightInfo for DualMockWeightInfo {
+	fn on_initialize_nothing() -> Weight {
+		if MockWeightInfo::get() {
+			Zero::zero()
+		} else {
+			<() as multi_phase::weights::WeightInfo>::on_initialize_nothing()
+		}
+	}
+	fn on_initialize_open_signed() -> Weight {
+		if MockWeightInfo::get() {
+			Zero::zero()
+		} else {
+			<() as multi_phase::weights::WeightInfo>::on_initialize_open_signed()
+		}
+	}
+	fn on_initialize_open_unsigned_with_snapshot() -> Weight {
+		if MockWeightInfo::get() {
+			Zero::zero()
+		} else {
+			<() as multi_phase::weights::WeightInfo>::on_initialize_open_unsigned_with_snapshot()
+		}
+	}
+	fn on_initialize_open_unsigned_without_snapshot() -> Weight {
+		if MockWeightInfo::get() {
+			Zero::zero()
+		} else {
+			<() as multi_phase::weights::WeightInfo>::on_initialize_open_unsigned_without_snapshot()
+		}
+	}
+	fn finalize_signed_phase_accept_solution() -> Weight {
+		if MockWeightInfo::get() {
+			Zero::zero()
+		} else {
+			<() as multi_phase::weights::WeightInfo>::finalize_signed_phase_accept_solution()
+		}
+	}
+	fn finalize_signed_phase_reject_solution() -> Weight {
+		if MockWeightInfo::get() {
+			Zero::zero()
+		} else {
+			<() as multi_phase::weights::WeightInfo>::finalize_signed_phase_reject_solution()
+		}
+	}
+	fn submit(c: u32) -> Weight {
+		if MockWeightInfo::get() {
+			Zero::zero()
+		} else {
+			<() as multi_phase::weights::WeightInfo>::submit(c)
+		}
+	}
+	fn submit_unsigned(v: u32, t: u32, a: u32, d: u32) -> Weight {
+		if MockWeightInfo::get() {
+			// 10 base
+			// 5 per edge.
+			(10 as Weight).saturating_add((5 * a) as Weight)
+		} else {
+			<() as multi_phase::weights::WeightInfo>::submit_unsigned(v, t, a, d)
+		}
+	}
+	fn feasibility_check(v: u32, t: u32, a: u32, d: u32) -> Weight {
+		if MockWeightInfo::get() {
+			// 10 base
+			// 5 per edge.
+			(10 as Weight).saturating_add((5 * a) as Weight)
+		} else {
+			<() as multi_phase::weights::WeightInfo>::feasibility_check(v, t, a, d)
+		}
+	}
+}
+
+impl crate::Config for Runtime {
+	type Event = MetaEvent;
+	type Currency = Balances;
+	type SignedPhase = SignedPhase;
+	type UnsignedPhase = UnsignedPhase;
+	type SolutionImprovementThreshold = SolutionImprovementThreshold;
+	type SignedMaxSubmissions = SignedMaxSubmissions;
+	type SignedRewardBase = SignedRewardBase;
+	type SignedDepositBase = SignedDepositBase;
+	type SignedDepositByte = SignedDepositByte;
+	type SignedDepositWeight = SignedDepositWeight;
+	type SlashHandler = ();
+	type RewardHandler = ();
+	type MinerMaxIterations = MinerMaxIterations;
+	type MinerMaxWeight = MinerMaxWeight;
+	type MinerTxPriority = MinerTxPriority;
+	type DataProvider = StakingMock;
+	type WeightInfo = DualMockWeightInfo;
+	type OnChainAccuracy = Perbill;
+	type Fallback = Fallback;
+	type CompactSolution = TestCompact;
+}
+
+impl<LocalCall> frame_system::offchain::SendTransactionTypes<LocalCall> for Runtime
+where
+	OuterCall: From<LocalCall>,
+{
+	type OverarchingCall = OuterCall;
+	type Extrinsic = Extrinsic;
+}
+
+pub type Extrinsic = TestXt<OuterCall, ()>;
+
+#[derive(Default)]
+pub struct ExtBuilder {}
+
+pub struct StakingMock;
+impl ElectionDataProvider<AccountId, u64> for StakingMock {
+	const MAXIMUM_VOTES_PER_VOTER: u32 = <TestCompact as CompactSolution>::LIMIT as u32;
+
+	fn targets() -> Vec<AccountId> {
+		Targets::get()
+	}
+	fn voters() -> Vec<(AccountId, VoteWeight, Vec<AccountId>)> {
+		Voters::get()
+	}
+	fn desired_targets() -> u32 {
+		DesiredTargets::get()
+	}
+	fn next_election_prediction(now: u64) -> u64 {
+		now + EpochLength::get() - now % EpochLength::get()
+	}
+}
+
+impl ExtBuilder {
+	pub fn miner_tx_priority(self, p: u64) -> Self {
+		<MinerTxPriority>::set(p);
+		self
+	}
+	pub fn solution_improvement_threshold(self, p: Perbill) -> Self {
+		<SolutionImprovementThreshold>::set(p);
+		self
+	}
+	pub fn phases(self, signed: u64, unsigned: u64) -> Self {
+		<SignedPhase>::set(signed);
+		<UnsignedPhase>::set(unsigned);
+		self
+	}
+	pub fn fallback(self, fallback: FallbackStrategy) -> Self {
+		<Fallback>::set(fallback);
+		self
+	}
+	pub fn miner_weight(self, weight: Weight) -> Self {
+		<MinerMaxWeight>::set(weight);
+		self
+	}
+	pub fn mock_weight_info(self, mock: bool) -> Self {
+		<MockWeightInfo>::set(mock);
+		self
+	}
+	pub fn desired_targets(self, t: u32) -> Self {
+		<DesiredTargets>::set(t);
+		self
+	}
+	pub fn add_voter(self, who: AccountId, stake: VoteWeight, targets: Vec<AccountId>) -> Self {
+		VOTERS.with(|v| v.borrow_mut().push((who, stake, targets)));
+		self
+	}
+	pub fn signed_max_submission(self, count: u32) -> Self {
+		<SignedMaxSubmissions>::set(count);
+		self
+	}
+	pub fn signed_deposit(self, base: u64, byte: u64, weight: u64) -> Self {
+		<SignedDepositBase>::set(base);
+		<SignedDepositByte>::set(byte);
+		<SignedDepositWeight>::set(weight);
+		self
+	}
+	pub fn build(self) -> sp_io::TestExternalities {
+		let mut storage =
+			frame_system::GenesisConfig::default().build_storage::<Runtime>().unwrap();
+
+		let _ = pallet_balances::GenesisConfig::<Runtime> {
+			balances: vec![
+				// bunch of account for submitting stuff only.
+				(99, 100),
+				(999, 100),
+				(9999, 100),
+			],
+		}
+		.assimilate_storage(&mut storage);
+
+		sp_io::TestExternalities::from(storage)
+	}
+
+	pub fn build_offchainify(
+		self,
+		iters: u32,
+	) -> (sp_io::TestExternalities, Arc<RwLock<PoolState>>) {
+		let mut ext = self.build();
+		let (offchain, offchain_state) = TestOffchainExt::new();
+		let (pool, pool_state) = TestTransactionPoolExt::new();
+
+		let mut seed = [0_u8; 32];
+		seed[0..4].copy_from_slice(&iters.to_le_bytes());
+		offchain_state.write().seed = seed;
+
+		ext.register_extension(OffchainExt::new(offchain));
+		ext.register_extension(TransactionPoolExt::new(pool));
+
+		(ext, pool_state)
+	}
+
+	pub fn build_and_execute(self, test: impl FnOnce() -> ()) {
+		self.build().execute_with(test)
+	}
+}
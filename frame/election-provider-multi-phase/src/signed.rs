@@ -0,0 +1,640 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The signed phase implementation.
+
+use crate::*;
+use codec::Encode;
+use sp_runtime::traits::Saturating;
+
+/// A signed submission, as stored in [`SignedSubmissions`].
+pub type SignedSubmissionOf<T> =
+	SignedSubmission<<T as frame_system::Config>::AccountId, BalanceOf<T>, CompactOf<T>>;
+
+impl<T: Config> Module<T>
+where
+	ExtendedBalance: From<InnerOf<CompactAccuracyOf<T>>>,
+{
+	/// Finish the signed phase. Process the signed submissions from best to worse until a valid one
+	/// is found, rewarding the best one and slashing the invalid ones along the way.
+	///
+	/// Returns true if we have a good solution in the signed phase, and the consumed weight.
+	///
+	/// This drains the [`SignedSubmissions`], potentially storing the best valid one in
+	/// [`QueuedSolution`].
+	pub fn finalize_signed_phase() -> (bool, Weight) {
+		let mut all_submissions: Vec<SignedSubmissionOf<T>> = <SignedSubmissions<T>>::take();
+		let mut found_solution = false;
+		let mut weight = T::DbWeight::get().reads(1);
+
+		while let Some(best) = all_submissions.pop() {
+			let SignedSubmission { solution, who, deposit, reward } = best;
+			let active_voters = solution.compact.voter_count() as u32;
+			let feasibility_weight = {
+				// defensive only: at the end of signed phase, snapshot will exist.
+				let SolutionOrSnapshotSize { voters, targets } =
+					Self::snapshot_metadata().unwrap_or_default();
+				let desired_targets = Self::desired_targets().unwrap_or_default();
+				T::WeightInfo::feasibility_check(voters, targets, active_voters, desired_targets)
+			};
+			// the feasibility check itself has some weight
+			weight = weight.saturating_add(feasibility_weight);
+			match Self::feasibility_check(solution, ElectionCompute::Signed) {
+				Ok(ready_solution) => {
+					Self::finalize_signed_phase_accept_solution(
+						ready_solution,
+						&who,
+						deposit,
+						reward,
+					);
+					found_solution = true;
+
+					weight = weight
+						.saturating_add(T::WeightInfo::finalize_signed_phase_accept_solution());
+					break;
+				}
+				Err(_) => {
+					Self::finalize_signed_phase_reject_solution(&who, deposit);
+					weight = weight
+						.saturating_add(T::WeightInfo::finalize_signed_phase_reject_solution());
+				}
+			}
+		}
+
+		// Any unprocessed solution is pointless to even consider. Feasible or malicious,
+		// they didn't end up being used. Unreserve the bonds.
+		let discarded = all_submissions.len();
+		for SignedSubmission { who, deposit, .. } in all_submissions {
+			let _remaining = T::Currency::unreserve(&who, deposit);
+			weight = weight.saturating_add(T::DbWeight::get().writes(1));
+			debug_assert!(_remaining.is_zero());
+		}
+
+		log!(
+			debug,
+			"closed signed phase, found solution? {}, discarded {}",
+			found_solution,
+			discarded,
+		);
+		(found_solution, weight)
+	}
+
+	/// Helper function for the case where a solution is accepted in the signed phase.
+	///
+	/// Extracted to facilitate with weight calculation.
+	///
+	/// Infallible
+	pub fn finalize_signed_phase_accept_solution(
+		ready_solution: ReadySolution<T::AccountId>,
+		who: &T::AccountId,
+		deposit: BalanceOf<T>,
+		reward: BalanceOf<T>,
+	) {
+		// write this ready solution.
+		<QueuedSolution<T>>::put(ready_solution);
+
+		// emit reward event
+		Self::deposit_event(RawEvent::Rewarded(who.clone()));
+
+		// unreserve deposit.
+		let _remaining = T::Currency::unreserve(who, deposit);
+		debug_assert!(_remaining.is_zero());
+
+		// Reward.
+		let positive_imbalance = T::Currency::deposit_creating(who, reward);
+		T::RewardHandler::on_unbalanced(positive_imbalance);
+	}
+
+	/// Helper function for the case where a solution is rejected in the signed phase.
+	///
+	/// Extracted to facilitate with weight calculation.
+	///
+	/// Infallible
+	pub fn finalize_signed_phase_reject_solution(who: &T::AccountId, deposit: BalanceOf<T>) {
+		Self::deposit_event(RawEvent::Slashed(who.clone()));
+		let (negative_imbalance, _remaining) = T::Currency::slash_reserved(who, deposit);
+		debug_assert!(_remaining.is_zero());
+		T::SlashHandler::on_unbalanced(negative_imbalance);
+	}
+
+	/// Insert a solution into the queue while maintaining an ordering by solution quality.
+	///
+	/// Solutions are ordered in reverse: strong solutions have high indices.
+	///
+	/// If insertion was successful, returns the index of the newly inserted item, along with the
+	/// submission that had to be removed from the queue to make room for it, if any.
+	///
+	/// Invariant: The returned index is always a valid index in `queue` and can safely be used to
+	/// inspect the newly inserted element.
+	pub fn insert_submission(
+		who: &T::AccountId,
+		queue: &mut Vec<SignedSubmissionOf<T>>,
+		solution: RawSolution<CompactOf<T>>,
+		size: SolutionOrSnapshotSize,
+	) -> Option<(usize, Option<SignedSubmissionOf<T>>)> {
+		// from the last score, compare and see if the current one is better. If none, then the
+		// awarded index is 0.
+		let outcome = queue
+			.iter()
+			.enumerate()
+			.rev()
+			.find_map(|(i, s)| {
+				if is_score_better::<Perbill>(
+					solution.score,
+					s.solution.score,
+					T::SolutionImprovementThreshold::get(),
+				) {
+					Some(i + 1)
+				} else {
+					None
+				}
+			})
+			.or(Some(0))
+			.and_then(|at| {
+				if at == 0 && queue.len() as u32 >= T::SignedMaxSubmissions::get() {
+					// if this is worse than all, and the queue is full, don't bother.
+					None
+				} else {
+					// add to the designated spot. If the length is too much, remove one.
+					let reward = T::SignedRewardBase::get();
+					let deposit = Self::deposit_for(&solution, size);
+					let submission =
+						SignedSubmission { who: who.clone(), deposit, reward, solution };
+					// Proof: `at` must always less than or equal queue.len() for this not to panic.
+					// It is either 0 (in which case `0 <= queue.len()`) or one of the queue indices
+					// + 1. The biggest queue index is `queue.len() - 1`, thus `at <= queue.len()`.
+					queue.insert(at, submission);
+					if queue.len() as u32 > T::SignedMaxSubmissions::get() {
+						// the weakest submission is dropped. `at` can not be zero here, since a
+						// full queue rejects a solution that is not better than any queued one.
+						let ejected = queue.remove(0);
+						Some((at - 1, Some(ejected)))
+					} else {
+						Some((at, None))
+					}
+				}
+			});
+
+		debug_assert!(queue.len() as u32 <= T::SignedMaxSubmissions::get());
+		outcome
+	}
+
+	/// The feasibility weight of the given raw solution.
+	pub fn feasibility_weight_of(
+		solution: &RawSolution<CompactOf<T>>,
+		size: SolutionOrSnapshotSize,
+	) -> Weight {
+		T::WeightInfo::feasibility_check(
+			size.voters,
+			size.targets,
+			solution.compact.voter_count() as u32,
+			solution.compact.unique_targets().len() as u32,
+		)
+	}
+
+	/// Collect sufficient deposit to store this solution this chain.
+	///
+	/// The deposit is composed of 3 main elements:
+	///
+	/// 1. base deposit, fixed for all submissions.
+	/// 2. a per-byte deposit, for renting the state usage.
+	/// 3. a per-weight deposit, for the potential weight usage in an upcoming on_initialize
+	pub fn deposit_for(
+		solution: &RawSolution<CompactOf<T>>,
+		size: SolutionOrSnapshotSize,
+	) -> BalanceOf<T> {
+		let encoded_len: BalanceOf<T> = solution.using_encoded(|e| e.len() as u32).into();
+		let feasibility_weight = Self::feasibility_weight_of(solution, size);
+
+		let len_deposit = T::SignedDepositByte::get().saturating_mul(encoded_len);
+		let weight_deposit =
+			T::SignedDepositWeight::get().saturating_mul(feasibility_weight.saturated_into());
+
+		T::SignedDepositBase::get().saturating_add(len_deposit).saturating_add(weight_deposit)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{mock::*, *};
+
+	#[test]
+	fn cannot_submit_too_early() {
+		ExtBuilder::default().build_and_execute(|| {
+			roll_to(2);
+			assert_eq!(MultiPhase::current_phase(), Phase::Off);
+
+			// create a temp snapshot only for this test.
+			MultiPhase::create_snapshot();
+			let solution = raw_solution();
+
+			assert_noop!(
+				MultiPhase::submit(Origin::signed(10), solution, 0),
+				Error::<Runtime>::PreDispatchEarlySubmission,
+			);
+		})
+	}
+
+	#[test]
+	fn wrong_witness_fails() {
+		ExtBuilder::default().build_and_execute(|| {
+			roll_to(15);
+			assert!(MultiPhase::current_phase().is_signed());
+
+			let solution = raw_solution();
+			assert_ok!(MultiPhase::submit(Origin::signed(99), solution.clone(), 0));
+
+			// one submission is queued; claiming zero underestimates the weight.
+			assert_noop!(
+				MultiPhase::submit(Origin::signed(999), solution, 0),
+				Error::<Runtime>::SignedInvalidWitness,
+			);
+		})
+	}
+
+	#[test]
+	fn should_pay_deposit() {
+		ExtBuilder::default().build_and_execute(|| {
+			roll_to(15);
+			assert!(MultiPhase::current_phase().is_signed());
+
+			let solution = raw_solution();
+			assert_eq!(balances(&99), (100, 0));
+
+			assert_ok!(MultiPhase::submit(Origin::signed(99), solution, 0));
+
+			assert_eq!(balances(&99), (95, 5));
+			assert_eq!(MultiPhase::signed_submissions().first().unwrap().deposit, 5);
+			assert_eq!(
+				multi_phase_events(),
+				vec![
+					RawEvent::SignedPhaseStarted(1),
+					RawEvent::SolutionStored(ElectionCompute::Signed),
+				],
+			);
+		})
+	}
+
+	#[test]
+	fn cannot_pay_deposit() {
+		ExtBuilder::default().signed_deposit(200, 0, 0).build_and_execute(|| {
+			roll_to(15);
+			assert!(MultiPhase::current_phase().is_signed());
+
+			let solution = raw_solution();
+			assert_noop!(
+				MultiPhase::submit(Origin::signed(99), solution, 0),
+				Error::<Runtime>::SignedCannotPayDeposit,
+			);
+		})
+	}
+
+	#[test]
+	fn good_solution_is_rewarded() {
+		ExtBuilder::default().build_and_execute(|| {
+			roll_to(15);
+			assert!(MultiPhase::current_phase().is_signed());
+
+			let solution = raw_solution();
+			assert_eq!(balances(&99), (100, 0));
+
+			assert_ok!(MultiPhase::submit(Origin::signed(99), solution, 0));
+			assert_eq!(balances(&99), (95, 5));
+
+			assert!(MultiPhase::finalize_signed_phase().0);
+			assert_eq!(balances(&99), (100 + 7, 0));
+			assert_eq!(MultiPhase::queued_solution().unwrap().compute, ElectionCompute::Signed);
+		})
+	}
+
+	#[test]
+	fn bad_solution_is_slashed() {
+		ExtBuilder::default().build_and_execute(|| {
+			roll_to(15);
+			assert!(MultiPhase::current_phase().is_signed());
+
+			let mut solution = raw_solution();
+			assert_eq!(balances(&99), (100, 0));
+
+			// make the solution invalid.
+			solution.score[0] += 1;
+
+			assert_ok!(MultiPhase::submit(Origin::signed(99), solution, 0));
+			assert_eq!(balances(&99), (95, 5));
+
+			// no good solution was stored.
+			assert!(!MultiPhase::finalize_signed_phase().0);
+			// and the bond is gone.
+			assert_eq!(balances(&99), (95, 0));
+			assert!(MultiPhase::queued_solution().is_none());
+		})
+	}
+
+	#[test]
+	fn suppressed_solution_gets_bond_back() {
+		ExtBuilder::default().build_and_execute(|| {
+			roll_to(15);
+			assert!(MultiPhase::current_phase().is_signed());
+
+			let mut solution = raw_solution();
+			assert_eq!(balances(&99), (100, 0));
+			assert_eq!(balances(&999), (100, 0));
+
+			// submit as correct.
+			assert_ok!(MultiPhase::submit(Origin::signed(99), solution.clone(), 0));
+
+			// make the solution invalid and weaker.
+			solution.score[0] -= 1;
+			assert_ok!(MultiPhase::submit(Origin::signed(999), solution, 1));
+			assert_eq!(balances(&99), (95, 5));
+			assert_eq!(balances(&999), (95, 5));
+
+			assert!(MultiPhase::finalize_signed_phase().0);
+
+			// 99 is rewarded.
+			assert_eq!(balances(&99), (100 + 7, 0));
+			// 999 gets everything back.
+			assert_eq!(balances(&999), (100, 0));
+		})
+	}
+
+	#[test]
+	fn cannot_submit_worse_with_full_queue() {
+		ExtBuilder::default().build_and_execute(|| {
+			roll_to(15);
+			assert!(MultiPhase::current_phase().is_signed());
+
+			for s in 0..SignedMaxSubmissions::get() {
+				// score is always getting better
+				let solution = RawSolution { score: [(5 + s).into(), 0, 0], ..Default::default() };
+				assert_ok!(MultiPhase::submit(Origin::signed(99), solution, s));
+			}
+
+			// weaker.
+			let solution = RawSolution { score: [4, 0, 0], ..Default::default() };
+
+			assert_noop!(
+				MultiPhase::submit(Origin::signed(99), solution, SignedMaxSubmissions::get()),
+				Error::<Runtime>::SignedQueueFull,
+			);
+		})
+	}
+
+	#[test]
+	fn weakest_is_removed_if_better_provided() {
+		ExtBuilder::default().build_and_execute(|| {
+			roll_to(15);
+			assert!(MultiPhase::current_phase().is_signed());
+
+			for s in 0..SignedMaxSubmissions::get() {
+				// score is always getting better
+				let solution = RawSolution { score: [(5 + s).into(), 0, 0], ..Default::default() };
+				assert_ok!(MultiPhase::submit(Origin::signed(99), solution, s));
+			}
+
+			assert_eq!(
+				MultiPhase::signed_submissions()
+					.into_iter()
+					.map(|s| s.solution.score[0])
+					.collect::<Vec<_>>(),
+				vec![5, 6, 7, 8, 9]
+			);
+			assert_eq!(balances(&99), (75, 25));
+
+			// better.
+			let solution = RawSolution { score: [20, 0, 0], ..Default::default() };
+			assert_ok!(MultiPhase::submit(Origin::signed(999), solution, 5));
+
+			// the one with score 5 was rejected, the new one inserted.
+			assert_eq!(
+				MultiPhase::signed_submissions()
+					.into_iter()
+					.map(|s| s.solution.score[0])
+					.collect::<Vec<_>>(),
+				vec![6, 7, 8, 9, 20]
+			);
+			// and the deposit of the ejected one is returned.
+			assert_eq!(balances(&99), (80, 20));
+			assert_eq!(balances(&999), (95, 5));
+		})
+	}
+
+	#[test]
+	fn weakest_is_removed_if_better_provided_wont_remove_self() {
+		ExtBuilder::default().build_and_execute(|| {
+			roll_to(15);
+			assert!(MultiPhase::current_phase().is_signed());
+
+			for s in 1..SignedMaxSubmissions::get() {
+				// score is always getting better
+				let solution = RawSolution { score: [(5 + s).into(), 0, 0], ..Default::default() };
+				assert_ok!(MultiPhase::submit(Origin::signed(99), solution, s));
+			}
+
+			let solution = RawSolution { score: [4, 0, 0], ..Default::default() };
+			assert_ok!(MultiPhase::submit(Origin::signed(99), solution, 4));
+
+			assert_eq!(
+				MultiPhase::signed_submissions()
+					.into_iter()
+					.map(|s| s.solution.score[0])
+					.collect::<Vec<_>>(),
+				vec![4, 6, 7, 8, 9],
+			);
+
+			// better.
+			let solution = RawSolution { score: [5, 0, 0], ..Default::default() };
+			assert_ok!(MultiPhase::submit(Origin::signed(99), solution, 5));
+
+			// the one with score 4 was ejected, the new one inserted.
+			assert_eq!(
+				MultiPhase::signed_submissions()
+					.into_iter()
+					.map(|s| s.solution.score[0])
+					.collect::<Vec<_>>(),
+				vec![5, 6, 7, 8, 9],
+			);
+		})
+	}
+
+	#[test]
+	fn early_ejected_solution_gets_bond_back() {
+		ExtBuilder::default().signed_deposit(2, 0, 0).build_and_execute(|| {
+			roll_to(15);
+			assert!(MultiPhase::current_phase().is_signed());
+
+			for s in 0..SignedMaxSubmissions::get() {
+				// score is always getting better
+				let solution = RawSolution { score: [(5 + s).into(), 0, 0], ..Default::default() };
+				assert_ok!(MultiPhase::submit(Origin::signed(99), solution, s));
+			}
+
+			assert_eq!(balances(&99).1, 2 * 5);
+			assert_eq!(balances(&999).1, 0);
+
+			// better.
+			let solution = RawSolution { score: [20, 0, 0], ..Default::default() };
+			assert_ok!(MultiPhase::submit(Origin::signed(999), solution, 5));
+
+			// got one bond back.
+			assert_eq!(balances(&99).1, 2 * 4);
+			assert_eq!(balances(&999).1, 2);
+		})
+	}
+
+	#[test]
+	fn equally_good_solution_is_not_accepted() {
+		ExtBuilder::default().signed_max_submission(3).build_and_execute(|| {
+			roll_to(15);
+			assert!(MultiPhase::current_phase().is_signed());
+
+			for i in 0..SignedMaxSubmissions::get() {
+				let solution = RawSolution { score: [(5 + i).into(), 0, 0], ..Default::default() };
+				assert_ok!(MultiPhase::submit(Origin::signed(99), solution, i));
+			}
+			assert_eq!(
+				MultiPhase::signed_submissions()
+					.into_iter()
+					.map(|s| s.solution.score[0])
+					.collect::<Vec<_>>(),
+				vec![5, 6, 7]
+			);
+
+			// 5 is not accepted. This will only cause processing with no benefit.
+			let solution = RawSolution { score: [5, 0, 0], ..Default::default() };
+			assert_noop!(
+				MultiPhase::submit(Origin::signed(99), solution, 3),
+				Error::<Runtime>::SignedQueueFull,
+			);
+		})
+	}
+
+	#[test]
+	fn all_in_one_signed_submission_scenario() {
+		// a combination of:
+		// - good_solution_is_rewarded
+		// - bad_solution_is_slashed
+		// - suppressed_solution_gets_bond_back
+		ExtBuilder::default().build_and_execute(|| {
+			roll_to(15);
+			assert!(MultiPhase::current_phase().is_signed());
+
+			assert_eq!(balances(&99), (100, 0));
+			assert_eq!(balances(&999), (100, 0));
+			assert_eq!(balances(&9999), (100, 0));
+			let mut solution = raw_solution();
+
+			// submit a correct one.
+			assert_ok!(MultiPhase::submit(Origin::signed(99), solution.clone(), 0));
+
+			// make the solution invalidly better and submit. This ought to be slashed.
+			solution.score[0] += 1;
+			assert_ok!(MultiPhase::submit(Origin::signed(999), solution.clone(), 1));
+
+			// make the solution invalidly worse and submit. This ought to be suppressed and
+			// returned.
+			solution.score[0] -= 2;
+			assert_ok!(MultiPhase::submit(Origin::signed(9999), solution, 2));
+
+			assert_eq!(
+				MultiPhase::signed_submissions().iter().map(|x| x.who).collect::<Vec<_>>(),
+				vec![9999, 99, 999]
+			);
+
+			// _some_ good solution was stored.
+			assert!(MultiPhase::finalize_signed_phase().0);
+
+			// 99 is rewarded.
+			assert_eq!(balances(&99), (100 + 7, 0));
+			// 999 is slashed.
+			assert_eq!(balances(&999), (95, 0));
+			// 9999 gets everything back.
+			assert_eq!(balances(&9999), (100, 0));
+		})
+	}
+
+	#[test]
+	fn signed_phase_is_finalized_when_unsigned_phase_opens() {
+		ExtBuilder::default().build_and_execute(|| {
+			roll_to(15);
+			assert!(MultiPhase::current_phase().is_signed());
+
+			let solution = raw_solution();
+			assert_ok!(MultiPhase::submit(Origin::signed(99), solution, 0));
+
+			// since a good signed solution is queued, the unsigned phase is not enabled.
+			roll_to(25);
+			assert_eq!(MultiPhase::current_phase(), Phase::Unsigned((false, 25)));
+			assert!(MultiPhase::signed_submissions().is_empty());
+			assert_eq!(MultiPhase::queued_solution().unwrap().compute, ElectionCompute::Signed);
+			assert_eq!(balances(&99), (100 + 7, 0));
+
+			let supports = MultiPhase::elect().unwrap();
+			assert_eq!(supports.len(), 2);
+			assert_eq!(
+				multi_phase_events(),
+				vec![
+					RawEvent::SignedPhaseStarted(1),
+					RawEvent::SolutionStored(ElectionCompute::Signed),
+					RawEvent::Rewarded(99),
+					RawEvent::UnsignedPhaseStarted(1),
+					RawEvent::ElectionFinalized(Some(ElectionCompute::Signed)),
+				],
+			);
+		})
+	}
+
+	#[test]
+	fn early_termination_returns_deposits() {
+		ExtBuilder::default().build_and_execute(|| {
+			roll_to(15);
+			assert!(MultiPhase::current_phase().is_signed());
+
+			let solution = raw_solution();
+			assert_ok!(MultiPhase::submit(Origin::signed(99), solution, 0));
+			assert_eq!(balances(&99), (95, 5));
+
+			// an unexpected call to elect: the queue is never processed.
+			roll_to(20);
+			MultiPhase::elect().unwrap();
+
+			assert!(MultiPhase::signed_submissions().is_empty());
+			assert_eq!(balances(&99), (100, 0));
+		})
+	}
+
+	#[test]
+	fn deposit_depends_on_size_and_weight() {
+		ExtBuilder::default()
+			.signed_deposit(5, 1, 0)
+			.mock_weight_info(true)
+			.build_and_execute(|| {
+				roll_to(15);
+				assert!(MultiPhase::current_phase().is_signed());
+
+				let solution = raw_solution();
+				let size = MultiPhase::snapshot_metadata().unwrap();
+				let len = solution.encode().len() as u64;
+				assert_eq!(MultiPhase::deposit_for(&solution, size), 5 + len);
+
+				// the mocked feasibility weight is 10 + 5 per active voter.
+				<SignedDepositWeight>::set(1);
+				let voters = solution.compact.voter_count() as u64;
+				assert_eq!(MultiPhase::deposit_for(&solution, size), 5 + len + 10 + 5 * voters);
+			})
+	}
+}
@@ -112,14 +112,25 @@
 //!
 //! pub type Executive = executive::Executive<Runtime, Block, Context, Runtime, AllModules, CustomOnRuntimeUpgrade>;
 //! ```
+//!
+//! ### Multi-block migrations
+//!
+//! Migrations that are too heavy for a single block can implement
+//! `frame_support::storage::migration::SteppedMigration` and be passed as the
+//! `MultiBlockMigrations` generic parameter. They are started after a runtime upgrade and make
+//! progress at the beginning of every block, with the weight left after `on_initialize` but at
+//! most `MigrationWeightRatio` of `max_block`, until all of them completed. The progress is
+//! stored under `MIGRATION_CURSOR_KEY` and reported through `frame_system` events.
+//! Use `MigrationCallFilter` as `BaseCallFilter` to refuse calls while a migration is ongoing.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
 use sp_std::{prelude::*, marker::PhantomData};
 use frame_support::{
 	StorageValue, StorageMap, weights::{GetDispatchInfo, DispatchInfo, DispatchClass},
-	traits::{OnInitialize, OnFinalize, OnRuntimeUpgrade, OffchainWorker},
+	traits::{OnInitialize, OnFinalize, OnRuntimeUpgrade, OffchainWorker, Get},
 	dispatch::PostDispatchInfo,
+	storage::migration::{self, SteppedMigrations, MigrationProgress},
 };
 use sp_runtime::{
 	generic::Digest, ApplyExtrinsicResult,
@@ -128,6 +139,7 @@ use sp_runtime::{
 		Block as BlockT, Dispatchable, Saturating,
 	},
 	transaction_validity::{TransactionValidity, TransactionSource},
+	Perbill,
 };
 use codec::{Codec, Encode};
 use frame_system::{extrinsics_root, DigestOf};
@@ -142,6 +154,11 @@ pub type CheckedOf<E, C> = <E as Checkable<C>>::Checked;
 pub type CallOf<E, C> = <CheckedOf<E, C> as Applyable>::Call;
 pub type OriginOf<E, C> = <CallOf<E, C> as Dispatchable>::Origin;
 
+frame_support::parameter_types! {
+	/// The default share of `max_block` that multi-block migrations may use in a block.
+	pub const DefaultMigrationWeightRatio: Perbill = Perbill::from_percent(50);
+}
+
 /// Main entry point for certain runtime actions as e.g. `execute_block`.
 ///
 /// Generic parameters:
@@ -153,8 +170,31 @@ pub type OriginOf<E, C> = <CallOf<E, C> as Dispatchable>::Origin;
 /// - `OnRuntimeUpgrade`: Custom logic that should be called after a runtime upgrade. Modules are
 ///                       already called by `AllModules`. It will be called before all modules will
 ///                       be called.
-pub struct Executive<System, Block, Context, UnsignedValidator, AllModules, OnRuntimeUpgrade = ()>(
-	PhantomData<(System, Block, Context, UnsignedValidator, AllModules, OnRuntimeUpgrade)>
+/// - `MultiBlockMigrations`: Tuple of `SteppedMigration`s that are started after a runtime upgrade
+///                           and executed at the beginning of each block, using the weight left
+///                           in the block after `on_initialize`, until all of them completed.
+/// - `MigrationWeightRatio`: The share of `max_block` the `MultiBlockMigrations` may use at most
+///                           in a single block.
+pub struct Executive<
+	System,
+	Block,
+	Context,
+	UnsignedValidator,
+	AllModules,
+	OnRuntimeUpgrade = (),
+	MultiBlockMigrations = (),
+	MigrationWeightRatio = DefaultMigrationWeightRatio,
+>(
+	PhantomData<(
+		System,
+		Block,
+		Context,
+		UnsignedValidator,
+		AllModules,
+		OnRuntimeUpgrade,
+		MultiBlockMigrations,
+		MigrationWeightRatio,
+	)>
 );
 
 impl<
@@ -168,8 +208,18 @@ impl<
 		OnFinalize<System::BlockNumber> +
 		OffchainWorker<System::BlockNumber>,
 	COnRuntimeUpgrade: OnRuntimeUpgrade,
-> ExecuteBlock<Block> for
-	Executive<System, Block, Context, UnsignedValidator, AllModules, COnRuntimeUpgrade>
+	MultiBlockMigrations: SteppedMigrations,
+	MigrationWeightRatio: Get<Perbill>,
+> ExecuteBlock<Block> for Executive<
+	System,
+	Block,
+	Context,
+	UnsignedValidator,
+	AllModules,
+	COnRuntimeUpgrade,
+	MultiBlockMigrations,
+	MigrationWeightRatio,
+>
 where
 	Block::Extrinsic: Checkable<Context> + Codec,
 	CheckedOf<Block::Extrinsic, Context>:
@@ -180,7 +230,16 @@ where
 	UnsignedValidator: ValidateUnsigned<Call=CallOf<Block::Extrinsic, Context>>,
 {
 	fn execute_block(block: Block) {
-		Executive::<System, Block, Context, UnsignedValidator, AllModules>::execute_block(block);
+		Executive::<
+			System,
+			Block,
+			Context,
+			UnsignedValidator,
+			AllModules,
+			COnRuntimeUpgrade,
+			MultiBlockMigrations,
+			MigrationWeightRatio,
+		>::execute_block(block);
 	}
 }
 
@@ -195,7 +254,18 @@ impl<
 		OnFinalize<System::BlockNumber> +
 		OffchainWorker<System::BlockNumber>,
 	COnRuntimeUpgrade: OnRuntimeUpgrade,
-> Executive<System, Block, Context, UnsignedValidator, AllModules, COnRuntimeUpgrade>
+	MultiBlockMigrations: SteppedMigrations,
+	MigrationWeightRatio: Get<Perbill>,
+> Executive<
+	System,
+	Block,
+	Context,
+	UnsignedValidator,
+	AllModules,
+	COnRuntimeUpgrade,
+	MultiBlockMigrations,
+	MigrationWeightRatio,
+>
 where
 	Block::Extrinsic: Checkable<Context> + Codec,
 	CheckedOf<Block::Extrinsic, Context>:
@@ -205,6 +275,39 @@ where
 	OriginOf<Block::Extrinsic, Context>: From<Option<System::AccountId>>,
 	UnsignedValidator: ValidateUnsigned<Call=CallOf<Block::Extrinsic, Context>>,
 {
+	/// Execute all `OnRuntimeUpgrade` of this runtime, and return the aggregate weight.
+	///
+	/// The `MultiBlockMigrations` are only started here, they are executed by the following blocks.
+	pub fn execute_on_runtime_upgrade() -> frame_support::weights::Weight {
+		let mut weight = 0;
+		// System is not part of `AllModules`, so we need to call this manually.
+		weight = weight.saturating_add(<frame_system::Module::<System> as OnRuntimeUpgrade>::on_runtime_upgrade());
+		weight = weight.saturating_add(COnRuntimeUpgrade::on_runtime_upgrade());
+		weight = weight.saturating_add(<AllModules as OnRuntimeUpgrade>::on_runtime_upgrade());
+		migration::start_migrations::<MultiBlockMigrations>();
+		weight
+	}
+
+	/// Execute all `OnRuntimeUpgrade` of this runtime, including the pre and post migration checks.
+	///
+	/// This should only be used for testing, e.g. through the `TryRuntime_on_runtime_upgrade`
+	/// runtime api. The pre-checks are executed before any migration and the post-checks after
+	/// all of them. The first failing check aborts the process and its error is returned.
+	#[cfg(feature = "try-runtime")]
+	pub fn try_runtime_upgrade() -> Result<frame_support::weights::Weight, &'static str> {
+		<frame_system::Module::<System> as OnRuntimeUpgrade>::pre_upgrade()?;
+		COnRuntimeUpgrade::pre_upgrade()?;
+		<AllModules as OnRuntimeUpgrade>::pre_upgrade()?;
+
+		let weight = Self::execute_on_runtime_upgrade();
+
+		<frame_system::Module::<System> as OnRuntimeUpgrade>::post_upgrade()?;
+		COnRuntimeUpgrade::post_upgrade()?;
+		<AllModules as OnRuntimeUpgrade>::post_upgrade()?;
+
+		Ok(weight)
+	}
+
 	/// Start the execution of a particular block.
 	pub fn initialize_block(header: &System::Header) {
 		sp_io::init_tracing();
@@ -236,10 +339,7 @@ where
 	) {
 		let mut weight = 0;
 		if Self::runtime_upgraded() {
-			// System is not part of `AllModules`, so we need to call this manually.
-			weight = weight.saturating_add(<frame_system::Module::<System> as OnRuntimeUpgrade>::on_runtime_upgrade());
-			weight = weight.saturating_add(COnRuntimeUpgrade::on_runtime_upgrade());
-			weight = weight.saturating_add(<AllModules as OnRuntimeUpgrade>::on_runtime_upgrade());
+			weight = weight.saturating_add(Self::execute_on_runtime_upgrade());
 		}
 		<frame_system::Module<System>>::initialize(
 			block_number,
@@ -259,9 +359,36 @@ where
 		);
 		<frame_system::Module::<System>>::register_extra_weight_unchecked(weight, DispatchClass::Mandatory);
 
+		Self::step_migrations();
+
 		frame_system::Module::<System>::note_finished_initialize();
 	}
 
+	/// Make progress on the ongoing multi-block migration with the weight left in the block, but
+	/// at most `MigrationWeightRatio` of `max_block`.
+	fn step_migrations() {
+		if !migration::is_migration_ongoing() {
+			return
+		}
+		let max_block = <System::BlockWeights as frame_support::traits::Get<_>>::get().max_block;
+		let remaining = max_block
+			.saturating_sub(<frame_system::Module<System>>::block_weight().total())
+			.min(MigrationWeightRatio::get() * max_block);
+		let (weight, progress) = migration::step_migrations::<MultiBlockMigrations>(remaining);
+		<frame_system::Module::<System>>::register_extra_weight_unchecked(weight, DispatchClass::Mandatory);
+
+		for event in progress {
+			let event: frame_system::Event<System> = match event {
+				MigrationProgress::Advanced(id, steps) =>
+					frame_system::RawEvent::MigrationAdvanced(id, steps),
+				MigrationProgress::Completed(id, steps) =>
+					frame_system::RawEvent::MigrationCompleted(id, steps),
+				MigrationProgress::Failed(id) => frame_system::RawEvent::MigrationFailed(id),
+			};
+			<frame_system::Module<System>>::deposit_event(event);
+		}
+	}
+
 	/// Returns if the runtime was upgraded since the last time this function was called.
 	fn runtime_upgraded() -> bool {
 		let last = frame_system::LastRuntimeUpgrade::get();
@@ -679,6 +806,8 @@ mod tests {
 
 	// Will contain `true` when the custom runtime logic was called.
 	const CUSTOM_ON_RUNTIME_KEY: &[u8] = &*b":custom:on_runtime";
+	#[cfg(feature = "try-runtime")]
+	const FAIL_POST_UPGRADE_KEY: &[u8] = &*b":fail:post_upgrade";
 
 	struct CustomOnRuntimeUpgrade;
 	impl OnRuntimeUpgrade for CustomOnRuntimeUpgrade {
@@ -687,6 +816,15 @@ mod tests {
 			sp_io::storage::set(CUSTOM_ON_RUNTIME_KEY, &true.encode());
 			100
 		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade() -> Result<(), &'static str> {
+			frame_support::ensure!(
+				sp_io::storage::get(FAIL_POST_UPGRADE_KEY).is_none(),
+				"Custom post upgrade check failed",
+			);
+			Ok(())
+		}
 	}
 
 	type Executive = super::Executive<
@@ -698,6 +836,41 @@ mod tests {
 		CustomOnRuntimeUpgrade
 	>;
 
+	const MIGRATION_WEIGHT_KEY: &[u8] = b":test:migration_weight:";
+
+	/// Takes three blocks to complete, every step consumes all the weight it is given and records
+	/// it under `MIGRATION_WEIGHT_KEY`.
+	struct ThreeBlockMigration;
+	impl frame_support::storage::migration::SteppedMigration for ThreeBlockMigration {
+		type Cursor = u32;
+
+		fn id() -> Vec<u8> { b"three".to_vec() }
+
+		fn step(
+			cursor: Option<u32>,
+			remaining_weight: Weight,
+		) -> Result<(Option<u32>, Weight), migration::SteppedMigrationError> {
+			let steps = cursor.unwrap_or(0) + 1;
+			sp_io::storage::set(MIGRATION_WEIGHT_KEY, &remaining_weight.encode());
+			Ok((if steps < 3 { Some(steps) } else { None }, remaining_weight))
+		}
+	}
+
+	parameter_types! {
+		pub const MigrationWeightRatio: Perbill = Perbill::from_percent(25);
+	}
+
+	type MigratingExecutive = super::Executive<
+		Runtime,
+		Block<TestXt>,
+		ChainContext<Runtime>,
+		Runtime,
+		AllModules,
+		(),
+		(ThreeBlockMigration,),
+		MigrationWeightRatio,
+	>;
+
 	fn extra(nonce: u64, fee: Balance) -> SignedExtra {
 		(
 			frame_system::CheckEra::from(Era::Immortal),
@@ -1091,6 +1264,18 @@ mod tests {
 		});
 	}
 
+	#[test]
+	#[cfg(feature = "try-runtime")]
+	fn try_runtime_upgrade_reports_failing_post_check() {
+		new_test_ext(1).execute_with(|| {
+			assert!(Executive::try_runtime_upgrade().is_ok());
+			assert_eq!(sp_io::storage::get(CUSTOM_ON_RUNTIME_KEY).unwrap(), true.encode());
+
+			sp_io::storage::set(FAIL_POST_UPGRADE_KEY, &true.encode());
+			assert_eq!(Executive::try_runtime_upgrade(), Err("Custom post upgrade check failed"));
+		});
+	}
+
 	#[test]
 	fn all_weights_are_recorded_correctly() {
 		new_test_ext(1).execute_with(|| {
@@ -1131,6 +1316,59 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn multi_block_migrations_run_after_runtime_upgrade() {
+		new_test_ext(1).execute_with(|| {
+			RUNTIME_VERSION.with(|v| *v.borrow_mut() = Default::default());
+			let initialize = |n| MigratingExecutive::initialize_block(&Header::new(
+				n,
+				H256::default(),
+				H256::default(),
+				[69u8; 32].into(),
+				Digest::default(),
+			));
+			let last_event = || System::events().pop().expect("Event expected").event;
+			let max_block = <Runtime as frame_system::Config>::BlockWeights::get().max_block;
+
+			initialize(1);
+			assert!(!migration::is_migration_ongoing());
+
+			RUNTIME_VERSION.with(|v| *v.borrow_mut() = sp_version::RuntimeVersion {
+				spec_version: 1,
+				..Default::default()
+			});
+
+			initialize(2);
+			assert!(migration::is_migration_ongoing());
+			let migration_weight = MigrationWeightRatio::get() * max_block;
+			assert_eq!(
+				sp_io::storage::get(MIGRATION_WEIGHT_KEY),
+				Some(migration_weight.encode()),
+			);
+			assert!(System::block_weight().total() < max_block);
+			assert_eq!(
+				last_event(),
+				Event::frame_system(frame_system::RawEvent::MigrationAdvanced(b"three".to_vec(), 1)),
+			);
+
+			initialize(3);
+			assert_eq!(
+				last_event(),
+				Event::frame_system(frame_system::RawEvent::MigrationAdvanced(b"three".to_vec(), 2)),
+			);
+
+			initialize(4);
+			assert!(!migration::is_migration_ongoing());
+			assert_eq!(
+				last_event(),
+				Event::frame_system(frame_system::RawEvent::MigrationCompleted(b"three".to_vec(), 3)),
+			);
+
+			initialize(5);
+			assert!(System::events().is_empty());
+		});
+	}
+
 	#[test]
 	fn offchain_worker_works_as_expected() {
 		new_test_ext(1).execute_with(|| {
@@ -0,0 +1,527 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # Lottery Module
+//!
+//! A module for running a lottery which rewards the use of specific calls.
+//!
+//! ## Overview
+//!
+//! The lottery is meant to incentivize the use of a set of calls chosen by governance. A
+//! privileged origin (`ManagerOrigin`) whitelists a set of calls with `set_calls` and starts a
+//! lottery with `start_lottery`, choosing the ticket `price`, the `length` of the lottery, the
+//! `delay` between its end and the payout, and whether it should `repeat`.
+//!
+//! Users take part by wrapping one of the whitelisted calls in `buy_ticket`. The wrapped call is
+//! always dispatched; if it succeeds and the lottery is running, the `price` is transferred into
+//! the lottery pot and the user receives a ticket. A user may buy at most one ticket per
+//! whitelisted call in each lottery.
+//!
+//! The payout is scheduled with the `Scheduler` when the lottery starts. At the payout block, a
+//! winning ticket is chosen using the configured source of `Randomness` and the whole pot is paid
+//! out to its owner. If the lottery is set to repeat, a new one starts straight away with the same
+//! configuration and its payout is scheduled, until `stop_repeat` is called.
+//!
+//! ### Terminology
+//!
+//! * **Call index:** The pair of the module index and the call index of a call within the
+//!   runtime's outer `Call`, which identifies a whitelisted call independently of its arguments.
+//! * **Pot:** The funds held by the lottery account, above its existential deposit, which are paid
+//!   out to the winner.
+//!
+//! ## Interface
+//!
+//! ### Dispatchable Functions
+//!
+//! * `buy_ticket` - Dispatch a whitelisted call and buy a lottery ticket for it.
+//! * `set_calls` - Set the calls which are valid for buying a ticket. Called by `ManagerOrigin`.
+//! * `start_lottery` - Start a new lottery. Called by `ManagerOrigin`.
+//! * `stop_repeat` - Don't start a new lottery once the current one ends. Called by
+//!   `ManagerOrigin`.
+//! * `payout` - Pay out the current lottery once it reached its payout block. Dispatched by the
+//!   `Scheduler` as Root.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+pub mod weights;
+
+use sp_std::prelude::*;
+use sp_runtime::{
+	DispatchError, ModuleId, RuntimeDebug,
+	traits::{AccountIdConversion, Dispatchable, Saturating, Zero},
+};
+use frame_support::{
+	Parameter, decl_module, decl_error, decl_event, decl_storage, ensure,
+	dispatch::{DispatchResult, GetDispatchInfo},
+	traits::{
+		Currency, ReservableCurrency, Get, EnsureOrigin, ExistenceRequirement::KeepAlive,
+		Randomness, schedule::{Named as ScheduleNamed, DispatchTime, HARD_DEADLINE},
+	},
+};
+use frame_system::{ensure_signed, ensure_root};
+use codec::{Encode, Decode};
+pub use weights::WeightInfo;
+
+type BalanceOf<T> =
+	<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+/// The prefix of the names under which the payouts are scheduled.
+const LOTTERY_ID: [u8; 8] = *b"lottery ";
+
+/// The module configuration trait.
+pub trait Config: frame_system::Config {
+	/// The Lottery's module id.
+	type ModuleId: Get<ModuleId>;
+
+	/// A dispatchable call.
+	type Call: Parameter + Dispatchable<Origin=Self::Origin> + GetDispatchInfo
+		+ From<frame_system::Call<Self>> + From<Call<Self>>;
+
+	/// The Scheduler, used to dispatch the payout of a lottery.
+	type Scheduler: ScheduleNamed<Self::BlockNumber, <Self as Config>::Call, Self::PalletsOrigin>;
+
+	/// Overarching type of all pallets origins.
+	type PalletsOrigin: From<frame_system::RawOrigin<Self::AccountId>>;
+
+	/// The currency trait.
+	type Currency: ReservableCurrency<Self::AccountId>;
+
+	/// Something that provides randomness in the runtime.
+	type Randomness: Randomness<Self::Hash>;
+
+	/// The overarching event type.
+	type Event: From<Event<Self>> + Into<<Self as frame_system::Config>::Event>;
+
+	/// The manager origin.
+	type ManagerOrigin: EnsureOrigin<Self::Origin>;
+
+	/// The max number of calls available in a single lottery.
+	type MaxCalls: Get<usize>;
+
+	/// Used to determine if a call would be valid for purchasing a ticket.
+	///
+	/// Be conscious of the implementation used here. We assume at worst that
+	/// a vector of `MaxCalls` indices are queried for any call validation.
+	/// You may need to provide a custom benchmark if this assumption is broken.
+	type ValidateCall: ValidateCall<Self>;
+
+	/// Weight information for extrinsics in this pallet.
+	type WeightInfo: WeightInfo;
+}
+
+/// The index of a call: the module index and the call index within that module.
+pub type CallIndex = (u8, u8);
+
+/// The configuration of a running lottery.
+#[derive(Encode, Decode, Default, Eq, PartialEq, RuntimeDebug)]
+pub struct LotteryConfig<BlockNumber, Balance> {
+	/// Price per entry.
+	price: Balance,
+	/// Starting block of the lottery.
+	start: BlockNumber,
+	/// Length of the lottery (start + length = end).
+	length: BlockNumber,
+	/// Delay for choosing the winner of the lottery. (start + length + delay = payout).
+	/// Randomness in the "payout" block will be used to determine the winner.
+	delay: BlockNumber,
+	/// Whether this lottery will repeat after it completes.
+	repeat: bool,
+}
+
+/// Determines whether a call may be used to buy a lottery ticket.
+pub trait ValidateCall<T: Config> {
+	/// Returns `true` if `call` may be used to buy a ticket.
+	fn validate_call(call: &<T as Config>::Call) -> bool;
+}
+
+impl<T: Config> ValidateCall<T> for () {
+	fn validate_call(_: &<T as Config>::Call) -> bool { false }
+}
+
+impl<T: Config> ValidateCall<T> for Module<T> {
+	/// A call is valid if its call index is in the whitelist set by `set_calls`.
+	fn validate_call(call: &<T as Config>::Call) -> bool {
+		let valid_calls = CallIndices::get();
+		let call_index = match Self::call_to_index(&call) {
+			Ok(call_index) => call_index,
+			Err(_) => return false,
+		};
+		valid_calls.iter().any(|c| call_index == *c)
+	}
+}
+
+decl_storage! {
+	trait Store for Module<T: Config> as Lottery {
+		/// The index of the current (or most recent) lottery.
+		LotteryIndex: u32;
+		/// The configuration for the current lottery.
+		Lottery: Option<LotteryConfig<T::BlockNumber, BalanceOf<T>>>;
+		/// Users who have purchased a ticket: the index of the lottery they last took part in and
+		/// the calls they bought tickets with in it.
+		Participants: map hasher(twox_64_concat) T::AccountId => (u32, Vec<CallIndex>);
+		/// Total number of tickets sold in the current lottery.
+		TicketsCount: u32;
+		/// Each ticket's owner.
+		///
+		/// May hold data from a previous lottery; only the first `TicketsCount` tickets belong to
+		/// the current one.
+		Tickets: map hasher(twox_64_concat) u32 => Option<T::AccountId>;
+		/// The calls stored in this pallet to be used in an active lottery if configured
+		/// by `Config::ValidateCall`.
+		CallIndices: Vec<CallIndex>;
+	}
+}
+
+decl_event!(
+	pub enum Event<T> where
+		<T as frame_system::Config>::AccountId,
+		Balance = BalanceOf<T>,
+	{
+		/// A lottery has been started!
+		LotteryStarted,
+		/// A new set of calls have been set!
+		CallsUpdated,
+		/// A winner has been chosen! \[winner, pot\]
+		Winner(AccountId, Balance),
+		/// A ticket has been bought! \[who, call_index\]
+		TicketBought(AccountId, CallIndex),
+	}
+);
+
+decl_error! {
+	pub enum Error for Module<T: Config> {
+		/// An overflow has occurred.
+		Overflow,
+		/// A lottery has not been configured.
+		NotConfigured,
+		/// A lottery is already in progress.
+		InProgress,
+		/// A lottery has already ended.
+		AlreadyEnded,
+		/// The call is not valid for an open lottery.
+		InvalidCall,
+		/// You are already participating in the lottery with this call.
+		AlreadyParticipating,
+		/// Too many calls for a single lottery.
+		TooManyCalls,
+		/// Failed to encode calls
+		EncodingFailed,
+		/// The lottery has not reached its payout block yet.
+		NotEnded,
+		/// The payout of the lottery could not be scheduled.
+		FailedToSchedule,
+	}
+}
+
+decl_module! {
+	pub struct Module<T: Config> for enum Call where origin: T::Origin, system = frame_system {
+		/// The Lottery's module id.
+		const ModuleId: ModuleId = T::ModuleId::get();
+
+		/// The max number of calls available in a single lottery.
+		const MaxCalls: u32 = T::MaxCalls::get() as u32;
+
+		type Error = Error<T>;
+
+		fn deposit_event() = default;
+
+		/// Buy a ticket to enter the lottery.
+		///
+		/// This extrinsic acts as a passthrough function for `call`. In all
+		/// situations where `call` alone would succeed, this extrinsic should
+		/// succeed.
+		///
+		/// If `call` is successful, then we will attempt to purchase a ticket,
+		/// which may fail silently. To detect success of a ticket purchase, you
+		/// should listen for the `TicketBought` event.
+		///
+		/// This extrinsic must be called by a signed origin.
+		#[weight =
+			T::WeightInfo::buy_ticket()
+				.saturating_add(call.get_dispatch_info().weight)
+		]
+		fn buy_ticket(origin, call: Box<<T as Config>::Call>) {
+			let caller = ensure_signed(origin.clone())?;
+			call.clone().dispatch(origin).map_err(|e| e.error)?;
+
+			let _ = Self::do_buy_ticket(&caller, &call);
+		}
+
+		/// Set calls in storage which can be used to purchase a lottery ticket.
+		///
+		/// This function only matters if you use the `ValidateCall` implementation
+		/// provided by this pallet, which uses storage to determine the valid calls.
+		///
+		/// This extrinsic must be called by the Manager origin.
+		#[weight = T::WeightInfo::set_calls(calls.len() as u32)]
+		fn set_calls(origin, calls: Vec<<T as Config>::Call>) {
+			T::ManagerOrigin::ensure_origin(origin)?;
+			ensure!(calls.len() <= T::MaxCalls::get(), Error::<T>::TooManyCalls);
+			if calls.is_empty() {
+				CallIndices::kill();
+			} else {
+				let indices = Self::calls_to_indices(&calls)?;
+				CallIndices::put(indices);
+			}
+			Self::deposit_event(RawEvent::CallsUpdated);
+		}
+
+		/// Start a lottery using the provided configuration.
+		///
+		/// This extrinsic must be called by the `ManagerOrigin`.
+		///
+		/// Parameters:
+		///
+		/// * `price`: The cost of a single ticket.
+		/// * `length`: How long the lottery should run for starting at the current block.
+		/// * `delay`: How long after the lottery end we should wait before picking a winner.
+		/// * `repeat`: If the lottery should repeat when completed.
+		#[weight = T::WeightInfo::start_lottery()]
+		fn start_lottery(origin,
+			price: BalanceOf<T>,
+			length: T::BlockNumber,
+			delay: T::BlockNumber,
+			repeat: bool,
+		) {
+			T::ManagerOrigin::ensure_origin(origin)?;
+			Lottery::<T>::try_mutate(|lottery| -> DispatchResult {
+				ensure!(lottery.is_none(), Error::<T>::InProgress);
+				let index = LotteryIndex::get();
+				let new_index = index.checked_add(1).ok_or(Error::<T>::Overflow)?;
+				let start = frame_system::Module::<T>::block_number();
+				let payout_block = start.saturating_add(length).saturating_add(delay);
+				Self::schedule_payout(new_index, payout_block)?;
+				// Use new_index to more easily track everything with the current state.
+				*lottery = Some(LotteryConfig {
+					price,
+					start,
+					length,
+					delay,
+					repeat,
+				});
+				LotteryIndex::put(new_index);
+				Ok(())
+			})?;
+			// Make sure pot exists.
+			let lottery_account = Self::account_id();
+			if T::Currency::total_balance(&lottery_account).is_zero() {
+				T::Currency::deposit_creating(&lottery_account, T::Currency::minimum_balance());
+			}
+			Self::deposit_event(RawEvent::LotteryStarted);
+		}
+
+		/// If a lottery is repeating, you can use this to stop the repeat.
+		/// The lottery will continue to run to completion.
+		///
+		/// This extrinsic must be called by the `ManagerOrigin`.
+		#[weight = T::WeightInfo::stop_repeat()]
+		fn stop_repeat(origin) {
+			T::ManagerOrigin::ensure_origin(origin)?;
+			Lottery::<T>::mutate(|lottery| {
+				if let Some(config) = lottery {
+					config.repeat = false
+				}
+			});
+		}
+
+		/// Pay out the current lottery to the owner of a randomly chosen ticket.
+		///
+		/// If no ticket was sold, the pot carries over to the next lottery. If the lottery
+		/// repeats, a new one is started straight away and its payout scheduled.
+		///
+		/// This extrinsic must be called by Root, and is dispatched by the `Scheduler` at the
+		/// payout block of the lottery.
+		#[weight = T::WeightInfo::payout_end().max(T::WeightInfo::payout_repeat())]
+		fn payout(origin) {
+			ensure_root(origin)?;
+			let mut config = Lottery::<T>::get().ok_or(Error::<T>::NotConfigured)?;
+			let now = frame_system::Module::<T>::block_number();
+			let payout_block = config.start
+				.saturating_add(config.length)
+				.saturating_add(config.delay);
+			ensure!(payout_block <= now, Error::<T>::NotEnded);
+
+			let (lottery_account, lottery_balance) = Self::pot();
+			let ticket_count = TicketsCount::take();
+
+			// If no ticket was sold, the pot carries over to the next lottery.
+			if let Some(winner) = Self::choose_winner(ticket_count)
+				.and_then(|winning_number| Tickets::<T>::get(winning_number))
+			{
+				// Not much we can do if this fails...
+				let res = T::Currency::transfer(
+					&lottery_account,
+					&winner,
+					lottery_balance,
+					KeepAlive,
+				);
+				debug_assert!(res.is_ok());
+				Self::deposit_event(RawEvent::Winner(winner, lottery_balance));
+			}
+
+			// We choose not to kill `Participants` and `Tickets` to avoid a large number of
+			// writes at one time. Instead, data persists between lotteries, but is not used if it
+			// is not relevant.
+			if config.repeat {
+				// If lottery should repeat, increment index by 1 and start again from now.
+				let new_index = LotteryIndex::get().saturating_add(1);
+				let next_payout = now.saturating_add(config.length).saturating_add(config.delay);
+				if Self::schedule_payout(new_index, next_payout).is_ok() {
+					LotteryIndex::put(new_index);
+					config.start = now;
+					Lottery::<T>::put(config);
+					return Ok(())
+				}
+				frame_support::print("LOGIC ERROR: payout/schedule_payout failed");
+			}
+			// Else, kill the lottery storage.
+			Lottery::<T>::kill();
+		}
+	}
+}
+
+/// The number of times we try to generate a random number without modulo bias before accepting
+/// a biased one.
+const MAX_GENERATE_RANDOM: u32 = 10;
+
+impl<T: Config> Module<T> {
+	/// The account ID of the lottery pot.
+	///
+	/// This actually does computation. If you need to keep using it, then make sure you cache the
+	/// value and only call this once.
+	pub fn account_id() -> T::AccountId {
+		T::ModuleId::get().into_account()
+	}
+
+	/// Return the pot account and amount of money in the pot.
+	/// The existential deposit is not part of the pot so lottery account never gets deleted.
+	fn pot() -> (T::AccountId, BalanceOf<T>) {
+		let account_id = Self::account_id();
+		let balance = T::Currency::free_balance(&account_id)
+			.saturating_sub(T::Currency::minimum_balance());
+
+		(account_id, balance)
+	}
+
+	/// Schedule the payout of the lottery with the given `index` at block `when`.
+	fn schedule_payout(index: u32, when: T::BlockNumber) -> DispatchResult {
+		T::Scheduler::schedule_named(
+			(LOTTERY_ID, index).encode(),
+			DispatchTime::At(when),
+			None,
+			HARD_DEADLINE,
+			frame_system::RawOrigin::Root.into(),
+			Call::payout().into(),
+		).map(|_| ()).map_err(|_| Error::<T>::FailedToSchedule.into())
+	}
+
+	/// Converts a vector of calls into a vector of call indices.
+	fn calls_to_indices(calls: &[<T as Config>::Call]) -> Result<Vec<CallIndex>, DispatchError> {
+		let mut indices = Vec::with_capacity(calls.len());
+		for c in calls.iter() {
+			let index = Self::call_to_index(c)?;
+			indices.push(index)
+		}
+		Ok(indices)
+	}
+
+	/// Convert a call to its call index by encoding the call and taking the first two bytes.
+	fn call_to_index(call: &<T as Config>::Call) -> Result<CallIndex, DispatchError> {
+		let encoded_call = call.encode();
+		if encoded_call.len() < 2 { Err(Error::<T>::EncodingFailed)? }
+		Ok((encoded_call[0], encoded_call[1]))
+	}
+
+	/// Logic for buying a ticket.
+	fn do_buy_ticket(caller: &T::AccountId, call: &<T as Config>::Call) -> DispatchResult {
+		// Check the call is valid lottery
+		let config = Lottery::<T>::get().ok_or(Error::<T>::NotConfigured)?;
+		let block_number = frame_system::Module::<T>::block_number();
+		ensure!(
+			block_number < config.start.saturating_add(config.length),
+			Error::<T>::AlreadyEnded,
+		);
+		ensure!(T::ValidateCall::validate_call(call), Error::<T>::InvalidCall);
+		let call_index = Self::call_to_index(call)?;
+		let ticket_count = TicketsCount::get();
+		let new_ticket_count = ticket_count.checked_add(1).ok_or(Error::<T>::Overflow)?;
+		// Try to update the participant status
+		Participants::<T>::try_mutate(&caller, |participant| -> DispatchResult {
+			let (lottery_index, participating_calls) = participant;
+			let index = LotteryIndex::get();
+			// If lottery index doesn't match, then reset participating calls and index.
+			if *lottery_index != index {
+				*participating_calls = Vec::new();
+				*lottery_index = index;
+			} else {
+				// Check that user is not already participating under this call.
+				ensure!(
+					!participating_calls.iter().any(|c| call_index == *c),
+					Error::<T>::AlreadyParticipating,
+				);
+			}
+			// Check user has enough funds and send it to the Lottery account.
+			T::Currency::transfer(caller, &Self::account_id(), config.price, KeepAlive)?;
+			// Create a new ticket.
+			TicketsCount::put(new_ticket_count);
+			Tickets::<T>::insert(ticket_count, caller.clone());
+			participating_calls.push(call_index);
+			Ok(())
+		})?;
+
+		Self::deposit_event(RawEvent::TicketBought(caller.clone(), call_index));
+
+		Ok(())
+	}
+
+	/// Randomly choose a winner from among the total number of participants, or `None` if there
+	/// are no participants.
+	fn choose_winner(total: u32) -> Option<u32> {
+		if total.is_zero() {
+			return None
+		}
+		let mut random_number = Self::generate_random_number(0);
+
+		// Best effort attempt to remove bias from modulus operator.
+		for i in 1 .. MAX_GENERATE_RANDOM {
+			if random_number < u32::max_value() - u32::max_value() % total {
+				break;
+			}
+
+			random_number = Self::generate_random_number(i);
+		}
+
+		Some(random_number % total)
+	}
+
+	/// Generate a random number from a given seed.
+	///
+	/// Note that there is potential bias introduced by using modulus operator.
+	/// You should call this function with different seed values until the random
+	/// number lies within `u32::max_value() - u32::max_value() % n`.
+	fn generate_random_number(seed: u32) -> u32 {
+		let random_seed = T::Randomness::random(&(T::ModuleId::get(), seed).encode());
+		<u32>::decode(&mut random_seed.as_ref())
+			.expect("secure hashes should always be bigger than u32; qed")
+	}
+}
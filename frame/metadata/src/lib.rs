@@ -134,6 +134,45 @@ pub struct FunctionArgumentMetadata {
 	pub ty: DecodeDifferentStr,
 }
 
+/// The class of a dispatchable function, mirroring `DispatchClass` of `frame-support`.
+#[derive(Clone, Copy, PartialEq, Eq, Encode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Decode, Serialize))]
+pub enum DispatchClassMetadata {
+	/// A normal dispatch.
+	Normal,
+	/// An operational dispatch.
+	Operational,
+	/// A mandatory dispatch, always included regardless of its weight.
+	Mandatory,
+}
+
+/// The dispatch information of a function, as computed from its `#[weight]` annotation.
+///
+/// As the weight of a call usually depends on its arguments, the information is computed for
+/// the call with all its arguments decoded from zero bytes. The weight is thus the base weight
+/// of the call, i.e. a lower bound in most cases.
+#[derive(Clone, PartialEq, Eq, Encode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Decode, Serialize))]
+pub struct FunctionDispatchMetadata {
+	/// The weight of the call with zeroed arguments.
+	pub base_weight: u64,
+	/// The class of the call.
+	pub class: DispatchClassMetadata,
+	/// Whether the call pays a transaction fee.
+	pub pays_fee: bool,
+}
+
+/// All the metadata about a function, including its dispatch information.
+#[derive(Clone, PartialEq, Eq, Encode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Decode, Serialize))]
+pub struct FunctionMetadataV13 {
+	pub name: DecodeDifferentStr,
+	pub arguments: DecodeDifferentArray<FunctionArgumentMetadata>,
+	pub documentation: DecodeDifferentArray<&'static str, StringBuf>,
+	/// `None` if the arguments of the call can not be decoded from zero bytes.
+	pub dispatch_info: Option<FunctionDispatchMetadata>,
+}
+
 /// Newtype wrapper for support encoding functions (actual the result of the function).
 #[derive(Clone, Eq)]
 pub struct FnEncode<E>(pub fn() -> E) where E: Encode + 'static;
@@ -272,12 +311,19 @@ impl sp_std::fmt::Debug for DefaultByteGetter {
 #[derive(Clone, PartialEq, Eq, Encode, RuntimeDebug)]
 #[cfg_attr(feature = "std", derive(Decode, Serialize))]
 pub enum StorageHasher {
+	/// 128-bit Blake2 hash, the key can not be recovered from the final key.
 	Blake2_128,
+	/// 256-bit Blake2 hash, the key can not be recovered from the final key.
 	Blake2_256,
+	/// 128-bit Blake2 hash followed by the encoded key.
 	Blake2_128Concat,
+	/// 128-bit XX hash, not safe for keys controlled by users.
 	Twox128,
+	/// 256-bit XX hash, not safe for keys controlled by users.
 	Twox256,
+	/// 64-bit XX hash followed by the encoded key, not safe for keys controlled by users.
 	Twox64Concat,
+	/// The encoded key itself.
 	Identity,
 }
 
@@ -302,6 +348,106 @@ pub enum StorageEntryType {
 	},
 }
 
+/// A storage entry type, as of version 13 of the metadata.
+///
+/// Compared to [`StorageEntryType`] it adds maps with an arbitrary number of keys.
+#[derive(Clone, PartialEq, Eq, Encode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Decode, Serialize))]
+pub enum StorageEntryTypeV13 {
+	Plain(DecodeDifferentStr),
+	Map {
+		hasher: StorageHasher,
+		key: DecodeDifferentStr,
+		value: DecodeDifferentStr,
+		// is_linked flag previously, unused now to keep backwards compat
+		unused: bool,
+	},
+	DoubleMap {
+		hasher: StorageHasher,
+		key1: DecodeDifferentStr,
+		key2: DecodeDifferentStr,
+		value: DecodeDifferentStr,
+		key2_hasher: StorageHasher,
+	},
+	NMap {
+		keys: DecodeDifferentArray<&'static str, StringBuf>,
+		hashers: DecodeDifferentArray<StorageHasher>,
+		value: DecodeDifferentStr,
+	},
+}
+
+/// The layout of the keys produced by a storage hasher, for clients to decode storage keys.
+#[derive(Clone, PartialEq, Eq, Encode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Decode, Serialize))]
+pub struct StorageHasherMetadata {
+	pub hasher: StorageHasher,
+	/// The length in bytes of the hash.
+	pub hash_len: u32,
+	/// Whether the encoded key follows the hash, so that it can be recovered from the final key.
+	pub concat_key: bool,
+	pub documentation: DecodeDifferentArray<&'static str, StringBuf>,
+}
+
+/// The layout of all the storage hashers.
+pub const STORAGE_HASHERS: &[StorageHasherMetadata] = &[
+	StorageHasherMetadata {
+		hasher: StorageHasher::Blake2_128,
+		hash_len: 16,
+		concat_key: false,
+		documentation: DecodeDifferent::Encode(&[
+			" 128-bit Blake2 hash, the key can not be recovered from the final key.",
+		]),
+	},
+	StorageHasherMetadata {
+		hasher: StorageHasher::Blake2_256,
+		hash_len: 32,
+		concat_key: false,
+		documentation: DecodeDifferent::Encode(&[
+			" 256-bit Blake2 hash, the key can not be recovered from the final key.",
+		]),
+	},
+	StorageHasherMetadata {
+		hasher: StorageHasher::Blake2_128Concat,
+		hash_len: 16,
+		concat_key: true,
+		documentation: DecodeDifferent::Encode(&[
+			" 128-bit Blake2 hash followed by the encoded key.",
+		]),
+	},
+	StorageHasherMetadata {
+		hasher: StorageHasher::Twox128,
+		hash_len: 16,
+		concat_key: false,
+		documentation: DecodeDifferent::Encode(&[
+			" 128-bit XX hash, not safe for keys controlled by users.",
+		]),
+	},
+	StorageHasherMetadata {
+		hasher: StorageHasher::Twox256,
+		hash_len: 32,
+		concat_key: false,
+		documentation: DecodeDifferent::Encode(&[
+			" 256-bit XX hash, not safe for keys controlled by users.",
+		]),
+	},
+	StorageHasherMetadata {
+		hasher: StorageHasher::Twox64Concat,
+		hash_len: 8,
+		concat_key: true,
+		documentation: DecodeDifferent::Encode(&[
+			" 64-bit XX hash followed by the encoded key, not safe for keys controlled by users.",
+		]),
+	},
+	StorageHasherMetadata {
+		hasher: StorageHasher::Identity,
+		hash_len: 0,
+		concat_key: true,
+		documentation: DecodeDifferent::Encode(&[
+			" The encoded key itself.",
+		]),
+	},
+];
+
 /// A storage entry modifier.
 #[derive(Clone, PartialEq, Eq, Encode, RuntimeDebug)]
 #[cfg_attr(feature = "std", derive(Decode, Serialize))]
@@ -319,6 +465,28 @@ pub struct StorageMetadata {
 	pub entries: DecodeDifferent<&'static [StorageEntryMetadata], Vec<StorageEntryMetadata>>,
 }
 
+/// All the metadata about one storage entry, as of version 13 of the metadata.
+#[derive(Clone, PartialEq, Eq, Encode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Decode, Serialize))]
+pub struct StorageEntryMetadataV13 {
+	pub name: DecodeDifferentStr,
+	pub modifier: StorageEntryModifier,
+	pub ty: StorageEntryTypeV13,
+	pub default: ByteGetter,
+	pub documentation: DecodeDifferentArray<&'static str, StringBuf>,
+	/// The maximum number of items of the value, if it is a bounded collection.
+	pub value_bound: DecodeDifferent<FnEncode<Option<u32>>, Option<u32>>,
+}
+
+/// All metadata of the storage, as of version 13 of the metadata.
+#[derive(Clone, PartialEq, Eq, Encode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Decode, Serialize))]
+pub struct StorageMetadataV13 {
+	/// The common prefix used by all storage entries.
+	pub prefix: DecodeDifferent<&'static str, StringBuf>,
+	pub entries: DecodeDifferent<&'static [StorageEntryMetadataV13], Vec<StorageEntryMetadataV13>>,
+}
+
 /// Metadata prefixed by a u32 for reserved usage
 #[derive(Eq, Encode, PartialEq, RuntimeDebug)]
 #[cfg_attr(feature = "std", derive(Decode, Serialize))]
@@ -366,6 +534,8 @@ pub enum RuntimeMetadata {
 	V11(RuntimeMetadataDeprecated),
 	/// Version 12 for runtime metadata.
 	V12(RuntimeMetadataV12),
+	/// Version 13 for runtime metadata.
+	V13(RuntimeMetadataV13),
 }
 
 /// Enum that should fail.
@@ -396,8 +566,21 @@ pub struct RuntimeMetadataV12 {
 	pub extrinsic: ExtrinsicMetadata,
 }
 
+/// The metadata of a runtime, with the dispatch information of the calls, the maps with an
+/// arbitrary number of keys and the layout of the storage hashers.
+#[derive(Eq, Encode, PartialEq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Decode, Serialize))]
+pub struct RuntimeMetadataV13 {
+	/// Metadata of all the modules.
+	pub modules: DecodeDifferentArray<ModuleMetadataV13>,
+	/// Metadata of the extrinsic.
+	pub extrinsic: ExtrinsicMetadata,
+	/// The layout of the hashers used by the storage entries.
+	pub storage_hashers: DecodeDifferentArray<StorageHasherMetadata>,
+}
+
 /// The latest version of the metadata.
-pub type RuntimeMetadataLastVersion = RuntimeMetadataV12;
+pub type RuntimeMetadataLastVersion = RuntimeMetadataV13;
 
 /// All metadata about an runtime module.
 #[derive(Clone, PartialEq, Eq, Encode, RuntimeDebug)]
@@ -414,6 +597,21 @@ pub struct ModuleMetadata {
 	pub index: u8,
 }
 
+/// All metadata about an runtime module, with the dispatch information of the calls.
+#[derive(Clone, PartialEq, Eq, Encode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Decode, Serialize))]
+pub struct ModuleMetadataV13 {
+	pub name: DecodeDifferentStr,
+	pub storage: Option<DecodeDifferent<FnEncode<StorageMetadataV13>, StorageMetadataV13>>,
+	pub calls: Option<DecodeDifferent<FnEncode<Vec<FunctionMetadataV13>>, Vec<FunctionMetadataV13>>>,
+	pub event: ODFnA<EventMetadata>,
+	pub constants: DFnA<ModuleConstantMetadata>,
+	pub errors: DFnA<ErrorMetadata>,
+	/// Define the index of the module, this index will be used for the encoding of module event,
+	/// call and origin variants.
+	pub index: u8,
+}
+
 type ODFnA<T> = Option<DFnA<T>>;
 type DFnA<T> = DecodeDifferent<FnEncode<&'static [T]>, Vec<T>>;
 
@@ -423,8 +621,14 @@ impl Into<sp_core::OpaqueMetadata> for RuntimeMetadataPrefixed {
 	}
 }
 
-impl Into<RuntimeMetadataPrefixed> for RuntimeMetadataLastVersion {
+impl Into<RuntimeMetadataPrefixed> for RuntimeMetadataV12 {
 	fn into(self) -> RuntimeMetadataPrefixed {
 		RuntimeMetadataPrefixed(META_RESERVED, RuntimeMetadata::V12(self))
 	}
 }
+
+impl Into<RuntimeMetadataPrefixed> for RuntimeMetadataV13 {
+	fn into(self) -> RuntimeMetadataPrefixed {
+		RuntimeMetadataPrefixed(META_RESERVED, RuntimeMetadata::V13(self))
+	}
+}
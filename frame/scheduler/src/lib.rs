@@ -60,7 +60,9 @@ use sp_runtime::{RuntimeDebug, traits::{Zero, One, BadOrigin, Saturating}};
 use frame_support::{
 	decl_module, decl_storage, decl_event, decl_error, IterableStorageMap,
 	dispatch::{Dispatchable, DispatchError, DispatchResult, Parameter},
-	traits::{Get, schedule::{self, DispatchTime}, OriginTrait, EnsureOrigin, IsType},
+	traits::{
+		Get, schedule::{self, DispatchTime}, OriginTrait, EnsureOrigin, IsType, OnRuntimeUpgrade,
+	},
 	weights::{GetDispatchInfo, Weight},
 };
 use frame_system::{self as system, ensure_signed};
@@ -444,6 +446,26 @@ impl<T: Config> Module<T> {
 		}
 	}
 
+	/// Checks to run before `migrate_v1_to_t2`, in order to test the migration with try-runtime.
+	#[cfg(feature = "try-runtime")]
+	pub fn pre_migrate_v1_to_t2() -> Result<(), &'static str> {
+		frame_support::ensure!(
+			StorageVersion::get() == Releases::V1,
+			"Scheduler storage should be V1 before the migration",
+		);
+		Ok(())
+	}
+
+	/// Checks to run after `migrate_v1_to_t2`, in order to test the migration with try-runtime.
+	#[cfg(feature = "try-runtime")]
+	pub fn post_migrate_v1_to_t2() -> Result<(), &'static str> {
+		frame_support::ensure!(
+			StorageVersion::get() == Releases::V2,
+			"Scheduler storage should be V2 after the migration",
+		);
+		Ok(())
+	}
+
 	/// Helper to migrate scheduler when the pallet origin type has changed.
 	pub fn migrate_origin<OldOrigin: Into<T::PalletsOrigin> + codec::Decode>() {
 		Agenda::<T>::translate::<
@@ -716,6 +738,32 @@ impl<T: Config> schedule::Named<T::BlockNumber, <T as Config>::Call, T::PalletsO
 	}
 }
 
+/// Migrates the storage of the scheduler from `V1` to `V2`.
+///
+/// This is [`Module::migrate_v1_to_t2`] as an `OnRuntimeUpgrade`, so that it can be included in
+/// the runtime upgrade of a runtime and its checks are run by try-runtime.
+pub struct MigrateV1ToT2<T>(PhantomData<T>);
+
+impl<T: Config> OnRuntimeUpgrade for MigrateV1ToT2<T> {
+	fn on_runtime_upgrade() -> Weight {
+		if Module::<T>::migrate_v1_to_t2() {
+			T::BlockWeights::get().max_block
+		} else {
+			T::DbWeight::get().reads(1)
+		}
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn pre_upgrade() -> Result<(), &'static str> {
+		Module::<T>::pre_migrate_v1_to_t2()
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn post_upgrade() -> Result<(), &'static str> {
+		Module::<T>::post_migrate_v1_to_t2()
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -1465,6 +1513,21 @@ mod tests {
 		});
 	}
 
+	#[test]
+	#[cfg(feature = "try-runtime")]
+	fn migration_to_v2_checks_work() {
+		new_test_ext().execute_with(|| {
+			StorageVersion::put(Releases::V1);
+			assert_ok!(MigrateV1ToT2::<Test>::pre_upgrade());
+			assert!(MigrateV1ToT2::<Test>::post_upgrade().is_err());
+
+			MigrateV1ToT2::<Test>::on_runtime_upgrade();
+
+			assert_ok!(MigrateV1ToT2::<Test>::post_upgrade());
+			assert!(MigrateV1ToT2::<Test>::pre_upgrade().is_err());
+		});
+	}
+
 	#[test]
 	fn test_migrate_origin() {
 		new_test_ext().execute_with(|| {
@@ -19,8 +19,9 @@
 
 #![cfg(test)]
 
-use sp_runtime::traits::IdentityLookup;
+use sp_runtime::{Perbill, traits::IdentityLookup};
 use frame_support::{impl_outer_origin, impl_outer_dispatch, parameter_types};
+use sp_election_providers::onchain;
 
 type AccountId = u64;
 type AccountIndex = u32;
@@ -153,6 +154,13 @@ impl<C> frame_system::offchain::SendTransactionTypes<C> for Test where
 	type Extrinsic = Extrinsic;
 }
 
+impl onchain::Config for Test {
+	type AccountId = <Self as frame_system::Config>::AccountId;
+	type BlockNumber = <Self as frame_system::Config>::BlockNumber;
+	type Accuracy = Perbill;
+	type DataProvider = pallet_staking::Module<Self>;
+}
+
 impl pallet_staking::Config for Test {
 	type Currency = Balances;
 	type UnixTime = pallet_timestamp::Module<Self>;
@@ -175,6 +183,7 @@ impl pallet_staking::Config for Test {
 	type MaxIterations = ();
 	type MinSolutionScoreBump = ();
 	type OffchainSolutionWeightLimit = ();
+	type ElectionProvider = onchain::OnChainSequentialPhragmen<Self>;
 	type WeightInfo = ();
 }
 
@@ -173,6 +173,10 @@ impl<
 		})
 	}
 
+	fn average_session_length() -> BlockNumber {
+		Period::get()
+	}
+
 	fn weight(_now: BlockNumber) -> Weight {
 		// Weight note: `estimate_next_session_rotation` has no storage reads and trivial computational overhead.
 		// There should be no risk to the chain having this weight value be zero for now.
@@ -845,6 +849,10 @@ impl<T: Config> EstimateNextNewSession<T::BlockNumber> for Module<T> {
 		T::NextSessionRotation::estimate_next_session_rotation(now)
 	}
 
+	fn average_session_length() -> T::BlockNumber {
+		T::NextSessionRotation::average_session_length()
+	}
+
 	fn weight(now: T::BlockNumber) -> Weight {
 		T::NextSessionRotation::weight(now)
 	}
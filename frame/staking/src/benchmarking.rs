@@ -21,7 +21,7 @@ use super::*;
 use crate::Module as Staking;
 use testing_utils::*;
 
-use sp_runtime::traits::One;
+use sp_runtime::traits::{One, Bounded};
 use frame_system::RawOrigin;
 pub use frame_benchmarking::{benchmarks, account, whitelisted_caller, whitelist_account};
 const SEED: u32 = 0;
@@ -707,6 +707,44 @@ benchmarks! {
 			).is_err()
 		);
 	}
+
+	set_staking_limits {
+		// This function always does the same thing... just write to 5 storage items.
+	}: _(
+		RawOrigin::Root,
+		BalanceOf::<T>::max_value(),
+		BalanceOf::<T>::max_value(),
+		Some(u32::max_value()),
+		Some(u32::max_value()),
+		Some(Percent::from_percent(100))
+	) verify {
+		assert_eq!(MinNominatorBond::<T>::get(), BalanceOf::<T>::max_value());
+		assert_eq!(MinValidatorBond::<T>::get(), BalanceOf::<T>::max_value());
+		assert_eq!(MaxNominatorsCount::get(), Some(u32::max_value()));
+		assert_eq!(MaxValidatorsCount::get(), Some(u32::max_value()));
+		assert_eq!(ChillThreshold::get(), Some(Percent::from_percent(100)));
+	}
+
+	chill_other {
+		let (stash, controller) = create_stash_controller::<T>(USER_SEED, 100, Default::default())?;
+		Staking::<T>::validate(
+			RawOrigin::Signed(controller.clone()).into(),
+			ValidatorPrefs::default(),
+		)?;
+		Staking::<T>::set_staking_limits(
+			RawOrigin::Root.into(),
+			BalanceOf::<T>::max_value(),
+			BalanceOf::<T>::max_value(),
+			Some(0),
+			Some(0),
+			Some(Percent::from_percent(0))
+		)?;
+		let caller = whitelisted_caller();
+		let controller_lookup = T::Lookup::unlookup(controller.clone());
+	}: _(RawOrigin::Signed(caller), controller_lookup)
+	verify {
+		assert!(!Validators::<T>::contains_key(stash));
+	}
 }
 
 #[cfg(test)]
@@ -834,6 +872,8 @@ mod tests {
 			assert_ok!(test_benchmark_new_era::<Test>());
 			assert_ok!(test_benchmark_do_slash::<Test>());
 			assert_ok!(test_benchmark_payout_all::<Test>());
+			assert_ok!(test_benchmark_set_staking_limits::<Test>());
+			assert_ok!(test_benchmark_chill_other::<Test>());
 			// only run one of them to same time on the CI. ignore the other two.
 			assert_ok!(test_benchmark_submit_solution_initial::<Test>());
 		});
@@ -253,6 +253,10 @@
 //! values until the total difference among votes of a particular nominator are less than a
 //! threshold.
 //!
+//! If no solution has been submitted through the offchain election window, the election is
+//! delegated to [`Config::ElectionProvider`], for which this module acts as the
+//! [`ElectionDataProvider`](sp_election_providers::ElectionDataProvider).
+//!
 //! ## GenesisConfig
 //!
 //! The Staking module depends on the [`GenesisConfig`](./struct.GenesisConfig.html). The
@@ -328,8 +332,9 @@ use frame_system::{
 use sp_npos_elections::{
 	ExtendedBalance, Assignment, ElectionScore, ElectionResult as PrimitiveElectionResult,
 	build_support_map, evaluate_support, seq_phragmen, generate_solution_type,
-	is_score_better, VotingLimit, SupportMap, VoteWeight,
+	is_score_better, VotingLimit, Support, VoteWeight,
 };
+use sp_election_providers::ElectionProvider;
 pub use weights::WeightInfo;
 
 const STAKING_ID: LockIdentifier = *b"staking ";
@@ -661,7 +666,7 @@ pub struct UnappliedSlash<AccountId, Balance: HasCompact> {
 /// Indicate how an election round was computed.
 #[derive(PartialEq, Eq, Clone, Copy, Encode, Decode, RuntimeDebug)]
 pub enum ElectionCompute {
-	/// Result was forcefully computed on chain at the end of the session.
+	/// Result was forcefully computed at the end of the session by [`Config::ElectionProvider`].
 	OnChain,
 	/// Result was submitted and accepted to the chain via a signed transaction.
 	Signed,
@@ -864,6 +869,16 @@ pub trait Config: frame_system::Config + SendTransactionTypes<Call<Self>> {
 	/// enough to fit in the block.
 	type OffchainSolutionWeightLimit: Get<Weight>;
 
+	/// Something that provides the election functionality.
+	///
+	/// This is used whenever no solution has been queued through the offchain election window.
+	type ElectionProvider: sp_election_providers::ElectionProvider<
+		Self::AccountId,
+		Self::BlockNumber,
+		// we only accept an election provider that has staking as data provider.
+		DataProvider = Module<Self>,
+	>;
+
 	/// Weight information for extrinsics in this pallet.
 	type WeightInfo: WeightInfo;
 }
@@ -895,11 +910,12 @@ enum Releases {
 	V2_0_0,
 	V3_0_0,
 	V4_0_0,
+	V5_0_0,
 }
 
 impl Default for Releases {
 	fn default() -> Self {
-		Releases::V4_0_0
+		Releases::V5_0_0
 	}
 }
 
@@ -937,13 +953,44 @@ decl_storage! {
 		pub Payee get(fn payee): map hasher(twox_64_concat) T::AccountId => RewardDestination<T::AccountId>;
 
 		/// The map from (wannabe) validator stash key to the preferences of that validator.
+		///
+		/// When updating this storage item, you must also update the `CounterForValidators`.
 		pub Validators get(fn validators):
 			map hasher(twox_64_concat) T::AccountId => ValidatorPrefs;
 
+		/// A tracker to keep count of the number of items in the `Validators` map.
+		pub CounterForValidators get(fn counter_for_validators): u32;
+
+		/// The maximum validator count before we stop allowing new validators to join.
+		///
+		/// When this value is not set, no limits are enforced.
+		pub MaxValidatorsCount get(fn max_validators_count): Option<u32>;
+
 		/// The map from nominator stash key to the set of stash keys of all validators to nominate.
+		///
+		/// When updating this storage item, you must also update the `CounterForNominators`.
 		pub Nominators get(fn nominators):
 			map hasher(twox_64_concat) T::AccountId => Option<Nominations<T::AccountId>>;
 
+		/// A tracker to keep count of the number of items in the `Nominators` map.
+		pub CounterForNominators get(fn counter_for_nominators): u32;
+
+		/// The maximum nominator count before we stop allowing new nominators to join.
+		///
+		/// When this value is not set, no limits are enforced.
+		pub MaxNominatorsCount get(fn max_nominators_count): Option<u32>;
+
+		/// The minimum active bond to become and maintain the role of a nominator.
+		pub MinNominatorBond get(fn min_nominator_bond): BalanceOf<T>;
+
+		/// The minimum active bond to become and maintain the role of a validator.
+		pub MinValidatorBond get(fn min_validator_bond): BalanceOf<T>;
+
+		/// The threshold for when users can start calling `chill_other` for other validators /
+		/// nominators. The threshold is compared to the actual number of validators / nominators
+		/// (`CounterFor*`) in the system compared to the configured max (`Max*Count`).
+		pub ChillThreshold get(fn chill_threshold): Option<Percent>;
+
 		/// The current era index.
 		///
 		/// This is the latest planned era, depending on how the Session pallet queues the validator
@@ -1080,11 +1127,16 @@ decl_storage! {
 		/// forcing into account.
 		pub IsCurrentSessionFinal get(fn is_current_session_final): bool = false;
 
+		/// The last planned session scheduled by the session pallet.
+		///
+		/// This is basically in sync with the call to [`SessionManager::new_session`].
+		pub CurrentPlannedSession get(fn current_planned_session): SessionIndex;
+
 		/// True if network has been upgraded to this version.
 		/// Storage version of the pallet.
 		///
-		/// This is set to v3.0.0 for new networks.
-		StorageVersion build(|_: &GenesisConfig<T>| Releases::V4_0_0): Releases;
+		/// This is set to v5.0.0 for new networks.
+		StorageVersion build(|_: &GenesisConfig<T>| Releases::V5_0_0): Releases;
 	}
 	add_extra_genesis {
 		config(stakers):
@@ -1219,6 +1271,16 @@ decl_error! {
 		IncorrectHistoryDepth,
 		/// Incorrect number of slashing spans provided.
 		IncorrectSlashingSpans,
+		/// Can not bond with value less than minimum required.
+		InsufficientBond,
+		/// There are too many nominators in the system. Governance needs to adjust the staking
+		/// settings to keep things safe for the runtime.
+		TooManyNominators,
+		/// There are too many validators in the system. Governance needs to adjust the staking
+		/// settings to keep things safe for the runtime.
+		TooManyValidators,
+		/// The user does not meet the requirements to be chilled by another account.
+		CannotChillOther,
 	}
 }
 
@@ -1264,6 +1326,14 @@ decl_module! {
 
 		fn deposit_event() = default;
 
+		fn on_runtime_upgrade() -> Weight {
+			if StorageVersion::get() == Releases::V4_0_0 {
+				Self::migrate_to_v5()
+			} else {
+				T::DbWeight::get().reads(1)
+			}
+		}
+
 		/// sets `ElectionStatus` to `Open(now)` where `now` is the block number at which the
 		/// election window has opened, if we are at the last session and less blocks than
 		/// `T::ElectionLookahead` is remaining until the next new session schedule. The offchain
@@ -1539,6 +1609,17 @@ decl_module! {
 					ledger.active = Zero::zero();
 				}
 
+				// Make sure that the user maintains enough active bond for their role.
+				// If a user runs into this error, they should chill first.
+				let min_active_bond = if <Nominators<T>>::contains_key(&ledger.stash) {
+					Self::min_nominator_bond()
+				} else if <Validators<T>>::contains_key(&ledger.stash) {
+					Self::min_validator_bond()
+				} else {
+					Zero::zero()
+				};
+				ensure!(ledger.active >= min_active_bond, Error::<T>::InsufficientBond);
+
 				// Note: in case there is no current era it is fine to bond one era more.
 				let era = Self::current_era().unwrap_or(0) + T::BondingDuration::get();
 				ledger.unlocking.push(UnlockChunk { value, era });
@@ -1630,17 +1711,32 @@ decl_module! {
 		/// -----------
 		/// Weight: O(1)
 		/// DB Weight:
-		/// - Read: Era Election Status, Ledger
-		/// - Write: Nominators, Validators
+		/// - Read: Era Election Status, Ledger, MinValidatorBond, Validators, MaxValidatorsCount,
+		///   CounterForValidators
+		/// - Write: Nominators, Validators, CounterForNominators, CounterForValidators
 		/// # </weight>
 		#[weight = T::WeightInfo::validate()]
 		pub fn validate(origin, prefs: ValidatorPrefs) {
 			ensure!(Self::era_election_status().is_closed(), Error::<T>::CallNotAllowed);
 			let controller = ensure_signed(origin)?;
 			let ledger = Self::ledger(&controller).ok_or(Error::<T>::NotController)?;
+			ensure!(ledger.active >= Self::min_validator_bond(), Error::<T>::InsufficientBond);
 			let stash = &ledger.stash;
-			<Nominators<T>>::remove(stash);
-			<Validators<T>>::insert(stash, prefs);
+
+			// If this error is reached, we need to adjust the `MinValidatorBond` and start
+			// calling `chill_other`. Until then, we explicitly block new validators to protect
+			// the runtime.
+			if !<Validators<T>>::contains_key(stash) {
+				if let Some(max_validators) = MaxValidatorsCount::get() {
+					ensure!(
+						CounterForValidators::get() < max_validators,
+						Error::<T>::TooManyValidators,
+					);
+				}
+			}
+
+			Self::do_remove_nominator(stash);
+			Self::do_add_validator(stash, prefs);
 		}
 
 		/// Declare the desire to nominate `targets` for the origin controller.
@@ -1659,15 +1755,30 @@ decl_module! {
 		/// Weight: O(N)
 		/// where N is the number of targets
 		/// DB Weight:
-		/// - Reads: Era Election Status, Ledger, Current Era
-		/// - Writes: Validators, Nominators
+		/// - Reads: Era Election Status, Ledger, Current Era, MinNominatorBond, Nominators,
+		///   MaxNominatorsCount, CounterForNominators
+		/// - Writes: Validators, Nominators, CounterForValidators, CounterForNominators
 		/// # </weight>
 		#[weight = T::WeightInfo::nominate(targets.len() as u32)]
 		pub fn nominate(origin, targets: Vec<<T::Lookup as StaticLookup>::Source>) {
 			ensure!(Self::era_election_status().is_closed(), Error::<T>::CallNotAllowed);
 			let controller = ensure_signed(origin)?;
 			let ledger = Self::ledger(&controller).ok_or(Error::<T>::NotController)?;
+			ensure!(ledger.active >= Self::min_nominator_bond(), Error::<T>::InsufficientBond);
 			let stash = &ledger.stash;
+
+			// If this error is reached, we need to adjust the `MinNominatorBond` and start
+			// calling `chill_other`. Until then, we explicitly block new nominators to protect
+			// the runtime.
+			if !<Nominators<T>>::contains_key(stash) {
+				if let Some(max_nominators) = MaxNominatorsCount::get() {
+					ensure!(
+						CounterForNominators::get() < max_nominators,
+						Error::<T>::TooManyNominators,
+					);
+				}
+			}
+
 			ensure!(!targets.is_empty(), Error::<T>::EmptyTargets);
 			let targets = targets.into_iter()
 				.take(MAX_NOMINATIONS)
@@ -1681,8 +1792,8 @@ decl_module! {
 				suppressed: false,
 			};
 
-			<Validators<T>>::remove(stash);
-			<Nominators<T>>::insert(stash, &nominations);
+			Self::do_remove_validator(stash);
+			Self::do_add_nominator(stash, nominations);
 		}
 
 		/// Declare no desire to either validate or nominate.
@@ -1699,8 +1810,8 @@ decl_module! {
 		/// --------
 		/// Weight: O(1)
 		/// DB Weight:
-		/// - Read: EraElectionStatus, Ledger
-		/// - Write: Validators, Nominators
+		/// - Read: EraElectionStatus, Ledger, Validators, Nominators
+		/// - Write: Validators, Nominators, CounterForValidators or CounterForNominators
 		/// # </weight>
 		#[weight = T::WeightInfo::chill()]
 		fn chill(origin) {
@@ -2161,6 +2272,119 @@ decl_module! {
 
 			Ok(adjustments)
 		}
+
+		/// Update the various staking limits of this pallet.
+		///
+		/// * `min_nominator_bond`: The minimum active bond needed to be a nominator.
+		/// * `min_validator_bond`: The minimum active bond needed to be a validator.
+		/// * `max_nominator_count`: The max number of users who can be a nominator at once.
+		///   When set to `None`, no limit is enforced.
+		/// * `max_validator_count`: The max number of users who can be a validator at once.
+		///   When set to `None`, no limit is enforced.
+		/// * `threshold`: The ratio of the max counts above which `chill_other` may be called
+		///   by anyone. When set to `None`, only the controller itself can be chilled.
+		///
+		/// Origin must be Root to call this function.
+		///
+		/// NOTE: Existing nominators and validators will not be affected by this update.
+		/// To kick people under the new limits, `chill_other` should be called.
+		///
+		/// # <weight>
+		/// Weight: O(1)
+		/// Write: MinNominatorBond, MinValidatorBond, MaxNominatorsCount, MaxValidatorsCount,
+		///   ChillThreshold
+		/// # </weight>
+		#[weight = T::WeightInfo::set_staking_limits()]
+		fn set_staking_limits(
+			origin,
+			min_nominator_bond: BalanceOf<T>,
+			min_validator_bond: BalanceOf<T>,
+			max_nominator_count: Option<u32>,
+			max_validator_count: Option<u32>,
+			threshold: Option<Percent>,
+		) {
+			ensure_root(origin)?;
+			<MinNominatorBond<T>>::put(min_nominator_bond);
+			<MinValidatorBond<T>>::put(min_validator_bond);
+			MaxNominatorsCount::set(max_nominator_count);
+			MaxValidatorsCount::set(max_validator_count);
+			ChillThreshold::set(threshold);
+		}
+
+		/// Declare a `controller` to stop participating as either a validator or nominator.
+		///
+		/// Effects will be felt at the beginning of the next era.
+		///
+		/// The dispatch origin for this call must be _Signed_, but can be called by anyone.
+		///
+		/// If the caller is the same as the controller being targeted, then no further checks
+		/// are enforced, and this function behaves just like `chill`.
+		///
+		/// If the caller is different than the controller being targeted, the following
+		/// conditions must be met:
+		/// * A `ChillThreshold` must be set, which defines how close to the max nominators or
+		///   validators we must reach before users can start chilling one-another.
+		/// * A `MaxNominatorsCount` and `MaxValidatorsCount` must be set, which is used to
+		///   determine how close we are to the threshold.
+		/// * The active bond of the targeted stash must be below `MinNominatorBond` or
+		///   `MinValidatorBond`, depending on its role.
+		///
+		/// This can be helpful if bond requirements are updated, and we need to remove old users
+		/// who do not satisfy these requirements.
+		///
+		/// # <weight>
+		/// Weight: O(1)
+		/// DB Weight:
+		/// - Read: EraElectionStatus, Ledger, ChillThreshold, Max*Count, CounterFor*,
+		///   Min*Bond, Validators, Nominators
+		/// - Write: Validators or Nominators, CounterForValidators or CounterForNominators
+		/// # </weight>
+		#[weight = T::WeightInfo::chill_other()]
+		fn chill_other(origin, controller: <T::Lookup as StaticLookup>::Source) {
+			ensure!(Self::era_election_status().is_closed(), Error::<T>::CallNotAllowed);
+			let caller = ensure_signed(origin)?;
+			let controller = T::Lookup::lookup(controller)?;
+			let ledger = Self::ledger(&controller).ok_or(Error::<T>::NotController)?;
+			let stash = ledger.stash;
+
+			// In order for one user to chill another user, the following conditions must be met:
+			// * A `ChillThreshold` is set which defines how close to the max nominators or
+			//   validators we must reach before users can start chilling one-another.
+			// * A `MaxNominatorsCount` and `MaxValidatorsCount` which is used to determine how
+			//   close we are to the threshold.
+			// * The active bond of the user is below the current minimum for their role.
+			// Otherwise, if caller is the same as the controller, this is just like `chill`.
+			if caller != controller {
+				let threshold = Self::chill_threshold().ok_or(Error::<T>::CannotChillOther)?;
+				if <Nominators<T>>::contains_key(&stash) {
+					let max_nominator_count = MaxNominatorsCount::get()
+						.ok_or(Error::<T>::CannotChillOther)?;
+					let current_nominator_count = CounterForNominators::get();
+					ensure!(
+						threshold * max_nominator_count < current_nominator_count,
+						Error::<T>::CannotChillOther,
+					);
+					ensure!(
+						ledger.active < Self::min_nominator_bond(),
+						Error::<T>::CannotChillOther,
+					);
+				} else if <Validators<T>>::contains_key(&stash) {
+					let max_validator_count = MaxValidatorsCount::get()
+						.ok_or(Error::<T>::CannotChillOther)?;
+					let current_validator_count = CounterForValidators::get();
+					ensure!(
+						threshold * max_validator_count < current_validator_count,
+						Error::<T>::CannotChillOther,
+					);
+					ensure!(
+						ledger.active < Self::min_validator_bond(),
+						Error::<T>::CannotChillOther,
+					);
+				}
+			}
+
+			Self::chill_stash(&stash);
+		}
 	}
 }
 
@@ -2346,10 +2570,81 @@ impl<T: Config> Module<T> {
 		<Ledger<T>>::insert(controller, ledger);
 	}
 
+	/// Initialize `CounterForValidators` and `CounterForNominators` from the current content of
+	/// `Validators` and `Nominators`, and bump the storage version to v5.0.0.
+	pub fn migrate_to_v5() -> Weight {
+		let validator_count = <Validators<T>>::iter().count() as u32;
+		let nominator_count = <Nominators<T>>::iter().count() as u32;
+
+		CounterForValidators::put(validator_count);
+		CounterForNominators::put(nominator_count);
+		StorageVersion::put(Releases::V5_0_0);
+
+		log!(
+			info,
+			"💸 Migrated staking to v5.0.0 with {} validators and {} nominators",
+			validator_count,
+			nominator_count,
+		);
+
+		T::DbWeight::get().reads_writes(
+			(validator_count + nominator_count + 1) as Weight,
+			3,
+		)
+	}
+
 	/// Chill a stash account.
 	fn chill_stash(stash: &T::AccountId) {
-		<Validators<T>>::remove(stash);
-		<Nominators<T>>::remove(stash);
+		Self::do_remove_validator(stash);
+		Self::do_remove_nominator(stash);
+	}
+
+	/// Add a nominator to the `Nominators` storage map, keeping `CounterForNominators` in sync.
+	///
+	/// If the nominator already exists, their nominations will be updated.
+	pub fn do_add_nominator(who: &T::AccountId, nominations: Nominations<T::AccountId>) {
+		if !<Nominators<T>>::contains_key(who) {
+			CounterForNominators::mutate(|x| *x = x.saturating_add(1));
+		}
+		<Nominators<T>>::insert(who, nominations);
+	}
+
+	/// Remove a nominator from the `Nominators` storage map, keeping `CounterForNominators` in
+	/// sync.
+	///
+	/// Returns `true` if `who` was removed from `Nominators`, otherwise `false`.
+	pub fn do_remove_nominator(who: &T::AccountId) -> bool {
+		if <Nominators<T>>::contains_key(who) {
+			<Nominators<T>>::remove(who);
+			CounterForNominators::mutate(|x| *x = x.saturating_sub(1));
+			true
+		} else {
+			false
+		}
+	}
+
+	/// Add a validator to the `Validators` storage map, keeping `CounterForValidators` in sync.
+	///
+	/// If the validator already exists, their preferences will be updated.
+	pub fn do_add_validator(who: &T::AccountId, prefs: ValidatorPrefs) {
+		if !<Validators<T>>::contains_key(who) {
+			CounterForValidators::mutate(|x| *x = x.saturating_add(1));
+		}
+		<Validators<T>>::insert(who, prefs);
+	}
+
+	/// Remove a validator from the `Validators` storage map, keeping `CounterForValidators` in
+	/// sync.
+	///
+	/// Returns `true` if `who` was removed from `Validators`, otherwise `false`.
+	pub fn do_remove_validator(who: &T::AccountId) -> bool {
+		if <Validators<T>>::contains_key(who) {
+			<Validators<T>>::remove(who);
+			CounterForValidators::mutate(|x| *x = x.saturating_sub(1));
+			true
+		} else {
+			false
+		}
 	}
 
 	/// Actually make a payment to a staker. This uses the currency's reward function
@@ -2380,6 +2675,7 @@ impl<T: Config> Module<T> {
 
 	/// Plan a new session potentially trigger a new era.
 	fn new_session(session_index: SessionIndex) -> Option<Vec<T::AccountId>> {
+		CurrentPlannedSession::put(session_index);
 		if let Some(current_era) = Self::current_era() {
 			// Initial era has been set.
 
@@ -2818,14 +3114,15 @@ impl<T: Config> Module<T> {
 	}
 
 	/// Select a new validator set from the assembled stakers and their role preferences. It tries
-	/// first to peek into [`QueuedElected`]. Otherwise, it runs a new on-chain phragmen election.
+	/// first to peek into [`QueuedElected`]. Otherwise, it requests a new election result from
+	/// [`Config::ElectionProvider`].
 	///
 	/// If [`QueuedElected`] and [`QueuedScore`] exists, they are both removed. No further storage
 	/// is updated.
 	fn try_do_election() -> Option<ElectionResult<T::AccountId, BalanceOf<T>>> {
 		// an election result from either a stored submission or locally executed one.
 		let next_result = <QueuedElected<T>>::take().or_else(||
-			Self::do_on_chain_phragmen()
+			Self::enact_election()
 		);
 
 		// either way, kill this. We remove it here to make sure it always has the exact same
@@ -2835,56 +3132,44 @@ impl<T: Config> Module<T> {
 		next_result
 	}
 
-	/// Execute election and return the new results. The edge weights are processed into support
-	/// values.
-	///
-	/// This is basically a wrapper around [`Self::do_phragmen`] which translates
-	/// `PrimitiveElectionResult` into `ElectionResult`.
+	/// Request a new election result from [`Config::ElectionProvider`] and process its supports
+	/// into exposures.
 	///
 	/// No storage item is updated.
-	pub fn do_on_chain_phragmen() -> Option<ElectionResult<T::AccountId, BalanceOf<T>>> {
-		if let Some(phragmen_result) = Self::do_phragmen::<ChainAccuracy>(0) {
-			let elected_stashes = phragmen_result.winners.iter()
-				.map(|(s, _)| s.clone())
-				.collect::<Vec<T::AccountId>>();
-			let assignments = phragmen_result.assignments;
-
-			let staked_assignments = sp_npos_elections::assignment_ratio_to_staked(
-				assignments,
-				Self::slashable_balance_of_fn(),
-			);
-
-			let supports = build_support_map::<T::AccountId>(
-				&elected_stashes,
-				&staked_assignments,
-			)
-			.map_err(|_|
-				log!(
-					error,
-					"💸 on-chain phragmen is failing due to a problem in the result. This must be a bug."
-				)
-			)
+	fn enact_election() -> Option<ElectionResult<T::AccountId, BalanceOf<T>>> {
+		let supports = T::ElectionProvider::elect()
+			.map_err(|err| log!(error, "💸 Election provider failed due to {:?}", err))
 			.ok()?;
 
-			// collect exposures
-			let exposures = Self::collect_exposure(supports);
-
-			// In order to keep the property required by `on_session_ending` that we must return the
-			// new validator set even if it's the same as the old, as long as any underlying
-			// economic conditions have changed, we don't attempt to do any optimization where we
-			// compare against the prior set.
-			Some(ElectionResult::<T::AccountId, BalanceOf<T>> {
-				elected_stashes,
-				exposures,
-				compute: ElectionCompute::OnChain,
-			})
-		} else {
-			// There were not enough candidates for even our minimal level of functionality. This is
-			// bad. We should probably disable all functionality except for block production and let
-			// the chain keep producing blocks until we can decide on a sufficiently substantial
-			// set. TODO: #2494
-			None
+		if supports.len() < Self::minimum_validator_count().max(1) as usize {
+			// There were not enough candidates for even our minimal level of functionality. This
+			// is bad. We should probably disable all functionality except for block production
+			// and let the chain keep producing blocks until we can decide on a sufficiently
+			// substantial set. TODO: #2494
+			log!(
+				error,
+				"💸 Chain does not have enough staking candidates to operate. Era {:?}.",
+				Self::current_era(),
+			);
+			return None
 		}
+
+		let elected_stashes = supports.iter()
+			.map(|(s, _)| s.clone())
+			.collect::<Vec<T::AccountId>>();
+
+		// collect exposures
+		let exposures = Self::collect_exposure(supports);
+
+		// In order to keep the property required by `on_session_ending` that we must return the
+		// new validator set even if it's the same as the old, as long as any underlying
+		// economic conditions have changed, we don't attempt to do any optimization where we
+		// compare against the prior set.
+		Some(ElectionResult::<T::AccountId, BalanceOf<T>> {
+			elected_stashes,
+			exposures,
+			compute: ElectionCompute::OnChain,
+		})
 	}
 
 	/// Execute phragmen election and return the new results. No post-processing is applied and the
@@ -2897,17 +3182,42 @@ impl<T: Config> Module<T> {
 	-> Option<PrimitiveElectionResult<T::AccountId, Accuracy>>
 		where ExtendedBalance: From<InnerOf<Accuracy>>
 	{
+		let all_nominators = Self::get_npos_voters();
+		let all_validators = Self::get_npos_targets();
+
+		if all_validators.len() < Self::minimum_validator_count().max(1) as usize {
+			// If we don't have enough candidates, nothing to do.
+			log!(error, "💸 Chain does not have enough staking candidates to operate. Era {:?}.", Self::current_era());
+			None
+		} else {
+			seq_phragmen::<_, Accuracy>(
+				Self::validator_count() as usize,
+				all_validators,
+				all_nominators,
+				Some((iterations, 0)), // exactly run `iterations` rounds.
+			)
+			.map_err(|err| log!(error, "Call to seq-phragmen failed due to {}", err))
+			.ok()
+		}
+	}
+
+	/// Get all of the voters that are eligible for the npos election.
+	///
+	/// This will use all on-chain nominators, and all the validators will inject a self vote.
+	///
+	/// Nomination targets which were nominated before the most recent slashing span of the target
+	/// are ignored.
+	pub fn get_npos_voters() -> Vec<(T::AccountId, VoteWeight, Vec<T::AccountId>)> {
 		let weight_of = Self::slashable_balance_of_fn();
-		let mut all_nominators: Vec<(T::AccountId, VoteWeight, Vec<T::AccountId>)> = Vec::new();
-		let mut all_validators = Vec::new();
+		let mut all_voters = Vec::new();
+
 		for (validator, _) in <Validators<T>>::iter() {
 			// append self vote
 			let self_vote = (validator.clone(), weight_of(&validator), vec![validator.clone()]);
-			all_nominators.push(self_vote);
-			all_validators.push(validator);
+			all_voters.push(self_vote);
 		}
 
-		let nominator_votes = <Nominators<T>>::iter().map(|(nominator, nominations)| {
+		for (nominator, nominations) in <Nominators<T>>::iter() {
 			let Nominations { submitted_in, mut targets, suppressed: _ } = nominations;
 
 			// Filter out nomination targets which were nominated before the most recent
@@ -2919,32 +3229,21 @@ impl<T: Config> Module<T> {
 				)
 			});
 
-			(nominator, targets)
-		});
-		all_nominators.extend(nominator_votes.map(|(n, ns)| {
-			let s = weight_of(&n);
-			(n, s, ns)
-		}));
-
-		if all_validators.len() < Self::minimum_validator_count().max(1) as usize {
-			// If we don't have enough candidates, nothing to do.
-			log!(error, "💸 Chain does not have enough staking candidates to operate. Era {:?}.", Self::current_era());
-			None
-		} else {
-			seq_phragmen::<_, Accuracy>(
-				Self::validator_count() as usize,
-				all_validators,
-				all_nominators,
-				Some((iterations, 0)), // exactly run `iterations` rounds.
-			)
-			.map_err(|err| log!(error, "Call to seq-phragmen failed due to {}", err))
-			.ok()
+			let vote_weight = weight_of(&nominator);
+			all_voters.push((nominator, vote_weight, targets));
 		}
+
+		all_voters
+	}
+
+	/// Get all of the targets that are eligible for the npos election, i.e. all validators.
+	pub fn get_npos_targets() -> Vec<T::AccountId> {
+		<Validators<T>>::iter().map(|(v, _)| v).collect::<Vec<_>>()
 	}
 
 	/// Consume a set of [`Supports`] from [`sp_npos_elections`] and collect them into a [`Exposure`]
 	fn collect_exposure(
-		supports: SupportMap<T::AccountId>,
+		supports: impl IntoIterator<Item = (T::AccountId, Support<T::AccountId>)>,
 	) -> Vec<(T::AccountId, Exposure<T::AccountId, BalanceOf<T>>)> {
 		let total_issuance = T::Currency::total_issuance();
 		let to_currency = |e: ExtendedBalance| T::CurrencyToVote::to_currency(e, total_issuance);
@@ -2992,8 +3291,8 @@ impl<T: Config> Module<T> {
 		<Ledger<T>>::remove(&controller);
 
 		<Payee<T>>::remove(stash);
-		<Validators<T>>::remove(stash);
-		<Nominators<T>>::remove(stash);
+		Self::do_remove_validator(stash);
+		Self::do_remove_nominator(stash);
 
 		system::Module::<T>::dec_ref(stash);
 
@@ -3083,6 +3382,50 @@ impl<T: Config> Module<T> {
 	}
 }
 
+impl<T: Config> sp_election_providers::ElectionDataProvider<T::AccountId, T::BlockNumber>
+	for Module<T>
+{
+	const MAXIMUM_VOTES_PER_VOTER: u32 = MAX_NOMINATIONS as u32;
+
+	fn desired_targets() -> u32 {
+		Self::validator_count()
+	}
+
+	fn voters() -> Vec<(T::AccountId, VoteWeight, Vec<T::AccountId>)> {
+		Self::get_npos_voters()
+	}
+
+	fn targets() -> Vec<T::AccountId> {
+		Self::get_npos_targets()
+	}
+
+	fn next_election_prediction(now: T::BlockNumber) -> T::BlockNumber {
+		let current_era = Self::current_era().unwrap_or(0);
+		let current_session = Self::current_planned_session();
+		let current_era_start_session_index =
+			Self::eras_start_session_index(current_era).unwrap_or(0);
+		let era_length = current_session
+			.saturating_sub(current_era_start_session_index)
+			.min(T::SessionsPerEra::get());
+
+		let session_length = T::NextNewSession::average_session_length();
+
+		let until_this_session_end = T::NextNewSession::estimate_next_new_session(now)
+			.unwrap_or_default()
+			.saturating_sub(now);
+
+		let sessions_left: T::BlockNumber = T::SessionsPerEra::get()
+			.saturating_sub(era_length)
+			// one session is computed in this_session_end.
+			.saturating_sub(1)
+			.into();
+
+		now.saturating_add(
+			until_this_session_end.saturating_add(sessions_left.saturating_mul(session_length))
+		)
+	}
+}
+
 /// In this implementation `new_session(session)` must be called before `end_session(session-1)`
 /// i.e. the new session must be planned before the ending of the previous session.
 ///
@@ -35,6 +35,7 @@ use sp_runtime::{
 	traits::{IdentityLookup, Zero},
 };
 use sp_staking::offence::{OffenceDetails, OnOffenceHandler};
+use sp_election_providers::onchain;
 use std::{cell::RefCell, collections::HashSet};
 
 pub const INIT_TIMESTAMP: u64 = 30_000;
@@ -250,6 +251,13 @@ impl OnUnbalanced<NegativeImbalanceOf<Test>> for RewardRemainderMock {
 	}
 }
 
+impl onchain::Config for Test {
+	type AccountId = <Self as frame_system::Config>::AccountId;
+	type BlockNumber = <Self as frame_system::Config>::BlockNumber;
+	type Accuracy = Perbill;
+	type DataProvider = Module<Self>;
+}
+
 impl Config for Test {
 	type Currency = Balances;
 	type UnixTime = Timestamp;
@@ -272,6 +280,7 @@ impl Config for Test {
 	type MaxNominatorRewardedPerValidator = MaxNominatorRewardedPerValidator;
 	type UnsignedPriority = UnsignedPriority;
 	type OffchainSolutionWeightLimit = OffchainSolutionWeightLimit;
+	type ElectionProvider = onchain::OnChainSequentialPhragmen<Self>;
 	type WeightInfo = ();
 }
 
@@ -506,12 +515,20 @@ fn post_conditions() {
 	check_nominators();
 	check_exposures();
 	check_ledgers();
+	check_count();
 }
 
 pub(crate) fn active_era() -> EraIndex {
 	Staking::active_era().unwrap().index
 }
 
+fn check_count() {
+	let nominator_count = Nominators::<Test>::iter().count() as u32;
+	let validator_count = Validators::<Test>::iter().count() as u32;
+	assert_eq!(nominator_count, CounterForNominators::get());
+	assert_eq!(validator_count, CounterForValidators::get());
+}
+
 fn check_ledgers() {
 	// check the ledger of all stakers.
 	Bonded::<Test>::iter().for_each(|(_, ctrl)| assert_ledger_consistent(ctrl))
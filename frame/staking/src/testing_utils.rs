@@ -32,6 +32,9 @@ const SEED: u32 = 0;
 pub fn clear_validators_and_nominators<T: Config>() {
 	Validators::<T>::remove_all();
 	Nominators::<T>::remove_all();
+
+	CounterForValidators::kill();
+	CounterForNominators::kill();
 }
 
 /// Grab a funded user.
@@ -25,7 +25,7 @@ use sp_runtime::{
 use sp_staking::offence::OffenceDetails;
 use frame_support::{
 	assert_ok, assert_noop, StorageMap,
-	traits::{Currency, ReservableCurrency, OnInitialize, OnFinalize},
+	traits::{Currency, ReservableCurrency, OnInitialize, OnFinalize, OnRuntimeUpgrade},
 };
 use pallet_balances::Error as BalancesError;
 use substrate_test_utils::assert_eq_uvec;
@@ -4778,3 +4778,302 @@ fn cannot_rebond_to_lower_than_ed() {
 			);
 		})
 }
+
+#[test]
+fn min_bond_checks_work() {
+	ExtBuilder::default()
+		.existential_deposit(100)
+		.build_and_execute(|| {
+			// setup
+			assert_ok!(Staking::set_staking_limits(Origin::root(), 1_000, 1_500, None, None, None));
+
+			assert_ok!(Staking::bond(Origin::signed(3), 4, 500, RewardDestination::Controller));
+			assert_noop!(
+				Staking::nominate(Origin::signed(4), vec![1]),
+				Error::<Test>::InsufficientBond,
+			);
+			assert_noop!(
+				Staking::validate(Origin::signed(4), ValidatorPrefs::default()),
+				Error::<Test>::InsufficientBond,
+			);
+
+			// 1000 is enough for nominator
+			assert_ok!(Staking::bond_extra(Origin::signed(3), 500));
+			assert_ok!(Staking::nominate(Origin::signed(4), vec![1]));
+			assert_noop!(
+				Staking::validate(Origin::signed(4), ValidatorPrefs::default()),
+				Error::<Test>::InsufficientBond,
+			);
+
+			// 1500 is enough for validator
+			assert_ok!(Staking::bond_extra(Origin::signed(3), 500));
+			assert_ok!(Staking::nominate(Origin::signed(4), vec![1]));
+			assert_ok!(Staking::validate(Origin::signed(4), ValidatorPrefs::default()));
+
+			// can't unbond below the minimum of the current role.
+			assert_noop!(Staking::unbond(Origin::signed(4), 100), Error::<Test>::InsufficientBond);
+			assert_ok!(Staking::nominate(Origin::signed(4), vec![1]));
+			assert_ok!(Staking::unbond(Origin::signed(4), 500));
+			assert_noop!(Staking::unbond(Origin::signed(4), 100), Error::<Test>::InsufficientBond);
+
+			// once chilled, everything can be unbonded.
+			assert_ok!(Staking::chill(Origin::signed(4)));
+			assert_ok!(Staking::unbond(Origin::signed(4), 1000));
+		})
+}
+
+#[test]
+fn chill_other_works() {
+	ExtBuilder::default()
+		.existential_deposit(100)
+		.build_and_execute(|| {
+			// 3 validators and 1 nominator are set up by the mock.
+			assert_eq!(CounterForValidators::get(), 3);
+			assert_eq!(CounterForNominators::get(), 1);
+
+			// stash and controller accounts of the new nominators (a, b) and validators (c, d).
+			let accounts = |i: u64| (1000 + 4 * i, 1001 + 4 * i, 1002 + 4 * i, 1003 + 4 * i);
+			for i in 0 .. 15 {
+				let (a, b, c, d) = accounts(i);
+				Balances::make_free_balance_be(&a, 100_000);
+				Balances::make_free_balance_be(&b, 100_000);
+				Balances::make_free_balance_be(&c, 100_000);
+				Balances::make_free_balance_be(&d, 100_000);
+
+				// Nominator
+				assert_ok!(Staking::bond(
+					Origin::signed(a), b, 1000, RewardDestination::Controller,
+				));
+				assert_ok!(Staking::nominate(Origin::signed(b), vec![1]));
+
+				// Validator
+				assert_ok!(Staking::bond(
+					Origin::signed(c), d, 1500, RewardDestination::Controller,
+				));
+				assert_ok!(Staking::validate(Origin::signed(d), ValidatorPrefs::default()));
+			}
+
+			// To chill other users, we need to:
+			// * Set a minimum bond amount
+			// * Set a limit
+			// * Set a threshold
+			//
+			// If any of these are missing, we do not have enough information to allow the
+			// `chill_other` to succeed from one user to another.
+
+			// Can't chill these users
+			assert_noop!(
+				Staking::chill_other(Origin::signed(1337), 1001),
+				Error::<Test>::CannotChillOther,
+			);
+			assert_noop!(
+				Staking::chill_other(Origin::signed(1337), 1003),
+				Error::<Test>::CannotChillOther,
+			);
+
+			// Change the minimum bond... but no limits.
+			assert_ok!(Staking::set_staking_limits(Origin::root(), 1_500, 2_000, None, None, None));
+
+			// Still can't chill these users
+			assert_noop!(
+				Staking::chill_other(Origin::signed(1337), 1001),
+				Error::<Test>::CannotChillOther,
+			);
+			assert_noop!(
+				Staking::chill_other(Origin::signed(1337), 1003),
+				Error::<Test>::CannotChillOther,
+			);
+
+			// Add limits, but no threshold
+			assert_ok!(Staking::set_staking_limits(
+				Origin::root(), 1_500, 2_000, Some(10), Some(10), None,
+			));
+
+			// Still can't chill these users
+			assert_noop!(
+				Staking::chill_other(Origin::signed(1337), 1001),
+				Error::<Test>::CannotChillOther,
+			);
+			assert_noop!(
+				Staking::chill_other(Origin::signed(1337), 1003),
+				Error::<Test>::CannotChillOther,
+			);
+
+			// Add threshold, but no limits
+			assert_ok!(Staking::set_staking_limits(
+				Origin::root(), 1_500, 2_000, None, None, Some(Percent::from_percent(0)),
+			));
+
+			// Still can't chill these users
+			assert_noop!(
+				Staking::chill_other(Origin::signed(1337), 1001),
+				Error::<Test>::CannotChillOther,
+			);
+			assert_noop!(
+				Staking::chill_other(Origin::signed(1337), 1003),
+				Error::<Test>::CannotChillOther,
+			);
+
+			// Add threshold and limits
+			assert_ok!(Staking::set_staking_limits(
+				Origin::root(), 1_500, 2_000, Some(10), Some(10), Some(Percent::from_percent(75)),
+			));
+
+			assert_eq!(CounterForNominators::get(), 16);
+			assert_eq!(CounterForValidators::get(), 18);
+
+			// Users can now be chilled down to 7 people, so we remove 9 nominators (16 -> 7) and
+			// 11 validators (18 -> 7).
+			for i in 6 .. 15 {
+				let (_, b, _, _) = accounts(i);
+				assert_ok!(Staking::chill_other(Origin::signed(1337), b));
+			}
+			for i in 4 .. 15 {
+				let (_, _, _, d) = accounts(i);
+				assert_ok!(Staking::chill_other(Origin::signed(1337), d));
+			}
+
+			// Cant go lower.
+			assert_noop!(
+				Staking::chill_other(Origin::signed(1337), 1001),
+				Error::<Test>::CannotChillOther,
+			);
+			assert_noop!(
+				Staking::chill_other(Origin::signed(1337), 1003),
+				Error::<Test>::CannotChillOther,
+			);
+
+			// Users can always chill themselves.
+			assert_ok!(Staking::chill_other(Origin::signed(1001), 1001));
+			assert_ok!(Staking::chill_other(Origin::signed(1003), 1003));
+		})
+}
+
+#[test]
+fn capped_stakers_works() {
+	ExtBuilder::default().build_and_execute(|| {
+		let validator_count = CounterForValidators::get();
+		assert_eq!(validator_count, 3);
+		let nominator_count = CounterForNominators::get();
+		assert_eq!(nominator_count, 1);
+
+		// Change the maximums
+		let max = 10;
+		assert_ok!(Staking::set_staking_limits(
+			Origin::root(), 10, 10, Some(max), Some(max), Some(Percent::from_percent(0)),
+		));
+
+		// can create `max - validator_count` validators
+		let mut some_existing_validator = AccountId::default();
+		for i in 0 .. max - validator_count {
+			let (_, controller) = testing_utils::create_stash_controller::<Test>(
+				i + 10_000_000, 100, RewardDestination::Controller,
+			).unwrap();
+			assert_ok!(Staking::validate(Origin::signed(controller), ValidatorPrefs::default()));
+			some_existing_validator = controller;
+		}
+
+		// but no more
+		let (_, last_validator) = testing_utils::create_stash_controller::<Test>(
+			1337, 100, RewardDestination::Controller,
+		).unwrap();
+
+		assert_noop!(
+			Staking::validate(Origin::signed(last_validator), ValidatorPrefs::default()),
+			Error::<Test>::TooManyValidators,
+		);
+
+		// same with nominators
+		let mut some_existing_nominator = AccountId::default();
+		for i in 0 .. max - nominator_count {
+			let (_, controller) = testing_utils::create_stash_controller::<Test>(
+				i + 20_000_000, 100, RewardDestination::Controller,
+			).unwrap();
+			assert_ok!(Staking::nominate(Origin::signed(controller), vec![1]));
+			some_existing_nominator = controller;
+		}
+
+		// one more is too many
+		let (_, last_nominator) = testing_utils::create_stash_controller::<Test>(
+			30_000_000, 100, RewardDestination::Controller,
+		).unwrap();
+		assert_noop!(
+			Staking::nominate(Origin::signed(last_nominator), vec![1]),
+			Error::<Test>::TooManyNominators,
+		);
+
+		// Re-nominate works fine
+		assert_ok!(Staking::nominate(Origin::signed(some_existing_nominator), vec![1]));
+		// Re-validate works fine
+		assert_ok!(Staking::validate(
+			Origin::signed(some_existing_validator),
+			ValidatorPrefs::default(),
+		));
+
+		// No problem when we set to `None` again
+		assert_ok!(Staking::set_staking_limits(Origin::root(), 10, 10, None, None, None));
+		assert_ok!(Staking::nominate(Origin::signed(last_nominator), vec![1]));
+		assert_ok!(Staking::validate(Origin::signed(last_validator), ValidatorPrefs::default()));
+	})
+}
+
+#[test]
+fn migrate_to_v5_sets_counters() {
+	ExtBuilder::default().build_and_execute(|| {
+		let validator_count = Validators::<Test>::iter().count() as u32;
+		let nominator_count = Nominators::<Test>::iter().count() as u32;
+
+		// pretend we are still on the old storage layout.
+		CounterForValidators::kill();
+		CounterForNominators::kill();
+		StorageVersion::put(Releases::V4_0_0);
+
+		<Staking as OnRuntimeUpgrade>::on_runtime_upgrade();
+
+		assert_eq!(CounterForValidators::get(), validator_count);
+		assert_eq!(CounterForNominators::get(), nominator_count);
+		assert_eq!(StorageVersion::get(), Releases::V5_0_0);
+	})
+}
+
+mod election_data_provider {
+	use super::*;
+	use sp_election_providers::ElectionDataProvider;
+
+	#[test]
+	fn voters_include_self_vote() {
+		ExtBuilder::default().nominate(false).build_and_execute(|| {
+			assert!(<Validators<Test>>::iter().map(|(x, _)| x).all(|v| Staking::voters()
+				.into_iter()
+				.find(|(w, _, t)| v == *w && t[0] == *w)
+				.is_some()))
+		})
+	}
+
+	#[test]
+	fn targets_are_all_validators() {
+		ExtBuilder::default().build_and_execute(|| {
+			assert_eq_uvec!(<Staking as ElectionDataProvider<_, _>>::targets(), vec![11, 21, 31]);
+			assert_eq!(<Staking as ElectionDataProvider<_, _>>::desired_targets(), 2);
+		})
+	}
+
+	#[test]
+	fn next_election_prediction_works() {
+		ExtBuilder::default().session_per_era(3).session_length(10).build_and_execute(|| {
+			assert_eq!(System::block_number(), 1);
+			assert_eq!(Staking::current_planned_session(), 1);
+			assert_eq!(Staking::next_election_prediction(System::block_number()), 20);
+
+			run_to_block(19);
+			assert_eq!(Staking::current_planned_session(), 2);
+			assert_eq!(Staking::next_election_prediction(System::block_number()), 20);
+
+			// the new era is planned at block 20, the next one is an era later.
+			run_to_block(21);
+			assert_eq!(Staking::current_era(), Some(1));
+			assert_eq!(Staking::current_planned_session(), 3);
+			assert_eq!(Staking::next_election_prediction(System::block_number()), 50);
+		})
+	}
+}
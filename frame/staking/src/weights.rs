@@ -67,6 +67,8 @@ pub trait WeightInfo {
 	fn reap_stash(_s: u32, ) -> Weight;
 	fn new_era(_v: u32, _n: u32, ) -> Weight;
 	fn submit_solution_better(_v: u32, _n: u32, _a: u32, _w: u32, ) -> Weight;
+	fn set_staking_limits() -> Weight;
+	fn chill_other() -> Weight;
 
 }
 
@@ -106,22 +108,22 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			.saturating_add(T::DbWeight::get().writes((1 as Weight).saturating_mul(s as Weight)))
 	}
 	fn validate() -> Weight {
-		(25_691_000 as Weight)
-			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+		(31_012_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(6 as Weight))
 			.saturating_add(T::DbWeight::get().writes(2 as Weight))
 
 	}
 	fn nominate(n: u32, ) -> Weight {
 		(35_374_000 as Weight)
 			.saturating_add((203_000 as Weight).saturating_mul(n as Weight))
-			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().reads(7 as Weight))
 			.saturating_add(T::DbWeight::get().writes(2 as Weight))
 
 	}
 	fn chill() -> Weight {
 		(25_227_000 as Weight)
-			.saturating_add(T::DbWeight::get().reads(2 as Weight))
-			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+			.saturating_add(T::DbWeight::get().reads(4 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
 
 	}
 	fn set_payee() -> Weight {
@@ -235,6 +237,17 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			.saturating_add(T::DbWeight::get().writes(2 as Weight))
 
 	}
+	fn set_staking_limits() -> Weight {
+		(5_028_000 as Weight)
+			.saturating_add(T::DbWeight::get().writes(5 as Weight))
+
+	}
+	fn chill_other() -> Weight {
+		(35_758_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(5 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+
+	}
 
 }
 
@@ -273,22 +286,22 @@ impl WeightInfo for () {
 			.saturating_add(RocksDbWeight::get().writes((1 as Weight).saturating_mul(s as Weight)))
 	}
 	fn validate() -> Weight {
-		(25_691_000 as Weight)
-			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+		(31_012_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(6 as Weight))
 			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
 
 	}
 	fn nominate(n: u32, ) -> Weight {
 		(35_374_000 as Weight)
 			.saturating_add((203_000 as Weight).saturating_mul(n as Weight))
-			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().reads(7 as Weight))
 			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
 
 	}
 	fn chill() -> Weight {
 		(25_227_000 as Weight)
-			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
-			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+			.saturating_add(RocksDbWeight::get().reads(4 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
 
 	}
 	fn set_payee() -> Weight {
@@ -402,5 +415,16 @@ impl WeightInfo for () {
 			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
 
 	}
+	fn set_staking_limits() -> Weight {
+		(5_028_000 as Weight)
+			.saturating_add(RocksDbWeight::get().writes(5 as Weight))
+
+	}
+	fn chill_other() -> Weight {
+		(35_758_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(5 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+
+	}
 
 }
@@ -155,6 +155,7 @@ fn construct_runtime_parsed(definition: RuntimeDefinition) -> Result<TokenStream
 	let module_to_index = decl_pallet_runtime_setup(&modules, &scrate);
 
 	let dispatch = decl_outer_dispatch(&name, modules.iter(), &scrate);
+	let base_call_filter = decl_base_call_filter(&name, modules.iter(), &scrate);
 	let metadata = decl_runtime_metadata(&name, modules.iter(), &scrate, &unchecked_extrinsic);
 	let outer_config = decl_outer_config(&name, modules.iter(), &scrate);
 	let inherent = decl_outer_inherent(
@@ -194,6 +195,8 @@ fn construct_runtime_parsed(definition: RuntimeDefinition) -> Result<TokenStream
 
 		#dispatch
 
+		#base_call_filter
+
 		#metadata
 
 		#outer_config
@@ -352,6 +355,47 @@ fn decl_outer_dispatch<'a>(
 	)
 }
 
+fn decl_base_call_filter<'a>(
+	runtime: &'a Ident,
+	module_declarations: impl Iterator<Item = &'a Module>,
+	scrate: &'a TokenStream2,
+) -> TokenStream2 {
+	let filters = module_declarations
+		.filter_map(|module_declaration| {
+			let filter = module_declaration.find_part("Call")?.filter.as_ref()?;
+			let name = &module_declaration.name;
+			Some(quote!(
+				Call::#name(call) => <#filter as #scrate::traits::Filter<
+					#scrate::dispatch::CallableCallFor<#name, #runtime>
+				>>::filter(call),
+			))
+		})
+		.collect::<Vec<_>>();
+
+	// Only runtimes that declare a filter get a `BaseCallFilter`, so it doesn't clash with
+	// types of the same name in runtimes that don't use the feature.
+	if filters.is_empty() {
+		return TokenStream2::new()
+	}
+
+	quote!(
+		/// The call filter composed of the call filters declared by the modules of the runtime,
+		/// e.g. `Call(MyFilter)`. Calls of modules without a filter are always allowed.
+		///
+		/// Meant to be used as `BaseCallFilter` of `frame_system::Config`.
+		pub struct BaseCallFilter;
+		impl #scrate::traits::Filter<Call> for BaseCallFilter {
+			#[allow(unreachable_patterns)]
+			fn filter(call: &Call) -> bool {
+				match call {
+					#( #filters )*
+					_ => true,
+				}
+			}
+		}
+	)
+}
+
 fn decl_outer_origin<'a>(
 	runtime_name: &'a Ident,
 	modules_except_system: impl Iterator<Item = &'a Module>,
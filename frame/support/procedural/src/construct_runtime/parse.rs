@@ -285,6 +285,11 @@ impl ModulePartKeyword {
 	fn all_generic_arg() -> &'static [&'static str] {
 		&["Event", "Origin", "Config"]
 	}
+
+	/// Returns `true` if this module part is allowed to declare a call filter.
+	fn allows_filter(&self) -> bool {
+		self.name() == "Call"
+	}
 }
 
 impl Spanned for ModulePartKeyword {
@@ -306,6 +311,8 @@ impl Spanned for ModulePartKeyword {
 pub struct ModulePart {
 	pub keyword: ModulePartKeyword,
 	pub generics: syn::Generics,
+	/// Optional call filter of the module (e.g. `Call(MyFilter)`).
+	pub filter: Option<syn::Type>,
 }
 
 impl Parse for ModulePart {
@@ -324,9 +331,24 @@ impl Parse for ModulePart {
 			return Err(syn::Error::new(keyword.span(), msg));
 		}
 
+		let filter = if input.peek(token::Paren) {
+			if !keyword.allows_filter() {
+				let msg = format!(
+					"`{}` is not allowed to have a call filter. Only `Call` is allowed to have one.",
+					keyword.name(),
+				);
+				return Err(syn::Error::new(keyword.span(), msg));
+			}
+			let filter: ext::Parens<syn::Type> = input.parse()?;
+			Some(filter.content)
+		} else {
+			None
+		};
+
 		Ok(Self {
 			keyword,
 			generics,
+			filter,
 		})
 	}
 }
@@ -0,0 +1,112 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Implementation of `HasKeyPrefix` and `HasReversibleKeyPrefix` for tuples of storage keys.
+
+use proc_macro2::{Span, TokenStream};
+use quote::{quote, ToTokens};
+use syn::{Ident, Result};
+
+/// The maximum number of keys a tuple of storage keys can contain.
+const MAX_IDENTS: usize = 18;
+
+/// Implementation of the `impl_key_prefix_for_tuples!` macro.
+///
+/// For each tuple of `Key` of size 2 to `MAX_IDENTS`, and for each strict leading subset of
+/// its keys, implements `HasKeyPrefix` and `HasReversibleKeyPrefix` with this subset as prefix.
+pub fn impl_key_prefix_for_tuples(input: proc_macro::TokenStream) -> Result<TokenStream> {
+	if !input.is_empty() {
+		return Err(syn::Error::new(Span::call_site(), "No arguments expected"));
+	}
+
+	let mut all_trait_impls = TokenStream::new();
+
+	for i in 2..=MAX_IDENTS {
+		let current_tuple = (0..i)
+			.map(|n| Ident::new(&format!("Tuple{}", n), Span::call_site()))
+			.collect::<Vec<_>>();
+
+		for prefix_count in 1..i {
+			let (prefixes, suffixes) = current_tuple.split_at(prefix_count);
+
+			let hashers = current_tuple.iter().map(hasher_ident).collect::<Vec<_>>();
+			let kargs = prefixes.iter()
+				.map(|ident| Ident::new(&format!("KArg{}", ident), Span::call_site()))
+				.collect::<Vec<_>>();
+			let partial_keygen = generate_keygen(prefixes);
+			let suffix_keygen = generate_keygen(suffixes);
+			let suffix_tuple = generate_tuple(suffixes);
+
+			let trait_impls = quote! {
+				impl<
+					#( #current_tuple: FullCodec, )*
+					#( #hashers: StorageHasher, )*
+					#( #kargs: EncodeLike<#prefixes> ),*
+				> HasKeyPrefix<( #( #kargs, )* )> for ( #( Key<#hashers, #current_tuple>, )* ) {
+					type Suffix = #suffix_tuple;
+
+					fn partial_key(prefix: ( #( #kargs, )* )) -> Vec<u8> {
+						<#partial_keygen>::final_key(prefix)
+					}
+				}
+
+				impl<
+					#( #current_tuple: FullCodec, )*
+					#( #hashers: ReversibleStorageHasher, )*
+					#( #kargs: EncodeLike<#prefixes> ),*
+				> HasReversibleKeyPrefix<( #( #kargs, )* )>
+					for ( #( Key<#hashers, #current_tuple>, )* )
+				{
+					fn decode_partial_key(
+						key_material: &[u8],
+					) -> Result<Self::Suffix, codec::Error> {
+						<#suffix_keygen>::decode_final_key(key_material).map(|k| k.0)
+					}
+				}
+			};
+
+			all_trait_impls.extend(trait_impls);
+		}
+	}
+
+	Ok(all_trait_impls)
+}
+
+fn hasher_ident(ident: &Ident) -> Ident {
+	Ident::new(&format!("Hasher{}", ident), Span::call_site())
+}
+
+/// Generate the type of the keys, a single type is not wrapped in a tuple.
+fn generate_tuple(idents: &[Ident]) -> TokenStream {
+	if idents.len() == 1 {
+		idents[0].to_token_stream()
+	} else {
+		quote!( ( #( #idents ),* ) )
+	}
+}
+
+/// Generate the key generator of the keys, a single `Key` is not wrapped in a tuple.
+fn generate_keygen(idents: &[Ident]) -> TokenStream {
+	if idents.len() == 1 {
+		let key = &idents[0];
+		let hasher = hasher_ident(key);
+		quote!( Key<#hasher, #key> )
+	} else {
+		let hashers = idents.iter().map(hasher_ident);
+		quote!( ( #( Key<#hashers, #idents> ),* ) )
+	}
+}
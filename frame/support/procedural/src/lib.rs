@@ -26,6 +26,8 @@ mod transactional;
 mod debug_no_bound;
 mod clone_no_bound;
 mod partial_eq_no_bound;
+mod pallet;
+mod key_prefix;
 
 use proc_macro::TokenStream;
 
@@ -295,6 +297,15 @@ pub fn decl_storage(input: TokenStream) -> TokenStream {
 /// module4 .., // Here module4 is given index 1
 /// ```
 ///
+/// The `Call` part can declare a call filter of the module, e.g. `Call(MyFilter)`, where
+/// `MyFilter` implements `Filter` for the `Call` type of the module. The filters of all modules
+/// are combined into the generated `BaseCallFilter`, which implements `Filter<Call>` and can be
+/// used as `BaseCallFilter` of `frame_system::Config`. `BaseCallFilter` is only generated if at
+/// least one module declares a filter:
+/// ```nocompile
+/// Balances: pallet_balances::{Module, Call(NoTransferAll), Storage, Event<T>} = 5,
+/// ```
+///
 /// # Note
 ///
 /// The population of the genesis storage depends on the order of modules. So, if one of your
@@ -399,6 +410,12 @@ pub fn derive_eq_no_bound(input: TokenStream) -> TokenStream {
 	).into()
 }
 
+/// Macro to define a pallet. Docs are at `frame_support::pallet`.
+#[proc_macro_attribute]
+pub fn pallet(attr: TokenStream, item: TokenStream) -> TokenStream {
+	pallet::pallet(attr, item)
+}
+
 #[proc_macro_attribute]
 pub fn require_transactional(attr: TokenStream, input: TokenStream) -> TokenStream {
 	transactional::require_transactional(attr, input).unwrap_or_else(|e| e.to_compile_error().into())
@@ -408,3 +425,9 @@ pub fn require_transactional(attr: TokenStream, input: TokenStream) -> TokenStre
 pub fn crate_to_pallet_version(input: TokenStream) -> TokenStream {
 	pallet_version::crate_to_pallet_version(input).unwrap_or_else(|e| e.to_compile_error()).into()
 }
+
+#[proc_macro]
+#[doc(hidden)]
+pub fn impl_key_prefix_for_tuples(input: TokenStream) -> TokenStream {
+	key_prefix::impl_key_prefix_for_tuples(input).unwrap_or_else(|e| e.to_compile_error()).into()
+}
@@ -0,0 +1,98 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::pallet::Def;
+use frame_support_procedural_tools::clean_type_string;
+
+/// * Impl fn module_constant_metadata for pallet.
+pub fn expand_constants(def: &mut Def) -> proc_macro2::TokenStream {
+	let frame_support = &def.frame_support;
+	let pallet_ident = &def.pallet_struct.pallet;
+
+	let default_byte_getter = |ident: &syn::Ident| syn::Ident::new(
+		&format!("{}DefaultByteGetter", ident),
+		ident.span(),
+	);
+
+	let default_byte_getter_struct_defs = def.config.consts_metadata.iter()
+		.map(|const_| {
+			let ident = &const_.ident;
+			let const_type = &const_.type_;
+			let default_byte_getter = default_byte_getter(ident);
+
+			quote::quote!(
+				#[allow(non_upper_case_types)]
+				#[allow(non_camel_case_types)]
+				struct #default_byte_getter<T>(
+					#frame_support::sp_std::marker::PhantomData<T>
+				);
+
+				impl<T: Config> #frame_support::dispatch::DefaultByte
+					for #default_byte_getter<T>
+				{
+					fn default_byte(&self) -> #frame_support::sp_std::vec::Vec<u8> {
+						let value = <T::#ident as #frame_support::traits::Get<#const_type>>::get();
+						#frame_support::codec::Encode::encode(&value)
+					}
+				}
+
+				unsafe impl<T: Config> Send for #default_byte_getter<T> {}
+				unsafe impl<T: Config> Sync for #default_byte_getter<T> {}
+			)
+		});
+
+	let consts = def.config.consts_metadata.iter()
+		.map(|const_| {
+			let const_type = &const_.type_;
+			let const_type_str = clean_type_string(&quote::quote!(#const_type).to_string());
+			let ident = &const_.ident;
+			let ident_str = format!("{}", ident);
+			let doc = const_.doc.clone().into_iter();
+			let default_byte_getter = default_byte_getter(ident);
+
+			quote::quote!(
+				#frame_support::dispatch::ModuleConstantMetadata {
+					name: #frame_support::dispatch::DecodeDifferent::Encode(#ident_str),
+					ty: #frame_support::dispatch::DecodeDifferent::Encode(#const_type_str),
+					value: #frame_support::dispatch::DecodeDifferent::Encode(
+						#frame_support::dispatch::DefaultByteGetter(
+							&#default_byte_getter::<T>(
+								#frame_support::sp_std::marker::PhantomData
+							)
+						)
+					),
+					documentation: #frame_support::dispatch::DecodeDifferent::Encode(
+						&[ #( #doc ),* ]
+					),
+				}
+			)
+		});
+
+	quote::quote!{
+		impl<T: Config> #pallet_ident<T> {
+
+			#[doc(hidden)]
+			pub fn module_constants_metadata()
+				-> &'static [#frame_support::dispatch::ModuleConstantMetadata]
+			{
+				#( #default_byte_getter_struct_defs )*
+
+				&[ #( #consts ),* ]
+			}
+		}
+	}
+}
@@ -0,0 +1,132 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::pallet::Def;
+
+/// * Add __Ignore variant on Event
+/// * Impl various trait on Event including metadata
+/// * if deposit_event is defined, implement deposit_event on module.
+pub fn expand_event(def: &mut Def) -> proc_macro2::TokenStream {
+	let event = if let Some(event) = &def.event {
+		event
+	} else {
+		return Default::default()
+	};
+
+	let frame_support = &def.frame_support;
+	let frame_system = &def.frame_system;
+	let event_ident = &event.event;
+
+	let (event_use_gen, event_impl_gen) = if event.is_generic {
+		(quote::quote!(<T>), quote::quote!(<T: Config>))
+	} else {
+		(quote::quote!(), quote::quote!())
+	};
+
+	let metadata = event.metadata.iter()
+		.map(|(ident, args, docs)| {
+			let name = format!("{}", ident);
+			quote::quote!(
+				#frame_support::event::EventMetadata {
+					name: #frame_support::event::DecodeDifferent::Encode(#name),
+					arguments: #frame_support::event::DecodeDifferent::Encode(&[
+						#( #args, )*
+					]),
+					documentation: #frame_support::event::DecodeDifferent::Encode(&[
+						#( #docs, )*
+					]),
+				},
+			)
+		});
+
+	let event_item = {
+		let item = &mut def.item.content.as_mut().expect("Checked by def parser").1[event.index];
+		if let syn::Item::Enum(item) = item {
+			item
+		} else {
+			unreachable!("Checked by event parser")
+		}
+	};
+
+	// Phantom data is added for generic event.
+	if event.is_generic {
+		let variant = syn::parse_quote!(
+			#[doc(hidden)]
+			#[codec(skip)]
+			__Ignore(
+				#frame_support::sp_std::marker::PhantomData<(T,)>,
+				#frame_support::Never,
+			)
+		);
+
+		// Push ignore variant at the end.
+		event_item.variants.push(variant);
+	}
+
+	// derive some traits because system event require Clone, FullCodec, Eq, PartialEq and Debug
+	event_item.attrs.push(syn::parse_quote!(
+		#[derive(
+			#frame_support::CloneNoBound,
+			#frame_support::EqNoBound,
+			#frame_support::PartialEqNoBound,
+			#frame_support::RuntimeDebugNoBound,
+			#frame_support::codec::Encode,
+			#frame_support::codec::Decode,
+		)]
+	));
+
+	let deposit_event = if let Some((fn_vis, fn_span)) = &event.deposit_event {
+		let pallet_ident = &def.pallet_struct.pallet;
+		let event_use_gen = &event_use_gen;
+
+		quote::quote_spanned!(*fn_span =>
+			impl<T: Config> #pallet_ident<T> {
+				#fn_vis fn deposit_event(event: #event_ident #event_use_gen) {
+					let event = <
+						<T as Config>::Event as
+						From<#event_ident #event_use_gen>
+					>::from(event);
+
+					let event = <
+						<T as Config>::Event as
+						Into<<T as #frame_system::Config>::Event>
+					>::into(event);
+
+					<#frame_system::Module<T>>::deposit_event(event)
+				}
+			}
+		)
+	} else {
+		Default::default()
+	};
+
+	quote::quote!(
+		#deposit_event
+
+		impl #event_impl_gen From<#event_ident #event_use_gen> for () {
+			fn from(_: #event_ident #event_use_gen) -> () { () }
+		}
+
+		impl #event_impl_gen #event_ident #event_use_gen {
+			#[allow(dead_code)]
+			#[doc(hidden)]
+			pub fn metadata() -> &'static [#frame_support::event::EventMetadata] {
+				&[ #( #metadata )* ]
+			}
+		}
+	)
+}
@@ -0,0 +1,328 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::pallet::Def;
+use crate::pallet::parse::storage::{Metadata, QueryKind};
+use frame_support_procedural_tools::{clean_type_string, bounded_collection_bound};
+
+/// Generate the prefix_ident related the the storage.
+/// prefix_ident is used for the prefix struct to be given to storage as first generic param.
+fn prefix_ident(storage_ident: &syn::Ident) -> syn::Ident {
+	syn::Ident::new(&format!("_GeneratedPrefixForStorage{}", storage_ident), storage_ident.span())
+}
+
+/// * replace the first generic `_` by the generated prefix structure
+/// * generate metadatas
+pub fn expand_storages(def: &mut Def) -> proc_macro2::TokenStream {
+	let frame_support = &def.frame_support;
+	let frame_system = &def.frame_system;
+	let pallet_ident = &def.pallet_struct.pallet;
+
+	// Replace first arg `_` by the generated prefix structure.
+	// Add `#[allow(type_alias_bounds)]`
+	for storage_def in def.storages.iter_mut() {
+		let item = &mut def.item.content.as_mut().expect("Checked by def").1[storage_def.index];
+
+		let typ_item = if let syn::Item::Type(t) = item {
+			t
+		} else {
+			unreachable!("Checked by def");
+		};
+
+		typ_item.attrs.push(syn::parse_quote!(#[allow(type_alias_bounds)]));
+
+		let typ_path = if let syn::Type::Path(p) = &mut *typ_item.ty {
+			p
+		} else {
+			unreachable!("Checked by def");
+		};
+
+		let args = if let syn::PathArguments::AngleBracketed(args) =
+			&mut typ_path.path.segments[0].arguments
+		{
+			args
+		} else {
+			unreachable!("Checked by def");
+		};
+
+		let prefix_ident = prefix_ident(&storage_def.ident);
+		args.args[0] = syn::parse_quote!( #prefix_ident<T> );
+	}
+
+	let mut entries = Vec::new();
+	let mut entries_v13 = Vec::new();
+	for storage in def.storages.iter() {
+		let docs = &storage.docs;
+
+		let ident = &storage.ident;
+		let full_ident = quote::quote!( #ident<T> );
+
+		let metadata_trait = match &storage.metadata {
+			Metadata::Value { .. } =>
+				quote::quote!(#frame_support::storage::types::StorageValueMetadata),
+			Metadata::Map { .. } =>
+				quote::quote!(#frame_support::storage::types::StorageMapMetadata),
+			Metadata::DoubleMap { .. } =>
+				quote::quote!(#frame_support::storage::types::StorageDoubleMapMetadata),
+			Metadata::NMap { .. } =>
+				quote::quote!(#frame_support::storage::types::StorageNMapMetadata),
+		};
+
+		// The variants shared by `StorageEntryType` and `StorageEntryTypeV13`.
+		let common_ty = |entry_type: proc_macro2::TokenStream| match &storage.metadata {
+			Metadata::Value { value } => {
+				let value = clean_type_string(&quote::quote!(#value).to_string());
+				Some(quote::quote!(
+					#frame_support::metadata::#entry_type::Plain(
+						#frame_support::metadata::DecodeDifferent::Encode(#value)
+					)
+				))
+			},
+			Metadata::Map { key, value } => {
+				let value = clean_type_string(&quote::quote!(#value).to_string());
+				let key = clean_type_string(&quote::quote!(#key).to_string());
+				Some(quote::quote!(
+					#frame_support::metadata::#entry_type::Map {
+						hasher: <#full_ident as #metadata_trait>::HASHER,
+						key: #frame_support::metadata::DecodeDifferent::Encode(#key),
+						value: #frame_support::metadata::DecodeDifferent::Encode(#value),
+						unused: false,
+					}
+				))
+			},
+			Metadata::DoubleMap { key1, key2, value } => {
+				let value = clean_type_string(&quote::quote!(#value).to_string());
+				let key1 = clean_type_string(&quote::quote!(#key1).to_string());
+				let key2 = clean_type_string(&quote::quote!(#key2).to_string());
+				Some(quote::quote!(
+					#frame_support::metadata::#entry_type::DoubleMap {
+						hasher: <#full_ident as #metadata_trait>::HASHER1,
+						key2_hasher: <#full_ident as #metadata_trait>::HASHER2,
+						key1: #frame_support::metadata::DecodeDifferent::Encode(#key1),
+						key2: #frame_support::metadata::DecodeDifferent::Encode(#key2),
+						value: #frame_support::metadata::DecodeDifferent::Encode(#value),
+					}
+				))
+			},
+			Metadata::NMap { .. } => None,
+		};
+
+		let ty_v13 = match &storage.metadata {
+			Metadata::NMap { keys, value, .. } => {
+				let keys = keys
+					.iter()
+					.map(|key| clean_type_string(&quote::quote!(#key).to_string()))
+					.collect::<Vec<_>>();
+				let value = clean_type_string(&quote::quote!(#value).to_string());
+				quote::quote!(
+					#frame_support::metadata::StorageEntryTypeV13::NMap {
+						keys: #frame_support::metadata::DecodeDifferent::Encode(&[
+							#( #keys, )*
+						]),
+						hashers: #frame_support::metadata::DecodeDifferent::Encode(
+							<#full_ident as #metadata_trait>::HASHERS,
+						),
+						value: #frame_support::metadata::DecodeDifferent::Encode(#value),
+					}
+				)
+			},
+			_ => common_ty(quote::quote!(StorageEntryTypeV13))
+				.expect("Only `NMap` has no common type; qed"),
+		};
+
+		let value = match &storage.metadata {
+			Metadata::Value { value } | Metadata::Map { value, .. }
+				| Metadata::DoubleMap { value, .. } | Metadata::NMap { value, .. } => value,
+		};
+		let bound = match value {
+			syn::GenericArgument::Type(value) => bounded_collection_bound(value),
+			_ => None,
+		};
+		let value_bound = match bound {
+			Some(bound) => quote::quote!( #frame_support::metadata::value_bound::<#bound> ),
+			None => quote::quote!( #frame_support::metadata::no_value_bound ),
+		};
+
+		let entry_fields = quote::quote!(
+			name: #frame_support::metadata::DecodeDifferent::Encode(
+				<#full_ident as #metadata_trait>::NAME
+			),
+			modifier: <#full_ident as #metadata_trait>::MODIFIER,
+			default: #frame_support::metadata::DecodeDifferent::Encode(
+				<#full_ident as #metadata_trait>::DEFAULT
+			),
+			documentation: #frame_support::metadata::DecodeDifferent::Encode(&[
+				#( #docs, )*
+			]),
+		);
+
+		// Maps with an arbitrary number of keys can't be described in version 12 of the metadata.
+		if let Some(ty) = common_ty(quote::quote!(StorageEntryType)) {
+			entries.push(quote::quote!(
+				#frame_support::metadata::StorageEntryMetadata {
+					#entry_fields
+					ty: #ty,
+				}
+			));
+		}
+		entries_v13.push(quote::quote!(
+			#frame_support::metadata::StorageEntryMetadataV13 {
+				#entry_fields
+				ty: #ty_v13,
+				value_bound: #frame_support::metadata::DecodeDifferent::Encode(
+					#frame_support::metadata::FnEncode(#value_bound)
+				),
+			}
+		));
+	}
+
+	let getters = def.storages.iter()
+		.map(|storage| if let Some(getter) = &storage.getter {
+			let docs = storage.docs.iter().map(|doc| quote::quote!( #[doc = #doc] ));
+			let ident = &storage.ident;
+			let full_ident = quote::quote!( #ident<T> );
+
+			match &storage.metadata {
+				Metadata::Value { value } => {
+					let query = match storage.query_kind.as_ref().expect("Checked by def") {
+						QueryKind::OptionQuery => quote::quote!(Option<#value>),
+						QueryKind::ValueQuery => quote::quote!(#value),
+					};
+					quote::quote!(
+						#( #docs )*
+						pub fn #getter() -> #query {
+							<#full_ident>::get()
+						}
+					)
+				},
+				Metadata::Map { key, value } => {
+					let query = match storage.query_kind.as_ref().expect("Checked by def") {
+						QueryKind::OptionQuery => quote::quote!(Option<#value>),
+						QueryKind::ValueQuery => quote::quote!(#value),
+					};
+					quote::quote!(
+						#( #docs )*
+						pub fn #getter<KArg>(k: KArg) -> #query where
+							KArg: #frame_support::codec::EncodeLike<#key>,
+						{
+							<#full_ident>::get(k)
+						}
+					)
+				},
+				Metadata::DoubleMap { key1, key2, value } => {
+					let query = match storage.query_kind.as_ref().expect("Checked by def") {
+						QueryKind::OptionQuery => quote::quote!(Option<#value>),
+						QueryKind::ValueQuery => quote::quote!(#value),
+					};
+					quote::quote!(
+						#( #docs )*
+						pub fn #getter<KArg1, KArg2>(k1: KArg1, k2: KArg2) -> #query where
+							KArg1: #frame_support::codec::EncodeLike<#key1>,
+							KArg2: #frame_support::codec::EncodeLike<#key2>,
+						{
+							<#full_ident>::get(k1, k2)
+						}
+					)
+				},
+				Metadata::NMap { keygen, value, .. } => {
+					let query = match storage.query_kind.as_ref().expect("Checked by def") {
+						QueryKind::OptionQuery => quote::quote!(Option<#value>),
+						QueryKind::ValueQuery => quote::quote!(#value),
+					};
+					quote::quote!(
+						#( #docs )*
+						pub fn #getter<KArg>(key: KArg) -> #query where
+							KArg: #frame_support::storage::types::EncodeLikeTuple<
+								<#keygen as #frame_support::storage::types::KeyGenerator>::KArg
+							>
+								+ #frame_support::storage::types::TupleToEncodedIter,
+						{
+							<#full_ident>::get(key)
+						}
+					)
+				},
+			}
+		} else {
+			Default::default()
+		});
+
+	let prefix_structs = def.storages.iter().map(|storage_def| {
+		let prefix_struct_ident = prefix_ident(&storage_def.ident);
+		let prefix_struct_vis = &storage_def.vis;
+		let prefix_struct_const = storage_def.ident.to_string();
+
+		quote::quote!(
+			#prefix_struct_vis struct #prefix_struct_ident<T>(
+				#frame_support::sp_std::marker::PhantomData<(T,)>
+			);
+			impl<T: Config> #frame_support::traits::StorageInstance
+				for #prefix_struct_ident<T>
+			{
+				fn pallet_prefix() -> &'static str {
+					<
+						<T as #frame_system::Config>::PalletInfo
+						as #frame_support::traits::PalletInfo
+					>::name::<#pallet_ident<T>>()
+						.expect("Every active pallet has a name in the runtime; qed")
+				}
+				const STORAGE_PREFIX: &'static str = #prefix_struct_const;
+			}
+		)
+	});
+
+	quote::quote!(
+		impl<T: Config> #pallet_ident<T> {
+			#[doc(hidden)]
+			pub fn storage_metadata() -> #frame_support::metadata::StorageMetadata {
+				#frame_support::metadata::StorageMetadata {
+					prefix: #frame_support::metadata::DecodeDifferent::Encode(
+						<
+							<T as #frame_system::Config>::PalletInfo as
+							#frame_support::traits::PalletInfo
+						>::name::<#pallet_ident<T>>()
+							.expect("Every active pallet has a name in the runtime; qed")
+					),
+					entries: #frame_support::metadata::DecodeDifferent::Encode(
+						&[ #( #entries, )* ]
+					),
+				}
+			}
+
+			#[doc(hidden)]
+			pub fn storage_metadata_v13() -> #frame_support::metadata::StorageMetadataV13 {
+				#frame_support::metadata::StorageMetadataV13 {
+					prefix: #frame_support::metadata::DecodeDifferent::Encode(
+						<
+							<T as #frame_system::Config>::PalletInfo as
+							#frame_support::traits::PalletInfo
+						>::name::<#pallet_ident<T>>()
+							.expect("Every active pallet has a name in the runtime; qed")
+					),
+					entries: #frame_support::metadata::DecodeDifferent::Encode(
+						&[ #( #entries_v13, )* ]
+					),
+				}
+			}
+		}
+
+		impl<T: Config> #pallet_ident<T> {
+			#( #getters )*
+		}
+
+		#( #prefix_structs )*
+	)
+}
@@ -0,0 +1,225 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::helper;
+use syn::spanned::Spanned;
+use quote::ToTokens;
+
+/// List of additional token to be used for parsing.
+mod keyword {
+	syn::custom_keyword!(Config);
+	syn::custom_keyword!(constant);
+}
+
+/// Input definition for the pallet config.
+pub struct ConfigDef {
+	/// The index of item in pallet module.
+	pub index: usize,
+	/// Whether the trait has the associated type `Event`, note that those bounds are checked:
+	/// * `IsType<Self as frame_system::Config>::Event`
+	/// * `From<Event>` or `From<Event<T>>`
+	pub has_event_type: bool,
+	/// Const associated type.
+	pub consts_metadata: Vec<ConstMetadataDef>,
+}
+
+/// Input definition for a constant in pallet config.
+pub struct ConstMetadataDef {
+	/// Name of the associated type.
+	pub ident: syn::Ident,
+	/// The type in Get, e.g. `u32` in `type Foo: Get<u32>;`, but `Self` is replaced by `T`
+	pub type_: syn::Type,
+	/// The doc associated
+	pub doc: Vec<syn::Lit>,
+}
+
+impl syn::parse::Parse for ConstMetadataDef {
+	fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+		let doc = helper::get_doc_literals(&syn::Attribute::parse_outer(input)?);
+		input.parse::<syn::Token![type]>()?;
+		let ident = input.parse::<syn::Ident>()?;
+		input.parse::<syn::Token![:]>()?;
+		let path = input.parse::<syn::Path>()?;
+		input.parse::<syn::Token![;]>()?;
+
+		let expected_get = || syn::Error::new(path.span(), "expected `Get<$SomeType>`");
+		let segment = path.segments.last()
+			.filter(|segment| segment.ident == "Get")
+			.ok_or_else(expected_get)?;
+		let type_ = match &segment.arguments {
+			syn::PathArguments::AngleBracketed(args) if args.args.len() == 1 => {
+				match &args.args[0] {
+					syn::GenericArgument::Type(type_) => type_,
+					_ => return Err(expected_get()),
+				}
+			},
+			_ => return Err(expected_get()),
+		};
+		let type_ = syn::parse2::<syn::Type>(helper::replace_self_by_t(type_.to_token_stream()))
+			.expect("Internal error: replacing `Self` by `T` should result in valid type");
+
+		Ok(Self { ident, type_, doc })
+	}
+}
+
+/// Parse for `#[pallet::constant]`
+pub struct TypeAttrConst;
+
+impl syn::parse::Parse for TypeAttrConst {
+	fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+		input.parse::<syn::Token![#]>()?;
+		let content;
+		syn::bracketed!(content in input);
+		content.parse::<syn::Ident>()?;
+		content.parse::<syn::Token![::]>()?;
+		content.parse::<keyword::constant>()?;
+
+		Ok(Self)
+	}
+}
+
+/// Parse for `frame_system::Config` where `frame_system` is the resolved crate name.
+pub struct ConfigBoundParse(syn::Ident);
+
+impl syn::parse::Parse for ConfigBoundParse {
+	fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+		let ident = input.parse::<syn::Ident>()?;
+		input.parse::<syn::Token![::]>()?;
+		input.parse::<keyword::Config>()?;
+
+		Ok(Self(ident))
+	}
+}
+
+/// Check that the bounds of the `Event` associated type contain a bound whose last path segment
+/// is the given name.
+fn has_bound(type_: &syn::TraitItemType, name: &str) -> bool {
+	type_.bounds.iter().any(|bound| match bound {
+		syn::TypeParamBound::Trait(bound) =>
+			bound.path.segments.last().map_or(false, |segment| segment.ident == name),
+		_ => false,
+	})
+}
+
+impl ConfigDef {
+	pub fn try_from(
+		frame_system: &syn::Ident,
+		attr_span: proc_macro2::Span,
+		index: usize,
+		item: &mut syn::Item,
+	) -> syn::Result<Self> {
+		let item = if let syn::Item::Trait(item) = item {
+			item
+		} else {
+			let msg = "Invalid pallet::config, expected trait definition";
+			return Err(syn::Error::new(item.span(), msg));
+		};
+
+		if !matches!(item.vis, syn::Visibility::Public(_)) {
+			let msg = "Invalid pallet::config, trait must be public";
+			return Err(syn::Error::new(item.span(), msg));
+		}
+
+		syn::parse2::<keyword::Config>(item.ident.to_token_stream())?;
+
+		if !item.generics.params.is_empty() || item.generics.where_clause.is_some() {
+			let msg = "Invalid pallet::config, expected no generics nor where clause, \
+				instantiable pallets are not supported by the pallet macro";
+			return Err(syn::Error::new(item.generics.span(), msg));
+		}
+
+		let has_frame_system_supertrait = item.supertraits.iter().any(|s| {
+			syn::parse2::<ConfigBoundParse>(s.to_token_stream())
+				.map_or(false, |b| b.0 == *frame_system)
+		});
+
+		if !has_frame_system_supertrait {
+			let found = if item.supertraits.is_empty() {
+				"none".to_string()
+			} else {
+				let mut found = item.supertraits.iter()
+					.fold(String::new(), |acc, s| format!("{}`{}`, ", acc, quote::quote!(#s)));
+				found.pop();
+				found.pop();
+				found
+			};
+
+			let msg = format!(
+				"Invalid pallet::trait, expected explicit `{}::Config` as supertrait, \
+				found {}. \
+				(try `pub trait Config: frame_system::Config {{ ...`)",
+				frame_system,
+				found,
+			);
+			return Err(syn::Error::new(attr_span, msg));
+		}
+
+		let mut has_event_type = false;
+		let mut consts_metadata = vec![];
+		for trait_item in &mut item.items {
+			if let syn::TraitItem::Type(type_) = trait_item {
+				if type_.ident == "Event" {
+					if !has_bound(type_, "From") || !has_bound(type_, "IsType") {
+						let msg = format!(
+							"Invalid `type Event`, associated type `Event` is reserved and must \
+							bound: `From<Event>` or `From<Event<Self>>`, and \
+							`IsType<<Self as {}::Config>::Event>`",
+							frame_system,
+						);
+						return Err(syn::Error::new(type_.span(), msg));
+					}
+					has_event_type = true;
+				}
+			}
+
+			let type_attrs_const: Vec<TypeAttrConst> = helper::take_item_attrs(trait_item)?;
+
+			if type_attrs_const.len() > 1 {
+				let msg = "Invalid attribute in pallet::config, only one attribute is expected";
+				return Err(syn::Error::new(trait_item.span(), msg));
+			}
+
+			if type_attrs_const.len() == 1 {
+				match trait_item {
+					syn::TraitItem::Type(type_) => {
+						let constant = syn::parse2::<ConstMetadataDef>(type_.to_token_stream())
+							.map_err(|e| {
+								let error_msg = "Invalid usage of `#[pallet::constant]`, syntax \
+									must be `type $SomeIdent: Get<$SomeType>;`";
+								let mut err = syn::Error::new(type_.span(), error_msg);
+								err.combine(e);
+								err
+							})?;
+
+						consts_metadata.push(constant);
+					},
+					_ => {
+						let msg = "Invalid pallet::constant in pallet::config, expected type trait \
+							item";
+						return Err(syn::Error::new(trait_item.span(), msg));
+					},
+				}
+			}
+		}
+
+		Ok(Self {
+			index,
+			has_event_type,
+			consts_metadata,
+		})
+	}
+}
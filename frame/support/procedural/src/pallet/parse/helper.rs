@@ -0,0 +1,330 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use syn::spanned::Spanned;
+use quote::ToTokens;
+
+/// List of additional token to be used for parsing.
+mod keyword {
+	syn::custom_keyword!(Config);
+	syn::custom_keyword!(T);
+	syn::custom_keyword!(Pallet);
+}
+
+/// Trait implemented for syn items to get mutable references on their attributes.
+///
+/// NOTE: verbatim variants are not supported.
+pub trait MutItemAttrs {
+	fn mut_item_attrs(&mut self) -> Option<&mut Vec<syn::Attribute>>;
+}
+
+/// Take the first pallet attribute (e.g. attribute like `#[pallet..]`) and decode it to `Attr`
+pub fn take_first_item_attr<Attr>(item: &mut impl MutItemAttrs) -> syn::Result<Option<Attr>> where
+	Attr: syn::parse::Parse,
+{
+	let attrs = if let Some(attrs) = item.mut_item_attrs() {
+		attrs
+	} else {
+		return Ok(None)
+	};
+
+	if let Some(index) = attrs.iter()
+		.position(|attr|
+			attr.path.segments.first().map_or(false, |segment| segment.ident == "pallet")
+		)
+	{
+		let pallet_attr = attrs.remove(index);
+		Ok(Some(syn::parse2(pallet_attr.into_token_stream())?))
+	} else {
+		Ok(None)
+	}
+}
+
+/// Take all the pallet attributes (e.g. attribute like `#[pallet..]`) and decode them to `Attr`
+pub fn take_item_attrs<Attr>(item: &mut impl MutItemAttrs) -> syn::Result<Vec<Attr>> where
+	Attr: syn::parse::Parse,
+{
+	let mut pallet_attrs = Vec::new();
+
+	while let Some(attr) = take_first_item_attr(item)? {
+		pallet_attrs.push(attr)
+	}
+
+	Ok(pallet_attrs)
+}
+
+impl MutItemAttrs for syn::Item {
+	fn mut_item_attrs(&mut self) -> Option<&mut Vec<syn::Attribute>> {
+		match self {
+			Self::Const(item) => Some(item.attrs.as_mut()),
+			Self::Enum(item) => Some(item.attrs.as_mut()),
+			Self::ExternCrate(item) => Some(item.attrs.as_mut()),
+			Self::Fn(item) => Some(item.attrs.as_mut()),
+			Self::ForeignMod(item) => Some(item.attrs.as_mut()),
+			Self::Impl(item) => Some(item.attrs.as_mut()),
+			Self::Macro(item) => Some(item.attrs.as_mut()),
+			Self::Macro2(item) => Some(item.attrs.as_mut()),
+			Self::Mod(item) => Some(item.attrs.as_mut()),
+			Self::Static(item) => Some(item.attrs.as_mut()),
+			Self::Struct(item) => Some(item.attrs.as_mut()),
+			Self::Trait(item) => Some(item.attrs.as_mut()),
+			Self::TraitAlias(item) => Some(item.attrs.as_mut()),
+			Self::Type(item) => Some(item.attrs.as_mut()),
+			Self::Union(item) => Some(item.attrs.as_mut()),
+			Self::Use(item) => Some(item.attrs.as_mut()),
+			_ => None,
+		}
+	}
+}
+
+impl MutItemAttrs for syn::TraitItem {
+	fn mut_item_attrs(&mut self) -> Option<&mut Vec<syn::Attribute>> {
+		match self {
+			Self::Const(item) => Some(item.attrs.as_mut()),
+			Self::Method(item) => Some(item.attrs.as_mut()),
+			Self::Type(item) => Some(item.attrs.as_mut()),
+			Self::Macro(item) => Some(item.attrs.as_mut()),
+			_ => None,
+		}
+	}
+}
+
+impl MutItemAttrs for Vec<syn::Attribute> {
+	fn mut_item_attrs(&mut self) -> Option<&mut Vec<syn::Attribute>> {
+		Some(self)
+	}
+}
+
+/// Return all doc attributes literals found.
+pub fn get_doc_literals(attrs: &Vec<syn::Attribute>) -> Vec<syn::Lit> {
+	attrs.iter()
+		.filter_map(|attr| {
+			if let Ok(syn::Meta::NameValue(meta)) = attr.parse_meta() {
+				if meta.path.get_ident().map_or(false, |ident| ident == "doc") {
+					Some(meta.lit)
+				} else {
+					None
+				}
+			} else {
+				None
+			}
+		})
+		.collect()
+}
+
+/// Replace all occurrences of `Self` by `T` in the given tokens.
+///
+/// Used to convert types written in the `Config` trait (e.g. `Self::AccountId`) to types usable
+/// in implementations generic over `T`.
+pub fn replace_self_by_t(input: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+	input.into_iter()
+		.map(|token_tree| match token_tree {
+			proc_macro2::TokenTree::Group(group) =>
+				proc_macro2::Group::new(
+					group.delimiter(),
+					replace_self_by_t(group.stream())
+				).into(),
+			proc_macro2::TokenTree::Ident(ident) if ident == "Self" =>
+				proc_macro2::Ident::new("T", ident.span()).into(),
+			other => other
+		})
+		.collect()
+}
+
+/// Parse for `<T: Config>`.
+struct ImplGen;
+
+impl syn::parse::Parse for ImplGen {
+	fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+		input.parse::<syn::Token![<]>()?;
+		input.parse::<keyword::T>()?;
+		input.parse::<syn::Token![:]>()?;
+		input.parse::<keyword::Config>()?;
+		input.parse::<syn::Token![>]>()?;
+		Ok(Self)
+	}
+}
+
+/// Parse for `<T>` or `<T: Config>`.
+struct TypeDefGen;
+
+impl syn::parse::Parse for TypeDefGen {
+	fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+		input.parse::<syn::Token![<]>()?;
+		input.parse::<keyword::T>()?;
+		if input.peek(syn::Token![:]) {
+			input.parse::<syn::Token![:]>()?;
+			input.parse::<keyword::Config>()?;
+		}
+		input.parse::<syn::Token![>]>()?;
+		Ok(Self)
+	}
+}
+
+/// Parse for `<T>`.
+struct TypeDefGenNoBounds;
+
+impl syn::parse::Parse for TypeDefGenNoBounds {
+	fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+		input.parse::<syn::Token![<]>()?;
+		input.parse::<keyword::T>()?;
+		input.parse::<syn::Token![>]>()?;
+		Ok(Self)
+	}
+}
+
+/// Check that no where clause is used, where clauses are not supported on pallet items.
+fn check_no_where_clause(gen: &syn::Generics) -> syn::Result<()> {
+	if let Some(where_clause) = &gen.where_clause {
+		let msg = "Invalid pallet item, where clauses are not supported on pallet items";
+		return Err(syn::Error::new(where_clause.span(), msg));
+	}
+
+	Ok(())
+}
+
+/// Check the syntax: `<T: Config>`
+///
+/// `span` is used in case generics is empty (empty generics has span == call_site).
+pub fn check_impl_gen(gen: &syn::Generics, span: proc_macro2::Span) -> syn::Result<()> {
+	check_no_where_clause(gen)?;
+
+	syn::parse2::<ImplGen>(gen.to_token_stream())
+		.map_err(|e| {
+			let msg = "Invalid generics: expected `T: Config`";
+			let mut err = syn::Error::new(span, msg);
+			err.combine(e);
+			err
+		})?;
+
+	Ok(())
+}
+
+/// Check the syntax:
+/// * either `` (no generics)
+/// * or `<T>`
+/// * or `<T: Config>`
+///
+/// return whether the type is generic over `T`.
+///
+/// `span` is used in case generics is empty (empty generics has span == call_site).
+pub fn check_type_def_optional_gen(
+	gen: &syn::Generics,
+	span: proc_macro2::Span,
+) -> syn::Result<bool> {
+	check_no_where_clause(gen)?;
+
+	if gen.params.is_empty() {
+		return Ok(false)
+	}
+
+	syn::parse2::<TypeDefGen>(gen.to_token_stream())
+		.map_err(|e| {
+			let msg = "Invalid type def generics: expected nothing, or `T` or `T: Config`";
+			let mut err = syn::Error::new(span, msg);
+			err.combine(e);
+			err
+		})?;
+
+	Ok(true)
+}
+
+/// Check the syntax:
+/// * either `<T>`
+/// * or `<T: Config>`
+///
+/// `span` is used in case generics is empty (empty generics has span == call_site).
+pub fn check_type_def_gen(gen: &syn::Generics, span: proc_macro2::Span) -> syn::Result<()> {
+	check_no_where_clause(gen)?;
+
+	syn::parse2::<TypeDefGen>(gen.to_token_stream())
+		.map_err(|e| {
+			let msg = "Invalid type def generics: expected `T` or `T: Config`";
+			let mut err = syn::Error::new(span, msg);
+			err.combine(e);
+			err
+		})?;
+
+	Ok(())
+}
+
+/// Check the syntax: `<T>`
+///
+/// `span` is used in case generics is empty (empty generics has span == call_site).
+pub fn check_type_def_gen_no_bounds(
+	gen: &syn::Generics,
+	span: proc_macro2::Span,
+) -> syn::Result<()> {
+	check_no_where_clause(gen)?;
+
+	syn::parse2::<TypeDefGenNoBounds>(gen.to_token_stream())
+		.map_err(|e| {
+			let msg = "Invalid type def generics: expected `T`";
+			let mut err = syn::Error::new(span, msg);
+			err.combine(e);
+			err
+		})?;
+
+	Ok(())
+}
+
+/// Check the syntax:
+/// * either `` (no generics)
+/// * or `<T: Config>`
+///
+/// return whether the item is generic over `T`.
+///
+/// `span` is used in case generics is empty (empty generics has span == call_site).
+pub fn check_optional_impl_gen(
+	gen: &syn::Generics,
+	span: proc_macro2::Span,
+) -> syn::Result<bool> {
+	check_no_where_clause(gen)?;
+
+	if gen.params.is_empty() {
+		return Ok(false)
+	}
+
+	check_impl_gen(gen, span)?;
+
+	Ok(true)
+}
+
+/// Check the syntax: `Pallet<T>`
+pub fn check_pallet_struct_usage(type_: &Box<syn::Type>) -> syn::Result<()> {
+	pub struct Checker;
+	impl syn::parse::Parse for Checker {
+		fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+			input.parse::<keyword::Pallet>()?;
+			input.parse::<syn::Token![<]>()?;
+			input.parse::<keyword::T>()?;
+			input.parse::<syn::Token![>]>()?;
+
+			Ok(Self)
+		}
+	}
+
+	syn::parse2::<Checker>(type_.to_token_stream())
+		.map_err(|e| {
+			let msg = "Invalid pallet struct: expected `Pallet<T>`";
+			let mut err = syn::Error::new(type_.span(), msg);
+			err.combine(e);
+			err
+		})?;
+
+	Ok(())
+}
@@ -0,0 +1,61 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::helper;
+use syn::spanned::Spanned;
+
+/// Definition of the pallet origin type.
+///
+/// Either:
+/// * `type Origin`
+/// * `struct Origin`
+/// * `enum Origin`
+pub struct OriginDef {
+	/// The index of item in pallet module.
+	pub index: usize,
+	/// Whether the origin is generic over `T`.
+	pub is_generic: bool,
+}
+
+impl OriginDef {
+	pub fn try_from(index: usize, item: &mut syn::Item) -> syn::Result<Self> {
+		let item_span = item.span();
+		let (vis, ident, generics) = match &item {
+			syn::Item::Enum(item) => (&item.vis, &item.ident, &item.generics),
+			syn::Item::Struct(item) => (&item.vis, &item.ident, &item.generics),
+			syn::Item::Type(item) => (&item.vis, &item.ident, &item.generics),
+			_ => {
+				let msg = "Invalid pallet::origin, expected enum or struct or type";
+				return Err(syn::Error::new(item.span(), msg));
+			},
+		};
+
+		let is_generic = helper::check_type_def_optional_gen(generics, ident.span())?;
+
+		if !matches!(vis, syn::Visibility::Public(_)) {
+			let msg = "Invalid pallet::origin, Origin must be public";
+			return Err(syn::Error::new(item_span, msg));
+		}
+
+		if ident != "Origin" {
+			let msg = "Invalid pallet::origin, ident must `Origin`";
+			return Err(syn::Error::new(ident.span(), msg));
+		}
+
+		Ok(OriginDef { index, is_generic })
+	}
+}
@@ -17,18 +17,22 @@
 
 //! Implementation of `storage_metadata` on module structure, used by construct_runtime.
 
-use frame_support_procedural_tools::clean_type_string;
+use frame_support_procedural_tools::{clean_type_string, bounded_collection_bound};
 use proc_macro2::TokenStream;
 use quote::quote;
 use super::{DeclStorageDefExt, StorageLineDefExt, StorageLineTypeDef};
 
-fn storage_line_metadata_type(scrate: &TokenStream, line: &StorageLineDefExt) -> TokenStream {
+fn storage_line_metadata_type(
+	scrate: &TokenStream,
+	line: &StorageLineDefExt,
+	entry_type: &syn::Ident,
+) -> TokenStream {
 	let value_type = &line.value_type;
 	let value_type = clean_type_string(&quote!( #value_type ).to_string());
 	match &line.storage_type {
 		StorageLineTypeDef::Simple(_) => {
 			quote!{
-				#scrate::metadata::StorageEntryType::Plain(
+				#scrate::metadata::#entry_type::Plain(
 					#scrate::metadata::DecodeDifferent::Encode(#value_type),
 				)
 			}
@@ -38,7 +42,7 @@ fn storage_line_metadata_type(scrate: &TokenStream, line: &StorageLineDefExt) ->
 			let key = &map.key;
 			let key = clean_type_string(&quote!(#key).to_string());
 			quote!{
-				#scrate::metadata::StorageEntryType::Map {
+				#scrate::metadata::#entry_type::Map {
 					hasher: #scrate::metadata::#hasher,
 					key: #scrate::metadata::DecodeDifferent::Encode(#key),
 					value: #scrate::metadata::DecodeDifferent::Encode(#value_type),
@@ -54,7 +58,7 @@ fn storage_line_metadata_type(scrate: &TokenStream, line: &StorageLineDefExt) ->
 			let key2 = &map.key2;
 			let key2 = clean_type_string(&quote!(#key2).to_string());
 			quote!{
-				#scrate::metadata::StorageEntryType::DoubleMap {
+				#scrate::metadata::#entry_type::DoubleMap {
 					hasher: #scrate::metadata::#hasher1,
 					key1: #scrate::metadata::DecodeDifferent::Encode(#key1),
 					key2: #scrate::metadata::DecodeDifferent::Encode(#key2),
@@ -142,7 +146,10 @@ fn default_byte_getter(
 
 pub fn impl_metadata(scrate: &TokenStream, def: &DeclStorageDefExt) -> TokenStream {
 	let mut entries = TokenStream::new();
+	let mut entries_v13 = TokenStream::new();
 	let mut default_byte_getter_struct_defs = TokenStream::new();
+	let entry_type = syn::Ident::new("StorageEntryType", proc_macro2::Span::call_site());
+	let entry_type_v13 = syn::Ident::new("StorageEntryTypeV13", proc_macro2::Span::call_site());
 
 	for line in def.storage_lines.iter() {
 		let str_name = line.name.to_string();
@@ -153,7 +160,12 @@ pub fn impl_metadata(scrate: &TokenStream, def: &DeclStorageDefExt) -> TokenStre
 			quote!(#scrate::metadata::StorageEntryModifier::Default)
 		};
 
-		let ty = storage_line_metadata_type(scrate, line);
+		let ty = storage_line_metadata_type(scrate, line, &entry_type);
+		let ty_v13 = storage_line_metadata_type(scrate, line, &entry_type_v13);
+		let value_bound = match bounded_collection_bound(&line.value_type) {
+			Some(bound) => quote!( #scrate::metadata::value_bound::<#bound> ),
+			None => quote!( #scrate::metadata::no_value_bound ),
+		};
 
 		let (
 			default_byte_getter_struct_def,
@@ -182,8 +194,24 @@ pub fn impl_metadata(scrate: &TokenStream, def: &DeclStorageDefExt) -> TokenStre
 			},
 		};
 
+		let entry_v13 = quote! {
+			#scrate::metadata::StorageEntryMetadataV13 {
+				name: #scrate::metadata::DecodeDifferent::Encode(#str_name),
+				modifier: #modifier,
+				ty: #ty_v13,
+				default: #scrate::metadata::DecodeDifferent::Encode(
+					#scrate::metadata::DefaultByteGetter(&#default_byte_getter_struct_instance)
+				),
+				documentation: #scrate::metadata::DecodeDifferent::Encode(&[ #docs ]),
+				value_bound: #scrate::metadata::DecodeDifferent::Encode(
+					#scrate::metadata::FnEncode(#value_bound)
+				),
+			},
+		};
+
 		default_byte_getter_struct_defs.extend(default_byte_getter_struct_def);
 		entries.extend(entry);
+		entries_v13.extend(entry_v13);
 	}
 
 	let prefix = if let Some(instance) = &def.module_instance {
@@ -201,6 +229,13 @@ pub fn impl_metadata(scrate: &TokenStream, def: &DeclStorageDefExt) -> TokenStre
 		}
 	);
 
+	let store_metadata_v13 = quote!(
+		#scrate::metadata::StorageMetadataV13 {
+			prefix: #scrate::metadata::DecodeDifferent::Encode(#prefix),
+			entries: #scrate::metadata::DecodeDifferent::Encode(&[ #entries_v13 ][..]),
+		}
+	);
+
 	let module_struct = &def.module_struct;
 	let module_impl = &def.module_impl;
 	let where_clause = &def.where_clause;
@@ -213,6 +248,11 @@ pub fn impl_metadata(scrate: &TokenStream, def: &DeclStorageDefExt) -> TokenStre
 			pub fn storage_metadata() -> #scrate::metadata::StorageMetadata {
 				#store_metadata
 			}
+
+			#[doc(hidden)]
+			pub fn storage_metadata_v13() -> #scrate::metadata::StorageMetadataV13 {
+				#store_metadata_v13
+			}
 		}
 	)
 }
@@ -23,7 +23,7 @@ use frame_support_procedural_tools::generate_crate_access_2018;
 pub fn transactional(_attr: TokenStream, input: TokenStream) -> Result<TokenStream> {
 	let ItemFn { attrs, vis, sig, block } = syn::parse(input)?;
 
-	let crate_ = generate_crate_access_2018()?;
+	let crate_ = generate_crate_access_2018("frame-support")?;
 	let output = quote! {
 		#(#attrs)*
 		#vis #sig {
@@ -45,7 +45,7 @@ pub fn transactional(_attr: TokenStream, input: TokenStream) -> Result<TokenStre
 pub fn require_transactional(_attr: TokenStream, input: TokenStream) -> Result<TokenStream> {
 	let ItemFn { attrs, vis, sig, block } = syn::parse(input)?;
 
-	let crate_ = generate_crate_access_2018()?;
+	let crate_ = generate_crate_access_2018("frame-support")?;
 	let output = quote! {
 		#(#attrs)*
 		#vis #sig {
@@ -46,17 +46,17 @@ pub fn generate_crate_access(unique_id: &str, def_crate: &str) -> TokenStream {
 	}
 }
 
-/// Generate the crate access for the `frame-support` crate using 2018 syntax.
+/// Generate the crate access for the crate using 2018 syntax.
 ///
-/// Output will for example be `frame_support`.
-pub fn generate_crate_access_2018() -> Result<TokenStream, Error> {
-	if std::env::var("CARGO_PKG_NAME").unwrap() == "frame-support" {
-		Ok(quote::quote!( frame_support ))
+/// for `frame-support` output will for example be `frame_support`.
+pub fn generate_crate_access_2018(def_crate: &str) -> Result<syn::Ident, Error> {
+	if std::env::var("CARGO_PKG_NAME").unwrap() == def_crate {
+		let name = def_crate.to_string().replace("-", "_");
+		Ok(syn::Ident::new(&name, Span::call_site()))
 	} else {
-		match crate_name("frame-support") {
+		match crate_name(def_crate) {
 			Ok(name) => {
-				let name = Ident::new(&name, Span::call_site());
-				Ok(quote!( #name ))
+				Ok(Ident::new(&name, Span::call_site()))
 			},
 			Err(e) => {
 				Err(Error::new(Span::call_site(), &e))
@@ -93,6 +93,31 @@ pub fn generate_hidden_includes(unique_id: &str, def_crate: &str) -> TokenStream
 
 // fn to remove white spaces around string types
 // (basically whitespaces around tokens)
+/// Returns the bound `S` of a `BoundedVec<_, S>` or `BoundedBTreeMap<_, _, S>` type.
+///
+/// The type is only matched by the name of its last path segment, so aliases of bounded
+/// collections are not recognized.
+pub fn bounded_collection_bound(ty: &syn::Type) -> Option<&syn::Type> {
+	let segment = match ty {
+		syn::Type::Path(path) => path.path.segments.last()?,
+		_ => return None,
+	};
+	let arg_count = match segment.ident.to_string().as_str() {
+		"BoundedVec" => 2,
+		"BoundedBTreeMap" => 3,
+		_ => return None,
+	};
+	match &segment.arguments {
+		syn::PathArguments::AngleBracketed(args) if args.args.len() == arg_count => {
+			match args.args.last()? {
+				syn::GenericArgument::Type(bound) => Some(bound),
+				_ => None,
+			}
+		},
+		_ => None,
+	}
+}
+
 pub fn clean_type_string(input: &str) -> String {
 	input
 		.replace(" ::", "::")
@@ -22,7 +22,8 @@ pub use crate::sp_std::{result, fmt, prelude::{Vec, Clone, Eq, PartialEq}, marke
 pub use crate::codec::{Codec, EncodeLike, Decode, Encode, Input, Output, HasCompact, EncodeAsRef};
 pub use frame_metadata::{
 	FunctionMetadata, DecodeDifferent, DecodeDifferentArray, FunctionArgumentMetadata,
-	ModuleConstantMetadata, DefaultByte, DefaultByteGetter, ModuleErrorMetadata, ErrorMetadata
+	ModuleConstantMetadata, DefaultByte, DefaultByteGetter, ModuleErrorMetadata, ErrorMetadata,
+	FunctionMetadataV13, FunctionDispatchMetadata, DispatchClassMetadata,
 };
 pub use crate::weights::{
 	GetDispatchInfo, DispatchInfo, WeighData, ClassifyDispatch, TransactionPriority, Weight,
@@ -64,6 +65,42 @@ pub type CallableCallFor<A, R> = <A as Callable<R>>::Call;
 pub trait Parameter: Codec + EncodeLike + Clone + Eq + fmt::Debug {}
 impl<T> Parameter for T where T: Codec + EncodeLike + Clone + Eq + fmt::Debug {}
 
+/// Extend the metadata of the functions of `Call` with their dispatch information.
+///
+/// The dispatch information of each function is computed with all its arguments decoded from
+/// zero bytes, as the call index of a function is its position in `functions`.
+#[doc(hidden)]
+pub fn functions_metadata_v13<Call: Decode + GetDispatchInfo>(
+	functions: &'static [FunctionMetadata],
+) -> Vec<FunctionMetadataV13> {
+	use crate::weights::{DispatchClass, Pays};
+
+	functions.iter().enumerate().map(|(index, function)| {
+		let call_index = [index as u8];
+		let dispatch_info = Call::decode(&mut sp_runtime::traits::TrailingZeroInput::new(&call_index))
+			.ok()
+			.map(|call| {
+				let info = call.get_dispatch_info();
+				FunctionDispatchMetadata {
+					base_weight: info.weight,
+					class: match info.class {
+						DispatchClass::Normal => DispatchClassMetadata::Normal,
+						DispatchClass::Operational => DispatchClassMetadata::Operational,
+						DispatchClass::Mandatory => DispatchClassMetadata::Mandatory,
+					},
+					pays_fee: info.pays_fee == Pays::Yes,
+				}
+			});
+
+		FunctionMetadataV13 {
+			name: function.name.clone(),
+			arguments: function.arguments.clone(),
+			documentation: function.documentation.clone(),
+			dispatch_info,
+		}
+	}).collect()
+}
+
 /// Declares a `Module` struct and a `Call` enum, which implements the dispatch logic.
 ///
 /// ## Declaration
@@ -2114,6 +2151,7 @@ macro_rules! __dispatch_impl_metadata {
 	(
 		$mod_type:ident<$trait_instance:ident: $trait_name:ident$(<I>, $instance:ident: $instantiable:path)?>
 		{ $( $other_where_bounds:tt )* }
+		$call_type:ident
 		$($rest:tt)*
 	) => {
 		impl<$trait_instance: $trait_name $(<I>, $instance: $instantiable)?> $mod_type<$trait_instance $(, $instance)?>
@@ -2122,7 +2160,15 @@ macro_rules! __dispatch_impl_metadata {
 			#[doc(hidden)]
 			#[allow(dead_code)]
 			pub fn call_functions() -> &'static [$crate::dispatch::FunctionMetadata] {
-				$crate::__call_to_functions!($($rest)*)
+				$crate::__call_to_functions!($call_type $($rest)*)
+			}
+
+			#[doc(hidden)]
+			#[allow(dead_code)]
+			pub fn call_functions_v13() -> $crate::dispatch::Vec<$crate::dispatch::FunctionMetadataV13> {
+				$crate::dispatch::functions_metadata_v13::<$call_type<$trait_instance $(, $instance)?>>(
+					Self::call_functions()
+				)
 			}
 		}
 	}
@@ -2578,6 +2624,40 @@ mod tests {
 		assert_eq!(EXPECTED_METADATA, metadata);
 	}
 
+	#[test]
+	fn module_json_metadata_v13() {
+		let metadata = Module::<TraitImpl>::call_functions_v13();
+		assert_eq!(metadata.len(), EXPECTED_METADATA.len());
+		for (function, expected) in metadata.iter().zip(EXPECTED_METADATA) {
+			assert_eq!(function.name, expected.name);
+			assert_eq!(function.arguments, expected.arguments);
+			assert_eq!(function.documentation, expected.documentation);
+		}
+
+		let dispatch_infos = metadata.into_iter().map(|f| f.dispatch_info).collect::<Vec<_>>();
+		let normal = |base_weight| Some(FunctionDispatchMetadata {
+			base_weight,
+			class: DispatchClassMetadata::Normal,
+			pays_fee: true,
+		});
+		assert_eq!(
+			dispatch_infos,
+			vec![
+				normal(0),
+				normal(0),
+				normal(0),
+				normal(3),
+				normal(0),
+				normal(0),
+				Some(FunctionDispatchMetadata {
+					base_weight: 5,
+					class: DispatchClassMetadata::Operational,
+					pays_fee: true,
+				}),
+			],
+		);
+	}
+
 	#[test]
 	fn compact_attr() {
 		let call: Call<TraitImpl> = Call::aux_1(1);
@@ -19,9 +19,22 @@ pub use frame_metadata::{
 	DecodeDifferent, FnEncode, RuntimeMetadata, ModuleMetadata, RuntimeMetadataLastVersion,
 	DefaultByteGetter, RuntimeMetadataPrefixed, StorageEntryMetadata, StorageMetadata,
 	StorageEntryType, StorageEntryModifier, DefaultByte, StorageHasher, ModuleErrorMetadata,
-	ExtrinsicMetadata,
+	ExtrinsicMetadata, RuntimeMetadataV12, RuntimeMetadataV13, ModuleMetadataV13,
+	StorageMetadataV13, StorageEntryMetadataV13, StorageEntryTypeV13, STORAGE_HASHERS,
 };
 
+/// The bound of a storage value which is a bounded collection with the bound `S`.
+#[doc(hidden)]
+pub fn value_bound<S: crate::traits::Get<u32>>() -> Option<u32> {
+	Some(S::get())
+}
+
+/// The bound of a storage value which is not a bounded collection.
+#[doc(hidden)]
+pub fn no_value_bound() -> Option<u32> {
+	None
+}
+
 /// Implements the metadata support for the given runtime and all its modules.
 ///
 /// Example:
@@ -69,22 +82,62 @@ macro_rules! impl_runtime_metadata {
 			$( $rest:tt )*
 	) => {
 		impl $runtime {
+			/// The metadata of the runtime, in version 12.
+			///
+			/// Kept as the default version for the clients which don't support version 13 yet. Maps
+			/// with an arbitrary number of keys can't be described in this version and are left out.
 			pub fn metadata() -> $crate::metadata::RuntimeMetadataPrefixed {
-				$crate::metadata::RuntimeMetadataLastVersion {
-						modules: $crate::__runtime_modules_to_metadata!($runtime;; $( $rest )*),
-						extrinsic: $crate::metadata::ExtrinsicMetadata {
-							version: <$ext as $crate::sp_runtime::traits::ExtrinsicMetadata>::VERSION,
-							signed_extensions: <
-									<
-										$ext as $crate::sp_runtime::traits::ExtrinsicMetadata
-									>::SignedExtensions as $crate::sp_runtime::traits::SignedExtension
-								>::identifier()
-									.into_iter()
-									.map($crate::metadata::DecodeDifferent::Encode)
-									.collect(),
-						},
+				$crate::metadata::RuntimeMetadataV12 {
+					modules: $crate::__runtime_modules_to_metadata!(
+						$runtime;
+						ModuleMetadata, call_functions, storage_metadata;;
+						$( $rest )*
+					),
+					extrinsic: $crate::__runtime_extrinsic_metadata!($ext),
+				}.into()
+			}
+
+			/// The metadata of the runtime, in version 13.
+			pub fn metadata_v13() -> $crate::metadata::RuntimeMetadataPrefixed {
+				$crate::metadata::RuntimeMetadataV13 {
+					modules: $crate::__runtime_modules_to_metadata!(
+						$runtime;
+						ModuleMetadataV13, call_functions_v13, storage_metadata_v13;;
+						$( $rest )*
+					),
+					extrinsic: $crate::__runtime_extrinsic_metadata!($ext),
+					storage_hashers: $crate::metadata::DecodeDifferent::Encode(
+						$crate::metadata::STORAGE_HASHERS
+					),
 				}.into()
 			}
+
+			/// The metadata of the runtime in the given version, if supported.
+			pub fn metadata_at_version(version: u32) -> Option<$crate::metadata::RuntimeMetadataPrefixed> {
+				match version {
+					12 => Some(Self::metadata()),
+					13 => Some(Self::metadata_v13()),
+					_ => None,
+				}
+			}
+		}
+	}
+}
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __runtime_extrinsic_metadata {
+	($ext:ident) => {
+		$crate::metadata::ExtrinsicMetadata {
+			version: <$ext as $crate::sp_runtime::traits::ExtrinsicMetadata>::VERSION,
+			signed_extensions: <
+					<
+						$ext as $crate::sp_runtime::traits::ExtrinsicMetadata
+					>::SignedExtensions as $crate::sp_runtime::traits::SignedExtension
+				>::identifier()
+					.into_iter()
+					.map($crate::metadata::DecodeDifferent::Encode)
+					.collect(),
 		}
 	}
 }
@@ -94,6 +147,7 @@ macro_rules! impl_runtime_metadata {
 macro_rules! __runtime_modules_to_metadata {
 	(
 		$runtime: ident;
+		$module_metadata:ident, $call_functions:ident, $storage_metadata:ident;
 		$( $metadata:expr ),*;
 		$mod:ident::$module:ident $( < $instance:ident > )? as $name:ident
 			{ index $index:tt }
@@ -103,14 +157,15 @@ macro_rules! __runtime_modules_to_metadata {
 	) => {
 		$crate::__runtime_modules_to_metadata!(
 			$runtime;
-			$( $metadata, )* $crate::metadata::ModuleMetadata {
+			$module_metadata, $call_functions, $storage_metadata;
+			$( $metadata, )* $crate::metadata::$module_metadata {
 				name: $crate::metadata::DecodeDifferent::Encode(stringify!($name)),
 				index: $index,
 				storage: $crate::__runtime_modules_to_metadata_calls_storage!(
-					$mod, $module $( <$instance> )?, $runtime, $(with $kw)*
+					$mod, $module $( <$instance> )?, $runtime, $storage_metadata, $(with $kw)*
 				),
 				calls: $crate::__runtime_modules_to_metadata_calls_call!(
-					$mod, $module $( <$instance> )?, $runtime, $(with $kw)*
+					$mod, $module $( <$instance> )?, $runtime, $call_functions, $(with $kw)*
 				),
 				event: $crate::__runtime_modules_to_metadata_calls_event!(
 					$mod, $module $( <$instance> )?, $runtime, $(with $kw)*
@@ -131,6 +186,7 @@ macro_rules! __runtime_modules_to_metadata {
 	};
 	(
 		$runtime:ident;
+		$module_metadata:ident, $call_functions:ident, $storage_metadata:ident;
 		$( $metadata:expr ),*;
 	) => {
 		$crate::metadata::DecodeDifferent::Encode(&[ $( $metadata ),* ])
@@ -144,12 +200,13 @@ macro_rules! __runtime_modules_to_metadata_calls_call {
 		$mod: ident,
 		$module: ident $( <$instance:ident> )?,
 		$runtime: ident,
+		$call_functions: ident,
 		with Call
 		$(with $kws:ident)*
 	) => {
 		Some($crate::metadata::DecodeDifferent::Encode(
 			$crate::metadata::FnEncode(
-				$mod::$module::<$runtime $(, $mod::$instance )?>::call_functions
+				$mod::$module::<$runtime $(, $mod::$instance )?>::$call_functions
 			)
 		))
 	};
@@ -157,17 +214,19 @@ macro_rules! __runtime_modules_to_metadata_calls_call {
 		$mod: ident,
 		$module: ident $( <$instance:ident> )?,
 		$runtime: ident,
+		$call_functions: ident,
 		with $_:ident
 		$(with $kws:ident)*
 	) => {
 		$crate::__runtime_modules_to_metadata_calls_call! {
-			$mod, $module $( <$instance> )?, $runtime, $(with $kws)*
+			$mod, $module $( <$instance> )?, $runtime, $call_functions, $(with $kws)*
 		};
 	};
 	(
 		$mod: ident,
 		$module: ident $( <$instance:ident> )?,
 		$runtime: ident,
+		$call_functions: ident,
 	) => {
 		None
 	};
@@ -217,12 +276,13 @@ macro_rules! __runtime_modules_to_metadata_calls_storage {
 		$mod: ident,
 		$module: ident $( <$instance:ident> )?,
 		$runtime: ident,
+		$storage_metadata: ident,
 		with Storage
 		$(with $kws:ident)*
 	) => {
 		Some($crate::metadata::DecodeDifferent::Encode(
 			$crate::metadata::FnEncode(
-				$mod::$module::<$runtime $(, $mod::$instance )?>::storage_metadata
+				$mod::$module::<$runtime $(, $mod::$instance )?>::$storage_metadata
 			)
 		))
 	};
@@ -230,17 +290,19 @@ macro_rules! __runtime_modules_to_metadata_calls_storage {
 		$mod: ident,
 		$module: ident $( <$instance:ident> )?,
 		$runtime: ident,
+		$storage_metadata: ident,
 		with $_:ident
 		$(with $kws:ident)*
 	) => {
 		$crate::__runtime_modules_to_metadata_calls_storage! {
-			$mod, $module $( <$instance> )?, $runtime, $(with $kws)*
+			$mod, $module $( <$instance> )?, $runtime, $storage_metadata, $(with $kws)*
 		};
 	};
 	(
 		$mod: ident,
 		$module: ident $( <$instance:ident> )?,
 		$runtime: ident,
+		$storage_metadata: ident,
 	) => {
 		None
 	};
@@ -255,7 +317,8 @@ mod tests {
 	use frame_metadata::{
 		EventMetadata, StorageEntryModifier, StorageEntryType, FunctionMetadata, StorageEntryMetadata,
 		ModuleMetadata, RuntimeMetadataPrefixed, DefaultByte, ModuleConstantMetadata, DefaultByteGetter,
-		ErrorMetadata, ExtrinsicMetadata,
+		ErrorMetadata, ExtrinsicMetadata, FunctionMetadataV13, FunctionDispatchMetadata,
+		DispatchClassMetadata,
 	};
 	use codec::{Encode, Decode};
 	use crate::traits::Get;
@@ -486,7 +549,7 @@ mod tests {
 
 	#[test]
 	fn runtime_metadata() {
-		let expected_metadata: RuntimeMetadataLastVersion = RuntimeMetadataLastVersion {
+		let expected_metadata: RuntimeMetadataV12 = RuntimeMetadataV12 {
 			modules: DecodeDifferent::Encode(&[
 				ModuleMetadata {
 					name: DecodeDifferent::Encode("System"),
@@ -622,4 +685,56 @@ mod tests {
 
 		pretty_assertions::assert_eq!(expected_metadata, metadata_decoded.unwrap());
 	}
+
+	#[test]
+	fn runtime_metadata_v13() {
+		assert!(TestRuntime::metadata_at_version(11).is_none());
+		assert_eq!(TestRuntime::metadata_at_version(12), Some(TestRuntime::metadata()));
+		assert_eq!(TestRuntime::metadata_at_version(13), Some(TestRuntime::metadata_v13()));
+
+		let metadata_encoded = TestRuntime::metadata_v13().encode();
+		let metadata = match RuntimeMetadataPrefixed::decode(&mut &metadata_encoded[..]).unwrap().1 {
+			RuntimeMetadata::V13(metadata) => metadata,
+			_ => panic!("Metadata is encoded in version 13"),
+		};
+		let modules = match metadata.modules {
+			DecodeDifferent::Decoded(modules) => modules,
+			DecodeDifferent::Encode(_) => unreachable!("Metadata has been decoded"),
+		};
+
+		assert_eq!(modules.len(), 3);
+		assert!(modules[0].calls.is_none());
+		pretty_assertions::assert_eq!(
+			modules[1].calls,
+			Some(DecodeDifferent::Decoded(vec![
+				FunctionMetadataV13 {
+					name: DecodeDifferent::Encode("aux_0"),
+					arguments: DecodeDifferent::Encode(&[]),
+					documentation: DecodeDifferent::Encode(&[]),
+					dispatch_info: Some(FunctionDispatchMetadata {
+						base_weight: 0,
+						class: DispatchClassMetadata::Normal,
+						pays_fee: true,
+					}),
+				},
+			])),
+		);
+
+		let storage = match &modules[2].storage {
+			Some(DecodeDifferent::Decoded(storage)) => storage,
+			_ => unreachable!("Metadata has been decoded"),
+		};
+		assert_eq!(storage.prefix, DecodeDifferent::Encode("TestStorage"));
+		let entries = match &storage.entries {
+			DecodeDifferent::Decoded(entries) => entries,
+			DecodeDifferent::Encode(_) => unreachable!("Metadata has been decoded"),
+		};
+		assert_eq!(entries.len(), 1);
+		assert_eq!(entries[0].name, DecodeDifferent::Encode("StorageMethod"));
+		assert_eq!(entries[0].modifier, StorageEntryModifier::Optional);
+		assert_eq!(entries[0].ty, StorageEntryTypeV13::Plain(DecodeDifferent::Encode("u32")));
+		assert_eq!(entries[0].value_bound, DecodeDifferent::Decoded(None));
+
+		assert_eq!(metadata.storage_hashers, DecodeDifferent::Encode(STORAGE_HASHERS));
+	}
 }
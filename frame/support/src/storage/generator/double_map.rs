@@ -18,7 +18,7 @@
 use sp_std::prelude::*;
 use sp_std::borrow::Borrow;
 use codec::{FullCodec, FullEncode, Decode, Encode, EncodeLike};
-use crate::{storage::{self, unhashed, StorageAppend, PrefixIterator}, Never};
+use crate::{storage::{self, unhashed, StorageAppend, PrefixIterator, KeyPrefixIterator}, Never};
 use crate::hash::{StorageHasher, Twox128, ReversibleStorageHasher};
 
 /// Generator for `StorageDoubleMap` used by `decl_storage`.
@@ -333,47 +333,94 @@ impl<
 	G::Hasher2: ReversibleStorageHasher
 {
 	type PrefixIterator = PrefixIterator<(K2, V)>;
+	type PartialKeyIterator = KeyPrefixIterator<K2>;
 	type Iterator = PrefixIterator<(K1, K2, V)>;
+	type FullKeyIterator = KeyPrefixIterator<(K1, K2)>;
 
 	fn iter_prefix(k1: impl EncodeLike<K1>) -> Self::PrefixIterator {
 		let prefix = G::storage_double_map_final_key1(k1);
-		Self::PrefixIterator {
-			prefix: prefix.clone(),
-			previous_key: prefix,
-			drain: false,
-			closure: |raw_key_without_prefix, mut raw_value| {
+		Self::PrefixIterator::new(
+			prefix.clone(),
+			prefix,
+			|raw_key_without_prefix, mut raw_value| {
 				let mut key_material = G::Hasher2::reverse(raw_key_without_prefix);
 				Ok((K2::decode(&mut key_material)?, V::decode(&mut raw_value)?))
 			},
-		}
+		)
+	}
+
+	fn iter_prefix_from(
+		k1: impl EncodeLike<K1>,
+		starting_raw_key: Vec<u8>,
+	) -> Self::PrefixIterator {
+		let mut iter = Self::iter_prefix(k1);
+		iter.set_last_raw_key(starting_raw_key);
+		iter
+	}
+
+	fn iter_key_prefix(k1: impl EncodeLike<K1>) -> Self::PartialKeyIterator {
+		let prefix = G::storage_double_map_final_key1(k1);
+		Self::PartialKeyIterator::new(
+			prefix.clone(),
+			prefix,
+			|raw_key_without_prefix| {
+				let mut key_material = G::Hasher2::reverse(raw_key_without_prefix);
+				K2::decode(&mut key_material)
+			},
+		)
+	}
+
+	fn iter_key_prefix_from(
+		k1: impl EncodeLike<K1>,
+		starting_raw_key: Vec<u8>,
+	) -> Self::PartialKeyIterator {
+		let mut iter = Self::iter_key_prefix(k1);
+		iter.set_last_raw_key(starting_raw_key);
+		iter
 	}
 
 	fn drain_prefix(k1: impl EncodeLike<K1>) -> Self::PrefixIterator {
-		let mut iterator = Self::iter_prefix(k1);
-		iterator.drain = true;
-		iterator
+		Self::iter_prefix(k1).drain()
 	}
 
 	fn iter() -> Self::Iterator {
-		let prefix = G::prefix_hash();
-		Self::Iterator {
-			prefix: prefix.clone(),
-			previous_key: prefix,
-			drain: false,
-			closure: |raw_key_without_prefix, mut raw_value| {
+		Self::iter_from(G::prefix_hash())
+	}
+
+	fn iter_from(starting_raw_key: Vec<u8>) -> Self::Iterator {
+		Self::Iterator::new(
+			G::prefix_hash(),
+			starting_raw_key,
+			|raw_key_without_prefix, mut raw_value| {
 				let mut k1_k2_material = G::Hasher1::reverse(raw_key_without_prefix);
 				let k1 = K1::decode(&mut k1_k2_material)?;
 				let mut k2_material = G::Hasher2::reverse(k1_k2_material);
 				let k2 = K2::decode(&mut k2_material)?;
 				Ok((k1, k2, V::decode(&mut raw_value)?))
 			},
-		}
+		)
+	}
+
+	fn iter_keys() -> Self::FullKeyIterator {
+		Self::iter_keys_from(G::prefix_hash())
+	}
+
+	fn iter_keys_from(starting_raw_key: Vec<u8>) -> Self::FullKeyIterator {
+		Self::FullKeyIterator::new(
+			G::prefix_hash(),
+			starting_raw_key,
+			|raw_key_without_prefix| {
+				let mut k1_k2_material = G::Hasher1::reverse(raw_key_without_prefix);
+				let k1 = K1::decode(&mut k1_k2_material)?;
+				let mut k2_material = G::Hasher2::reverse(k1_k2_material);
+				let k2 = K2::decode(&mut k2_material)?;
+				Ok((k1, k2))
+			},
+		)
 	}
 
 	fn drain() -> Self::Iterator {
-		let mut iterator = Self::iter();
-		iterator.drain = true;
-		iterator
+		Self::iter().drain()
 	}
 
 	fn translate<O: Decode, F: Fn(K1, K2, O) -> Option<V>>(f: F) {
@@ -477,11 +524,28 @@ mod test_iterators {
 				vec![(3, 3, 3), (0, 0, 0), (2, 2, 2), (1, 1, 1)],
 			);
 
+			assert_eq!(
+				DoubleMap::iter_keys().collect::<Vec<_>>(),
+				vec![(3, 3), (0, 0), (2, 2), (1, 1)],
+			);
+
 			assert_eq!(
 				DoubleMap::iter_values().collect::<Vec<_>>(),
 				vec![3, 0, 2, 1],
 			);
 
+			let mut iter = DoubleMap::iter();
+			assert_eq!(iter.next(), Some((3, 3, 3)));
+			let last_raw_key = iter.last_raw_key().to_vec();
+			assert_eq!(
+				DoubleMap::iter_from(last_raw_key.clone()).collect::<Vec<_>>(),
+				vec![(0, 0, 0), (2, 2, 2), (1, 1, 1)],
+			);
+			assert_eq!(
+				DoubleMap::iter_keys_from(last_raw_key).collect::<Vec<_>>(),
+				vec![(0, 0), (2, 2), (1, 1)],
+			);
+
 			assert_eq!(
 				DoubleMap::drain().collect::<Vec<_>>(),
 				vec![(3, 3, 3), (0, 0, 0), (2, 2, 2), (1, 1, 1)],
@@ -507,11 +571,28 @@ mod test_iterators {
 				vec![(1, 1), (2, 2), (0, 0), (3, 3)],
 			);
 
+			assert_eq!(
+				DoubleMap::iter_key_prefix(k1).collect::<Vec<_>>(),
+				vec![1, 2, 0, 3],
+			);
+
 			assert_eq!(
 				DoubleMap::iter_prefix_values(k1).collect::<Vec<_>>(),
 				vec![1, 2, 0, 3],
 			);
 
+			let mut iter = DoubleMap::iter_prefix(k1);
+			assert_eq!(iter.next(), Some((1, 1)));
+			let last_raw_key = iter.last_raw_key().to_vec();
+			assert_eq!(
+				DoubleMap::iter_prefix_from(k1, last_raw_key.clone()).collect::<Vec<_>>(),
+				vec![(2, 2), (0, 0), (3, 3)],
+			);
+			assert_eq!(
+				DoubleMap::iter_key_prefix_from(k1, last_raw_key).collect::<Vec<_>>(),
+				vec![2, 0, 3],
+			);
+
 			assert_eq!(
 				DoubleMap::drain_prefix(k1).collect::<Vec<_>>(),
 				vec![(1, 1), (2, 2), (0, 0), (3, 3)],
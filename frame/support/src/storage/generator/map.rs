@@ -20,7 +20,7 @@ use sp_std::prelude::*;
 use sp_std::borrow::Borrow;
 use codec::{FullCodec, FullEncode, Decode, Encode, EncodeLike};
 use crate::{
-	storage::{self, unhashed, StorageAppend, PrefixIterator},
+	storage::{self, unhashed, StorageAppend, PrefixIterator, KeyPrefixIterator},
 	Never, hash::{StorageHasher, Twox128, ReversibleStorageHasher},
 };
 
@@ -140,19 +140,40 @@ impl<
 	G::Hasher: ReversibleStorageHasher
 {
 	type Iterator = PrefixIterator<(K, V)>;
+	type KeyIterator = KeyPrefixIterator<K>;
 
 	/// Enumerate all elements in the map.
 	fn iter() -> Self::Iterator {
-		let prefix = G::prefix_hash();
-		PrefixIterator {
-			prefix: prefix.clone(),
-			previous_key: prefix,
-			drain: false,
-			closure: |raw_key_without_prefix, mut raw_value| {
+		Self::iter_from(G::prefix_hash())
+	}
+
+	/// Enumerate all elements in the map after a given key.
+	fn iter_from(starting_raw_key: Vec<u8>) -> Self::Iterator {
+		PrefixIterator::new(
+			G::prefix_hash(),
+			starting_raw_key,
+			|raw_key_without_prefix, mut raw_value| {
 				let mut key_material = G::Hasher::reverse(raw_key_without_prefix);
 				Ok((K::decode(&mut key_material)?, V::decode(&mut raw_value)?))
 			},
-		}
+		)
+	}
+
+	/// Enumerate all keys in the map.
+	fn iter_keys() -> Self::KeyIterator {
+		Self::iter_keys_from(G::prefix_hash())
+	}
+
+	/// Enumerate all keys in the map after a given key.
+	fn iter_keys_from(starting_raw_key: Vec<u8>) -> Self::KeyIterator {
+		KeyPrefixIterator::new(
+			G::prefix_hash(),
+			starting_raw_key,
+			|raw_key_without_prefix| {
+				let mut key_material = G::Hasher::reverse(raw_key_without_prefix);
+				K::decode(&mut key_material)
+			},
+		)
 	}
 
 	/// Enumerate all elements in the map.
@@ -374,8 +395,17 @@ mod test_iterators {
 
 			assert_eq!(Map::iter().collect::<Vec<_>>(), vec![(3, 3), (0, 0), (2, 2), (1, 1)]);
 
+			assert_eq!(Map::iter_keys().collect::<Vec<_>>(), vec![3, 0, 2, 1]);
+
 			assert_eq!(Map::iter_values().collect::<Vec<_>>(), vec![3, 0, 2, 1]);
 
+			// Resume the iteration after the second element.
+			let mut iter = Map::iter();
+			assert_eq!(iter.by_ref().take(2).collect::<Vec<_>>(), vec![(3, 3), (0, 0)]);
+			let last_raw_key = iter.last_raw_key().to_vec();
+			assert_eq!(Map::iter_from(last_raw_key.clone()).collect::<Vec<_>>(), vec![(2, 2), (1, 1)]);
+			assert_eq!(Map::iter_keys_from(last_raw_key).collect::<Vec<_>>(), vec![2, 1]);
+
 			assert_eq!(Map::drain().collect::<Vec<_>>(), vec![(3, 3), (0, 0), (2, 2), (1, 1)]);
 
 			assert_eq!(Map::iter().collect::<Vec<_>>(), vec![]);
@@ -15,12 +15,16 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-//! Some utilities for helping access storage with arbitrary key types.
+//! Some utilities for helping access storage with arbitrary key types, and a framework for
+//! migrations that are executed over multiple blocks.
 
-use sp_std::prelude::*;
-use codec::{Encode, Decode};
+use sp_std::{prelude::*, marker::PhantomData};
+use codec::{Encode, Decode, FullCodec};
+use sp_runtime::RuntimeDebug;
 use crate::{StorageHasher, Twox128};
 use crate::hash::ReversibleStorageHasher;
+use crate::traits::Filter;
+use crate::weights::Weight;
 
 /// Utility to iterate through raw items in storage.
 pub struct StorageIterator<T> {
@@ -195,3 +199,407 @@ pub fn take_storage_item<K: Encode + Sized, T: Decode + Sized, H: StorageHasher>
 ) -> Option<T> {
 	take_storage_value(module, item, key.using_encoded(H::hash).as_ref())
 }
+
+/// The storage key under which the [`MigrationCursor`] of the ongoing multi-block migration is
+/// stored. The key is only present while a migration is in progress.
+pub const MIGRATION_CURSOR_KEY: &[u8] = b":migration_cursor:";
+
+/// The progress of the ongoing multi-block migrations.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct MigrationCursor {
+	/// Index of the migration that is currently executed.
+	pub index: u32,
+	/// The encoded cursor of the current migration, `None` if it did not make any step yet.
+	pub inner: Option<Vec<u8>>,
+	/// The number of steps the current migration made so far.
+	pub steps: u32,
+}
+
+/// Error of a single step of a [`SteppedMigration`].
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum SteppedMigrationError {
+	/// The remaining weight of the block is not enough to make any progress. The step is retried
+	/// in the next block.
+	InsufficientWeight {
+		/// The weight the step needs at least.
+		required: Weight,
+	},
+	/// The migration failed and cannot make any further progress.
+	Failed,
+}
+
+/// A migration that is executed step by step over multiple blocks.
+///
+/// Every step gets the cursor returned by the previous one and migrates as much as the given
+/// weight allows. The migration is done once a step returns no cursor.
+pub trait SteppedMigration {
+	/// The cursor that marks the progress of the migration, e.g. the last raw key migrated.
+	type Cursor: FullCodec;
+
+	/// The unique identifier of this migration, used in events.
+	fn id() -> Vec<u8>;
+
+	/// The maximum number of steps this migration is allowed to make before it is considered
+	/// failed, `None` for no limit.
+	fn max_steps() -> Option<u32> { None }
+
+	/// Make a single step of the migration, starting from `cursor` (`None` for the first step).
+	///
+	/// The step must not consume more than `remaining_weight`. Returns the cursor for the next
+	/// step, or `None` if the migration is complete, together with the weight consumed. A step
+	/// that is not complete and consumed no weight ends the stepping for the current block.
+	fn step(
+		cursor: Option<Self::Cursor>,
+		remaining_weight: Weight,
+	) -> Result<(Option<Self::Cursor>, Weight), SteppedMigrationError>;
+}
+
+/// A type erased [`SteppedMigration`], operating on encoded cursors.
+pub struct SteppedMigrationHandle {
+	/// See [`SteppedMigration::id`].
+	pub id: fn() -> Vec<u8>,
+	/// See [`SteppedMigration::max_steps`].
+	pub max_steps: fn() -> Option<u32>,
+	/// See [`SteppedMigration::step`].
+	pub step: fn(
+		Option<Vec<u8>>,
+		Weight,
+	) -> Result<(Option<Vec<u8>>, Weight), SteppedMigrationError>,
+}
+
+impl SteppedMigrationHandle {
+	/// Create the handle of the migration `M`.
+	pub fn new<M: SteppedMigration>() -> Self {
+		Self {
+			id: M::id,
+			max_steps: M::max_steps,
+			step: step_encoded::<M>,
+		}
+	}
+}
+
+fn step_encoded<M: SteppedMigration>(
+	cursor: Option<Vec<u8>>,
+	remaining_weight: Weight,
+) -> Result<(Option<Vec<u8>>, Weight), SteppedMigrationError> {
+	let cursor = match cursor {
+		Some(cursor) => Some(
+			M::Cursor::decode(&mut &cursor[..]).map_err(|_| SteppedMigrationError::Failed)?
+		),
+		None => None,
+	};
+	M::step(cursor, remaining_weight)
+		.map(|(cursor, weight)| (cursor.map(|c| c.encode()), weight))
+}
+
+/// A list of [`SteppedMigration`]s that are executed one after the other.
+///
+/// Implemented for tuples of [`SteppedMigration`]s.
+pub trait SteppedMigrations {
+	/// The handles of all migrations, in order of execution.
+	fn migrations() -> Vec<SteppedMigrationHandle>;
+}
+
+#[impl_trait_for_tuples::impl_for_tuples(30)]
+#[tuple_types_no_default_trait_bound]
+impl SteppedMigrations for Tuple {
+	for_tuples!( where #( Tuple: SteppedMigration )* );
+
+	fn migrations() -> Vec<SteppedMigrationHandle> {
+		let mut migrations = Vec::new();
+		for_tuples!( #( migrations.push(SteppedMigrationHandle::new::<Tuple>()); )* );
+		migrations
+	}
+}
+
+/// The progress made by [`step_migrations`].
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub enum MigrationProgress {
+	/// The migration with the given id made progress and is now at the given number of steps.
+	Advanced(Vec<u8>, u32),
+	/// The migration with the given id completed after the given number of steps.
+	Completed(Vec<u8>, u32),
+	/// The migration with the given id failed; all remaining migrations are aborted.
+	Failed(Vec<u8>),
+}
+
+/// The cursor of the ongoing multi-block migration, if any.
+pub fn migration_cursor() -> Option<MigrationCursor> {
+	crate::storage::unhashed::get(MIGRATION_CURSOR_KEY)
+}
+
+/// Returns `true` if a multi-block migration is in progress.
+pub fn is_migration_ongoing() -> bool {
+	crate::storage::unhashed::exists(MIGRATION_CURSOR_KEY)
+}
+
+/// Schedule the migrations `M` to be executed by [`step_migrations`], typically after a runtime
+/// upgrade.
+///
+/// Does nothing if `M` is empty or a migration is already in progress.
+pub fn start_migrations<M: SteppedMigrations>() {
+	if M::migrations().is_empty() {
+		return
+	}
+	if is_migration_ongoing() {
+		crate::debug::warn!(
+			"A multi-block migration is already in progress, not starting a new one."
+		);
+		return
+	}
+	crate::storage::unhashed::put(MIGRATION_CURSOR_KEY, &MigrationCursor {
+		index: 0,
+		inner: None,
+		steps: 0,
+	});
+}
+
+/// Make as many steps of the ongoing multi-block migration as `remaining_weight` allows.
+///
+/// Every step counts towards [`SteppedMigration::max_steps`], and a step that reports no weight
+/// without completing the migration ends the stepping until the next call, so a migration that
+/// does not make progress cannot loop forever.
+///
+/// Returns the weight consumed and the progress made. Once the last migration completed, or one
+/// of them failed, the [`MigrationCursor`] is removed and calls are no longer restricted by
+/// [`MigrationCallFilter`].
+pub fn step_migrations<M: SteppedMigrations>(
+	remaining_weight: Weight,
+) -> (Weight, Vec<MigrationProgress>) {
+	let mut cursor = match migration_cursor() {
+		Some(cursor) => cursor,
+		None => return (0, Vec::new()),
+	};
+	let migrations = M::migrations();
+	let mut consumed: Weight = 0;
+	let mut progress = Vec::new();
+	let mut advanced = false;
+
+	loop {
+		let migration = match migrations.get(cursor.index as usize) {
+			Some(migration) => migration,
+			None => {
+				crate::storage::unhashed::kill(MIGRATION_CURSOR_KEY);
+				return (consumed, progress)
+			},
+		};
+		if (migration.max_steps)().map_or(false, |max| cursor.steps >= max) {
+			progress.push(MigrationProgress::Failed((migration.id)()));
+			crate::storage::unhashed::kill(MIGRATION_CURSOR_KEY);
+			return (consumed, progress)
+		}
+		if consumed >= remaining_weight {
+			break
+		}
+
+		match (migration.step)(cursor.inner.clone(), remaining_weight - consumed) {
+			Ok((next, weight)) => {
+				consumed = consumed.saturating_add(weight);
+				cursor.steps = cursor.steps.saturating_add(1);
+				match next {
+					Some(next) => {
+						cursor.inner = Some(next);
+						advanced = true;
+						if weight == 0 {
+							break
+						}
+					},
+					None => {
+						progress.push(MigrationProgress::Completed((migration.id)(), cursor.steps));
+						cursor = MigrationCursor { index: cursor.index + 1, inner: None, steps: 0 };
+						advanced = false;
+					},
+				}
+			},
+			Err(SteppedMigrationError::InsufficientWeight { .. }) => break,
+			Err(SteppedMigrationError::Failed) => {
+				progress.push(MigrationProgress::Failed((migration.id)()));
+				crate::storage::unhashed::kill(MIGRATION_CURSOR_KEY);
+				return (consumed, progress)
+			},
+		}
+	}
+
+	if advanced {
+		if let Some(migration) = migrations.get(cursor.index as usize) {
+			progress.push(MigrationProgress::Advanced((migration.id)(), cursor.steps));
+		}
+	}
+	crate::storage::unhashed::put(MIGRATION_CURSOR_KEY, &cursor);
+	(consumed, progress)
+}
+
+/// A call filter that only lets calls accepted by `Base` through, and while a multi-block
+/// migration is in progress only those that are also accepted by `Allowed`.
+///
+/// This is meant to be used as the `BaseCallFilter` of `frame_system`, so that calls touching
+/// storage that is being migrated are refused until the migration is complete.
+pub struct MigrationCallFilter<Base, Allowed>(PhantomData<(Base, Allowed)>);
+
+impl<Call, Base: Filter<Call>, Allowed: Filter<Call>> Filter<Call>
+	for MigrationCallFilter<Base, Allowed>
+{
+	fn filter(call: &Call) -> bool {
+		Base::filter(call) && (!is_migration_ongoing() || Allowed::filter(call))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_io::TestExternalities;
+
+	const COUNTER_KEY: &[u8] = b"counter";
+
+	/// Counts to 5, one per step, each step weighing 10.
+	struct CountToFive;
+	impl SteppedMigration for CountToFive {
+		type Cursor = u32;
+
+		fn id() -> Vec<u8> { b"count".to_vec() }
+
+		fn step(
+			cursor: Option<u32>,
+			remaining_weight: Weight,
+		) -> Result<(Option<u32>, Weight), SteppedMigrationError> {
+			if remaining_weight < 10 {
+				return Err(SteppedMigrationError::InsufficientWeight { required: 10 })
+			}
+			let count = cursor.unwrap_or(0) + 1;
+			crate::storage::unhashed::put(COUNTER_KEY, &count);
+			Ok((if count < 5 { Some(count) } else { None }, 10))
+		}
+	}
+
+	struct NeverEnding;
+	impl SteppedMigration for NeverEnding {
+		type Cursor = ();
+
+		fn id() -> Vec<u8> { b"never".to_vec() }
+
+		fn max_steps() -> Option<u32> { Some(2) }
+
+		fn step(_: Option<()>, _: Weight) -> Result<(Option<()>, Weight), SteppedMigrationError> {
+			Ok((Some(()), 1))
+		}
+	}
+
+	struct Weightless;
+	impl SteppedMigration for Weightless {
+		type Cursor = ();
+
+		fn id() -> Vec<u8> { b"weightless".to_vec() }
+
+		fn max_steps() -> Option<u32> { Some(2) }
+
+		fn step(_: Option<()>, _: Weight) -> Result<(Option<()>, Weight), SteppedMigrationError> {
+			Ok((Some(()), 0))
+		}
+	}
+
+	struct Failing;
+	impl SteppedMigration for Failing {
+		type Cursor = ();
+
+		fn id() -> Vec<u8> { b"failing".to_vec() }
+
+		fn step(_: Option<()>, _: Weight) -> Result<(Option<()>, Weight), SteppedMigrationError> {
+			Err(SteppedMigrationError::Failed)
+		}
+	}
+
+	#[test]
+	fn stepped_migrations_run_over_multiple_blocks() {
+		TestExternalities::default().execute_with(|| {
+			assert_eq!(step_migrations::<(CountToFive,)>(100), (0, vec![]));
+
+			start_migrations::<(CountToFive, CountToFive)>();
+			assert!(is_migration_ongoing());
+
+			assert_eq!(
+				step_migrations::<(CountToFive, CountToFive)>(25),
+				(30, vec![MigrationProgress::Advanced(b"count".to_vec(), 3)]),
+			);
+			assert_eq!(
+				migration_cursor(),
+				Some(MigrationCursor { index: 0, inner: Some(3u32.encode()), steps: 3 }),
+			);
+
+			// Not enough weight for a single step.
+			assert_eq!(step_migrations::<(CountToFive, CountToFive)>(5), (0, vec![]));
+			assert_eq!(crate::storage::unhashed::get::<u32>(COUNTER_KEY), Some(3));
+
+			assert_eq!(
+				step_migrations::<(CountToFive, CountToFive)>(40),
+				(40, vec![
+					MigrationProgress::Completed(b"count".to_vec(), 5),
+					MigrationProgress::Advanced(b"count".to_vec(), 2),
+				]),
+			);
+			assert_eq!(
+				step_migrations::<(CountToFive, CountToFive)>(100),
+				(30, vec![MigrationProgress::Completed(b"count".to_vec(), 5)]),
+			);
+			assert!(!is_migration_ongoing());
+			assert_eq!(crate::storage::unhashed::get::<u32>(COUNTER_KEY), Some(5));
+		});
+	}
+
+	#[test]
+	fn failed_migration_aborts_remaining_ones() {
+		TestExternalities::default().execute_with(|| {
+			start_migrations::<(Failing, CountToFive)>();
+			assert_eq!(
+				step_migrations::<(Failing, CountToFive)>(100),
+				(0, vec![MigrationProgress::Failed(b"failing".to_vec())]),
+			);
+			assert!(!is_migration_ongoing());
+			assert_eq!(crate::storage::unhashed::get::<u32>(COUNTER_KEY), None);
+
+			start_migrations::<(NeverEnding,)>();
+			assert_eq!(
+				step_migrations::<(NeverEnding,)>(100),
+				(2, vec![MigrationProgress::Failed(b"never".to_vec())]),
+			);
+			assert!(!is_migration_ongoing());
+		});
+	}
+
+	#[test]
+	fn weightless_step_ends_the_block() {
+		TestExternalities::default().execute_with(|| {
+			start_migrations::<(Weightless,)>();
+			assert_eq!(
+				step_migrations::<(Weightless,)>(100),
+				(0, vec![MigrationProgress::Advanced(b"weightless".to_vec(), 1)]),
+			);
+			assert_eq!(
+				step_migrations::<(Weightless,)>(100),
+				(0, vec![MigrationProgress::Advanced(b"weightless".to_vec(), 2)]),
+			);
+			assert_eq!(
+				step_migrations::<(Weightless,)>(100),
+				(0, vec![MigrationProgress::Failed(b"weightless".to_vec())]),
+			);
+			assert!(!is_migration_ongoing());
+		});
+	}
+
+	#[test]
+	fn migration_call_filter_works() {
+		struct OnlyEven;
+		impl Filter<u32> for OnlyEven {
+			fn filter(n: &u32) -> bool { n % 2 == 0 }
+		}
+		type CallFilter = MigrationCallFilter<(), OnlyEven>;
+
+		TestExternalities::default().execute_with(|| {
+			assert!(CallFilter::filter(&1));
+			start_migrations::<(CountToFive,)>();
+			assert!(!CallFilter::filter(&1));
+			assert!(CallFilter::filter(&2));
+			step_migrations::<(CountToFive,)>(100);
+			assert!(CallFilter::filter(&1));
+		});
+	}
+}
@@ -0,0 +1,223 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Provides functionality around the transaction storage.
+//!
+//! Transactional storage provides functionality to run an entire code block
+//! in a storage transaction. This means that either the entire changes to the
+//! storage are committed or everything is thrown away. This simplifies the
+//! writing of functionality that may bail at any point of operation. Otherwise
+//! you would need to first verify all storage accesses and then do the storage
+//! modifications.
+//!
+//! Nesting of transactional layers is bounded by [`TRANSACTIONAL_LIMIT`]; trying to go
+//! deeper fails with [`TransactionalError::LimitReached`].
+
+use sp_io::storage::{start_transaction, commit_transaction, rollback_transaction};
+use sp_runtime::{DispatchError, TransactionOutcome, TransactionalError};
+
+/// The type that is being used to store the current number of active layers.
+pub type Layer = u32;
+
+/// The key that is holds the current number of active layers.
+///
+/// Encodes to `0x3a7472616e73616374696f6e5f6c6576656c3a`.
+pub const TRANSACTION_LEVEL_KEY: &[u8] = sp_core::storage::well_known_keys::TRANSACTION_LEVEL;
+
+/// The maximum number of nested layers.
+pub const TRANSACTIONAL_LIMIT: Layer = 255;
+
+/// Returns the current number of nested transactional layers.
+fn get_transaction_level() -> Layer {
+	crate::storage::unhashed::get_or_default::<Layer>(TRANSACTION_LEVEL_KEY)
+}
+
+/// Set the current number of nested transactional layers.
+fn set_transaction_level(level: Layer) {
+	crate::storage::unhashed::put::<Layer>(TRANSACTION_LEVEL_KEY, &level);
+}
+
+/// Kill the transactional layers storage.
+fn kill_transaction_level() {
+	crate::storage::unhashed::kill(TRANSACTION_LEVEL_KEY);
+}
+
+/// Increments the transaction level. Returns an error if levels go past the limit.
+///
+/// Returns a guard that when dropped decrements the transaction level automatically.
+fn inc_transaction_level(limit: Layer) -> Result<StorageLayerGuard, ()> {
+	let existing_levels = get_transaction_level();
+	if existing_levels >= limit {
+		return Err(())
+	}
+	// Cannot overflow because of check above.
+	set_transaction_level(existing_levels + 1);
+	Ok(StorageLayerGuard)
+}
+
+fn dec_transaction_level() {
+	let existing_levels = get_transaction_level();
+	if existing_levels == 0 {
+		crate::debug::warn!(
+			"We are underflowing with calculating transactional levels. Not great, but let's not panic..."
+		);
+	} else if existing_levels == 1 {
+		// Don't leave any trace of this storage item.
+		kill_transaction_level();
+	} else {
+		// Cannot underflow because of checks above.
+		set_transaction_level(existing_levels - 1);
+	}
+}
+
+struct StorageLayerGuard;
+
+impl Drop for StorageLayerGuard {
+	fn drop(&mut self) {
+		dec_transaction_level()
+	}
+}
+
+/// Check if the current call is within a transactional layer.
+pub fn is_transactional() -> bool {
+	get_transaction_level() > 0
+}
+
+/// Returns the number of transactional layers the current call is nested in.
+pub fn transactional_depth() -> Layer {
+	get_transaction_level()
+}
+
+/// Execute the supplied function in a new storage transaction.
+///
+/// All changes to storage performed by the supplied function are discarded if the returned
+/// outcome is `TransactionOutcome::Rollback`.
+///
+/// Transactions can be nested up to [`TRANSACTIONAL_LIMIT`] times; more than that will result
+/// in an error. Commits happen to the parent transaction.
+pub fn with_transaction<T, E, F>(f: F) -> Result<T, E>
+where
+	E: From<DispatchError>,
+	F: FnOnce() -> TransactionOutcome<Result<T, E>>,
+{
+	with_transaction_limit(TRANSACTIONAL_LIMIT, f)
+}
+
+/// Same as [`with_transaction`] but with a custom limit of nested transactional layers.
+///
+/// The limit is checked against the total nesting depth, including the layers opened by
+/// callers higher up the stack.
+pub fn with_transaction_limit<T, E, F>(limit: Layer, f: F) -> Result<T, E>
+where
+	E: From<DispatchError>,
+	F: FnOnce() -> TransactionOutcome<Result<T, E>>,
+{
+	use TransactionOutcome::*;
+
+	let _guard = inc_transaction_level(limit)
+		.map_err(|()| DispatchError::from(TransactionalError::LimitReached))?;
+
+	start_transaction();
+
+	match f() {
+		Commit(res) => {
+			commit_transaction();
+			res
+		},
+		Rollback(res) => {
+			rollback_transaction();
+			res
+		},
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{assert_noop, assert_ok, storage::unhashed};
+	use sp_io::TestExternalities;
+	use sp_runtime::DispatchResult;
+
+	#[test]
+	fn is_transactional_should_return_false() {
+		TestExternalities::default().execute_with(|| {
+			assert!(!is_transactional());
+		});
+	}
+
+	#[test]
+	fn is_transactional_should_not_error_in_with_transaction() {
+		TestExternalities::default().execute_with(|| {
+			assert_ok!(with_transaction(|| -> TransactionOutcome<DispatchResult> {
+				assert!(is_transactional());
+				TransactionOutcome::Commit(Ok(()))
+			}));
+
+			assert_noop!(
+				with_transaction(|| -> TransactionOutcome<DispatchResult> {
+					assert!(is_transactional());
+					TransactionOutcome::Rollback(Err("revert".into()))
+				}),
+				"revert"
+			);
+		});
+	}
+
+	fn recursive_transactional(num: u32) -> DispatchResult {
+		if num == 0 {
+			return Ok(())
+		}
+
+		with_transaction(|| -> TransactionOutcome<DispatchResult> {
+			let res = recursive_transactional(num - 1);
+			TransactionOutcome::Commit(res)
+		})
+	}
+
+	#[test]
+	fn transaction_limit_should_work() {
+		TestExternalities::default().execute_with(|| {
+			assert_eq!(transactional_depth(), 0);
+
+			assert_ok!(recursive_transactional(TRANSACTIONAL_LIMIT));
+			assert_noop!(
+				recursive_transactional(TRANSACTIONAL_LIMIT + 1),
+				TransactionalError::LimitReached,
+			);
+
+			assert_eq!(transactional_depth(), 0);
+			assert!(unhashed::get_raw(TRANSACTION_LEVEL_KEY).is_none());
+		});
+	}
+
+	#[test]
+	fn custom_transaction_limit_should_work() {
+		TestExternalities::default().execute_with(|| {
+			let nested = || with_transaction_limit(1, || -> TransactionOutcome<DispatchResult> {
+				TransactionOutcome::Commit(Ok(()))
+			});
+
+			assert_ok!(nested());
+			assert_noop!(
+				with_transaction(|| -> TransactionOutcome<DispatchResult> {
+					assert_eq!(transactional_depth(), 1);
+					TransactionOutcome::Commit(nested())
+				}),
+				TransactionalError::LimitReached,
+			);
+		});
+	}
+}
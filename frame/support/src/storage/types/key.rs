@@ -0,0 +1,252 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Storage key type, used by `StorageNMap` to describe the hasher and the type of each key.
+
+use crate::hash::{ReversibleStorageHasher, StorageHasher};
+use codec::{Encode, EncodeLike, FullCodec};
+use sp_std::prelude::*;
+
+/// A type used exclusively by storage maps as their key type.
+///
+/// The final key generated has the following form:
+/// ```nocompile
+/// Hasher1(encode(key1))
+///		++ Hasher2(encode(key2))
+///		++ ...
+///		++ HasherN(encode(keyN))
+/// ```
+pub struct Key<Hasher, KeyType>(core::marker::PhantomData<(Hasher, KeyType)>);
+
+/// A trait that contains the current key as an associated type.
+///
+/// It is implemented by `Key<Hasher, KeyType>` and by tuples of `Key`.
+pub trait KeyGenerator {
+	/// The type of the key, a tuple of the key types for tuple of `Key`.
+	type Key: EncodeLike<Self::Key>;
+	/// The tuple of key arguments.
+	type KArg: Encode;
+	/// A hasher function used when migrating from defunct hashers.
+	type HashFn: FnOnce(&[u8]) -> Vec<u8>;
+	/// The tuple of hasher functions, one for each key.
+	type HArg;
+
+	/// The metadata of the hashers, in the order of the keys.
+	const HASHER_METADATA: &'static [frame_metadata::StorageHasher];
+
+	/// Given a `key` tuple, calculate the final key by encoding each element individually and
+	/// hashing them using the corresponding hasher in the `KeyGenerator`.
+	fn final_key<KArg: EncodeLikeTuple<Self::KArg> + TupleToEncodedIter>(key: KArg) -> Vec<u8>;
+
+	/// Given a `key` tuple, migrate the keys from using the old hashers as given by `hash_fns`
+	/// to using the newer hashers as specified by this `KeyGenerator`.
+	fn migrate_key<KArg: EncodeLikeTuple<Self::KArg> + TupleToEncodedIter>(
+		key: &KArg,
+		hash_fns: Self::HArg,
+	) -> Vec<u8>;
+}
+
+/// A `KeyGenerator` made of a single key, used to build `KeyGenerator` for tuples.
+pub trait KeyGeneratorInner: KeyGenerator {
+	/// The hasher of the key.
+	type Hasher: StorageHasher;
+
+	/// Hash a given encoded key with the hasher.
+	fn final_hash(encoded: &[u8]) -> Vec<u8>;
+}
+
+impl<H: StorageHasher, K: FullCodec> KeyGenerator for Key<H, K> {
+	type Key = K;
+	type KArg = (K,);
+	type HashFn = Box<dyn FnOnce(&[u8]) -> Vec<u8>>;
+	type HArg = (Self::HashFn,);
+
+	const HASHER_METADATA: &'static [frame_metadata::StorageHasher] = &[H::METADATA];
+
+	fn final_key<KArg: EncodeLikeTuple<Self::KArg> + TupleToEncodedIter>(key: KArg) -> Vec<u8> {
+		H::hash(&key.to_encoded_iter().next().expect("should have at least one element!"))
+			.as_ref()
+			.to_vec()
+	}
+
+	fn migrate_key<KArg: EncodeLikeTuple<Self::KArg> + TupleToEncodedIter>(
+		key: &KArg,
+		hash_fns: Self::HArg,
+	) -> Vec<u8> {
+		(hash_fns.0)(&key.to_encoded_iter().next().expect("should have at least one element!"))
+	}
+}
+
+impl<H: StorageHasher, K: FullCodec> KeyGeneratorInner for Key<H, K> {
+	type Hasher = H;
+
+	fn final_hash(encoded: &[u8]) -> Vec<u8> {
+		H::hash(encoded).as_ref().to_vec()
+	}
+}
+
+/// A `KeyGenerator` whose keys can be decoded back from the final key, i.e. all its hashers are
+/// reversible.
+pub trait ReversibleKeyGenerator: KeyGenerator {
+	/// The hasher, or tuple of hashers, of the keys.
+	type ReversibleHasher;
+
+	/// Decode the key from the final key material, and return the remaining material.
+	fn decode_final_key(key_material: &[u8]) -> Result<(Self::Key, &[u8]), codec::Error>;
+}
+
+impl<H: ReversibleStorageHasher, K: FullCodec> ReversibleKeyGenerator for Key<H, K> {
+	type ReversibleHasher = H;
+
+	fn decode_final_key(key_material: &[u8]) -> Result<(Self::Key, &[u8]), codec::Error> {
+		let mut current_key_material = Self::ReversibleHasher::reverse(key_material);
+		let key = K::decode(&mut current_key_material)?;
+		Ok((key, current_key_material))
+	}
+}
+
+/// Marker trait to indicate that each element in the tuple encodes like the corresponding element
+/// in another tuple.
+pub trait EncodeLikeTuple<T> {}
+
+/// Trait to indicate that a tuple can be converted into an iterator of vectors of encoded bytes.
+pub trait TupleToEncodedIter {
+	/// Encode each element of the tuple individually.
+	fn to_encoded_iter(&self) -> sp_std::vec::IntoIter<Vec<u8>>;
+}
+
+impl<T: TupleToEncodedIter> TupleToEncodedIter for &T {
+	fn to_encoded_iter(&self) -> sp_std::vec::IntoIter<Vec<u8>> {
+		(*self).to_encoded_iter()
+	}
+}
+
+impl<T, U: EncodeLikeTuple<T>> EncodeLikeTuple<T> for &U {}
+
+/// A `KeyGenerator` for which a leading part of the keys, `P`, can be used as a prefix to iterate
+/// or remove values.
+pub trait HasKeyPrefix<P>: KeyGenerator {
+	/// The keys that follow the prefix.
+	type Suffix;
+
+	/// Calculate the part of the final key generated by the prefix.
+	fn partial_key(prefix: P) -> Vec<u8>;
+}
+
+/// A `HasKeyPrefix` for which the suffix can be decoded back from the final key material.
+pub trait HasReversibleKeyPrefix<P>: ReversibleKeyGenerator + HasKeyPrefix<P> {
+	/// Decode the suffix keys from the key material following the prefix.
+	fn decode_partial_key(key_material: &[u8]) -> Result<Self::Suffix, codec::Error>;
+}
+
+/// Call the given macro for each non-empty tail of the given list of `(Type KArg)` pairs.
+macro_rules! for_each_tuple {
+	($m:ident; $first:tt $($rest:tt)*) => {
+		$m!($first $($rest)*);
+		for_each_tuple!($m; $($rest)*);
+	};
+	($m:ident;) => {};
+}
+
+macro_rules! impl_encode_like_tuple {
+	($(($t:ident $k:ident))+) => {
+		impl<$($t: Encode, $k: EncodeLike<$t>),+> EncodeLikeTuple<($($t,)+)> for ($($k,)+) {}
+
+		impl<$($k: Encode),+> TupleToEncodedIter for ($($k,)+) {
+			#[allow(non_snake_case)]
+			fn to_encoded_iter(&self) -> sp_std::vec::IntoIter<Vec<u8>> {
+				let ($($k,)+) = self;
+				vec![$($k.encode()),+].into_iter()
+			}
+		}
+	};
+}
+
+macro_rules! impl_key_generator_tuple {
+	// A tuple with a single key is not a valid key generator, `Key` must be used directly.
+	(($t:ident $k:ident)) => {};
+	($(($t:ident $k:ident))+) => {
+		impl<$($t: KeyGeneratorInner),+> KeyGenerator for ($($t,)+) {
+			type Key = ($($t::Key,)+);
+			type KArg = ($($t::Key,)+);
+			type HashFn = Box<dyn FnOnce(&[u8]) -> Vec<u8>>;
+			type HArg = ($($t::HashFn,)+);
+
+			const HASHER_METADATA: &'static [frame_metadata::StorageHasher] =
+				&[$(<$t::Hasher as StorageHasher>::METADATA),+];
+
+			fn final_key<KArg: EncodeLikeTuple<Self::KArg> + TupleToEncodedIter>(
+				key: KArg,
+			) -> Vec<u8> {
+				let mut final_key = Vec::new();
+				let mut iter = key.to_encoded_iter();
+				$(
+					let next_encoded = iter.next()
+						.expect("KArg number should be equal to Key number");
+					final_key.extend_from_slice(&$t::final_hash(&next_encoded));
+				)+
+				final_key
+			}
+
+			#[allow(non_snake_case)]
+			fn migrate_key<KArg: EncodeLikeTuple<Self::KArg> + TupleToEncodedIter>(
+				key: &KArg,
+				hash_fns: Self::HArg,
+			) -> Vec<u8> {
+				let mut migrated_key = Vec::new();
+				let mut iter = key.to_encoded_iter();
+				let ($($k,)+) = hash_fns;
+				$(
+					let next_encoded = iter.next()
+						.expect("KArg number should be equal to Key number");
+					migrated_key.extend_from_slice(&($k)(&next_encoded));
+				)+
+				migrated_key
+			}
+		}
+
+		impl<$($t: KeyGeneratorInner + ReversibleKeyGenerator),+> ReversibleKeyGenerator
+			for ($($t,)+)
+		{
+			type ReversibleHasher = ($($t::ReversibleHasher,)+);
+
+			fn decode_final_key(key_material: &[u8]) -> Result<(Self::Key, &[u8]), codec::Error> {
+				let mut current_key_material = key_material;
+				let key = ($(
+					{
+						let (key, material) = $t::decode_final_key(current_key_material)?;
+						current_key_material = material;
+						key
+					},
+				)+);
+				Ok((key, current_key_material))
+			}
+		}
+	};
+}
+
+for_each_tuple!(impl_encode_like_tuple;
+	(T0 K0) (T1 K1) (T2 K2) (T3 K3) (T4 K4) (T5 K5) (T6 K6) (T7 K7) (T8 K8)
+	(T9 K9) (T10 K10) (T11 K11) (T12 K12) (T13 K13) (T14 K14) (T15 K15) (T16 K16) (T17 K17)
+);
+
+for_each_tuple!(impl_key_generator_tuple;
+	(T0 K0) (T1 K1) (T2 K2) (T3 K3) (T4 K4) (T5 K5) (T6 K6) (T7 K7) (T8 K8)
+	(T9 K9) (T10 K10) (T11 K11) (T12 K12) (T13 K13) (T14 K14) (T15 K15) (T16 K16) (T17 K17)
+);
+
+frame_support_procedural::impl_key_prefix_for_tuples!();
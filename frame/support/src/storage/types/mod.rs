@@ -24,10 +24,17 @@ use frame_metadata::{DefaultByte, StorageEntryModifier};
 mod value;
 mod map;
 mod double_map;
+mod nmap;
+mod key;
 
 pub use value::{StorageValue, StorageValueMetadata};
 pub use map::{StorageMap, StorageMapMetadata};
 pub use double_map::{StorageDoubleMap, StorageDoubleMapMetadata};
+pub use nmap::{StorageNMap, StorageNMapMetadata};
+pub use key::{
+	EncodeLikeTuple, HasKeyPrefix, HasReversibleKeyPrefix, Key, KeyGenerator, KeyGeneratorInner,
+	ReversibleKeyGenerator, TupleToEncodedIter,
+};
 
 /// Trait implementing how the storage optional value is converted into the queried type.
 ///
@@ -0,0 +1,29 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Traits for working with tokens and their associated datastructures.
+//!
+//! Unlike [`Currency`](super::Currency), these traits are not limited to a single asset: the
+//! [`fungibles`] family is keyed by an asset identifier, and [`fungible`] is its single-asset
+//! counterpart. [`fungible::ItemOf`] adapts the former into the latter. The [`nonfungibles`]
+//! family describes classes of unique, individually-owned assets.
+
+pub mod fungible;
+pub mod fungibles;
+pub mod nonfungibles;
+mod misc;
+pub use misc::{WithdrawConsequence, DepositConsequence, AssetId, Balance, BalanceConversion};
@@ -112,6 +112,11 @@ mod module2 {
 	}
 }
 
+pub struct DenyAll;
+impl<T> frame_support::traits::Filter<T> for DenyAll {
+	fn filter(_: &T) -> bool { false }
+}
+
 impl<I> module1::Config<I> for Runtime {}
 impl module2::Config for Runtime {}
 
@@ -140,7 +145,7 @@ frame_support::construct_runtime!(
 	{
 		System: system::{Module, Call, Event<T>, Origin<T>} = 30,
 		Module1_1: module1::<Instance1>::{Module, Call, Storage, Event<T>, Origin<T>},
-		Module2: module2::{Module, Call, Storage, Event, Origin},
+		Module2: module2::{Module, Call(DenyAll), Storage, Event, Origin},
 		Module1_2: module1::<Instance2>::{Module, Call, Storage, Event<T>, Origin<T>},
 		Module1_3: module1::<Instance3>::{Module, Storage} = 6,
 		Module1_4: module1::<Instance4>::{Module, Call} = 3,
@@ -281,10 +286,18 @@ fn call_codec() {
 	assert_eq!(Call::Module1_9(module1::Call::fail()).encode()[0], 13);
 }
 
+#[test]
+fn base_call_filter_works() {
+	use frame_support::traits::Filter;
+	assert!(BaseCallFilter::filter(&Call::System(system::Call::noop())));
+	assert!(BaseCallFilter::filter(&Call::Module1_1(module1::Call::fail())));
+	assert!(!BaseCallFilter::filter(&Call::Module2(module2::Call::fail())));
+}
+
 #[test]
 fn test_metadata() {
 	use frame_metadata::*;
-	let expected_metadata: RuntimeMetadataLastVersion = RuntimeMetadataLastVersion {
+	let expected_metadata: RuntimeMetadataV12 = RuntimeMetadataV12 {
 		modules: DecodeDifferent::Encode(&[
 			ModuleMetadata {
 				name: DecodeDifferent::Encode("System"),
@@ -0,0 +1,14 @@
+use frame_support::construct_runtime;
+
+construct_runtime! {
+	pub enum Runtime where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic
+	{
+		System: system::{Module},
+		Balance: balances::{Call, Event(DenyAll)},
+	}
+}
+
+fn main() {}
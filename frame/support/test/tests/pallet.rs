@@ -0,0 +1,600 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use frame_support::{
+	weights::{DispatchInfo, DispatchClass, Pays, GetDispatchInfo},
+	traits::{GetCallName, OnInitialize, OnFinalize, OnRuntimeUpgrade, GetPalletVersion, OnGenesis},
+	dispatch::UnfilteredDispatchable,
+	storage::unhashed,
+};
+use sp_runtime::DispatchError;
+use sp_io::{TestExternalities, hashing::{twox_64, twox_128, blake2_128}};
+
+#[frame_support::pallet]
+pub mod pallet {
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+
+	type BalanceOf<T> = <T as Config>::Balance;
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// Some comment
+		/// Some comment
+		#[pallet::constant]
+		type MyGetParam: Get<u32>;
+
+		/// Some comment
+		/// Some comment
+		#[pallet::constant]
+		type MyGetParam2: Get<u32>;
+
+		type Balance: Parameter + Default;
+
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+	}
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(crate) trait Store)]
+	pub struct Pallet<T>(PhantomData<T>);
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_initialize(_: BlockNumberFor<T>) -> Weight {
+			Self::deposit_event(Event::Something(10));
+			10
+		}
+		fn on_finalize(_: BlockNumberFor<T>) {
+			Self::deposit_event(Event::Something(20));
+		}
+		fn on_runtime_upgrade() -> Weight {
+			Self::deposit_event(Event::Something(30));
+			30
+		}
+		fn integrity_test() {
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Doc comment put in metadata
+		#[pallet::weight(Weight::from(*_foo))]
+		fn foo(
+			origin: OriginFor<T>,
+			#[pallet::compact] _foo: u32,
+			_bar: u32,
+		) -> DispatchResultWithPostInfo {
+			let _ = origin;
+			Self::deposit_event(Event::Something(3));
+			Ok(().into())
+		}
+
+		/// Doc comment put in metadata
+		#[pallet::weight(1)]
+		#[frame_support::transactional]
+		fn foo_transactional(
+			_origin: OriginFor<T>,
+			#[pallet::compact] foo: u32,
+		) -> DispatchResultWithPostInfo {
+			Self::deposit_event(Event::Something(0));
+			if foo == 0 {
+				Err(Error::<T>::InsufficientProposersBalance)?;
+			}
+
+			Ok(().into())
+		}
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// doc comment put into metadata
+		InsufficientProposersBalance,
+	}
+
+	#[pallet::event]
+	#[pallet::metadata(BalanceOf<T> = "Balance", u32 = "Other")]
+	#[pallet::generate_deposit(fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// doc comment put in metadata
+		Proposed(<T as frame_system::Config>::AccountId),
+		/// doc
+		Spending(BalanceOf<T>),
+		Something(u32),
+	}
+
+	#[pallet::storage]
+	pub type ValueWithBound<T: Config> = StorageValue<_, u32>;
+
+	#[pallet::storage]
+	pub type Value<T> = StorageValue<_, u32>;
+
+	#[pallet::type_value]
+	pub fn MyDefault<T: Config>() -> u16 {
+		T::MyGetParam::get() as u16
+	}
+
+	#[pallet::storage]
+	pub type Map<T> = StorageMap<_, Blake2_128Concat, u8, u16, ValueQuery, MyDefault<T>>;
+
+	#[pallet::storage]
+	pub type Map2<T> = StorageMap<_, Twox64Concat, u16, u32>;
+
+	#[pallet::storage]
+	pub type DoubleMap<T> = StorageDoubleMap<_, Blake2_128Concat, u8, Twox64Concat, u16, u32>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn double_map2)]
+	pub type DoubleMap2<T> = StorageDoubleMap<_, Twox64Concat, u16, Blake2_128Concat, u32, u64>;
+
+	#[pallet::storage]
+	pub type NMap<T> = StorageNMap<_, NMapKey<Blake2_128Concat, u8>, u32>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn nmap2)]
+	pub type NMap2<T> = StorageNMap<
+		_,
+		(NMapKey<Twox64Concat, u16>, NMapKey<Blake2_128Concat, u32>),
+		u64,
+	>;
+
+	#[pallet::storage]
+	pub type BoundedValue<T: Config> = StorageValue<_, BoundedVec<u32, T::MyGetParam>>;
+
+	#[pallet::genesis_config]
+	#[cfg_attr(feature = "std", derive(Default))]
+	pub struct GenesisConfig {
+		pub value: u32,
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> GenesisBuild<T> for GenesisConfig {
+		fn build(&self) {
+			<Value<T>>::put(self.value);
+		}
+	}
+
+	#[pallet::origin]
+	#[derive(EqNoBound, RuntimeDebugNoBound, CloneNoBound, PartialEqNoBound, Encode, Decode)]
+	pub struct Origin<T>(PhantomData<T>);
+
+	#[pallet::validate_unsigned]
+	impl<T: Config> ValidateUnsigned for Pallet<T> {
+		type Call = Call<T>;
+		fn validate_unsigned(
+			_source: TransactionSource,
+			_call: &Self::Call
+		) -> TransactionValidity {
+			Err(TransactionValidityError::Invalid(InvalidTransaction::Call))
+		}
+	}
+
+	#[pallet::inherent]
+	impl<T: Config> ProvideInherent for Pallet<T> {
+		type Call = Call<T>;
+		type Error = InherentError;
+
+		const INHERENT_IDENTIFIER: InherentIdentifier = INHERENT_IDENTIFIER;
+
+		fn create_inherent(_data: &InherentData) -> Option<Self::Call> {
+			unimplemented!();
+		}
+	}
+
+	#[derive(codec::Encode, sp_runtime::RuntimeDebug)]
+	#[cfg_attr(feature = "std", derive(codec::Decode))]
+	pub enum InherentError {
+	}
+
+	impl sp_inherents::IsFatalError for InherentError {
+		fn is_fatal_error(&self) -> bool {
+			unimplemented!();
+		}
+	}
+
+	pub const INHERENT_IDENTIFIER: InherentIdentifier = *b"testpall";
+}
+
+// Test that a pallet with non generic event and generic genesis_config is correctly handled
+#[frame_support::pallet]
+pub mod pallet2 {
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		type Event: From<Event> + IsType<<Self as frame_system::Config>::Event>;
+	}
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(crate) trait Store)]
+	pub struct Pallet<T>(PhantomData<T>);
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {}
+
+	#[pallet::storage]
+	pub type SomeValue<T: Config> = StorageValue<_, Vec<u32>>;
+
+	#[pallet::event]
+	#[pallet::generate_deposit(fn deposit_event)]
+	pub enum Event {
+		/// Something
+		Something(u32),
+	}
+
+	#[pallet::genesis_config]
+	pub struct GenesisConfig<T: Config> {
+		phantom: PhantomData<T>,
+	}
+
+	#[cfg(feature = "std")]
+	impl<T: Config> Default for GenesisConfig<T> {
+		fn default() -> Self {
+			GenesisConfig {
+				phantom: Default::default(),
+			}
+		}
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
+		fn build(&self) {}
+	}
+}
+
+frame_support::parameter_types!(
+	pub const MyGetParam: u32= 10;
+	pub const MyGetParam2: u32= 11;
+	pub const BlockHashCount: u32 = 250;
+);
+
+impl frame_system::Config for Runtime {
+	type BaseCallFilter = ();
+	type Origin = Origin;
+	type Index = u64;
+	type BlockNumber = u32;
+	type Call = Call;
+	type Hash = sp_runtime::testing::H256;
+	type Hashing = sp_runtime::traits::BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = sp_runtime::traits::IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = Event;
+	type BlockHashCount = BlockHashCount;
+	type BlockWeights = ();
+	type BlockLength = ();
+	type DbWeight = ();
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = ();
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+}
+impl pallet::Config for Runtime {
+	type Event = Event;
+	type MyGetParam = MyGetParam;
+	type MyGetParam2 = MyGetParam2;
+	type Balance = u64;
+}
+
+impl pallet2::Config for Runtime {
+	type Event = Event;
+}
+
+pub type Header = sp_runtime::generic::Header<u32, sp_runtime::traits::BlakeTwo256>;
+pub type Block = sp_runtime::generic::Block<Header, UncheckedExtrinsic>;
+pub type UncheckedExtrinsic = sp_runtime::generic::UncheckedExtrinsic<u32, Call, (), ()>;
+
+frame_support::construct_runtime!(
+	pub enum Runtime where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic
+	{
+		System: frame_system::{Module, Call, Event<T>},
+		Example: pallet::{Module, Call, Event<T>, Config, Storage, Inherent, Origin<T>, ValidateUnsigned},
+		Example2: pallet2::{Module, Call, Event, Config<T>, Storage},
+	}
+);
+
+#[test]
+fn transactional_works() {
+	TestExternalities::default().execute_with(|| {
+		frame_system::Module::<Runtime>::set_block_number(1);
+
+		pallet::Call::<Runtime>::foo_transactional(0).dispatch_bypass_filter(None.into())
+			.err().unwrap();
+		assert!(frame_system::Module::<Runtime>::events().is_empty());
+
+		pallet::Call::<Runtime>::foo_transactional(1).dispatch_bypass_filter(None.into()).unwrap();
+		assert_eq!(
+			frame_system::Module::<Runtime>::events().iter().map(|e| &e.event).collect::<Vec<_>>(),
+			vec![&Event::pallet(pallet::Event::Something(0))],
+		);
+	})
+}
+
+#[test]
+fn call_expand() {
+	let call_foo = pallet::Call::<Runtime>::foo(3, 0);
+	assert_eq!(
+		call_foo.get_dispatch_info(),
+		DispatchInfo {
+			weight: 3,
+			class: DispatchClass::Normal,
+			pays_fee: Pays::Yes,
+		}
+	);
+	assert_eq!(call_foo.get_call_name(), "foo");
+	assert_eq!(
+		pallet::Call::<Runtime>::get_call_names(),
+		&["foo", "foo_transactional"],
+	);
+}
+
+#[test]
+fn error_expand() {
+	assert_eq!(
+		format!("{:?}", pallet::Error::<Runtime>::InsufficientProposersBalance),
+		String::from("InsufficientProposersBalance"),
+	);
+	assert_eq!(
+		<&'static str>::from(pallet::Error::<Runtime>::InsufficientProposersBalance),
+		"InsufficientProposersBalance",
+	);
+	assert_eq!(
+		DispatchError::from(pallet::Error::<Runtime>::InsufficientProposersBalance),
+		DispatchError::Module {
+			index: 1,
+			error: 0,
+			message: Some("InsufficientProposersBalance"),
+		},
+	);
+}
+
+#[test]
+fn instance_expand() {
+	// Assert same type.
+	let _: pallet::__InherentHiddenInstance = ();
+}
+
+#[test]
+fn pallet_expand_deposit_event() {
+	TestExternalities::default().execute_with(|| {
+		frame_system::Module::<Runtime>::set_block_number(1);
+		pallet::Call::<Runtime>::foo(3, 0).dispatch_bypass_filter(None.into()).unwrap();
+		assert_eq!(
+			frame_system::Module::<Runtime>::events()[0].event,
+			Event::pallet(pallet::Event::Something(3)),
+		);
+	})
+}
+
+#[test]
+fn storage_expand() {
+	use frame_support::pallet_prelude::*;
+	use frame_support::StoragePrefixedMap;
+
+	fn twox_64_concat(d: &[u8]) -> Vec<u8> {
+		let mut v = twox_64(d).to_vec();
+		v.extend_from_slice(d);
+		v
+	}
+
+	fn blake2_128_concat(d: &[u8]) -> Vec<u8> {
+		let mut v = blake2_128(d).to_vec();
+		v.extend_from_slice(d);
+		v
+	}
+
+	TestExternalities::default().execute_with(|| {
+		pallet::Value::<Runtime>::put(1);
+		let k = [twox_128(b"Example"), twox_128(b"Value")].concat();
+		assert_eq!(unhashed::get::<u32>(&k), Some(1u32));
+
+		pallet::Map::<Runtime>::insert(1, 2);
+		let mut k = [twox_128(b"Example"), twox_128(b"Map")].concat();
+		k.extend(1u8.using_encoded(blake2_128_concat));
+		assert_eq!(unhashed::get::<u16>(&k), Some(2u16));
+		assert_eq!(&k[..32], &<pallet::Map<Runtime>>::final_prefix());
+
+		pallet::Map2::<Runtime>::insert(1, 2);
+		let mut k = [twox_128(b"Example"), twox_128(b"Map2")].concat();
+		k.extend(1u16.using_encoded(twox_64_concat));
+		assert_eq!(unhashed::get::<u32>(&k), Some(2u32));
+		assert_eq!(&k[..32], &<pallet::Map2<Runtime>>::final_prefix());
+
+		pallet::DoubleMap::<Runtime>::insert(&1, &2, &3);
+		let mut k = [twox_128(b"Example"), twox_128(b"DoubleMap")].concat();
+		k.extend(1u8.using_encoded(blake2_128_concat));
+		k.extend(2u16.using_encoded(twox_64_concat));
+		assert_eq!(unhashed::get::<u32>(&k), Some(3u32));
+		assert_eq!(&k[..32], &<pallet::DoubleMap<Runtime>>::final_prefix());
+
+		pallet::DoubleMap2::<Runtime>::insert(&1, &2, &3);
+		let mut k = [twox_128(b"Example"), twox_128(b"DoubleMap2")].concat();
+		k.extend(1u16.using_encoded(twox_64_concat));
+		k.extend(2u32.using_encoded(blake2_128_concat));
+		assert_eq!(unhashed::get::<u64>(&k), Some(3u64));
+		assert_eq!(&k[..32], &<pallet::DoubleMap2<Runtime>>::final_prefix());
+		assert_eq!(pallet::Pallet::<Runtime>::double_map2(1, 2), Some(3u64));
+
+		pallet::NMap::<Runtime>::insert((&1,), &3);
+		let mut k = [twox_128(b"Example"), twox_128(b"NMap")].concat();
+		k.extend(1u8.using_encoded(blake2_128_concat));
+		assert_eq!(unhashed::get::<u32>(&k), Some(3u32));
+		assert_eq!(&k[..32], &<pallet::NMap<Runtime>>::final_prefix());
+
+		pallet::NMap2::<Runtime>::insert((&1, &2), &3);
+		let mut k = [twox_128(b"Example"), twox_128(b"NMap2")].concat();
+		k.extend(1u16.using_encoded(twox_64_concat));
+		k.extend(2u32.using_encoded(blake2_128_concat));
+		assert_eq!(unhashed::get::<u64>(&k), Some(3u64));
+		assert_eq!(&k[..32], &<pallet::NMap2<Runtime>>::final_prefix());
+		assert_eq!(pallet::Pallet::<Runtime>::nmap2((1, 2)), Some(3u64));
+	})
+}
+
+#[test]
+fn pallet_hooks_expand() {
+	TestExternalities::default().execute_with(|| {
+		frame_system::Module::<Runtime>::set_block_number(1);
+
+		assert_eq!(AllModules::on_initialize(1), 10);
+		AllModules::on_finalize(1);
+
+		assert_eq!(pallet::Pallet::<Runtime>::storage_version(), None);
+		assert_eq!(AllModules::on_runtime_upgrade(), 30);
+		assert_eq!(
+			pallet::Pallet::<Runtime>::storage_version(),
+			Some(pallet::Pallet::<Runtime>::current_version()),
+		);
+
+		assert_eq!(
+			frame_system::Module::<Runtime>::events()[0].event,
+			Event::pallet(pallet::Event::Something(10)),
+		);
+		assert_eq!(
+			frame_system::Module::<Runtime>::events()[1].event,
+			Event::pallet(pallet::Event::Something(20)),
+		);
+		assert_eq!(
+			frame_system::Module::<Runtime>::events()[2].event,
+			Event::pallet(pallet::Event::Something(30)),
+		);
+	})
+}
+
+#[test]
+fn pallet_on_genesis() {
+	TestExternalities::default().execute_with(|| {
+		assert_eq!(pallet::Pallet::<Runtime>::storage_version(), None);
+		pallet::Pallet::<Runtime>::on_genesis();
+		assert_eq!(
+			pallet::Pallet::<Runtime>::storage_version(),
+			Some(pallet::Pallet::<Runtime>::current_version()),
+		);
+	})
+}
+
+#[test]
+fn genesis_config_build() {
+	let storage = GenesisConfig {
+		pallet: Some(pallet::GenesisConfig { value: 42 }),
+		pallet2: Some(Default::default()),
+	}.build_storage().unwrap();
+
+	TestExternalities::new(storage).execute_with(|| {
+		assert_eq!(pallet::Value::<Runtime>::get(), Some(42));
+	})
+}
+
+#[test]
+fn metadata() {
+	use frame_metadata::*;
+
+	let constants = pallet::Pallet::<Runtime>::module_constants_metadata();
+	assert_eq!(constants.len(), 2);
+	assert_eq!(constants[0].name, DecodeDifferent::Decoded("MyGetParam".to_string()));
+	assert_eq!(constants[0].ty, DecodeDifferent::Decoded("u32".to_string()));
+	assert_eq!(constants[0].value, DecodeDifferent::Decoded(10u32.encode()));
+
+	let calls = pallet::Pallet::<Runtime>::call_functions();
+	assert_eq!(calls.len(), 2);
+	assert_eq!(calls[0].name, DecodeDifferent::Encode("foo"));
+	let foo_args: Vec<_> = match &calls[0].arguments {
+		DecodeDifferent::Encode(args) => args.iter().map(|a| match &a.ty {
+			DecodeDifferent::Encode(ty) => *ty,
+			_ => unreachable!(),
+		}).collect(),
+		_ => unreachable!(),
+	};
+	assert_eq!(foo_args, vec!["Compact<u32>", "u32"]);
+
+	let events = pallet::Event::<Runtime>::metadata();
+	assert_eq!(events.len(), 3);
+	assert_eq!(events[1].arguments, DecodeDifferent::Encode(&["Balance"][..]));
+	assert_eq!(events[2].arguments, DecodeDifferent::Encode(&["Other"][..]));
+
+	let storage = pallet::Pallet::<Runtime>::storage_metadata();
+	assert_eq!(storage.prefix, DecodeDifferent::Encode("Example"));
+	let entries = match storage.entries {
+		DecodeDifferent::Encode(entries) => entries,
+		_ => unreachable!(),
+	};
+	assert_eq!(entries.len(), 7);
+	assert_eq!(
+		entries[2].ty,
+		StorageEntryType::Map {
+			hasher: StorageHasher::Blake2_128Concat,
+			key: DecodeDifferent::Encode("u8"),
+			value: DecodeDifferent::Encode("u16"),
+			unused: false,
+		},
+	);
+	assert_eq!(entries[2].modifier, StorageEntryModifier::Default);
+	assert_eq!(entries[2].default, DecodeDifferent::Decoded(10u16.encode()));
+	// Maps with an arbitrary number of keys are only part of version 13 of the metadata.
+	let storage = pallet::Pallet::<Runtime>::storage_metadata_v13();
+	let entries = match storage.entries {
+		DecodeDifferent::Encode(entries) => entries,
+		_ => unreachable!(),
+	};
+	assert_eq!(entries.len(), 9);
+	assert_eq!(
+		entries[2].ty,
+		StorageEntryTypeV13::Map {
+			hasher: StorageHasher::Blake2_128Concat,
+			key: DecodeDifferent::Encode("u8"),
+			value: DecodeDifferent::Encode("u16"),
+			unused: false,
+		},
+	);
+	assert_eq!(
+		entries[6].ty,
+		StorageEntryTypeV13::NMap {
+			keys: DecodeDifferent::Encode(&["u8"]),
+			hashers: DecodeDifferent::Encode(&[StorageHasher::Blake2_128Concat]),
+			value: DecodeDifferent::Encode("u32"),
+		},
+	);
+	assert_eq!(
+		entries[7].ty,
+		StorageEntryTypeV13::NMap {
+			keys: DecodeDifferent::Encode(&["u16", "u32"]),
+			hashers: DecodeDifferent::Encode(&[
+				StorageHasher::Twox64Concat,
+				StorageHasher::Blake2_128Concat,
+			]),
+			value: DecodeDifferent::Encode("u64"),
+		},
+	);
+	assert_eq!(entries[2].value_bound, DecodeDifferent::Decoded(None));
+	assert_eq!(
+		entries[8].ty,
+		StorageEntryTypeV13::Plain(DecodeDifferent::Encode("BoundedVec<u32, T::MyGetParam>")),
+	);
+	assert_eq!(entries[8].value_bound, DecodeDifferent::Decoded(Some(10)));
+
+	let errors = <pallet::Pallet<Runtime> as ModuleErrorMetadata>::metadata();
+	assert_eq!(errors.len(), 1);
+	assert_eq!(errors[0].name, DecodeDifferent::Encode("InsufficientProposersBalance"));
+}
@@ -66,12 +66,12 @@ fn storage_transaction_basic_commit() {
 		assert_eq!(Value::get(), 0);
 		assert!(!Map::contains_key("val0"));
 
-		with_transaction(|| {
+		let _: DispatchResult = with_transaction(|| {
 			Value::set(99);
 			Map::insert("val0", 99);
 			assert_eq!(Value::get(), 99);
 			assert_eq!(Map::get("val0"), 99);
-			Commit(())
+			Commit(Ok(()))
 		});
 
 		assert_eq!(Value::get(), 99);
@@ -86,12 +86,12 @@ fn storage_transaction_basic_rollback() {
 		assert_eq!(Value::get(), 0);
 		assert_eq!(Map::get("val0"), 0);
 
-		with_transaction(|| {
+		let _: DispatchResult = with_transaction(|| {
 			Value::set(99);
 			Map::insert("val0", 99);
 			assert_eq!(Value::get(), 99);
 			assert_eq!(Map::get("val0"), 99);
-			Rollback(())
+			Rollback(Ok(()))
 		});
 
 		assert_eq!(Value::get(), 0);
@@ -105,12 +105,12 @@ fn storage_transaction_rollback_then_commit() {
 		Value::set(1);
 		Map::insert("val1", 1);
 
-		with_transaction(|| {
+		let _: DispatchResult = with_transaction(|| {
 			Value::set(2);
 			Map::insert("val1", 2);
 			Map::insert("val2", 2);
 
-			with_transaction(|| {
+			let _: DispatchResult = with_transaction(|| {
 				Value::set(3);
 				Map::insert("val1", 3);
 				Map::insert("val2", 3);
@@ -121,7 +121,7 @@ fn storage_transaction_rollback_then_commit() {
 				assert_eq!(Map::get("val2"), 3);
 				assert_eq!(Map::get("val3"), 3);
 
-				Rollback(())
+				Rollback(Ok(()))
 			});
 
 			assert_eq!(Value::get(), 2);
@@ -129,7 +129,7 @@ fn storage_transaction_rollback_then_commit() {
 			assert_eq!(Map::get("val2"), 2);
 			assert_eq!(Map::get("val3"), 0);
 
-			Commit(())
+			Commit(Ok(()))
 		});
 
 		assert_eq!(Value::get(), 2);
@@ -145,12 +145,12 @@ fn storage_transaction_commit_then_rollback() {
 		Value::set(1);
 		Map::insert("val1", 1);
 
-		with_transaction(|| {
+		let _: DispatchResult = with_transaction(|| {
 			Value::set(2);
 			Map::insert("val1", 2);
 			Map::insert("val2", 2);
 
-			with_transaction(|| {
+			let _: DispatchResult = with_transaction(|| {
 				Value::set(3);
 				Map::insert("val1", 3);
 				Map::insert("val2", 3);
@@ -161,7 +161,7 @@ fn storage_transaction_commit_then_rollback() {
 				assert_eq!(Map::get("val2"), 3);
 				assert_eq!(Map::get("val3"), 3);
 
-				Commit(())
+				Commit(Ok(()))
 			});
 
 			assert_eq!(Value::get(), 3);
@@ -169,7 +169,7 @@ fn storage_transaction_commit_then_rollback() {
 			assert_eq!(Map::get("val2"), 3);
 			assert_eq!(Map::get("val3"), 3);
 
-			Rollback(())
+			Rollback(Ok(()))
 		});
 
 		assert_eq!(Value::get(), 1);
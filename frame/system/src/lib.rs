@@ -477,6 +477,12 @@ decl_event!(
 		NewAccount(AccountId),
 		/// An \[account\] was reaped.
 		KilledAccount(AccountId),
+		/// A multi-block migration made progress. \[id, steps\]
+		MigrationAdvanced(Vec<u8>, u32),
+		/// A multi-block migration completed. \[id, steps\]
+		MigrationCompleted(Vec<u8>, u32),
+		/// A multi-block migration failed; the remaining ones were aborted. \[id\]
+		MigrationFailed(Vec<u8>),
 	}
 );
 
@@ -1358,3 +1364,14 @@ impl<T: Config> Lookup for ChainContext<T> {
 		<T::Lookup as StaticLookup>::lookup(s)
 	}
 }
+
+/// Prelude to be used alongside pallet macro, for ease of use.
+pub mod pallet_prelude {
+	pub use crate::{ensure_signed, ensure_none, ensure_root};
+
+	/// Type alias for the `Origin` associated type of system config.
+	pub type OriginFor<T> = <T as crate::Config>::Origin;
+
+	/// Type alias for the `BlockNumber` associated type of system config.
+	pub type BlockNumberFor<T> = <T as crate::Config>::BlockNumber;
+}
@@ -0,0 +1,240 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # Asset Transaction Payment Module
+//!
+//! This module provides the [`ChargeAssetTxPayment`] signed extension, which lets the sender of a
+//! transaction choose to pay its fees either in the native currency or in an asset, e.g. one of
+//! `pallet-assets`. It is meant to replace `ChargeTransactionPayment` in the runtime's signed
+//! extensions.
+//!
+//! The fee is computed by `pallet-transaction-payment` as usual. When paying in the native
+//! currency, the fee is withdrawn through its [`Config::OnChargeTransaction`]. When paying in an
+//! asset, the fee is converted into the asset and withdrawn through
+//! [`Config::OnChargeAssetTransaction`]. In both cases, fees paid for unused weight are refunded
+//! after dispatch in the currency or asset they were paid in.
+//!
+//! The default [`FungiblesAdapter`] converts fees using a
+//! [`BalanceConversion`](frame_support::traits::tokens::BalanceConversion), such as
+//! `pallet_assets::BalanceToAssetBalance`, which uses the ratio of the asset's minimum balance to
+//! the native existential deposit.
+//!
+//! [`Config::OnChargeTransaction`]: pallet_transaction_payment::Config::OnChargeTransaction
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::{Encode, Decode};
+use frame_support::{
+	decl_module,
+	traits::tokens::fungibles,
+	weights::{DispatchInfo, PostDispatchInfo},
+	dispatch::DispatchResult,
+};
+use sp_runtime::{
+	FixedPointOperand,
+	transaction_validity::{TransactionValidityError, TransactionValidity, ValidTransaction},
+	traits::{SignedExtension, Dispatchable, DispatchInfoOf, PostDispatchInfoOf, Zero},
+};
+use pallet_transaction_payment::{ChargeTransactionPayment, OnChargeTransaction};
+
+#[cfg(test)]
+mod tests;
+
+mod payment;
+pub use payment::*;
+
+/// Balance type in which transaction fees are computed.
+pub(crate) type BalanceOf<T> = <<T as pallet_transaction_payment::Config>::OnChargeTransaction
+	as OnChargeTransaction<T>>::Balance;
+/// Liquidity info type of the native transaction payment.
+pub(crate) type LiquidityInfoOf<T> = <<T as pallet_transaction_payment::Config>::OnChargeTransaction
+	as OnChargeTransaction<T>>::LiquidityInfo;
+/// Asset id type of the fungibles used to pay fees.
+pub(crate) type AssetIdOf<T> = <<T as Config>::Fungibles
+	as fungibles::Inspect<<T as frame_system::Config>::AccountId>>::AssetId;
+/// Asset balance type of the fungibles used to pay fees.
+pub(crate) type AssetBalanceOf<T> = <<T as Config>::Fungibles
+	as fungibles::Inspect<<T as frame_system::Config>::AccountId>>::Balance;
+/// Asset id type of the asset transaction payment.
+pub(crate) type ChargeAssetIdOf<T> = <<T as Config>::OnChargeAssetTransaction
+	as OnChargeAssetTransaction<T>>::AssetId;
+/// Liquidity info type of the asset transaction payment.
+pub(crate) type ChargeAssetLiquidityOf<T> = <<T as Config>::OnChargeAssetTransaction
+	as OnChargeAssetTransaction<T>>::LiquidityInfo;
+
+/// The module configuration trait.
+pub trait Config: frame_system::Config + pallet_transaction_payment::Config {
+	/// The fungibles instance used to pay for transactions in assets.
+	type Fungibles: fungibles::Mutate<Self::AccountId>;
+
+	/// The actual transaction charging logic that charges the fees in assets.
+	type OnChargeAssetTransaction: OnChargeAssetTransaction<Self>;
+}
+
+decl_module! {
+	pub struct Module<T: Config> for enum Call where origin: T::Origin {}
+}
+
+/// Used to pass the initial payment info from pre- to post-dispatch.
+pub enum InitialPayment<T: Config> {
+	/// No initial fee was paid.
+	Nothing,
+	/// The initial fee was paid in the native currency.
+	Native(LiquidityInfoOf<T>),
+	/// The initial fee was paid in an asset.
+	Asset(ChargeAssetLiquidityOf<T>),
+}
+
+impl<T: Config> Default for InitialPayment<T> {
+	fn default() -> Self {
+		Self::Nothing
+	}
+}
+
+/// Require the transactor pay for themselves and maybe include a tip to gain additional priority
+/// in the queue. The fee is paid in the asset `asset_id` if given, otherwise in the native
+/// currency.
+#[derive(Encode, Decode, Clone, Eq, PartialEq)]
+pub struct ChargeAssetTxPayment<T: Config + Send + Sync> {
+	#[codec(compact)]
+	tip: BalanceOf<T>,
+	asset_id: Option<ChargeAssetIdOf<T>>,
+}
+
+impl<T: Config + Send + Sync> ChargeAssetTxPayment<T> where
+	T::Call: Dispatchable<Info=DispatchInfo, PostInfo=PostDispatchInfo>,
+	BalanceOf<T>: Send + Sync + FixedPointOperand,
+	ChargeAssetIdOf<T>: Send + Sync,
+{
+	/// utility constructor. Used only in client/factory code.
+	pub fn from(tip: BalanceOf<T>, asset_id: Option<ChargeAssetIdOf<T>>) -> Self {
+		Self { tip, asset_id }
+	}
+
+	/// Withdraw the fee through either `OnChargeAssetTransaction` or `OnChargeTransaction`,
+	/// depending on whether an asset was chosen.
+	fn withdraw_fee(
+		&self,
+		who: &T::AccountId,
+		call: &T::Call,
+		info: &DispatchInfoOf<T::Call>,
+		len: usize,
+	) -> Result<(BalanceOf<T>, InitialPayment<T>), TransactionValidityError> {
+		let fee = pallet_transaction_payment::Module::<T>::compute_fee(len as u32, info, self.tip);
+		debug_assert!(self.tip <= fee, "tip should be included in the computed fee");
+		if fee.is_zero() {
+			Ok((fee, InitialPayment::Nothing))
+		} else if let Some(asset_id) = self.asset_id {
+			T::OnChargeAssetTransaction::withdraw_fee(who, call, info, asset_id, fee, self.tip)
+				.map(|i| (fee, InitialPayment::Asset(i)))
+		} else {
+			<T::OnChargeTransaction as OnChargeTransaction<T>>::withdraw_fee(
+				who, call, info, fee, self.tip,
+			)
+				.map(|i| (fee, InitialPayment::Native(i)))
+		}
+	}
+}
+
+impl<T: Config + Send + Sync> sp_std::fmt::Debug for ChargeAssetTxPayment<T> {
+	#[cfg(feature = "std")]
+	fn fmt(&self, f: &mut sp_std::fmt::Formatter) -> sp_std::fmt::Result {
+		write!(f, "ChargeAssetTxPayment<{:?}, {:?}>", self.tip, self.asset_id.encode())
+	}
+	#[cfg(not(feature = "std"))]
+	fn fmt(&self, _: &mut sp_std::fmt::Formatter) -> sp_std::fmt::Result {
+		Ok(())
+	}
+}
+
+impl<T: Config + Send + Sync> SignedExtension for ChargeAssetTxPayment<T> where
+	BalanceOf<T>: Send + Sync + From<u64> + FixedPointOperand,
+	ChargeAssetIdOf<T>: Send + Sync,
+	T::Call: Dispatchable<Info=DispatchInfo, PostInfo=PostDispatchInfo>,
+{
+	const IDENTIFIER: &'static str = "ChargeAssetTxPayment";
+	type AccountId = T::AccountId;
+	type Call = T::Call;
+	type AdditionalSigned = ();
+	type Pre = (
+		// tip
+		BalanceOf<T>,
+		// who paid the fee
+		Self::AccountId,
+		// the fee withdrawn, in the native currency or in an asset
+		InitialPayment<T>,
+	);
+	fn additional_signed(&self) -> sp_std::result::Result<(), TransactionValidityError> { Ok(()) }
+
+	fn validate(
+		&self,
+		who: &Self::AccountId,
+		call: &Self::Call,
+		info: &DispatchInfoOf<Self::Call>,
+		len: usize,
+	) -> TransactionValidity {
+		let (fee, _) = self.withdraw_fee(who, call, info, len)?;
+		Ok(ValidTransaction {
+			priority: ChargeTransactionPayment::<T>::get_priority(len, info, fee),
+			..Default::default()
+		})
+	}
+
+	fn pre_dispatch(
+		self,
+		who: &Self::AccountId,
+		call: &Self::Call,
+		info: &DispatchInfoOf<Self::Call>,
+		len: usize
+	) -> Result<Self::Pre, TransactionValidityError> {
+		let (_fee, initial_payment) = self.withdraw_fee(who, call, info, len)?;
+		Ok((self.tip, who.clone(), initial_payment))
+	}
+
+	fn post_dispatch(
+		pre: Self::Pre,
+		info: &DispatchInfoOf<Self::Call>,
+		post_info: &PostDispatchInfoOf<Self::Call>,
+		len: usize,
+		_result: &DispatchResult,
+	) -> Result<(), TransactionValidityError> {
+		let (tip, who, initial_payment) = pre;
+		let actual_fee = pallet_transaction_payment::Module::<T>::compute_actual_fee(
+			len as u32,
+			info,
+			post_info,
+			tip,
+		);
+		match initial_payment {
+			InitialPayment::Native(already_withdrawn) => {
+				T::OnChargeTransaction::correct_and_deposit_fee(
+					&who, info, post_info, actual_fee, tip, already_withdrawn,
+				)?;
+			},
+			InitialPayment::Asset(already_withdrawn) => {
+				T::OnChargeAssetTransaction::correct_and_deposit_fee(
+					&who, info, post_info, actual_fee, tip, already_withdrawn,
+				)?;
+			},
+			InitialPayment::Nothing => {
+				// The fee was zero, so there is nothing to refund or deposit.
+				debug_assert!(tip.is_zero(), "tip should be zero if initial fee was zero.");
+			},
+		}
+		Ok(())
+	}
+}
@@ -0,0 +1,168 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Traits and default implementation for paying transaction fees in assets.
+
+use crate::{Config, BalanceOf, AssetIdOf, AssetBalanceOf};
+use codec::FullCodec;
+use frame_support::{
+	traits::tokens::{BalanceConversion, fungibles::{Inspect, Mutate}},
+	unsigned::TransactionValidityError,
+};
+use sp_runtime::{
+	traits::{DispatchInfoOf, One, PostDispatchInfoOf, Saturating, Zero},
+	transaction_validity::InvalidTransaction,
+};
+use sp_std::{fmt::Debug, marker::PhantomData};
+
+/// Handle withdrawing, refunding and depositing of transaction fees in assets.
+pub trait OnChargeAssetTransaction<T: Config> {
+	/// The type used to identify the asset in which the fee is paid.
+	type AssetId: FullCodec + Copy + Debug + Default + Eq;
+	/// The type used to carry the withdrawn fee from pre- to post-dispatch.
+	type LiquidityInfo;
+
+	/// Before the transaction is executed the payment of the transaction fees
+	/// need to be secured.
+	///
+	/// Note: The `fee` already includes the `tip` and is given in the native currency.
+	fn withdraw_fee(
+		who: &T::AccountId,
+		call: &T::Call,
+		dispatch_info: &DispatchInfoOf<T::Call>,
+		asset_id: Self::AssetId,
+		fee: BalanceOf<T>,
+		tip: BalanceOf<T>,
+	) -> Result<Self::LiquidityInfo, TransactionValidityError>;
+
+	/// After the transaction was executed the actual fee can be calculated.
+	/// This function should refund any overpaid fees and optionally deposit
+	/// the corrected amount.
+	///
+	/// Note: The `fee` already includes the `tip` and is given in the native currency.
+	fn correct_and_deposit_fee(
+		who: &T::AccountId,
+		dispatch_info: &DispatchInfoOf<T::Call>,
+		post_info: &PostDispatchInfoOf<T::Call>,
+		corrected_fee: BalanceOf<T>,
+		tip: BalanceOf<T>,
+		already_withdrawn: Self::LiquidityInfo,
+	) -> Result<(), TransactionValidityError>;
+}
+
+/// Handles the fees collected in an asset. By the time the handler is called, the fees have
+/// already been burned from the account that paid them.
+pub trait HandleCredit<AccountId, B: Inspect<AccountId>> {
+	/// Handle `amount` of `asset` that was collected as a transaction fee, e.g. by minting it
+	/// into the account of the block author.
+	fn handle_credit(asset: B::AssetId, amount: B::Balance);
+}
+
+/// Default implementation: the collected fees stay burned.
+impl<AccountId, B: Inspect<AccountId>> HandleCredit<AccountId, B> for () {
+	fn handle_credit(_asset: B::AssetId, _amount: B::Balance) {}
+}
+
+/// Implements the asset transaction payment for a module implementing the `fungibles` traits
+/// (eg. the pallet_assets), using a conversion from the native fee to the asset (implementing
+/// `BalanceConversion`) and a credit handler (implementing `HandleCredit`).
+pub struct FungiblesAdapter<CON, HC>(PhantomData<(CON, HC)>);
+
+/// Default implementation for a `fungibles` implementation and a credit handler.
+impl<T, CON, HC> OnChargeAssetTransaction<T> for FungiblesAdapter<CON, HC>
+where
+	T: Config,
+	CON: BalanceConversion<BalanceOf<T>, AssetIdOf<T>, AssetBalanceOf<T>>,
+	HC: HandleCredit<T::AccountId, T::Fungibles>,
+{
+	type AssetId = AssetIdOf<T>;
+	type LiquidityInfo = (AssetIdOf<T>, AssetBalanceOf<T>);
+
+	/// Convert the predicted fee into the asset and burn it from the transaction origin.
+	///
+	/// The origin must be referenced in `frame_system`, so that accounts which hold assets
+	/// without being otherwise provided for (e.g. zombies of `pallet_assets`) cannot pay.
+	///
+	/// Note: The `fee` already includes the `tip`.
+	fn withdraw_fee(
+		who: &T::AccountId,
+		_call: &T::Call,
+		_info: &DispatchInfoOf<T::Call>,
+		asset_id: Self::AssetId,
+		fee: BalanceOf<T>,
+		_tip: BalanceOf<T>,
+	) -> Result<Self::LiquidityInfo, TransactionValidityError> {
+		if frame_system::Module::<T>::refs(who).is_zero() {
+			return Err(InvalidTransaction::Payment.into());
+		}
+		let converted_fee = convert_fee::<T, CON>(fee, asset_id)?;
+		// The origin must be kept alive, as it does when paying in the native currency.
+		if converted_fee > T::Fungibles::reducible_balance(asset_id, who, true) {
+			return Err(InvalidTransaction::Payment.into());
+		}
+		T::Fungibles::burn_from(asset_id, who, converted_fee)
+			.map(|burned| (asset_id, burned))
+			.map_err(|_| InvalidTransaction::Payment.into())
+	}
+
+	/// Refund any overpaid fee and hand the rest over to the `[HandleCredit]` implementation.
+	///
+	/// Note: The `fee` already includes the `tip`.
+	fn correct_and_deposit_fee(
+		who: &T::AccountId,
+		_dispatch_info: &DispatchInfoOf<T::Call>,
+		_post_info: &PostDispatchInfoOf<T::Call>,
+		corrected_fee: BalanceOf<T>,
+		_tip: BalanceOf<T>,
+		already_withdrawn: Self::LiquidityInfo,
+	) -> Result<(), TransactionValidityError> {
+		let (asset_id, paid) = already_withdrawn;
+		let converted_fee = convert_fee::<T, CON>(corrected_fee, asset_id)?.min(paid);
+		// Calculate how much refund we should return
+		let refund_amount = paid.saturating_sub(converted_fee);
+		// refund to the the account that paid the fees. If this fails, the
+		// asset balance of the account would fall below the asset's minimum
+		// balance. In that case we don't refund anything.
+		let refunded = if refund_amount.is_zero() ||
+			T::Fungibles::mint_into(asset_id, who, refund_amount).is_err()
+		{
+			Zero::zero()
+		} else {
+			refund_amount
+		};
+		HC::handle_credit(asset_id, paid.saturating_sub(refunded));
+		Ok(())
+	}
+}
+
+/// Convert a native `fee` into an amount of `asset_id`.
+///
+/// We don't know the precision of the asset, so a non-zero fee which converts to less than one
+/// unit of the asset is rounded up to one unit.
+fn convert_fee<T, CON>(
+	fee: BalanceOf<T>,
+	asset_id: AssetIdOf<T>,
+) -> Result<AssetBalanceOf<T>, TransactionValidityError>
+where
+	T: Config,
+	CON: BalanceConversion<BalanceOf<T>, AssetIdOf<T>, AssetBalanceOf<T>>,
+{
+	let min_converted_fee = if fee.is_zero() { Zero::zero() } else { One::one() };
+	CON::to_asset_balance(fee, asset_id)
+		.map(|converted| converted.max(min_converted_fee))
+		.map_err(|_| InvalidTransaction::Payment.into())
+}
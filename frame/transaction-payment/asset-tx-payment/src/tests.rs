@@ -0,0 +1,370 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tests for the asset transaction payment module.
+
+use super::*;
+use frame_support::{
+	assert_ok, impl_outer_dispatch, impl_outer_origin, impl_outer_event, parameter_types,
+	traits::Get,
+	weights::{
+		DispatchClass, DispatchInfo, PostDispatchInfo, Pays, Weight,
+		WeightToFeePolynomial, WeightToFeeCoefficients, WeightToFeeCoefficient,
+	},
+};
+use pallet_balances::Call as BalancesCall;
+use pallet_transaction_payment::CurrencyAdapter;
+use sp_core::H256;
+use sp_runtime::{
+	Perbill,
+	testing::Header,
+	traits::{BlakeTwo256, ConvertInto, IdentityLookup},
+	transaction_validity::InvalidTransaction,
+};
+use std::cell::RefCell;
+use smallvec::smallvec;
+
+const CALL: &<Runtime as frame_system::Config>::Call =
+	&Call::Balances(BalancesCall::transfer(2, 69));
+
+impl_outer_dispatch! {
+	pub enum Call for Runtime where origin: Origin {
+		pallet_balances::Balances,
+		frame_system::System,
+	}
+}
+
+impl_outer_event! {
+	pub enum Event for Runtime {
+		system<T>,
+		pallet_balances<T>,
+		pallet_assets<T>,
+	}
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Runtime;
+
+use frame_system as system;
+impl_outer_origin!{
+	pub enum Origin for Runtime {}
+}
+
+thread_local! {
+	static EXTRINSIC_BASE_WEIGHT: RefCell<u64> = RefCell::new(0);
+	static FEES_CREDITED: RefCell<u64> = RefCell::new(0);
+}
+
+pub struct BlockWeights;
+impl Get<frame_system::limits::BlockWeights> for BlockWeights {
+	fn get() -> frame_system::limits::BlockWeights {
+		frame_system::limits::BlockWeights::builder()
+			.base_block(0)
+			.for_class(DispatchClass::all(), |weights| {
+				weights.base_extrinsic = EXTRINSIC_BASE_WEIGHT.with(|v| *v.borrow()).into();
+			})
+			.for_class(DispatchClass::non_mandatory(), |weights| {
+				weights.max_total = 1024.into();
+			})
+			.build_or_panic()
+	}
+}
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const TransactionByteFee: u64 = 1;
+}
+
+impl frame_system::Config for Runtime {
+	type BaseCallFilter = ();
+	type BlockWeights = BlockWeights;
+	type BlockLength = ();
+	type DbWeight = ();
+	type Origin = Origin;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Call = Call;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = Event;
+	type BlockHashCount = BlockHashCount;
+	type Version = ();
+	type PalletInfo = ();
+	type AccountData = pallet_balances::AccountData<u64>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+}
+
+parameter_types! {
+	pub const ExistentialDeposit: u64 = 1;
+}
+
+impl pallet_balances::Config for Runtime {
+	type Balance = u64;
+	type Event = Event;
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+	type MaxLocks = ();
+	type WeightInfo = ();
+}
+
+pub struct WeightToFee;
+impl WeightToFeePolynomial for WeightToFee {
+	type Balance = u64;
+
+	fn polynomial() -> WeightToFeeCoefficients<Self::Balance> {
+		smallvec![WeightToFeeCoefficient {
+			degree: 1,
+			coeff_frac: Perbill::zero(),
+			coeff_integer: 1,
+			negative: false,
+		}]
+	}
+}
+
+impl pallet_transaction_payment::Config for Runtime {
+	type OnChargeTransaction = CurrencyAdapter<Balances, ()>;
+	type TransactionByteFee = TransactionByteFee;
+	type WeightToFee = WeightToFee;
+	type FeeMultiplierUpdate = ();
+}
+
+parameter_types! {
+	pub const AssetDepositBase: u64 = 1;
+	pub const AssetDepositPerZombie: u64 = 1;
+}
+
+impl pallet_assets::Config for Runtime {
+	type Event = Event;
+	type Balance = u64;
+	type AssetId = u32;
+	type Currency = Balances;
+	type ForceOrigin = frame_system::EnsureRoot<u64>;
+	type AssetDepositBase = AssetDepositBase;
+	type AssetDepositPerZombie = AssetDepositPerZombie;
+	type WeightInfo = ();
+}
+
+/// Records the asset fees handed over after dispatch.
+pub struct RecordCredit;
+impl HandleCredit<u64, Assets> for RecordCredit {
+	fn handle_credit(_asset: u32, amount: u64) {
+		FEES_CREDITED.with(|v| *v.borrow_mut() += amount);
+	}
+}
+
+impl Config for Runtime {
+	type Fungibles = Assets;
+	type OnChargeAssetTransaction = FungiblesAdapter<
+		pallet_assets::BalanceToAssetBalance<Balances, Runtime, ConvertInto>,
+		RecordCredit,
+	>;
+}
+
+type Balances = pallet_balances::Module<Runtime>;
+type System = frame_system::Module<Runtime>;
+type Assets = pallet_assets::Module<Runtime>;
+
+/// The asset used to pay fees in the tests, owned by account 42.
+const ASSET_ID: u32 = 1;
+/// The minimum balance of `ASSET_ID`; one unit of fee is worth this much of the asset.
+const MIN_BALANCE: u64 = 2;
+
+fn new_test_ext(base_weight: u64) -> sp_io::TestExternalities {
+	EXTRINSIC_BASE_WEIGHT.with(|v| *v.borrow_mut() = base_weight);
+	FEES_CREDITED.with(|v| *v.borrow_mut() = 0);
+	let mut t = frame_system::GenesisConfig::default().build_storage::<Runtime>().unwrap();
+	pallet_balances::GenesisConfig::<Runtime> {
+		balances: vec![(1, 100), (2, 200), (42, 100)],
+	}.assimilate_storage(&mut t).unwrap();
+	let mut ext: sp_io::TestExternalities = t.into();
+	ext.execute_with(|| {
+		assert_ok!(Assets::force_create(Origin::root(), ASSET_ID, 42, 10, MIN_BALANCE));
+	});
+	ext
+}
+
+fn fees_credited() -> u64 {
+	FEES_CREDITED.with(|v| *v.borrow())
+}
+
+/// create a transaction info struct from weight. Handy to avoid building the whole struct.
+fn info_from_weight(w: Weight) -> DispatchInfo {
+	// pays_fee: Pays::Yes -- class: DispatchClass::Normal
+	DispatchInfo { weight: w, ..Default::default() }
+}
+
+fn post_info_from_weight(w: Weight) -> PostDispatchInfo {
+	PostDispatchInfo {
+		actual_weight: Some(w),
+		pays_fee: Default::default(),
+	}
+}
+
+fn default_post_info() -> PostDispatchInfo {
+	PostDispatchInfo {
+		actual_weight: None,
+		pays_fee: Default::default(),
+	}
+}
+
+#[test]
+fn transaction_payment_in_native_possible() {
+	new_test_ext(5).execute_with(|| {
+		let len = 10;
+		let pre = ChargeAssetTxPayment::<Runtime>::from(0, None)
+			.pre_dispatch(&1, CALL, &info_from_weight(5), len)
+			.unwrap();
+		assert_eq!(Balances::free_balance(1), 100 - 5 - 5 - 10);
+
+		assert_ok!(ChargeAssetTxPayment::<Runtime>::post_dispatch(
+			pre, &info_from_weight(5), &default_post_info(), len, &Ok(()),
+		));
+		assert_eq!(Balances::free_balance(1), 100 - 5 - 5 - 10);
+
+		let pre = ChargeAssetTxPayment::<Runtime>::from(5 /* tipped */, None)
+			.pre_dispatch(&2, CALL, &info_from_weight(100), len)
+			.unwrap();
+		assert_eq!(Balances::free_balance(2), 200 - 5 - 10 - 100 - 5);
+
+		assert_ok!(ChargeAssetTxPayment::<Runtime>::post_dispatch(
+			pre, &info_from_weight(100), &post_info_from_weight(50), len, &Ok(()),
+		));
+		assert_eq!(Balances::free_balance(2), 200 - 5 - 10 - 50 - 5);
+		assert_eq!(fees_credited(), 0);
+	});
+}
+
+#[test]
+fn transaction_payment_in_asset_possible() {
+	new_test_ext(5).execute_with(|| {
+		assert_ok!(Assets::mint(Origin::signed(42), ASSET_ID, 1, 1000));
+		let len = 10;
+		let pre = ChargeAssetTxPayment::<Runtime>::from(0, Some(ASSET_ID))
+			.pre_dispatch(&1, CALL, &info_from_weight(5), len)
+			.unwrap();
+		// the fee of 5 base, 5 weight and 10 length is converted at the ratio of the minimum
+		// balances.
+		let fee = (5 + 5 + 10) * MIN_BALANCE;
+		assert_eq!(Assets::balance(ASSET_ID, 1), 1000 - fee);
+		// the native balance is untouched.
+		assert_eq!(Balances::free_balance(1), 100);
+
+		assert_ok!(ChargeAssetTxPayment::<Runtime>::post_dispatch(
+			pre, &info_from_weight(5), &default_post_info(), len, &Ok(()),
+		));
+		assert_eq!(Assets::balance(ASSET_ID, 1), 1000 - fee);
+		assert_eq!(fees_credited(), fee);
+	});
+}
+
+#[test]
+fn transaction_payment_in_asset_refunds_unused_weight() {
+	new_test_ext(5).execute_with(|| {
+		assert_ok!(Assets::mint(Origin::signed(42), ASSET_ID, 2, 1000));
+		let len = 10;
+		let pre = ChargeAssetTxPayment::<Runtime>::from(5 /* tipped */, Some(ASSET_ID))
+			.pre_dispatch(&2, CALL, &info_from_weight(100), len)
+			.unwrap();
+		assert_eq!(Assets::balance(ASSET_ID, 2), 1000 - (5 + 10 + 100 + 5) * MIN_BALANCE);
+
+		assert_ok!(ChargeAssetTxPayment::<Runtime>::post_dispatch(
+			pre, &info_from_weight(100), &post_info_from_weight(50), len, &Ok(()),
+		));
+		// the fee for the 50 units of unused weight is refunded in the asset.
+		let fee = (5 + 10 + 50 + 5) * MIN_BALANCE;
+		assert_eq!(Assets::balance(ASSET_ID, 2), 1000 - fee);
+		assert_eq!(Balances::free_balance(2), 200);
+		assert_eq!(fees_credited(), fee);
+	});
+}
+
+#[test]
+fn transaction_payment_in_asset_requires_funds() {
+	new_test_ext(5).execute_with(|| {
+		let len = 10;
+		let error: TransactionValidityError = InvalidTransaction::Payment.into();
+		// the fee is 40 units of the asset.
+		assert_ok!(Assets::mint(Origin::signed(42), ASSET_ID, 1, 40));
+		// paying would take the account below the asset's minimum balance.
+		assert_eq!(
+			ChargeAssetTxPayment::<Runtime>::from(0, Some(ASSET_ID))
+				.pre_dispatch(&1, CALL, &info_from_weight(5), len)
+				.err(),
+			Some(error),
+		);
+		// an unknown asset can't be used to pay.
+		assert_eq!(
+			ChargeAssetTxPayment::<Runtime>::from(0, Some(ASSET_ID + 1))
+				.validate(&1, CALL, &info_from_weight(5), len)
+				.err(),
+			Some(error),
+		);
+		assert_ok!(Assets::mint(Origin::signed(42), ASSET_ID, 1, MIN_BALANCE));
+		assert!(
+			ChargeAssetTxPayment::<Runtime>::from(0, Some(ASSET_ID))
+				.pre_dispatch(&1, CALL, &info_from_weight(5), len)
+				.is_ok()
+		);
+		assert_eq!(Assets::balance(ASSET_ID, 1), MIN_BALANCE);
+		assert_eq!(Balances::free_balance(1), 100);
+	});
+}
+
+#[test]
+fn zombie_account_cannot_pay_in_asset() {
+	new_test_ext(5).execute_with(|| {
+		let len = 10;
+		// account 3 has no native funds, so it only holds the asset as a zombie.
+		assert_ok!(Assets::mint(Origin::signed(42), ASSET_ID, 3, 1000));
+		assert_eq!(System::refs(&3), 0);
+		assert_eq!(
+			ChargeAssetTxPayment::<Runtime>::from(0, Some(ASSET_ID))
+				.validate(&3, CALL, &info_from_weight(5), len)
+				.err(),
+			Some(InvalidTransaction::Payment.into()),
+		);
+		assert_eq!(Assets::balance(ASSET_ID, 3), 1000);
+	});
+}
+
+#[test]
+fn free_transaction_charges_no_asset() {
+	new_test_ext(100).execute_with(|| {
+		let len = 100;
+		// This is a completely free (and thus wholly insecure/DoS-ridden) transaction.
+		let operational_transaction = DispatchInfo {
+			weight: 0,
+			class: DispatchClass::Operational,
+			pays_fee: Pays::No,
+		};
+		// account 3 has neither native funds nor the asset.
+		let pre = ChargeAssetTxPayment::<Runtime>::from(0, Some(ASSET_ID))
+			.pre_dispatch(&3, CALL, &operational_transaction, len)
+			.unwrap();
+		assert_ok!(ChargeAssetTxPayment::<Runtime>::post_dispatch(
+			pre, &operational_transaction, &default_post_info(), len, &Ok(()),
+		));
+		assert_eq!(Assets::balance(ASSET_ID, 3), 0);
+		assert_eq!(fees_credited(), 0);
+	});
+}
@@ -0,0 +1,40 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Supporting types for try-runtime, testing and dry-running commands.
+//!
+//! This API should be implemented by the runtime of a node that wants to use the `try-runtime`
+//! CLI subcommand. It is only meant to be compiled in when the `try-runtime` feature is enabled,
+//! and never to be used on-chain.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use frame_support::weights::Weight;
+
+sp_api::decl_runtime_apis! {
+	/// Runtime api for testing the execution of a runtime upgrade.
+	pub trait TryRuntime {
+		/// Dry run the runtime upgrade.
+		///
+		/// All `OnRuntimeUpgrade` implementations of the runtime are executed, surrounded by their
+		/// `pre_upgrade` and `post_upgrade` checks.
+		///
+		/// Returns the weight consumed by the upgrade and the maximum weight of a block, or the
+		/// error of the first failing check.
+		fn on_runtime_upgrade() -> Result<(Weight, Weight), sp_runtime::RuntimeString>;
+	}
+}
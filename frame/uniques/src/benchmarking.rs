@@ -0,0 +1,548 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Uniques pallet benchmarking.
+
+use super::*;
+use sp_std::prelude::*;
+use sp_runtime::traits::Bounded;
+use frame_system::RawOrigin as SystemOrigin;
+use frame_benchmarking::{benchmarks, account, whitelisted_caller, whitelist_account};
+
+use crate::Module as Uniques;
+
+const SEED: u32 = 0;
+
+fn create_class<T: Config>()
+	-> (T::ClassId, T::AccountId, <T::Lookup as StaticLookup>::Source)
+{
+	let caller: T::AccountId = whitelisted_caller();
+	let caller_lookup = T::Lookup::unlookup(caller.clone());
+	let class = Default::default();
+	T::Currency::make_free_balance_be(&caller, DepositBalanceOf::<T>::max_value());
+	assert!(Uniques::<T>::create(
+		SystemOrigin::Signed(caller.clone()).into(),
+		class,
+		caller_lookup.clone(),
+	).is_ok());
+	(class, caller, caller_lookup)
+}
+
+fn add_class_metadata<T: Config>()
+	-> (T::AccountId, <T::Lookup as StaticLookup>::Source)
+{
+	let caller = Class::<T>::get(T::ClassId::default()).unwrap().owner;
+	if caller != whitelisted_caller() {
+		whitelist_account!(caller);
+	}
+	let caller_lookup = T::Lookup::unlookup(caller.clone());
+	assert!(Uniques::<T>::set_class_metadata(
+		SystemOrigin::Signed(caller.clone()).into(),
+		Default::default(),
+		vec![0; T::StringLimit::get() as usize],
+		false,
+	).is_ok());
+	(caller, caller_lookup)
+}
+
+fn mint_instance<T: Config>(index: u16)
+	-> (T::InstanceId, T::AccountId, <T::Lookup as StaticLookup>::Source)
+	where T::InstanceId: From<u16>
+{
+	let caller = Class::<T>::get(T::ClassId::default()).unwrap().admin;
+	if caller != whitelisted_caller() {
+		whitelist_account!(caller);
+	}
+	let caller_lookup = T::Lookup::unlookup(caller.clone());
+	let instance = index.into();
+	assert!(Uniques::<T>::mint(
+		SystemOrigin::Signed(caller.clone()).into(),
+		Default::default(),
+		instance,
+		caller_lookup.clone(),
+	).is_ok());
+	(instance, caller, caller_lookup)
+}
+
+fn add_instance_metadata<T: Config>(instance: T::InstanceId)
+	-> (T::AccountId, <T::Lookup as StaticLookup>::Source)
+{
+	let caller = Class::<T>::get(T::ClassId::default()).unwrap().owner;
+	if caller != whitelisted_caller() {
+		whitelist_account!(caller);
+	}
+	let caller_lookup = T::Lookup::unlookup(caller.clone());
+	assert!(Uniques::<T>::set_metadata(
+		SystemOrigin::Signed(caller.clone()).into(),
+		Default::default(),
+		instance,
+		vec![0; T::StringLimit::get() as usize],
+		false,
+	).is_ok());
+	(caller, caller_lookup)
+}
+
+fn add_instance_attribute<T: Config>(instance: T::InstanceId)
+	-> (Vec<u8>, T::AccountId, <T::Lookup as StaticLookup>::Source)
+{
+	let caller = Class::<T>::get(T::ClassId::default()).unwrap().owner;
+	if caller != whitelisted_caller() {
+		whitelist_account!(caller);
+	}
+	let caller_lookup = T::Lookup::unlookup(caller.clone());
+	let key = vec![0; T::KeyLimit::get() as usize];
+	assert!(Uniques::<T>::set_attribute(
+		SystemOrigin::Signed(caller.clone()).into(),
+		Default::default(),
+		Some(instance),
+		key.clone(),
+		vec![0; T::ValueLimit::get() as usize],
+	).is_ok());
+	(key, caller, caller_lookup)
+}
+
+fn assert_last_event<T: Config>(generic_event: <T as Config>::Event) {
+	let events = frame_system::Module::<T>::events();
+	let system_event: <T as frame_system::Config>::Event = generic_event.into();
+	// compare to the last event record
+	let frame_system::EventRecord { event, .. } = &events[events.len() - 1];
+	assert_eq!(event, &system_event);
+}
+
+benchmarks! {
+	where_clause {
+		where T::InstanceId: From<u16>,
+	}
+
+	_ { }
+
+	create {
+		let caller: T::AccountId = whitelisted_caller();
+		let caller_lookup = T::Lookup::unlookup(caller.clone());
+		T::Currency::make_free_balance_be(&caller, DepositBalanceOf::<T>::max_value());
+	}: _(SystemOrigin::Signed(caller.clone()), Default::default(), caller_lookup)
+	verify {
+		assert_last_event::<T>(RawEvent::Created(Default::default(), caller.clone(), caller).into());
+	}
+
+	force_create {
+		let caller: T::AccountId = whitelisted_caller();
+		let caller_lookup = T::Lookup::unlookup(caller.clone());
+	}: _(SystemOrigin::Root, Default::default(), caller_lookup, true)
+	verify {
+		assert_last_event::<T>(RawEvent::ForceCreated(Default::default(), caller).into());
+	}
+
+	destroy {
+		let n in 0 .. 1_000;
+		let m in 0 .. 1_000;
+		let a in 0 .. 1_000;
+
+		let (class, caller, _) = create_class::<T>();
+		add_class_metadata::<T>();
+		for i in 0..n {
+			mint_instance::<T>(i as u16);
+		}
+		for i in 0..m {
+			add_instance_metadata::<T>((i as u16).into());
+		}
+		for i in 0..a {
+			add_instance_attribute::<T>((i as u16).into());
+		}
+		let witness = Class::<T>::get(class).unwrap().destroy_witness();
+	}: _(SystemOrigin::Signed(caller), class, witness)
+	verify {
+		assert_last_event::<T>(RawEvent::Destroyed(class).into());
+	}
+
+	mint {
+		let (class, caller, caller_lookup) = create_class::<T>();
+		let instance = Default::default();
+	}: _(SystemOrigin::Signed(caller.clone()), class, instance, caller_lookup)
+	verify {
+		assert_last_event::<T>(RawEvent::Issued(class, instance, caller).into());
+	}
+
+	burn {
+		let a in 0 .. 1_000;
+
+		let (class, caller, caller_lookup) = create_class::<T>();
+		let (instance, ..) = mint_instance::<T>(0);
+		// Every attribute of the class is one of the burned instance.
+		for i in 0..a {
+			Uniques::<T>::set_attribute(
+				SystemOrigin::Signed(caller.clone()).into(),
+				class,
+				Some(instance),
+				i.encode(),
+				vec![0; T::ValueLimit::get() as usize],
+			)?;
+		}
+		let attributes = Class::<T>::get(class).unwrap().attributes;
+	}: _(SystemOrigin::Signed(caller.clone()), class, instance, Some(caller_lookup), attributes)
+	verify {
+		assert_last_event::<T>(RawEvent::Burned(class, instance, caller).into());
+	}
+
+	transfer {
+		let (class, caller, _) = create_class::<T>();
+		let (instance, ..) = mint_instance::<T>(0);
+
+		let target: T::AccountId = account("target", 0, SEED);
+		let target_lookup = T::Lookup::unlookup(target.clone());
+	}: _(SystemOrigin::Signed(caller.clone()), class, instance, target_lookup)
+	verify {
+		assert_last_event::<T>(RawEvent::Transferred(class, instance, caller, target).into());
+	}
+
+	redeposit {
+		let i in 0 .. 5_000;
+		let (class, caller, caller_lookup) = create_class::<T>();
+		let instances = (0..i).map(|x| mint_instance::<T>(x as u16).0).collect::<Vec<_>>();
+		Uniques::<T>::force_asset_status(
+			SystemOrigin::Root.into(),
+			class,
+			caller_lookup.clone(),
+			caller_lookup.clone(),
+			caller_lookup.clone(),
+			caller_lookup.clone(),
+			true,
+			false,
+		)?;
+	}: _(SystemOrigin::Signed(caller.clone()), class, instances.clone())
+	verify {
+		assert_last_event::<T>(RawEvent::Redeposited(class, instances).into());
+	}
+
+	freeze {
+		let (class, caller, _) = create_class::<T>();
+		let (instance, ..) = mint_instance::<T>(0);
+	}: _(SystemOrigin::Signed(caller.clone()), class, instance)
+	verify {
+		assert_last_event::<T>(RawEvent::Frozen(class, instance).into());
+	}
+
+	thaw {
+		let (class, caller, _) = create_class::<T>();
+		let (instance, ..) = mint_instance::<T>(0);
+		Uniques::<T>::freeze(
+			SystemOrigin::Signed(caller.clone()).into(),
+			class,
+			instance,
+		)?;
+	}: _(SystemOrigin::Signed(caller.clone()), class, instance)
+	verify {
+		assert_last_event::<T>(RawEvent::Thawed(class, instance).into());
+	}
+
+	freeze_class {
+		let (class, caller, _) = create_class::<T>();
+	}: _(SystemOrigin::Signed(caller.clone()), class)
+	verify {
+		assert_last_event::<T>(RawEvent::ClassFrozen(class).into());
+	}
+
+	thaw_class {
+		let (class, caller, _) = create_class::<T>();
+		let origin = SystemOrigin::Signed(caller.clone()).into();
+		Uniques::<T>::freeze_class(origin, class)?;
+	}: _(SystemOrigin::Signed(caller.clone()), class)
+	verify {
+		assert_last_event::<T>(RawEvent::ClassThawed(class).into());
+	}
+
+	transfer_ownership {
+		let (class, caller, _) = create_class::<T>();
+		let target: T::AccountId = account("target", 0, SEED);
+		let target_lookup = T::Lookup::unlookup(target.clone());
+		T::Currency::make_free_balance_be(&target, T::Currency::minimum_balance());
+	}: _(SystemOrigin::Signed(caller), class, target_lookup)
+	verify {
+		assert_last_event::<T>(RawEvent::OwnerChanged(class, target).into());
+	}
+
+	set_team {
+		let (class, caller, _) = create_class::<T>();
+		let target0 = T::Lookup::unlookup(account("target", 0, SEED));
+		let target1 = T::Lookup::unlookup(account("target", 1, SEED));
+		let target2 = T::Lookup::unlookup(account("target", 2, SEED));
+	}: _(SystemOrigin::Signed(caller), class, target0.clone(), target1.clone(), target2.clone())
+	verify {
+		assert_last_event::<T>(RawEvent::TeamChanged(
+			class,
+			account("target", 0, SEED),
+			account("target", 1, SEED),
+			account("target", 2, SEED),
+		).into());
+	}
+
+	force_asset_status {
+		let (class, _, caller_lookup) = create_class::<T>();
+	}: _(
+		SystemOrigin::Root,
+		class,
+		caller_lookup.clone(),
+		caller_lookup.clone(),
+		caller_lookup.clone(),
+		caller_lookup,
+		true,
+		false
+	)
+	verify {
+		assert_last_event::<T>(RawEvent::AssetStatusChanged(class).into());
+	}
+
+	set_attribute {
+		let key = vec![0u8; T::KeyLimit::get() as usize];
+		let value = vec![0u8; T::ValueLimit::get() as usize];
+
+		let (class, caller, _) = create_class::<T>();
+		let (instance, ..) = mint_instance::<T>(0);
+		add_instance_metadata::<T>(instance);
+	}: _(SystemOrigin::Signed(caller), class, Some(instance), key.clone(), value.clone())
+	verify {
+		assert_last_event::<T>(RawEvent::AttributeSet(class, Some(instance), key, value).into());
+	}
+
+	clear_attribute {
+		let (class, caller, _) = create_class::<T>();
+		let (instance, ..) = mint_instance::<T>(0);
+		add_instance_metadata::<T>(instance);
+		let (key, ..) = add_instance_attribute::<T>(instance);
+	}: _(SystemOrigin::Signed(caller), class, Some(instance), key.clone())
+	verify {
+		assert_last_event::<T>(RawEvent::AttributeCleared(class, Some(instance), key).into());
+	}
+
+	set_metadata {
+		let data = vec![0u8; T::StringLimit::get() as usize];
+
+		let (class, caller, _) = create_class::<T>();
+		let (instance, ..) = mint_instance::<T>(0);
+	}: _(SystemOrigin::Signed(caller), class, instance, data.clone(), false)
+	verify {
+		assert_last_event::<T>(RawEvent::MetadataSet(class, instance, data, false).into());
+	}
+
+	clear_metadata {
+		let (class, caller, _) = create_class::<T>();
+		let (instance, ..) = mint_instance::<T>(0);
+		add_instance_metadata::<T>(instance);
+	}: _(SystemOrigin::Signed(caller), class, instance)
+	verify {
+		assert_last_event::<T>(RawEvent::MetadataCleared(class, instance).into());
+	}
+
+	set_class_metadata {
+		let data = vec![0u8; T::StringLimit::get() as usize];
+
+		let (class, caller, _) = create_class::<T>();
+	}: _(SystemOrigin::Signed(caller), class, data.clone(), false)
+	verify {
+		assert_last_event::<T>(RawEvent::ClassMetadataSet(class, data, false).into());
+	}
+
+	clear_class_metadata {
+		let (class, caller, _) = create_class::<T>();
+		add_class_metadata::<T>();
+	}: _(SystemOrigin::Signed(caller), class)
+	verify {
+		assert_last_event::<T>(RawEvent::ClassMetadataCleared(class).into());
+	}
+
+	approve_transfer {
+		let (class, caller, _) = create_class::<T>();
+		let (instance, ..) = mint_instance::<T>(0);
+		let delegate: T::AccountId = account("delegate", 0, SEED);
+		let delegate_lookup = T::Lookup::unlookup(delegate.clone());
+	}: _(SystemOrigin::Signed(caller.clone()), class, instance, delegate_lookup)
+	verify {
+		assert_last_event::<T>(RawEvent::ApprovedTransfer(class, instance, caller, delegate).into());
+	}
+
+	cancel_approval {
+		let (class, caller, _) = create_class::<T>();
+		let (instance, ..) = mint_instance::<T>(0);
+		let delegate: T::AccountId = account("delegate", 0, SEED);
+		let delegate_lookup = T::Lookup::unlookup(delegate.clone());
+		let origin = SystemOrigin::Signed(caller.clone()).into();
+		Uniques::<T>::approve_transfer(origin, class, instance, delegate_lookup.clone())?;
+	}: _(SystemOrigin::Signed(caller.clone()), class, instance, Some(delegate_lookup))
+	verify {
+		assert_last_event::<T>(RawEvent::ApprovalCancelled(class, instance, caller, delegate).into());
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::mock::{new_test_ext, Test};
+
+	#[test]
+	fn create() {
+		new_test_ext().execute_with(|| {
+			assert!(test_benchmark_create::<Test>().is_ok());
+		});
+	}
+
+	#[test]
+	fn force_create() {
+		new_test_ext().execute_with(|| {
+			assert!(test_benchmark_force_create::<Test>().is_ok());
+		});
+	}
+
+	#[test]
+	fn destroy() {
+		new_test_ext().execute_with(|| {
+			assert!(test_benchmark_destroy::<Test>().is_ok());
+		});
+	}
+
+	#[test]
+	fn mint() {
+		new_test_ext().execute_with(|| {
+			assert!(test_benchmark_mint::<Test>().is_ok());
+		});
+	}
+
+	#[test]
+	fn burn() {
+		new_test_ext().execute_with(|| {
+			assert!(test_benchmark_burn::<Test>().is_ok());
+		});
+	}
+
+	#[test]
+	fn transfer() {
+		new_test_ext().execute_with(|| {
+			assert!(test_benchmark_transfer::<Test>().is_ok());
+		});
+	}
+
+	#[test]
+	fn redeposit() {
+		new_test_ext().execute_with(|| {
+			assert!(test_benchmark_redeposit::<Test>().is_ok());
+		});
+	}
+
+	#[test]
+	fn freeze() {
+		new_test_ext().execute_with(|| {
+			assert!(test_benchmark_freeze::<Test>().is_ok());
+		});
+	}
+
+	#[test]
+	fn thaw() {
+		new_test_ext().execute_with(|| {
+			assert!(test_benchmark_thaw::<Test>().is_ok());
+		});
+	}
+
+	#[test]
+	fn freeze_class() {
+		new_test_ext().execute_with(|| {
+			assert!(test_benchmark_freeze_class::<Test>().is_ok());
+		});
+	}
+
+	#[test]
+	fn thaw_class() {
+		new_test_ext().execute_with(|| {
+			assert!(test_benchmark_thaw_class::<Test>().is_ok());
+		});
+	}
+
+	#[test]
+	fn transfer_ownership() {
+		new_test_ext().execute_with(|| {
+			assert!(test_benchmark_transfer_ownership::<Test>().is_ok());
+		});
+	}
+
+	#[test]
+	fn set_team() {
+		new_test_ext().execute_with(|| {
+			assert!(test_benchmark_set_team::<Test>().is_ok());
+		});
+	}
+
+	#[test]
+	fn force_asset_status() {
+		new_test_ext().execute_with(|| {
+			assert!(test_benchmark_force_asset_status::<Test>().is_ok());
+		});
+	}
+
+	#[test]
+	fn set_attribute() {
+		new_test_ext().execute_with(|| {
+			assert!(test_benchmark_set_attribute::<Test>().is_ok());
+		});
+	}
+
+	#[test]
+	fn clear_attribute() {
+		new_test_ext().execute_with(|| {
+			assert!(test_benchmark_clear_attribute::<Test>().is_ok());
+		});
+	}
+
+	#[test]
+	fn set_metadata() {
+		new_test_ext().execute_with(|| {
+			assert!(test_benchmark_set_metadata::<Test>().is_ok());
+		});
+	}
+
+	#[test]
+	fn clear_metadata() {
+		new_test_ext().execute_with(|| {
+			assert!(test_benchmark_clear_metadata::<Test>().is_ok());
+		});
+	}
+
+	#[test]
+	fn set_class_metadata() {
+		new_test_ext().execute_with(|| {
+			assert!(test_benchmark_set_class_metadata::<Test>().is_ok());
+		});
+	}
+
+	#[test]
+	fn clear_class_metadata() {
+		new_test_ext().execute_with(|| {
+			assert!(test_benchmark_clear_class_metadata::<Test>().is_ok());
+		});
+	}
+
+	#[test]
+	fn approve_transfer() {
+		new_test_ext().execute_with(|| {
+			assert!(test_benchmark_approve_transfer::<Test>().is_ok());
+		});
+	}
+
+	#[test]
+	fn cancel_approval() {
+		new_test_ext().execute_with(|| {
+			assert!(test_benchmark_cancel_approval::<Test>().is_ok());
+		});
+	}
+}
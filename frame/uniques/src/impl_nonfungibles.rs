@@ -0,0 +1,148 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Implementations of the `nonfungibles` traits for the uniques module.
+
+use super::*;
+use frame_support::traits::tokens::nonfungibles;
+
+impl<T: Config> nonfungibles::Inspect<T::AccountId> for Module<T> {
+	type InstanceId = T::InstanceId;
+	type ClassId = T::ClassId;
+
+	fn owner(class: &Self::ClassId, instance: &Self::InstanceId) -> Option<T::AccountId> {
+		Asset::<T>::get(class, instance).map(|a| a.owner)
+	}
+
+	fn class_owner(class: &Self::ClassId) -> Option<T::AccountId> {
+		Class::<T>::get(class).map(|a| a.owner)
+	}
+
+	/// Returns the attribute value of `instance` of `class` corresponding to `key`.
+	///
+	/// When `key` is empty, we return the instance metadata value.
+	fn attribute(
+		class: &Self::ClassId,
+		instance: &Self::InstanceId,
+		key: &[u8],
+	) -> Option<Vec<u8>> {
+		if key.is_empty() {
+			// We make the empty key map to the instance metadata value.
+			InstanceMetadataOf::<T>::get(class, instance).map(|m| m.data)
+		} else {
+			Attribute::<T>::get(class, (Some(*instance), key)).map(|a| a.0)
+		}
+	}
+
+	/// Returns the attribute value of `class` corresponding to `key`.
+	///
+	/// When `key` is empty, we return the class metadata value.
+	fn class_attribute(class: &Self::ClassId, key: &[u8]) -> Option<Vec<u8>> {
+		if key.is_empty() {
+			// We make the empty key map to the class metadata value.
+			ClassMetadataOf::<T>::get(class).map(|m| m.data)
+		} else {
+			Attribute::<T>::get(class, (None::<T::InstanceId>, key)).map(|a| a.0)
+		}
+	}
+
+	/// Returns `true` if the asset `instance` of `class` may be transferred.
+	///
+	/// Neither the class nor the instance may be frozen.
+	fn can_transfer(class: &Self::ClassId, instance: &Self::InstanceId) -> bool {
+		match (Class::<T>::get(class), Asset::<T>::get(class, instance)) {
+			(Some(cd), Some(id)) => !cd.is_frozen && !id.is_frozen,
+			_ => false,
+		}
+	}
+}
+
+impl<T: Config> nonfungibles::InspectEnumerable<T::AccountId> for Module<T> {
+	fn classes() -> Box<dyn Iterator<Item = Self::ClassId>> {
+		Box::new(Class::<T>::iter_keys())
+	}
+
+	fn instances(class: &Self::ClassId) -> Box<dyn Iterator<Item = Self::InstanceId>> {
+		Box::new(Asset::<T>::iter_key_prefix(class))
+	}
+
+	fn owned(who: &T::AccountId) -> Box<dyn Iterator<Item = (Self::ClassId, Self::InstanceId)>> {
+		Box::new(Account::<T>::iter_key_prefix(who))
+	}
+
+	fn owned_in_class(
+		class: &Self::ClassId,
+		who: &T::AccountId,
+	) -> Box<dyn Iterator<Item = Self::InstanceId>> {
+		let class = *class;
+		Box::new(Account::<T>::iter_key_prefix(who)
+			.filter_map(move |(c, i)| if c == class { Some(i) } else { None }))
+	}
+}
+
+impl<T: Config> nonfungibles::Create<T::AccountId> for Module<T> {
+	/// Create a `class` of nonfungible assets to be owned by `who` and managed by `admin`.
+	///
+	/// The `ClassDeposit` is reserved from `who`.
+	fn create_class(
+		class: &Self::ClassId,
+		who: &T::AccountId,
+		admin: &T::AccountId,
+	) -> DispatchResult {
+		let deposit = T::ClassDeposit::get();
+		Self::do_create_class(*class, who.clone(), admin.clone(), deposit, false)?;
+		Self::deposit_event(RawEvent::Created(*class, who.clone(), admin.clone()));
+		Ok(())
+	}
+}
+
+impl<T: Config> nonfungibles::Mutate<T::AccountId> for Module<T> {
+	fn mint_into(
+		class: &Self::ClassId,
+		instance: &Self::InstanceId,
+		who: &T::AccountId,
+	) -> DispatchResult {
+		Self::do_mint(*class, *instance, who.clone(), |_| Ok(()))
+	}
+
+	fn burn_from(class: &Self::ClassId, instance: &Self::InstanceId) -> DispatchResult {
+		Self::do_burn(*class, *instance, |_, _| Ok(()))
+	}
+
+	fn set_attribute(
+		class: &Self::ClassId,
+		instance: &Self::InstanceId,
+		key: &[u8],
+		value: &[u8],
+	) -> DispatchResult {
+		Self::do_set_attribute(*class, Some(*instance), key.to_vec(), value.to_vec(), None)
+	}
+
+	fn set_class_attribute(class: &Self::ClassId, key: &[u8], value: &[u8]) -> DispatchResult {
+		Self::do_set_attribute(*class, None, key.to_vec(), value.to_vec(), None)
+	}
+}
+
+impl<T: Config> nonfungibles::Transfer<T::AccountId> for Module<T> {
+	fn transfer(
+		class: &Self::ClassId,
+		instance: &Self::InstanceId,
+		destination: &T::AccountId,
+	) -> DispatchResult {
+		Self::do_transfer(*class, *instance, destination.clone(), |_, _| Ok(()))
+	}
+}
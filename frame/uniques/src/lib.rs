@@ -0,0 +1,1484 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # Unique (Assets) Module
+//!
+//! A simple, secure module for dealing with non-fungible assets.
+//!
+//! ## Overview
+//!
+//! The Uniques module provides functionality for management of classes of non-fungible assets,
+//! including:
+//!
+//! * Asset Class Creation and Destruction
+//! * Asset Instance Issuance (Minting) and Burning
+//! * Asset Instance Transferal, including transfers by an approved delegate
+//! * Asset Instance and Class Freezing
+//! * Attributes and Metadata for Asset Instances and Classes
+//!
+//! To use it in your runtime, you need to implement the uniques [`Config`](./trait.Config.html).
+//!
+//! The supported dispatchable functions are documented in the [`Call`](./enum.Call.html) enum.
+//!
+//! ### Terminology
+//!
+//! * **Asset class**: A collection of non-fungible asset instances, with a common Owner and
+//!   management team.
+//! * **Asset instance**: A single, unique asset belonging to an asset class. It is identified by
+//!   its class together with an instance identifier, and is owned by exactly one account.
+//! * **Admin**: An account ID uniquely privileged to be able to thaw asset instances and classes,
+//!   as well as transfer, burn and approve transfers of any instance of the class.
+//! * **Issuer**: An account ID uniquely privileged to be able to mint instances of a class.
+//! * **Freezer**: An account ID uniquely privileged to be able to freeze asset instances and
+//!   classes.
+//! * **Owner**: An account ID uniquely privileged to be able to destroy an asset class, to set its
+//!   Issuer, Freezer or Admin, and to set its attributes and metadata. The Owner pays all
+//!   deposits for the class.
+//! * **Delegate**: An account approved by the owner of an instance (or the class's Admin) to
+//!   transfer that instance on their behalf.
+//! * **Freezing**: Removing the possibility of an unprivileged transfer of an asset instance, or
+//!   of every instance of a class.
+//! * **Attribute**: A key/value pair of arbitrary bytes set on an asset class or an instance of it.
+//! * **Metadata**: A single arbitrary byte string set on an asset class or an instance of it.
+//!
+//! ### Goals
+//!
+//! The uniques system in Substrate is designed to make the following possible:
+//!
+//! * Create asset classes in a permissioned or permissionless way, if permissionless, then with a
+//!   deposit required.
+//! * Allow the class's team to mint, burn and freeze instances.
+//! * Move instances between accounts, either directly or through an approved delegate.
+//! * Store attributes and metadata on-chain, paid for with deposits.
+//! * Allow other pallets to use the assets through the
+//!   [`nonfungibles`](../frame_support/traits/tokens/nonfungibles/index.html) traits.
+//!
+//! ## Interface
+//!
+//! ### Permissionless Functions
+//!
+//! * `create`: Creates a new asset class, taking the required deposit.
+//! * `transfer`: Transfer an asset instance owned by the sender, or which the sender has been
+//!   approved to transfer, to another account.
+//!
+//! ### Permissioned Functions
+//!
+//! * `force_create`: Creates a new asset class without taking any deposit.
+//! * `force_asset_status`: Sets the team, Owner and status of an asset class.
+//!
+//! ### Privileged Functions
+//!
+//! * `destroy`: Destroys an asset class; called by the class's Owner.
+//! * `mint`: Mints a new asset instance; called by the class's Issuer.
+//! * `burn`: Burns an asset instance; called by the class's Admin or the instance's owner.
+//! * `redeposit`: Updates the deposits held for a number of instances to the current amount;
+//!   called by the class's Owner.
+//! * `freeze`: Disallows further `transfer`s of an instance; called by the class's Freezer.
+//! * `thaw`: Allows further `transfer`s of an instance; called by the class's Admin.
+//! * `freeze_class`: Disallows further `transfer`s of any instance of a class; called by the
+//!   class's Freezer.
+//! * `thaw_class`: Allows further `transfer`s of the instances of a class; called by the class's
+//!   Admin.
+//! * `transfer_ownership`: Changes an asset class's Owner; called by the class's Owner.
+//! * `set_team`: Changes an asset class's Admin, Freezer and Issuer; called by the class's Owner.
+//! * `approve_transfer`: Approves a delegate to transfer an instance; called by the instance's
+//!   owner or the class's Admin.
+//! * `cancel_approval`: Cancels the approval of a delegate; called by the instance's owner or the
+//!   class's Admin.
+//! * `set_attribute`/`clear_attribute`: Sets or clears an attribute of a class or instance;
+//!   called by the class's Owner.
+//! * `set_metadata`/`clear_metadata`: Sets or clears the metadata of an instance; called by the
+//!   class's Owner.
+//! * `set_class_metadata`/`clear_class_metadata`: Sets or clears the metadata of a class; called
+//!   by the class's Owner.
+//!
+//! Please refer to the [`Call`](./enum.Call.html) enum and its associated variants for documentation on each function.
+//!
+//! ### Trait Implementations
+//!
+//! The module implements the
+//! [`nonfungibles`](../frame_support/traits/tokens/nonfungibles/index.html) traits `Inspect`,
+//! `InspectEnumerable`, `Create`, `Mutate` and `Transfer`, so that other pallets can create,
+//! inspect and move asset instances. Attributes set through `Mutate` take no deposit.
+//!
+//! ## Related Modules
+//!
+//! * [`System`](../frame_system/index.html)
+//! * [`Support`](../frame_support/index.html)
+//! * [`Assets`](../pallet_assets/index.html)
+
+// Ensure we're `no_std` when compiling for Wasm.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+pub mod weights;
+mod impl_nonfungibles;
+
+use sp_std::prelude::*;
+use sp_runtime::{RuntimeDebug, traits::{Member, Zero, StaticLookup, Saturating}};
+use codec::{Encode, Decode, HasCompact};
+use frame_support::{Parameter, decl_module, decl_event, decl_storage, decl_error, ensure,
+	traits::{Currency, ReservableCurrency, EnsureOrigin, Get, BalanceStatus::Reserved},
+	dispatch::{DispatchResult, DispatchError},
+};
+use frame_system::ensure_signed;
+pub use weights::WeightInfo;
+
+type DepositBalanceOf<T> =
+	<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+type ClassDetailsFor<T> =
+	ClassDetails<<T as frame_system::Config>::AccountId, DepositBalanceOf<T>>;
+type InstanceDetailsFor<T> =
+	InstanceDetails<<T as frame_system::Config>::AccountId, DepositBalanceOf<T>>;
+
+/// The module configuration trait.
+pub trait Config: frame_system::Config {
+	/// The overarching event type.
+	type Event: From<Event<Self>> + Into<<Self as frame_system::Config>::Event>;
+
+	/// Identifier for the class of asset.
+	type ClassId: Member + Parameter + Default + Copy + HasCompact;
+
+	/// The type used to identify a unique asset within an asset class.
+	type InstanceId: Member + Parameter + Default + Copy + HasCompact;
+
+	/// The currency mechanism, used for paying for reserves.
+	type Currency: ReservableCurrency<Self::AccountId>;
+
+	/// The origin which may forcibly create or destroy an asset class, or otherwise alter
+	/// privileged attributes.
+	type ForceOrigin: EnsureOrigin<Self::Origin>;
+
+	/// The basic amount of funds that must be reserved for an asset class.
+	type ClassDeposit: Get<DepositBalanceOf<Self>>;
+
+	/// The basic amount of funds that must be reserved for an asset instance.
+	type InstanceDeposit: Get<DepositBalanceOf<Self>>;
+
+	/// The basic amount of funds that must be reserved when adding metadata to an asset class or
+	/// instance.
+	type MetadataDepositBase: Get<DepositBalanceOf<Self>>;
+
+	/// The basic amount of funds that must be reserved when adding an attribute to an asset class
+	/// or instance.
+	type AttributeDepositBase: Get<DepositBalanceOf<Self>>;
+
+	/// The additional funds that must be reserved for each byte of metadata, or of an attribute's
+	/// key and value.
+	type DepositPerByte: Get<DepositBalanceOf<Self>>;
+
+	/// The maximum length of metadata stored on-chain.
+	type StringLimit: Get<u32>;
+
+	/// The maximum length of an attribute key.
+	type KeyLimit: Get<u32>;
+
+	/// The maximum length of an attribute value.
+	type ValueLimit: Get<u32>;
+
+	/// Weight information for extrinsics in this pallet.
+	type WeightInfo: WeightInfo;
+}
+
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug)]
+pub struct ClassDetails<AccountId, DepositBalance> {
+	/// Can change `owner`, `issuer`, `freezer` and `admin` accounts.
+	owner: AccountId,
+	/// Can mint tokens.
+	issuer: AccountId,
+	/// Can thaw tokens, force transfers and burn tokens from any account.
+	admin: AccountId,
+	/// Can freeze tokens.
+	freezer: AccountId,
+	/// The total balance deposited for all the storage associated with this asset class. Used by
+	/// `destroy`.
+	total_deposit: DepositBalance,
+	/// If `true`, then no deposit is needed to hold instances of this class.
+	free_holding: bool,
+	/// The total number of outstanding instances of this asset class.
+	instances: u32,
+	/// The total number of outstanding instance metadata of this asset class.
+	instance_metadatas: u32,
+	/// The total number of attributes for this asset class.
+	attributes: u32,
+	/// Whether the asset is frozen for non-admin transfers.
+	is_frozen: bool,
+}
+
+impl<AccountId, DepositBalance> ClassDetails<AccountId, DepositBalance> {
+	/// The witness data needed to destroy this asset class.
+	pub fn destroy_witness(&self) -> DestroyWitness {
+		DestroyWitness {
+			instances: self.instances,
+			instance_metadatas: self.instance_metadatas,
+			attributes: self.attributes,
+		}
+	}
+}
+
+/// Witness data for the destroy transactions.
+#[derive(Copy, Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug)]
+pub struct DestroyWitness {
+	/// The total number of outstanding instances of this asset class.
+	#[codec(compact)]
+	pub instances: u32,
+	/// The total number of outstanding instance metadata of this asset class.
+	#[codec(compact)]
+	pub instance_metadatas: u32,
+	/// The total number of attributes for this asset class.
+	#[codec(compact)]
+	pub attributes: u32,
+}
+
+/// Information concerning the ownership of a single unique asset.
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug)]
+pub struct InstanceDetails<AccountId, DepositBalance> {
+	/// The owner of this asset.
+	owner: AccountId,
+	/// The approved transferrer of this asset, if one is set.
+	approved: Option<AccountId>,
+	/// Whether the asset can be transferred or not.
+	is_frozen: bool,
+	/// The amount held in the pallet's default account for this asset. Free-hold assets will have
+	/// this as zero.
+	deposit: DepositBalance,
+}
+
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug)]
+pub struct ClassMetadata<DepositBalance> {
+	/// The balance deposited for this metadata.
+	///
+	/// This pays for the data stored in this struct.
+	deposit: DepositBalance,
+	/// General information concerning this asset. Limited in length by `StringLimit`. This will
+	/// generally be either a JSON dump or the hash of some JSON which can be found on a
+	/// hash-addressable global publication system such as IPFS.
+	data: Vec<u8>,
+	/// Whether the asset metadata may be changed by a non Force origin.
+	is_frozen: bool,
+}
+
+#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug)]
+pub struct InstanceMetadata<DepositBalance> {
+	/// The balance deposited for this metadata.
+	///
+	/// This pays for the data stored in this struct.
+	deposit: DepositBalance,
+	/// General information concerning this asset. Limited in length by `StringLimit`. This will
+	/// generally be either a JSON dump or the hash of some JSON which can be found on a
+	/// hash-addressable global publication system such as IPFS.
+	data: Vec<u8>,
+	/// Whether the asset metadata may be changed by a non Force origin.
+	is_frozen: bool,
+}
+
+decl_storage! {
+	trait Store for Module<T: Config> as Uniques {
+		/// Details of an asset class.
+		Class: map hasher(blake2_128_concat) T::ClassId => Option<ClassDetailsFor<T>>;
+
+		/// The assets held by any given account; set out this way so that assets owned by a single
+		/// account can be enumerated.
+		Account: double_map
+			hasher(blake2_128_concat) T::AccountId,
+			hasher(blake2_128_concat) (T::ClassId, T::InstanceId)
+			=> Option<()>;
+
+		/// The assets in existence and their ownership details.
+		Asset: double_map
+			hasher(blake2_128_concat) T::ClassId,
+			hasher(blake2_128_concat) T::InstanceId
+			=> Option<InstanceDetailsFor<T>>;
+
+		/// Metadata of an asset class.
+		ClassMetadataOf: map hasher(blake2_128_concat) T::ClassId
+			=> Option<ClassMetadata<DepositBalanceOf<T>>>;
+
+		/// Metadata of an asset instance.
+		InstanceMetadataOf: double_map
+			hasher(blake2_128_concat) T::ClassId,
+			hasher(blake2_128_concat) T::InstanceId
+			=> Option<InstanceMetadata<DepositBalanceOf<T>>>;
+
+		/// Attributes of an asset class or instance, together with the deposit paid for them.
+		Attribute: double_map
+			hasher(blake2_128_concat) T::ClassId,
+			hasher(blake2_128_concat) (Option<T::InstanceId>, Vec<u8>)
+			=> Option<(Vec<u8>, DepositBalanceOf<T>)>;
+	}
+}
+
+decl_event! {
+	pub enum Event<T> where
+		<T as frame_system::Config>::AccountId,
+		<T as Config>::ClassId,
+		<T as Config>::InstanceId,
+	{
+		/// An asset class was created. \[class, creator, owner\]
+		Created(ClassId, AccountId, AccountId),
+		/// An asset class was force-created. \[class, owner\]
+		ForceCreated(ClassId, AccountId),
+		/// An asset class was destroyed. \[class\]
+		Destroyed(ClassId),
+		/// An asset instance was issued. \[class, instance, owner\]
+		Issued(ClassId, InstanceId, AccountId),
+		/// An asset instance was transferred. \[class, instance, from, to\]
+		Transferred(ClassId, InstanceId, AccountId, AccountId),
+		/// An asset instance was destroyed. \[class, instance, owner\]
+		Burned(ClassId, InstanceId, AccountId),
+		/// Some asset instance was frozen. \[class, instance\]
+		Frozen(ClassId, InstanceId),
+		/// Some asset instance was thawed. \[class, instance\]
+		Thawed(ClassId, InstanceId),
+		/// Some asset class was frozen. \[class\]
+		ClassFrozen(ClassId),
+		/// Some asset class was thawed. \[class\]
+		ClassThawed(ClassId),
+		/// The owner changed \[class, new_owner\]
+		OwnerChanged(ClassId, AccountId),
+		/// The management team changed \[class, issuer, admin, freezer\]
+		TeamChanged(ClassId, AccountId, AccountId, AccountId),
+		/// An instance of an asset class has been approved by the owner for transfer by a
+		/// delegate. \[class, instance, owner, delegate\]
+		ApprovedTransfer(ClassId, InstanceId, AccountId, AccountId),
+		/// An approval for a delegate account to transfer an instance of an asset class was
+		/// cancelled by its owner. \[class, instance, owner, delegate\]
+		ApprovalCancelled(ClassId, InstanceId, AccountId, AccountId),
+		/// An asset class has had its attributes changed by the `Force` origin. \[class\]
+		AssetStatusChanged(ClassId),
+		/// New metadata has been set for an asset class. \[class, data, is_frozen\]
+		ClassMetadataSet(ClassId, Vec<u8>, bool),
+		/// Metadata has been cleared for an asset class. \[class\]
+		ClassMetadataCleared(ClassId),
+		/// New metadata has been set for an asset instance.
+		/// \[class, instance, data, is_frozen\]
+		MetadataSet(ClassId, InstanceId, Vec<u8>, bool),
+		/// Metadata has been cleared for an asset instance. \[class, instance\]
+		MetadataCleared(ClassId, InstanceId),
+		/// The deposits of some asset instances have been updated. \[class, successful_instances\]
+		Redeposited(ClassId, Vec<InstanceId>),
+		/// New attribute metadata has been set for an asset class or instance.
+		/// \[class, maybe_instance, key, value\]
+		AttributeSet(ClassId, Option<InstanceId>, Vec<u8>, Vec<u8>),
+		/// Attribute metadata has been cleared for an asset class or instance.
+		/// \[class, maybe_instance, key\]
+		AttributeCleared(ClassId, Option<InstanceId>, Vec<u8>),
+	}
+}
+
+decl_error! {
+	pub enum Error for Module<T: Config> {
+		/// The signing account has no permission to do the operation.
+		NoPermission,
+		/// The given asset ID is unknown.
+		Unknown,
+		/// The asset instance ID has already been used for an asset.
+		AlreadyExists,
+		/// The owner turned out to be different to what was expected.
+		WrongOwner,
+		/// Invalid witness data given.
+		BadWitness,
+		/// The asset ID is already taken.
+		InUse,
+		/// The asset instance or class is frozen.
+		Frozen,
+		/// The delegate turned out to be different to what was expected.
+		WrongDelegate,
+		/// There is no delegate approved.
+		NoDelegate,
+		/// The given metadata is longer than `StringLimit`.
+		BadMetadata,
+		/// The given attribute key or value is longer than `KeyLimit` or `ValueLimit`.
+		BadAttribute,
+		/// The number of instances of the asset class would overflow.
+		Overflow,
+	}
+}
+
+decl_module! {
+	pub struct Module<T: Config> for enum Call where origin: T::Origin {
+		type Error = Error<T>;
+
+		fn deposit_event() = default;
+
+		/// Issue a new class of non-fungible assets from a public origin.
+		///
+		/// This new asset class has no assets initially and its owner is the origin.
+		///
+		/// The origin must be Signed and the sender must have sufficient funds free.
+		///
+		/// `ClassDeposit` funds of sender are reserved.
+		///
+		/// Parameters:
+		/// - `class`: The identifier of the new asset class. This must not be currently in use.
+		/// - `admin`: The admin of this class of assets. The admin is the initial address of each
+		/// member of the asset class's admin team.
+		///
+		/// Emits `Created` event when successful.
+		///
+		/// Weight: `O(1)`
+		#[weight = T::WeightInfo::create()]
+		fn create(origin,
+			#[compact] class: T::ClassId,
+			admin: <T::Lookup as StaticLookup>::Source,
+		) -> DispatchResult {
+			let owner = ensure_signed(origin)?;
+			let admin = T::Lookup::lookup(admin)?;
+
+			let deposit = T::ClassDeposit::get();
+			Self::do_create_class(class, owner.clone(), admin.clone(), deposit, false)?;
+			Self::deposit_event(RawEvent::Created(class, owner, admin));
+			Ok(())
+		}
+
+		/// Issue a new class of non-fungible assets from a privileged origin.
+		///
+		/// This new asset class has no assets initially.
+		///
+		/// The origin must conform to `ForceOrigin`.
+		///
+		/// Unlike `create`, no funds are reserved.
+		///
+		/// - `class`: The identifier of the new asset. This must not be currently in use.
+		/// - `owner`: The owner of this class of assets. The owner has full superuser permissions
+		/// over this asset, but may later change and configure the permissions using
+		/// `transfer_ownership` and `set_team`.
+		/// - `free_holding`: Whether instances of this class may be minted, and attributes and
+		/// metadata set, without any deposit being taken.
+		///
+		/// Emits `ForceCreated` event when successful.
+		///
+		/// Weight: `O(1)`
+		#[weight = T::WeightInfo::force_create()]
+		fn force_create(origin,
+			#[compact] class: T::ClassId,
+			owner: <T::Lookup as StaticLookup>::Source,
+			free_holding: bool,
+		) -> DispatchResult {
+			T::ForceOrigin::ensure_origin(origin)?;
+			let owner = T::Lookup::lookup(owner)?;
+
+			Self::do_create_class(class, owner.clone(), owner.clone(), Zero::zero(), free_holding)?;
+			Self::deposit_event(RawEvent::ForceCreated(class, owner));
+			Ok(())
+		}
+
+		/// Destroy a class of non-fungible assets.
+		///
+		/// The origin must conform to `ForceOrigin` or must be Signed and the sender must be the
+		/// owner of the asset `class`.
+		///
+		/// - `class`: The identifier of the asset class to be destroyed.
+		/// - `witness`: Information on the instances, instance metadata and attributes of the
+		/// class, which must be at least the current amounts. See `ClassDetails::destroy_witness`.
+		///
+		/// Emits `Destroyed` event when successful.
+		///
+		/// Weight: `O(n + m + a)` where:
+		/// - `n = witness.instances`
+		/// - `m = witness.instance_metadatas`
+		/// - `a = witness.attributes`
+		#[weight = T::WeightInfo::destroy(
+			witness.instances,
+			witness.instance_metadatas,
+			witness.attributes,
+		)]
+		fn destroy(origin,
+			#[compact] class: T::ClassId,
+			witness: DestroyWitness,
+		) -> DispatchResult {
+			let maybe_check_owner = match T::ForceOrigin::try_origin(origin) {
+				Ok(_) => None,
+				Err(origin) => Some(ensure_signed(origin)?),
+			};
+
+			Class::<T>::try_mutate_exists(class, |maybe_details| {
+				let class_details = maybe_details.take().ok_or(Error::<T>::Unknown)?;
+				if let Some(check_owner) = maybe_check_owner {
+					ensure!(class_details.owner == check_owner, Error::<T>::NoPermission);
+				}
+				ensure!(class_details.instances <= witness.instances, Error::<T>::BadWitness);
+				ensure!(
+					class_details.instance_metadatas <= witness.instance_metadatas,
+					Error::<T>::BadWitness
+				);
+				ensure!(class_details.attributes <= witness.attributes, Error::<T>::BadWitness);
+
+				for (instance, details) in Asset::<T>::drain_prefix(&class) {
+					Account::<T>::remove(&details.owner, (class, instance));
+				}
+				InstanceMetadataOf::<T>::remove_prefix(&class);
+				ClassMetadataOf::<T>::remove(&class);
+				Attribute::<T>::remove_prefix(&class);
+				T::Currency::unreserve(&class_details.owner, class_details.total_deposit);
+
+				Self::deposit_event(RawEvent::Destroyed(class));
+				Ok(())
+			})
+		}
+
+		/// Mint an asset instance of a particular class.
+		///
+		/// The origin must be Signed and the sender must be the Issuer of the asset `class`.
+		///
+		/// - `class`: The class of the asset to be minted.
+		/// - `instance`: The instance value of the asset to be minted.
+		/// - `owner`: The initial owner of the minted asset.
+		///
+		/// Emits `Issued` event when successful.
+		///
+		/// Weight: `O(1)`
+		#[weight = T::WeightInfo::mint()]
+		fn mint(origin,
+			#[compact] class: T::ClassId,
+			#[compact] instance: T::InstanceId,
+			owner: <T::Lookup as StaticLookup>::Source,
+		) -> DispatchResult {
+			let origin = ensure_signed(origin)?;
+			let owner = T::Lookup::lookup(owner)?;
+
+			Self::do_mint(class, instance, owner, |class_details| {
+				ensure!(class_details.issuer == origin, Error::<T>::NoPermission);
+				Ok(())
+			})
+		}
+
+		/// Destroy a single asset instance.
+		///
+		/// Origin must be Signed and the sender should be the Admin of the asset `class` or the
+		/// owner of the instance.
+		///
+		/// - `class`: The class of the asset to be burned.
+		/// - `instance`: The instance of the asset to be burned.
+		/// - `check_owner`: If `Some` then the operation will fail with `WrongOwner` unless the
+		///   asset is owned by this value.
+		/// - `attributes`: The number of attributes of the asset `class`, which must be at least
+		///   the current amount. See `ClassDetails::destroy_witness`.
+		///
+		/// Any metadata and attributes of the instance are cleared and their deposits are freed
+		/// for the asset class owner.
+		///
+		/// Emits `Burned` with the actual amount burned.
+		///
+		/// Weight: `O(a)` where:
+		/// - `a = attributes`
+		/// Modes: `check_owner.is_some()`.
+		#[weight = T::WeightInfo::burn(*attributes)]
+		fn burn(origin,
+			#[compact] class: T::ClassId,
+			#[compact] instance: T::InstanceId,
+			check_owner: Option<<T::Lookup as StaticLookup>::Source>,
+			#[compact] attributes: u32,
+		) -> DispatchResult {
+			let origin = ensure_signed(origin)?;
+			let check_owner = check_owner.map(T::Lookup::lookup).transpose()?;
+
+			Self::do_burn(class, instance, |class_details, details| {
+				let is_permitted = class_details.admin == origin || details.owner == origin;
+				ensure!(is_permitted, Error::<T>::NoPermission);
+				ensure!(check_owner.map_or(true, |o| o == details.owner), Error::<T>::WrongOwner);
+				ensure!(class_details.attributes <= attributes, Error::<T>::BadWitness);
+				Ok(())
+			})
+		}
+
+		/// Move an asset from the sender account to another.
+		///
+		/// Origin must be Signed and the signing account must be either:
+		/// - the Admin of the asset `class`;
+		/// - the Owner of the asset `instance`;
+		/// - the approved delegate for the asset `instance` (in this case, the approval is reset).
+		///
+		/// Arguments:
+		/// - `class`: The class of the asset to be transferred.
+		/// - `instance`: The instance of the asset to be transferred.
+		/// - `dest`: The account to receive ownership of the asset.
+		///
+		/// Emits `Transferred`.
+		///
+		/// Weight: `O(1)`
+		#[weight = T::WeightInfo::transfer()]
+		fn transfer(origin,
+			#[compact] class: T::ClassId,
+			#[compact] instance: T::InstanceId,
+			dest: <T::Lookup as StaticLookup>::Source,
+		) -> DispatchResult {
+			let origin = ensure_signed(origin)?;
+			let dest = T::Lookup::lookup(dest)?;
+
+			Self::do_transfer(class, instance, dest, |class_details, details| {
+				if details.owner != origin && class_details.admin != origin {
+					let approved = details.approved.as_ref().map_or(false, |a| a == &origin);
+					ensure!(approved, Error::<T>::NoPermission);
+				}
+				Ok(())
+			})
+		}
+
+		/// Reevaluate the deposits on some assets.
+		///
+		/// Origin must be Signed and the sender should be the Owner of the asset `class`.
+		///
+		/// - `class`: The class of the asset to be frozen.
+		/// - `instances`: The instances of the asset class whose deposits will be reevaluated.
+		///
+		/// NOTE: This exists as a best-effort function. Any asset instances which are unknown or
+		/// in the case that the owner account does not have reservable funds to pay for a
+		/// deposit increase are ignored. Generally the owner isn't going to call this on instances
+		/// whose existing deposit is less than the refreshed deposit as it would only cost them,
+		/// so it's of little consequence.
+		///
+		/// It will still return an error in the case that the class is unknown of the signer is
+		/// not permitted to call it.
+		///
+		/// Emits `Redeposited` with the instances whose deposits were updated.
+		///
+		/// Weight: `O(instances.len())`
+		#[weight = T::WeightInfo::redeposit(instances.len() as u32)]
+		fn redeposit(origin,
+			#[compact] class: T::ClassId,
+			instances: Vec<T::InstanceId>,
+		) -> DispatchResult {
+			let origin = ensure_signed(origin)?;
+
+			let mut class_details = Class::<T>::get(&class).ok_or(Error::<T>::Unknown)?;
+			ensure!(class_details.owner == origin, Error::<T>::NoPermission);
+			let deposit = if class_details.free_holding {
+				Zero::zero()
+			} else {
+				T::InstanceDeposit::get()
+			};
+
+			let mut successful = Vec::with_capacity(instances.len());
+			for instance in instances.into_iter() {
+				let mut details = match Asset::<T>::get(&class, &instance) {
+					Some(x) => x,
+					None => continue,
+				};
+				let old = details.deposit;
+				if old == deposit {
+					continue
+				}
+				// NOTE: `class_details` is left untouched if this fails, so it's OK to carry on.
+				if Self::adjust_deposit(&mut class_details, old, deposit).is_err() {
+					continue
+				}
+				details.deposit = deposit;
+				Asset::<T>::insert(&class, &instance, &details);
+				successful.push(instance);
+			}
+			Class::<T>::insert(&class, &class_details);
+
+			Self::deposit_event(RawEvent::Redeposited(class, successful));
+			Ok(())
+		}
+
+		/// Disallow further unprivileged transfer of an asset instance.
+		///
+		/// Origin must be Signed and the sender should be the Freezer of the asset `class`.
+		///
+		/// - `class`: The class of the asset to be frozen.
+		/// - `instance`: The instance of the asset to be frozen.
+		///
+		/// Emits `Frozen`.
+		///
+		/// Weight: `O(1)`
+		#[weight = T::WeightInfo::freeze()]
+		fn freeze(origin, #[compact] class: T::ClassId, #[compact] instance: T::InstanceId) {
+			let origin = ensure_signed(origin)?;
+
+			let mut details = Asset::<T>::get(&class, &instance).ok_or(Error::<T>::Unknown)?;
+			let class_details = Class::<T>::get(&class).ok_or(Error::<T>::Unknown)?;
+			ensure!(class_details.freezer == origin, Error::<T>::NoPermission);
+
+			details.is_frozen = true;
+			Asset::<T>::insert(&class, &instance, &details);
+
+			Self::deposit_event(RawEvent::Frozen(class, instance));
+		}
+
+		/// Re-allow unprivileged transfer of an asset instance.
+		///
+		/// Origin must be Signed and the sender should be the Admin of the asset `class`.
+		///
+		/// - `class`: The class of the asset to be thawed.
+		/// - `instance`: The instance of the asset to be thawed.
+		///
+		/// Emits `Thawed`.
+		///
+		/// Weight: `O(1)`
+		#[weight = T::WeightInfo::thaw()]
+		fn thaw(origin, #[compact] class: T::ClassId, #[compact] instance: T::InstanceId) {
+			let origin = ensure_signed(origin)?;
+
+			let mut details = Asset::<T>::get(&class, &instance).ok_or(Error::<T>::Unknown)?;
+			let class_details = Class::<T>::get(&class).ok_or(Error::<T>::Unknown)?;
+			ensure!(class_details.admin == origin, Error::<T>::NoPermission);
+
+			details.is_frozen = false;
+			Asset::<T>::insert(&class, &instance, &details);
+
+			Self::deposit_event(RawEvent::Thawed(class, instance));
+		}
+
+		/// Disallow further unprivileged transfers for a whole asset class.
+		///
+		/// Origin must be Signed and the sender should be the Freezer of the asset `class`.
+		///
+		/// - `class`: The asset class to be frozen.
+		///
+		/// Emits `ClassFrozen`.
+		///
+		/// Weight: `O(1)`
+		#[weight = T::WeightInfo::freeze_class()]
+		fn freeze_class(origin, #[compact] class: T::ClassId) -> DispatchResult {
+			let origin = ensure_signed(origin)?;
+
+			Class::<T>::try_mutate(class, |maybe_details| {
+				let details = maybe_details.as_mut().ok_or(Error::<T>::Unknown)?;
+				ensure!(&origin == &details.freezer, Error::<T>::NoPermission);
+
+				details.is_frozen = true;
+
+				Self::deposit_event(RawEvent::ClassFrozen(class));
+				Ok(())
+			})
+		}
+
+		/// Re-allow unprivileged transfers for a whole asset class.
+		///
+		/// Origin must be Signed and the sender should be the Admin of the asset `class`.
+		///
+		/// - `class`: The class to be thawed.
+		///
+		/// Emits `ClassThawed`.
+		///
+		/// Weight: `O(1)`
+		#[weight = T::WeightInfo::thaw_class()]
+		fn thaw_class(origin, #[compact] class: T::ClassId) -> DispatchResult {
+			let origin = ensure_signed(origin)?;
+
+			Class::<T>::try_mutate(class, |maybe_details| {
+				let details = maybe_details.as_mut().ok_or(Error::<T>::Unknown)?;
+				ensure!(&origin == &details.admin, Error::<T>::NoPermission);
+
+				details.is_frozen = false;
+
+				Self::deposit_event(RawEvent::ClassThawed(class));
+				Ok(())
+			})
+		}
+
+		/// Change the Owner of an asset class.
+		///
+		/// Origin must be Signed and the sender should be the Owner of the asset `class`.
+		///
+		/// - `class`: The asset class whose owner should be changed.
+		/// - `owner`: The new Owner of this asset class. All deposits of the class are moved to
+		/// them.
+		///
+		/// Emits `OwnerChanged`.
+		///
+		/// Weight: `O(1)`
+		#[weight = T::WeightInfo::transfer_ownership()]
+		fn transfer_ownership(origin,
+			#[compact] class: T::ClassId,
+			owner: <T::Lookup as StaticLookup>::Source,
+		) -> DispatchResult {
+			let origin = ensure_signed(origin)?;
+			let owner = T::Lookup::lookup(owner)?;
+
+			Class::<T>::try_mutate(class, |maybe_details| {
+				let details = maybe_details.as_mut().ok_or(Error::<T>::Unknown)?;
+				ensure!(&origin == &details.owner, Error::<T>::NoPermission);
+				if details.owner == owner { return Ok(()) }
+
+				// Move the deposit to the new owner.
+				T::Currency::repatriate_reserved(
+					&details.owner,
+					&owner,
+					details.total_deposit,
+					Reserved,
+				)?;
+				details.owner = owner.clone();
+
+				Self::deposit_event(RawEvent::OwnerChanged(class, owner));
+				Ok(())
+			})
+		}
+
+		/// Change the Issuer, Admin and Freezer of an asset class.
+		///
+		/// Origin must be Signed and the sender should be the Owner of the asset `class`.
+		///
+		/// - `class`: The asset class whose team should be changed.
+		/// - `issuer`: The new Issuer of this asset class.
+		/// - `admin`: The new Admin of this asset class.
+		/// - `freezer`: The new Freezer of this asset class.
+		///
+		/// Emits `TeamChanged`.
+		///
+		/// Weight: `O(1)`
+		#[weight = T::WeightInfo::set_team()]
+		fn set_team(origin,
+			#[compact] class: T::ClassId,
+			issuer: <T::Lookup as StaticLookup>::Source,
+			admin: <T::Lookup as StaticLookup>::Source,
+			freezer: <T::Lookup as StaticLookup>::Source,
+		) -> DispatchResult {
+			let origin = ensure_signed(origin)?;
+			let issuer = T::Lookup::lookup(issuer)?;
+			let admin = T::Lookup::lookup(admin)?;
+			let freezer = T::Lookup::lookup(freezer)?;
+
+			Class::<T>::try_mutate(class, |maybe_details| {
+				let details = maybe_details.as_mut().ok_or(Error::<T>::Unknown)?;
+				ensure!(&origin == &details.owner, Error::<T>::NoPermission);
+
+				details.issuer = issuer.clone();
+				details.admin = admin.clone();
+				details.freezer = freezer.clone();
+
+				Self::deposit_event(RawEvent::TeamChanged(class, issuer, admin, freezer));
+				Ok(())
+			})
+		}
+
+		/// Approve an instance to be transferred by a delegated third-party account.
+		///
+		/// Origin must be Signed and must be the owner of the asset `instance` or the Admin of
+		/// the asset `class`.
+		///
+		/// - `class`: The class of the asset to be approved for delegated transfer.
+		/// - `instance`: The instance of the asset to be approved for delegated transfer.
+		/// - `delegate`: The account to delegate permission to transfer the asset. Any previously
+		/// approved delegate is replaced.
+		///
+		/// Emits `ApprovedTransfer` on success.
+		///
+		/// Weight: `O(1)`
+		#[weight = T::WeightInfo::approve_transfer()]
+		fn approve_transfer(origin,
+			#[compact] class: T::ClassId,
+			#[compact] instance: T::InstanceId,
+			delegate: <T::Lookup as StaticLookup>::Source,
+		) -> DispatchResult {
+			let origin = ensure_signed(origin)?;
+			let delegate = T::Lookup::lookup(delegate)?;
+
+			let class_details = Class::<T>::get(&class).ok_or(Error::<T>::Unknown)?;
+			let mut details = Asset::<T>::get(&class, &instance).ok_or(Error::<T>::Unknown)?;
+			let is_permitted = class_details.admin == origin || details.owner == origin;
+			ensure!(is_permitted, Error::<T>::NoPermission);
+
+			details.approved = Some(delegate.clone());
+			Asset::<T>::insert(&class, &instance, &details);
+
+			let event = RawEvent::ApprovedTransfer(class, instance, details.owner, delegate);
+			Self::deposit_event(event);
+			Ok(())
+		}
+
+		/// Cancel the prior approval for the transfer of an asset by a delegate.
+		///
+		/// Origin must be Signed and must be the owner of the asset `instance` or the Admin of
+		/// the asset `class`.
+		///
+		/// - `class`: The class of the asset of whose approval will be cancelled.
+		/// - `instance`: The instance of the asset of whose approval will be cancelled.
+		/// - `maybe_check_delegate`: If `Some` will ensure that the given account is the one to
+		///   which permission of transfer is delegated.
+		///
+		/// Emits `ApprovalCancelled` on success.
+		///
+		/// Weight: `O(1)`
+		#[weight = T::WeightInfo::cancel_approval()]
+		fn cancel_approval(origin,
+			#[compact] class: T::ClassId,
+			#[compact] instance: T::InstanceId,
+			maybe_check_delegate: Option<<T::Lookup as StaticLookup>::Source>,
+		) -> DispatchResult {
+			let origin = ensure_signed(origin)?;
+			let maybe_check_delegate = maybe_check_delegate.map(T::Lookup::lookup).transpose()?;
+
+			let class_details = Class::<T>::get(&class).ok_or(Error::<T>::Unknown)?;
+			let mut details = Asset::<T>::get(&class, &instance).ok_or(Error::<T>::Unknown)?;
+			let is_permitted = class_details.admin == origin || details.owner == origin;
+			ensure!(is_permitted, Error::<T>::NoPermission);
+
+			let old = details.approved.take().ok_or(Error::<T>::NoDelegate)?;
+			if let Some(check_delegate) = maybe_check_delegate {
+				ensure!(check_delegate == old, Error::<T>::WrongDelegate);
+			}
+			Asset::<T>::insert(&class, &instance, &details);
+
+			Self::deposit_event(RawEvent::ApprovalCancelled(class, instance, details.owner, old));
+			Ok(())
+		}
+
+		/// Alter the attributes of a given asset class.
+		///
+		/// Origin must be `ForceOrigin`.
+		///
+		/// - `class`: The identifier of the asset class.
+		/// - `owner`: The new Owner of this asset class. Any deposits are not moved.
+		/// - `issuer`: The new Issuer of this asset class.
+		/// - `admin`: The new Admin of this asset class.
+		/// - `freezer`: The new Freezer of this asset class.
+		/// - `free_holding`: Whether a deposit is taken for holding an instance of this asset
+		///   class.
+		/// - `is_frozen`: Whether this asset class is frozen except for permissioned/admin
+		/// instructions.
+		///
+		/// Emits `AssetStatusChanged` with the identity of the asset.
+		///
+		/// Weight: `O(1)`
+		#[weight = T::WeightInfo::force_asset_status()]
+		fn force_asset_status(origin,
+			#[compact] class: T::ClassId,
+			owner: <T::Lookup as StaticLookup>::Source,
+			issuer: <T::Lookup as StaticLookup>::Source,
+			admin: <T::Lookup as StaticLookup>::Source,
+			freezer: <T::Lookup as StaticLookup>::Source,
+			free_holding: bool,
+			is_frozen: bool,
+		) -> DispatchResult {
+			T::ForceOrigin::ensure_origin(origin)?;
+
+			Class::<T>::try_mutate(class, |maybe_details| {
+				let details = maybe_details.as_mut().ok_or(Error::<T>::Unknown)?;
+				details.owner = T::Lookup::lookup(owner)?;
+				details.issuer = T::Lookup::lookup(issuer)?;
+				details.admin = T::Lookup::lookup(admin)?;
+				details.freezer = T::Lookup::lookup(freezer)?;
+				details.free_holding = free_holding;
+				details.is_frozen = is_frozen;
+
+				Self::deposit_event(RawEvent::AssetStatusChanged(class));
+				Ok(())
+			})
+		}
+
+		/// Set an attribute for an asset class or instance.
+		///
+		/// Origin must be either `ForceOrigin` or Signed and the sender should be the Owner of the
+		/// asset `class`.
+		///
+		/// If the origin is Signed, then funds of signer are reserved according to the formula:
+		/// `AttributeDepositBase + DepositPerByte * (key.len + value.len)` taking into
+		/// account any already reserved funds.
+		///
+		/// - `class`: The identifier of the asset class whose instance's metadata to set.
+		/// - `maybe_instance`: The identifier of the asset instance whose metadata to set, or
+		/// `None` to set an attribute of the class itself.
+		/// - `key`: The key of the attribute.
+		/// - `value`: The value to which to set the attribute.
+		///
+		/// Emits `AttributeSet`.
+		///
+		/// Weight: `O(1)`
+		#[weight = T::WeightInfo::set_attribute()]
+		fn set_attribute(origin,
+			#[compact] class: T::ClassId,
+			maybe_instance: Option<T::InstanceId>,
+			key: Vec<u8>,
+			value: Vec<u8>,
+		) -> DispatchResult {
+			let maybe_check_owner = match T::ForceOrigin::try_origin(origin) {
+				Ok(_) => None,
+				Err(origin) => Some(ensure_signed(origin)?),
+			};
+			Self::do_set_attribute(class, maybe_instance, key, value, maybe_check_owner)
+		}
+
+		/// Clear an attribute for an asset class or instance.
+		///
+		/// Origin must be either `ForceOrigin` or Signed and the sender should be the Owner of the
+		/// asset `class`.
+		///
+		/// Any deposit is freed for the asset class owner.
+		///
+		/// - `class`: The identifier of the asset class whose instance's metadata to clear.
+		/// - `maybe_instance`: The identifier of the asset instance whose metadata to clear, or
+		/// `None` to clear an attribute of the class itself.
+		/// - `key`: The key of the attribute.
+		///
+		/// Emits `AttributeCleared`.
+		///
+		/// Weight: `O(1)`
+		#[weight = T::WeightInfo::clear_attribute()]
+		fn clear_attribute(origin,
+			#[compact] class: T::ClassId,
+			maybe_instance: Option<T::InstanceId>,
+			key: Vec<u8>,
+		) -> DispatchResult {
+			let maybe_check_owner = match T::ForceOrigin::try_origin(origin) {
+				Ok(_) => None,
+				Err(origin) => Some(ensure_signed(origin)?),
+			};
+
+			let mut class_details = Class::<T>::get(&class).ok_or(Error::<T>::Unknown)?;
+			if let Some(check_owner) = &maybe_check_owner {
+				ensure!(check_owner == &class_details.owner, Error::<T>::NoPermission);
+			}
+			ensure!(!Self::is_metadata_frozen(class, maybe_instance), Error::<T>::Frozen);
+
+			if let Some((_, deposit)) = Attribute::<T>::take(&class, (maybe_instance, &key)) {
+				class_details.attributes = class_details.attributes.saturating_sub(1);
+				class_details.total_deposit = class_details.total_deposit.saturating_sub(deposit);
+				T::Currency::unreserve(&class_details.owner, deposit);
+				Class::<T>::insert(&class, &class_details);
+				Self::deposit_event(RawEvent::AttributeCleared(class, maybe_instance, key));
+			}
+			Ok(())
+		}
+
+		/// Set the metadata for an asset instance.
+		///
+		/// Origin must be either `ForceOrigin` or Signed and the sender should be the Owner of the
+		/// asset `class`.
+		///
+		/// If the origin is Signed, then funds of signer are reserved according to the formula:
+		/// `MetadataDepositBase + DepositPerByte * data.len` taking into
+		/// account any already reserved funds.
+		///
+		/// - `class`: The identifier of the asset class whose instance's metadata to set.
+		/// - `instance`: The identifier of the asset instance whose metadata to set.
+		/// - `data`: The general information of this asset. Limited in length by `StringLimit`.
+		/// - `is_frozen`: Whether the metadata should be frozen against further changes.
+		///
+		/// Emits `MetadataSet`.
+		///
+		/// Weight: `O(1)`
+		#[weight = T::WeightInfo::set_metadata()]
+		fn set_metadata(origin,
+			#[compact] class: T::ClassId,
+			#[compact] instance: T::InstanceId,
+			data: Vec<u8>,
+			is_frozen: bool,
+		) -> DispatchResult {
+			let maybe_check_owner = match T::ForceOrigin::try_origin(origin) {
+				Ok(_) => None,
+				Err(origin) => Some(ensure_signed(origin)?),
+			};
+
+			ensure!(data.len() <= T::StringLimit::get() as usize, Error::<T>::BadMetadata);
+
+			let mut class_details = Class::<T>::get(&class).ok_or(Error::<T>::Unknown)?;
+			if let Some(check_owner) = &maybe_check_owner {
+				ensure!(check_owner == &class_details.owner, Error::<T>::NoPermission);
+			}
+
+			InstanceMetadataOf::<T>::try_mutate_exists(class, instance, |metadata| {
+				let was_frozen = metadata.as_ref().map_or(false, |m| m.is_frozen);
+				ensure!(maybe_check_owner.is_none() || !was_frozen, Error::<T>::Frozen);
+
+				if metadata.is_none() {
+					class_details.instance_metadatas = class_details.instance_metadatas
+						.saturating_add(1);
+				}
+				let old_deposit = metadata.take().map_or(Zero::zero(), |m| m.deposit);
+				let deposit = if !class_details.free_holding && maybe_check_owner.is_some() {
+					T::DepositPerByte::get()
+						.saturating_mul((data.len() as u32).into())
+						.saturating_add(T::MetadataDepositBase::get())
+				} else {
+					Zero::zero()
+				};
+				Self::adjust_deposit(&mut class_details, old_deposit, deposit)?;
+
+				*metadata = Some(InstanceMetadata { deposit, data: data.clone(), is_frozen });
+
+				Class::<T>::insert(&class, &class_details);
+				Self::deposit_event(RawEvent::MetadataSet(class, instance, data, is_frozen));
+				Ok(())
+			})
+		}
+
+		/// Clear the metadata for an asset instance.
+		///
+		/// Origin must be either `ForceOrigin` or Signed and the sender should be the Owner of the
+		/// asset `class`.
+		///
+		/// Any deposit is freed for the asset class owner.
+		///
+		/// - `class`: The identifier of the asset class whose instance's metadata to clear.
+		/// - `instance`: The identifier of the asset instance whose metadata to clear.
+		///
+		/// Emits `MetadataCleared`.
+		///
+		/// Weight: `O(1)`
+		#[weight = T::WeightInfo::clear_metadata()]
+		fn clear_metadata(origin,
+			#[compact] class: T::ClassId,
+			#[compact] instance: T::InstanceId,
+		) -> DispatchResult {
+			let maybe_check_owner = match T::ForceOrigin::try_origin(origin) {
+				Ok(_) => None,
+				Err(origin) => Some(ensure_signed(origin)?),
+			};
+
+			let mut class_details = Class::<T>::get(&class).ok_or(Error::<T>::Unknown)?;
+			if let Some(check_owner) = &maybe_check_owner {
+				ensure!(check_owner == &class_details.owner, Error::<T>::NoPermission);
+			}
+
+			InstanceMetadataOf::<T>::try_mutate_exists(class, instance, |metadata| {
+				let was_frozen = metadata.as_ref().map_or(false, |m| m.is_frozen);
+				ensure!(maybe_check_owner.is_none() || !was_frozen, Error::<T>::Frozen);
+
+				let deposit = metadata.take().ok_or(Error::<T>::Unknown)?.deposit;
+				class_details.instance_metadatas =
+					class_details.instance_metadatas.saturating_sub(1);
+				class_details.total_deposit = class_details.total_deposit.saturating_sub(deposit);
+				T::Currency::unreserve(&class_details.owner, deposit);
+
+				Class::<T>::insert(&class, &class_details);
+				Self::deposit_event(RawEvent::MetadataCleared(class, instance));
+				Ok(())
+			})
+		}
+
+		/// Set the metadata for an asset class.
+		///
+		/// Origin must be either `ForceOrigin` or `Signed` and the sender should be the Owner of
+		/// the asset `class`.
+		///
+		/// If the origin is `Signed`, then funds of signer are reserved according to the formula:
+		/// `MetadataDepositBase + DepositPerByte * data.len` taking into
+		/// account any already reserved funds.
+		///
+		/// - `class`: The identifier of the asset whose metadata to update.
+		/// - `data`: The general information of this asset. Limited in length by `StringLimit`.
+		/// - `is_frozen`: Whether the metadata should be frozen against further changes.
+		///
+		/// Emits `ClassMetadataSet`.
+		///
+		/// Weight: `O(1)`
+		#[weight = T::WeightInfo::set_class_metadata()]
+		fn set_class_metadata(origin,
+			#[compact] class: T::ClassId,
+			data: Vec<u8>,
+			is_frozen: bool,
+		) -> DispatchResult {
+			let maybe_check_owner = match T::ForceOrigin::try_origin(origin) {
+				Ok(_) => None,
+				Err(origin) => Some(ensure_signed(origin)?),
+			};
+
+			ensure!(data.len() <= T::StringLimit::get() as usize, Error::<T>::BadMetadata);
+
+			let mut class_details = Class::<T>::get(&class).ok_or(Error::<T>::Unknown)?;
+			if let Some(check_owner) = &maybe_check_owner {
+				ensure!(check_owner == &class_details.owner, Error::<T>::NoPermission);
+			}
+
+			ClassMetadataOf::<T>::try_mutate_exists(class, |metadata| {
+				let was_frozen = metadata.as_ref().map_or(false, |m| m.is_frozen);
+				ensure!(maybe_check_owner.is_none() || !was_frozen, Error::<T>::Frozen);
+
+				let old_deposit = metadata.take().map_or(Zero::zero(), |m| m.deposit);
+				let deposit = if !class_details.free_holding && maybe_check_owner.is_some() {
+					T::DepositPerByte::get()
+						.saturating_mul((data.len() as u32).into())
+						.saturating_add(T::MetadataDepositBase::get())
+				} else {
+					Zero::zero()
+				};
+				Self::adjust_deposit(&mut class_details, old_deposit, deposit)?;
+
+				*metadata = Some(ClassMetadata { deposit, data: data.clone(), is_frozen });
+
+				Class::<T>::insert(&class, &class_details);
+				Self::deposit_event(RawEvent::ClassMetadataSet(class, data, is_frozen));
+				Ok(())
+			})
+		}
+
+		/// Clear the metadata for an asset class.
+		///
+		/// Origin must be either `ForceOrigin` or `Signed` and the sender should be the Owner of
+		/// the asset `class`.
+		///
+		/// Any deposit is freed for the asset class owner.
+		///
+		/// - `class`: The identifier of the asset class whose metadata to clear.
+		///
+		/// Emits `ClassMetadataCleared`.
+		///
+		/// Weight: `O(1)`
+		#[weight = T::WeightInfo::clear_class_metadata()]
+		fn clear_class_metadata(origin, #[compact] class: T::ClassId) -> DispatchResult {
+			let maybe_check_owner = match T::ForceOrigin::try_origin(origin) {
+				Ok(_) => None,
+				Err(origin) => Some(ensure_signed(origin)?),
+			};
+
+			let mut class_details = Class::<T>::get(&class).ok_or(Error::<T>::Unknown)?;
+			if let Some(check_owner) = &maybe_check_owner {
+				ensure!(check_owner == &class_details.owner, Error::<T>::NoPermission);
+			}
+
+			ClassMetadataOf::<T>::try_mutate_exists(class, |metadata| {
+				let was_frozen = metadata.as_ref().map_or(false, |m| m.is_frozen);
+				ensure!(maybe_check_owner.is_none() || !was_frozen, Error::<T>::Frozen);
+
+				let deposit = metadata.take().ok_or(Error::<T>::Unknown)?.deposit;
+				class_details.total_deposit = class_details.total_deposit.saturating_sub(deposit);
+				T::Currency::unreserve(&class_details.owner, deposit);
+
+				Class::<T>::insert(&class, &class_details);
+				Self::deposit_event(RawEvent::ClassMetadataCleared(class));
+				Ok(())
+			})
+		}
+	}
+}
+
+// The main implementation block for the module.
+impl<T: Config> Module<T> {
+	// Public immutables
+
+	/// Get the owner of the asset `instance` of `class`, if it exists.
+	pub fn owner(class: T::ClassId, instance: T::InstanceId) -> Option<T::AccountId> {
+		Asset::<T>::get(class, instance).map(|i| i.owner)
+	}
+
+	/// Get the witness data needed to destroy the asset `class`, if it exists.
+	pub fn destroy_witness(class: T::ClassId) -> Option<DestroyWitness> {
+		Class::<T>::get(class).map(|c| c.destroy_witness())
+	}
+
+	/// Create the asset `class`, owned by `owner` and with `admin` as its whole team, and reserve
+	/// `deposit` from `owner`.
+	pub(crate) fn do_create_class(
+		class: T::ClassId,
+		owner: T::AccountId,
+		admin: T::AccountId,
+		deposit: DepositBalanceOf<T>,
+		free_holding: bool,
+	) -> DispatchResult {
+		ensure!(!Class::<T>::contains_key(class), Error::<T>::InUse);
+
+		T::Currency::reserve(&owner, deposit)?;
+
+		Class::<T>::insert(class, ClassDetails {
+			owner,
+			issuer: admin.clone(),
+			admin: admin.clone(),
+			freezer: admin,
+			total_deposit: deposit,
+			free_holding,
+			instances: 0,
+			instance_metadatas: 0,
+			attributes: 0,
+			is_frozen: false,
+		});
+		Ok(())
+	}
+
+	/// Mint the asset `instance` of `class` into `owner`, reserving the instance deposit from
+	/// the class owner unless the class is free-holding.
+	///
+	/// `with_details` may veto the operation, e.g. on grounds of permissions.
+	pub(crate) fn do_mint(
+		class: T::ClassId,
+		instance: T::InstanceId,
+		owner: T::AccountId,
+		with_details: impl FnOnce(&ClassDetailsFor<T>) -> DispatchResult,
+	) -> DispatchResult {
+		ensure!(!Asset::<T>::contains_key(class, instance), Error::<T>::AlreadyExists);
+
+		Class::<T>::try_mutate(&class, |maybe_class_details| -> DispatchResult {
+			let class_details = maybe_class_details.as_mut().ok_or(Error::<T>::Unknown)?;
+
+			with_details(class_details)?;
+
+			let instances = class_details.instances.checked_add(1).ok_or(Error::<T>::Overflow)?;
+			let deposit = if class_details.free_holding {
+				Zero::zero()
+			} else {
+				T::InstanceDeposit::get()
+			};
+			T::Currency::reserve(&class_details.owner, deposit)?;
+			class_details.total_deposit = class_details.total_deposit.saturating_add(deposit);
+			class_details.instances = instances;
+
+			Account::<T>::insert(&owner, (class, instance), ());
+			let details = InstanceDetails {
+				owner: owner.clone(),
+				approved: None,
+				is_frozen: false,
+				deposit,
+			};
+			Asset::<T>::insert(&class, &instance, details);
+			Ok(())
+		})?;
+
+		Self::deposit_event(RawEvent::Issued(class, instance, owner));
+		Ok(())
+	}
+
+	/// Burn the asset `instance` of `class`, clearing its metadata and attributes and returning
+	/// all their deposits to the class owner.
+	///
+	/// `with_details` may veto the operation, e.g. on grounds of permissions.
+	pub(crate) fn do_burn(
+		class: T::ClassId,
+		instance: T::InstanceId,
+		with_details: impl FnOnce(&ClassDetailsFor<T>, &InstanceDetailsFor<T>) -> DispatchResult,
+	) -> DispatchResult {
+		let owner = Class::<T>::try_mutate(&class, |maybe_class_details| {
+			let class_details = maybe_class_details.as_mut().ok_or(Error::<T>::Unknown)?;
+			let details = Asset::<T>::get(&class, &instance).ok_or(Error::<T>::Unknown)?;
+
+			with_details(class_details, &details)?;
+
+			let mut deposit = details.deposit;
+			if let Some(metadata) = InstanceMetadataOf::<T>::take(&class, &instance) {
+				class_details.instance_metadatas =
+					class_details.instance_metadatas.saturating_sub(1);
+				deposit = deposit.saturating_add(metadata.deposit);
+			}
+			// Attributes are keyed by the instance and the attribute key together, so those of
+			// the instance can only be found among all attributes of the class.
+			let attributes = Attribute::<T>::iter_prefix(&class)
+				.filter(|((maybe_instance, _), _)| maybe_instance.as_ref() == Some(&instance))
+				.collect::<Vec<_>>();
+			for (key, (_, attribute_deposit)) in attributes {
+				Attribute::<T>::remove(&class, key);
+				class_details.attributes = class_details.attributes.saturating_sub(1);
+				deposit = deposit.saturating_add(attribute_deposit);
+			}
+
+			// Return the deposits.
+			T::Currency::unreserve(&class_details.owner, deposit);
+			class_details.total_deposit = class_details.total_deposit.saturating_sub(deposit);
+			class_details.instances = class_details.instances.saturating_sub(1);
+			Ok::<_, DispatchError>(details.owner)
+		})?;
+
+		Asset::<T>::remove(&class, &instance);
+		Account::<T>::remove(&owner, (class, instance));
+
+		Self::deposit_event(RawEvent::Burned(class, instance, owner));
+		Ok(())
+	}
+
+	/// Transfer the asset `instance` of `class` to `dest`, clearing any approved delegate.
+	///
+	/// Neither the class nor the instance may be frozen. `with_details` may veto the operation,
+	/// e.g. on grounds of permissions.
+	pub(crate) fn do_transfer(
+		class: T::ClassId,
+		instance: T::InstanceId,
+		dest: T::AccountId,
+		with_details: impl FnOnce(
+			&ClassDetailsFor<T>,
+			&mut InstanceDetailsFor<T>,
+		) -> DispatchResult,
+	) -> DispatchResult {
+		let class_details = Class::<T>::get(&class).ok_or(Error::<T>::Unknown)?;
+		ensure!(!class_details.is_frozen, Error::<T>::Frozen);
+
+		let mut details = Asset::<T>::get(&class, &instance).ok_or(Error::<T>::Unknown)?;
+		ensure!(!details.is_frozen, Error::<T>::Frozen);
+		with_details(&class_details, &mut details)?;
+
+		Account::<T>::remove(&details.owner, (class, instance));
+		Account::<T>::insert(&dest, (class, instance), ());
+		let origin = details.owner;
+		details.owner = dest;
+		details.approved = None;
+		Asset::<T>::insert(&class, &instance, &details);
+
+		Self::deposit_event(RawEvent::Transferred(class, instance, origin, details.owner));
+		Ok(())
+	}
+
+	/// Set the attribute `key` of the asset `class` (or of its `maybe_instance`) to `value`.
+	///
+	/// If `maybe_check_owner` is `Some`, then it must be the Owner of the class, who also pays the
+	/// deposit unless the class is free-holding. Otherwise, no deposit is taken.
+	pub(crate) fn do_set_attribute(
+		class: T::ClassId,
+		maybe_instance: Option<T::InstanceId>,
+		key: Vec<u8>,
+		value: Vec<u8>,
+		maybe_check_owner: Option<T::AccountId>,
+	) -> DispatchResult {
+		ensure!(key.len() <= T::KeyLimit::get() as usize, Error::<T>::BadAttribute);
+		ensure!(value.len() <= T::ValueLimit::get() as usize, Error::<T>::BadAttribute);
+
+		let mut class_details = Class::<T>::get(&class).ok_or(Error::<T>::Unknown)?;
+		if let Some(check_owner) = &maybe_check_owner {
+			ensure!(check_owner == &class_details.owner, Error::<T>::NoPermission);
+		}
+		ensure!(!Self::is_metadata_frozen(class, maybe_instance), Error::<T>::Frozen);
+
+		let attribute = Attribute::<T>::get(&class, (maybe_instance, &key));
+		if attribute.is_none() {
+			class_details.attributes = class_details.attributes.saturating_add(1);
+		}
+		let old_deposit = attribute.map_or(Zero::zero(), |m| m.1);
+		let deposit = if !class_details.free_holding && maybe_check_owner.is_some() {
+			T::DepositPerByte::get()
+				.saturating_mul(((key.len() + value.len()) as u32).into())
+				.saturating_add(T::AttributeDepositBase::get())
+		} else {
+			Zero::zero()
+		};
+		Self::adjust_deposit(&mut class_details, old_deposit, deposit)?;
+
+		Attribute::<T>::insert(&class, (maybe_instance, &key), (&value, deposit));
+		Class::<T>::insert(&class, &class_details);
+		Self::deposit_event(RawEvent::AttributeSet(class, maybe_instance, key, value));
+		Ok(())
+	}
+
+	/// Whether the metadata of the asset `class` (or of its `maybe_instance`) is frozen.
+	fn is_metadata_frozen(class: T::ClassId, maybe_instance: Option<T::InstanceId>) -> bool {
+		match maybe_instance {
+			None => ClassMetadataOf::<T>::get(class).map_or(false, |m| m.is_frozen),
+			Some(instance) => InstanceMetadataOf::<T>::get(class, instance)
+				.map_or(false, |m| m.is_frozen),
+		}
+	}
+
+	/// Replace a deposit of `old` paid by the owner of a class with one of `new`, reserving or
+	/// unreserving the difference.
+	fn adjust_deposit(
+		class_details: &mut ClassDetailsFor<T>,
+		old: DepositBalanceOf<T>,
+		new: DepositBalanceOf<T>,
+	) -> DispatchResult {
+		if new > old {
+			T::Currency::reserve(&class_details.owner, new - old)?;
+		} else if new < old {
+			T::Currency::unreserve(&class_details.owner, old - new);
+		}
+		class_details.total_deposit = class_details.total_deposit
+			.saturating_add(new)
+			.saturating_sub(old);
+		Ok(())
+	}
+}
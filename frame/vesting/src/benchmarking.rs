@@ -40,21 +40,25 @@ fn add_locks<T: Config>(who: &T::AccountId, n: u8) {
 	}
 }
 
-fn add_vesting_schedule<T: Config>(who: &T::AccountId) -> Result<(), &'static str> {
+fn add_vesting_schedules<T: Config>(who: &T::AccountId, n: u32) -> Result<BalanceOf<T>, &'static str> {
 	let locked = 100u32;
 	let per_block = 10u32;
 	let starting_block = 1u32;
 
 	System::<T>::set_block_number(0u32.into());
 
-	// Add schedule to avoid `NotVesting` error.
-	Vesting::<T>::add_vesting_schedule(
-		&who,
-		locked.into(),
-		per_block.into(),
-		starting_block.into(),
-	)?;
-	Ok(())
+	// Add schedules to avoid `NotVesting` error.
+	let mut total_locked: BalanceOf<T> = Zero::zero();
+	for _ in 0 .. n {
+		Vesting::<T>::add_vesting_schedule(
+			&who,
+			locked.into(),
+			per_block.into(),
+			starting_block.into(),
+		)?;
+		total_locked = total_locked.saturating_add(locked.into());
+	}
+	Ok(total_locked)
 }
 
 benchmarks! {
@@ -62,16 +66,17 @@ benchmarks! {
 
 	vest_locked {
 		let l in 0 .. MaxLocksOf::<T>::get();
+		let s in 1 .. T::MaxVestingSchedules::get();
 
 		let caller = whitelisted_caller();
 		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
 		add_locks::<T>(&caller, l as u8);
-		add_vesting_schedule::<T>(&caller)?;
+		let expected_balance = add_vesting_schedules::<T>(&caller, s)?;
 		// At block zero, everything is vested.
 		System::<T>::set_block_number(T::BlockNumber::zero());
 		assert_eq!(
 			Vesting::<T>::vesting_balance(&caller),
-			Some(100u32.into()),
+			Some(expected_balance),
 			"Vesting schedule not added",
 		);
 	}: vest(RawOrigin::Signed(caller.clone()))
@@ -79,18 +84,19 @@ benchmarks! {
 		// Nothing happened since everything is still vested.
 		assert_eq!(
 			Vesting::<T>::vesting_balance(&caller),
-			Some(100u32.into()),
+			Some(expected_balance),
 			"Vesting schedule was removed",
 		);
 	}
 
 	vest_unlocked {
 		let l in 0 .. MaxLocksOf::<T>::get();
+		let s in 1 .. T::MaxVestingSchedules::get();
 
 		let caller = whitelisted_caller();
 		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
 		add_locks::<T>(&caller, l as u8);
-		add_vesting_schedule::<T>(&caller)?;
+		add_vesting_schedules::<T>(&caller, s)?;
 		// At block 20, everything is unvested.
 		System::<T>::set_block_number(20u32.into());
 		assert_eq!(
@@ -110,17 +116,18 @@ benchmarks! {
 
 	vest_other_locked {
 		let l in 0 .. MaxLocksOf::<T>::get();
+		let s in 1 .. T::MaxVestingSchedules::get();
 
 		let other: T::AccountId = account("other", 0, SEED);
 		let other_lookup: <T::Lookup as StaticLookup>::Source = T::Lookup::unlookup(other.clone());
 		T::Currency::make_free_balance_be(&other, BalanceOf::<T>::max_value());
 		add_locks::<T>(&other, l as u8);
-		add_vesting_schedule::<T>(&other)?;
+		let expected_balance = add_vesting_schedules::<T>(&other, s)?;
 		// At block zero, everything is vested.
 		System::<T>::set_block_number(T::BlockNumber::zero());
 		assert_eq!(
 			Vesting::<T>::vesting_balance(&other),
-			Some(100u32.into()),
+			Some(expected_balance),
 			"Vesting schedule not added",
 		);
 
@@ -130,19 +137,20 @@ benchmarks! {
 		// Nothing happened since everything is still vested.
 		assert_eq!(
 			Vesting::<T>::vesting_balance(&other),
-			Some(100u32.into()),
+			Some(expected_balance),
 			"Vesting schedule was removed",
 		);
 	}
 
 	vest_other_unlocked {
 		let l in 0 .. MaxLocksOf::<T>::get();
+		let s in 1 .. T::MaxVestingSchedules::get();
 
 		let other: T::AccountId = account("other", 0, SEED);
 		let other_lookup: <T::Lookup as StaticLookup>::Source = T::Lookup::unlookup(other.clone());
 		T::Currency::make_free_balance_be(&other, BalanceOf::<T>::max_value());
 		add_locks::<T>(&other, l as u8);
-		add_vesting_schedule::<T>(&other)?;
+		add_vesting_schedules::<T>(&other, s)?;
 		// At block 20, everything is unvested.
 		System::<T>::set_block_number(20u32.into());
 		assert_eq!(
@@ -164,13 +172,16 @@ benchmarks! {
 
 	vested_transfer {
 		let l in 0 .. MaxLocksOf::<T>::get();
+		let s in 0 .. T::MaxVestingSchedules::get() - 1;
 
 		let caller: T::AccountId = whitelisted_caller();
 		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
 		let target: T::AccountId = account("target", 0, SEED);
 		let target_lookup: <T::Lookup as StaticLookup>::Source = T::Lookup::unlookup(target.clone());
-		// Give target existing locks
+		// Give target existing locks and vesting schedules
+		T::Currency::make_free_balance_be(&target, T::Currency::minimum_balance());
 		add_locks::<T>(&target, l as u8);
+		add_vesting_schedules::<T>(&target, s)?;
 
 		let transfer_amount = T::MinVestedTransfer::get();
 
@@ -182,27 +193,30 @@ benchmarks! {
 	}: _(RawOrigin::Signed(caller), target_lookup, vesting_schedule)
 	verify {
 		assert_eq!(
-			T::MinVestedTransfer::get(),
+			T::MinVestedTransfer::get() + T::Currency::minimum_balance(),
 			T::Currency::free_balance(&target),
 			"Transfer didn't happen",
 		);
 		assert_eq!(
-			Vesting::<T>::vesting_balance(&target),
-			Some(T::MinVestedTransfer::get()),
-			"Lock not created",
+			Vesting::<T>::vesting(&target).map(|schedules| schedules.len()),
+			Some(s as usize + 1),
+			"Schedule not added",
 		);
 	}
 
 	force_vested_transfer {
 		let l in 0 .. MaxLocksOf::<T>::get();
+		let s in 0 .. T::MaxVestingSchedules::get() - 1;
 
 		let source: T::AccountId = account("source", 0, SEED);
 		let source_lookup: <T::Lookup as StaticLookup>::Source = T::Lookup::unlookup(source.clone());
 		T::Currency::make_free_balance_be(&source, BalanceOf::<T>::max_value());
 		let target: T::AccountId = account("target", 0, SEED);
 		let target_lookup: <T::Lookup as StaticLookup>::Source = T::Lookup::unlookup(target.clone());
-		// Give target existing locks
+		// Give target existing locks and vesting schedules
+		T::Currency::make_free_balance_be(&target, T::Currency::minimum_balance());
 		add_locks::<T>(&target, l as u8);
+		add_vesting_schedules::<T>(&target, s)?;
 
 		let transfer_amount = T::MinVestedTransfer::get();
 
@@ -214,14 +228,38 @@ benchmarks! {
 	}: _(RawOrigin::Root, source_lookup, target_lookup, vesting_schedule)
 	verify {
 		assert_eq!(
-			T::MinVestedTransfer::get(),
+			T::MinVestedTransfer::get() + T::Currency::minimum_balance(),
 			T::Currency::free_balance(&target),
 			"Transfer didn't happen",
 		);
 		assert_eq!(
-			Vesting::<T>::vesting_balance(&target),
-			Some(T::MinVestedTransfer::get()),
-			"Lock not created",
+			Vesting::<T>::vesting(&target).map(|schedules| schedules.len()),
+			Some(s as usize + 1),
+			"Schedule not added",
+		);
+	}
+
+	merge_schedules {
+		let l in 0 .. MaxLocksOf::<T>::get();
+		let s in 2 .. T::MaxVestingSchedules::get();
+
+		let caller = whitelisted_caller();
+		T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
+		add_locks::<T>(&caller, l as u8);
+		let expected_balance = add_vesting_schedules::<T>(&caller, s)?;
+		// Schedules are not vesting at block 0.
+		System::<T>::set_block_number(T::BlockNumber::zero());
+	}: _(RawOrigin::Signed(caller.clone()), 0, s - 1)
+	verify {
+		assert_eq!(
+			Vesting::<T>::vesting(&caller).map(|schedules| schedules.len()),
+			Some(s as usize - 1),
+			"Schedules not merged",
+		);
+		assert_eq!(
+			Vesting::<T>::vesting_balance(&caller),
+			Some(expected_balance),
+			"Vesting balance changed",
 		);
 	}
 }
@@ -241,6 +279,7 @@ mod tests {
 			assert_ok!(test_benchmark_vest_other_unlocked::<Test>());
 			assert_ok!(test_benchmark_vested_transfer::<Test>());
 			assert_ok!(test_benchmark_force_vested_transfer::<Test>());
+			assert_ok!(test_benchmark_merge_schedules::<Test>());
 		});
 	}
 }
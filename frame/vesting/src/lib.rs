@@ -32,6 +32,9 @@
 //! either `vest` (in typical case where the sender is calling on their own behalf) or `vest_other`
 //! in case the sender is calling on another account's behalf.
 //!
+//! An account can have up to `MaxVestingSchedules` vesting schedules at the same time, the locked
+//! amount being the sum of what each of them still has locked.
+//!
 //! ## Interface
 //!
 //! This module implements the `VestingSchedule` trait.
@@ -41,6 +44,9 @@
 //! - `vest` - Update the lock, reducing it in line with the amount "vested" so far.
 //! - `vest_other` - Update the lock of another account, reducing it in line with the amount
 //!   "vested" so far.
+//! - `vested_transfer` - Transfer funds to another account and add a vesting schedule for them.
+//! - `force_vested_transfer` - Same as `vested_transfer`, but from an arbitrary source account.
+//! - `merge_schedules` - Merge two vesting schedules of the sender into a single one.
 //!
 //! [`Call`]: ./enum.Call.html
 //! [`Config`]: ./trait.Config.html
@@ -51,16 +57,19 @@ mod benchmarking;
 pub mod weights;
 
 use sp_std::prelude::*;
-use sp_std::fmt::Debug;
+use sp_std::{fmt::Debug, convert::TryFrom, marker::PhantomData};
 use codec::{Encode, Decode};
 use sp_runtime::{DispatchResult, RuntimeDebug, traits::{
-	StaticLookup, Zero, AtLeast32BitUnsigned, MaybeSerializeDeserialize, Convert
+	StaticLookup, Zero, One, AtLeast32BitUnsigned, MaybeSerializeDeserialize, Convert, Saturating,
 }};
-use frame_support::{decl_module, decl_event, decl_storage, decl_error, ensure};
+use frame_support::{
+	decl_module, decl_event, decl_storage, decl_error, ensure, BoundedVec, IterableStorageMap,
+};
 use frame_support::traits::{
 	Currency, LockableCurrency, VestingSchedule, WithdrawReasons, LockIdentifier,
-	ExistenceRequirement, Get,
+	ExistenceRequirement, Get, OnRuntimeUpgrade,
 };
+use frame_support::weights::Weight;
 use frame_system::{ensure_signed, ensure_root};
 pub use weights::WeightInfo;
 
@@ -80,12 +89,30 @@ pub trait Config: frame_system::Config {
 	/// The minimum amount transferred to call `vested_transfer`.
 	type MinVestedTransfer: Get<BalanceOf<Self>>;
 
+	/// The maximum number of vesting schedules an account can have at the same time.
+	type MaxVestingSchedules: Get<u32>;
+
 	/// Weight information for extrinsics in this pallet.
 	type WeightInfo: WeightInfo;
 }
 
 const VESTING_ID: LockIdentifier = *b"vesting ";
 
+// A value placed in storage that represents the current version of the Vesting storage.
+// This value is used by the `on_runtime_upgrade` logic to determine whether we run
+// storage migration logic.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+enum Releases {
+	V0,
+	V1,
+}
+
+impl Default for Releases {
+	fn default() -> Self {
+		Releases::V0
+	}
+}
+
 /// Struct to encode the vesting schedule of an individual account.
 #[derive(Encode, Decode, Copy, Clone, PartialEq, Eq, RuntimeDebug)]
 pub struct VestingInfo<Balance, BlockNumber> {
@@ -117,14 +144,41 @@ impl<
 			Zero::zero()
 		}
 	}
+
+	/// Block number at which the schedule is fully vested, expressed as a balance.
+	///
+	/// A `per_block` of zero is treated as one, so that every schedule ends eventually.
+	pub fn ending_block_as_balance<
+		BlockNumberToBalance: Convert<BlockNumber, Balance>
+	>(&self) -> Balance {
+		let starting_block = BlockNumberToBalance::convert(self.starting_block);
+		let per_block = self.per_block.max(One::one());
+		let duration = if (self.locked % per_block).is_zero() {
+			self.locked / per_block
+		} else {
+			self.locked / per_block + One::one()
+		};
+		starting_block.saturating_add(duration)
+	}
 }
 
+/// The vesting schedules of an account.
+pub type VestingSchedules<T> = BoundedVec<
+	VestingInfo<BalanceOf<T>, <T as frame_system::Config>::BlockNumber>,
+	<T as Config>::MaxVestingSchedules,
+>;
+
 decl_storage! {
 	trait Store for Module<T: Config> as Vesting {
 		/// Information regarding the vesting of a given account.
 		pub Vesting get(fn vesting):
 			map hasher(blake2_128_concat) T::AccountId
-			=> Option<VestingInfo<BalanceOf<T>, T::BlockNumber>>;
+			=> Option<VestingSchedules<T>>;
+
+		/// Storage version of the pallet.
+		///
+		/// New networks start with last version.
+		StorageVersion build(|_| Releases::V1): Releases;
 	}
 	add_extra_genesis {
 		config(vesting): Vec<(T::AccountId, T::BlockNumber, T::BlockNumber, BalanceOf<T>)>;
@@ -143,13 +197,17 @@ decl_storage! {
 				let length_as_balance = T::BlockNumberToBalance::convert(length);
 				let per_block = locked / length_as_balance.max(sp_runtime::traits::One::one());
 
-				Vesting::<T>::insert(who, VestingInfo {
+				let mut schedules = Vesting::<T>::get(who).unwrap_or_default();
+				schedules.try_push(VestingInfo {
 					locked: locked,
 					per_block: per_block,
 					starting_block: begin
-				});
+				}).expect("Too many vesting schedules at genesis.");
+				let total_locked = schedules.iter()
+					.fold(Zero::zero(), |total: BalanceOf<T>, s| total.saturating_add(s.locked));
+				Vesting::<T>::insert(who, schedules);
 				let reasons = WithdrawReasons::TRANSFER | WithdrawReasons::RESERVE;
-				T::Currency::set_lock(VESTING_ID, who, locked, reasons);
+				T::Currency::set_lock(VESTING_ID, who, total_locked, reasons);
 			}
 		})
 	}
@@ -171,10 +229,15 @@ decl_error! {
 	pub enum Error for Module<T: Config> {
 		/// The account given is not vesting.
 		NotVesting,
-		/// An existing vesting schedule already exists for this account that cannot be clobbered.
-		ExistingVestingSchedule,
+		/// The account already has `MaxVestingSchedules` vesting schedules. Merge some of them
+		/// before adding another one.
+		AtMaxVestingSchedules,
 		/// Amount being transferred is too low to create a vesting schedule.
 		AmountLow,
+		/// A vesting schedule with the given index does not exist.
+		ScheduleIndexOutOfBounds,
+		/// The vesting schedule is invalid, e.g. it unlocks nothing per block.
+		InvalidScheduleParams,
 	}
 }
 
@@ -186,8 +249,15 @@ decl_module! {
 		/// The minimum amount to be transferred to create a new vesting schedule.
 		const MinVestedTransfer: BalanceOf<T> = T::MinVestedTransfer::get();
 
+		/// The maximum number of vesting schedules an account can have.
+		const MaxVestingSchedules: u32 = T::MaxVestingSchedules::get();
+
 		fn deposit_event() = default;
 
+		fn on_runtime_upgrade() -> Weight {
+			MigrateToV1::<T>::on_runtime_upgrade()
+		}
+
 		/// Unlock any vested funds of the sender account.
 		///
 		/// The dispatch origin for this call must be _Signed_ and the sender must have funds still
@@ -196,13 +266,13 @@ decl_module! {
 		/// Emits either `VestingCompleted` or `VestingUpdated`.
 		///
 		/// # <weight>
-		/// - `O(1)`.
+		/// - `O(S)` where `S` is the number of vesting schedules of the sender.
 		/// - DbWeight: 2 Reads, 2 Writes
 		///     - Reads: Vesting Storage, Balances Locks, [Sender Account]
 		///     - Writes: Vesting Storage, Balances Locks, [Sender Account]
 		/// # </weight>
-		#[weight = T::WeightInfo::vest_locked(MaxLocksOf::<T>::get())
-			.max(T::WeightInfo::vest_unlocked(MaxLocksOf::<T>::get()))
+		#[weight = T::WeightInfo::vest_locked(MaxLocksOf::<T>::get(), T::MaxVestingSchedules::get())
+			.max(T::WeightInfo::vest_unlocked(MaxLocksOf::<T>::get(), T::MaxVestingSchedules::get()))
 		]
 		fn vest(origin) -> DispatchResult {
 			let who = ensure_signed(origin)?;
@@ -219,13 +289,13 @@ decl_module! {
 		/// Emits either `VestingCompleted` or `VestingUpdated`.
 		///
 		/// # <weight>
-		/// - `O(1)`.
+		/// - `O(S)` where `S` is the number of vesting schedules of `target`.
 		/// - DbWeight: 3 Reads, 3 Writes
 		///     - Reads: Vesting Storage, Balances Locks, Target Account
 		///     - Writes: Vesting Storage, Balances Locks, Target Account
 		/// # </weight>
-		#[weight = T::WeightInfo::vest_other_locked(MaxLocksOf::<T>::get())
-			.max(T::WeightInfo::vest_other_unlocked(MaxLocksOf::<T>::get()))
+		#[weight = T::WeightInfo::vest_other_locked(MaxLocksOf::<T>::get(), T::MaxVestingSchedules::get())
+			.max(T::WeightInfo::vest_other_unlocked(MaxLocksOf::<T>::get(), T::MaxVestingSchedules::get()))
 		]
 		fn vest_other(origin, target: <T::Lookup as StaticLookup>::Source) -> DispatchResult {
 			ensure_signed(origin)?;
@@ -237,35 +307,28 @@ decl_module! {
 		/// The dispatch origin for this call must be _Signed_.
 		///
 		/// - `target`: The account that should be transferred the vested funds.
-		/// - `amount`: The amount of funds to transfer and will be vested.
 		/// - `schedule`: The vesting schedule attached to the transfer.
 		///
-		/// Emits `VestingCreated`.
+		/// The schedule is added to the existing schedules of `target`, of which there can be at
+		/// most `MaxVestingSchedules`.
+		///
+		/// Emits `VestingUpdated`.
 		///
 		/// # <weight>
-		/// - `O(1)`.
+		/// - `O(S)` where `S` is the number of vesting schedules of `target`.
 		/// - DbWeight: 3 Reads, 3 Writes
 		///     - Reads: Vesting Storage, Balances Locks, Target Account, [Sender Account]
 		///     - Writes: Vesting Storage, Balances Locks, Target Account, [Sender Account]
 		/// # </weight>
-		#[weight = T::WeightInfo::vested_transfer(MaxLocksOf::<T>::get())]
+		#[weight = T::WeightInfo::vested_transfer(MaxLocksOf::<T>::get(), T::MaxVestingSchedules::get())]
 		pub fn vested_transfer(
 			origin,
 			target: <T::Lookup as StaticLookup>::Source,
 			schedule: VestingInfo<BalanceOf<T>, T::BlockNumber>,
 		) -> DispatchResult {
 			let transactor = ensure_signed(origin)?;
-			ensure!(schedule.locked >= T::MinVestedTransfer::get(), Error::<T>::AmountLow);
-
-			let who = T::Lookup::lookup(target)?;
-			ensure!(!Vesting::<T>::contains_key(&who), Error::<T>::ExistingVestingSchedule);
-
-			T::Currency::transfer(&transactor, &who, schedule.locked, ExistenceRequirement::AllowDeath)?;
-
-			Self::add_vesting_schedule(&who, schedule.locked, schedule.per_block, schedule.starting_block)
-				.expect("user does not have an existing vesting schedule; q.e.d.");
-
-			Ok(())
+			let target = T::Lookup::lookup(target)?;
+			Self::do_vested_transfer(transactor, target, schedule)
 		}
 
 		/// Force a vested transfer.
@@ -274,18 +337,20 @@ decl_module! {
 		///
 		/// - `source`: The account whose funds should be transferred.
 		/// - `target`: The account that should be transferred the vested funds.
-		/// - `amount`: The amount of funds to transfer and will be vested.
 		/// - `schedule`: The vesting schedule attached to the transfer.
 		///
-		/// Emits `VestingCreated`.
+		/// The schedule is added to the existing schedules of `target`, of which there can be at
+		/// most `MaxVestingSchedules`.
+		///
+		/// Emits `VestingUpdated`.
 		///
 		/// # <weight>
-		/// - `O(1)`.
+		/// - `O(S)` where `S` is the number of vesting schedules of `target`.
 		/// - DbWeight: 4 Reads, 4 Writes
 		///     - Reads: Vesting Storage, Balances Locks, Target Account, Source Account
 		///     - Writes: Vesting Storage, Balances Locks, Target Account, Source Account
 		/// # </weight>
-		#[weight = T::WeightInfo::force_vested_transfer(MaxLocksOf::<T>::get())]
+		#[weight = T::WeightInfo::force_vested_transfer(MaxLocksOf::<T>::get(), T::MaxVestingSchedules::get())]
 		pub fn force_vested_transfer(
 			origin,
 			source: <T::Lookup as StaticLookup>::Source,
@@ -293,18 +358,54 @@ decl_module! {
 			schedule: VestingInfo<BalanceOf<T>, T::BlockNumber>,
 		) -> DispatchResult {
 			ensure_root(origin)?;
-			ensure!(schedule.locked >= T::MinVestedTransfer::get(), Error::<T>::AmountLow);
-
-			let target = T::Lookup::lookup(target)?;
 			let source = T::Lookup::lookup(source)?;
-			ensure!(!Vesting::<T>::contains_key(&target), Error::<T>::ExistingVestingSchedule);
-
-			T::Currency::transfer(&source, &target, schedule.locked, ExistenceRequirement::AllowDeath)?;
+			let target = T::Lookup::lookup(target)?;
+			Self::do_vested_transfer(source, target, schedule)
+		}
 
-			Self::add_vesting_schedule(&target, schedule.locked, schedule.per_block, schedule.starting_block)
-				.expect("user does not have an existing vesting schedule; q.e.d.");
+		/// Merge two vesting schedules of the sender into a single one.
+		///
+		/// The dispatch origin for this call must be _Signed_ and the sender must have funds still
+		/// locked under this module.
+		///
+		/// - `schedule1_index`: Index of the first schedule to merge.
+		/// - `schedule2_index`: Index of the second schedule to merge.
+		///
+		/// The merged schedule unlocks the amount both schedules still have locked, starting at
+		/// the later of the current block and the two starting blocks, and ending at the later of
+		/// the two ending blocks. It is appended after the remaining schedules. Merging a schedule
+		/// with itself is a no-op.
+		///
+		/// Emits either `VestingCompleted` or `VestingUpdated`.
+		///
+		/// # <weight>
+		/// - `O(S)` where `S` is the number of vesting schedules of the sender.
+		/// - DbWeight: 2 Reads, 2 Writes
+		///     - Reads: Vesting Storage, Balances Locks, [Sender Account]
+		///     - Writes: Vesting Storage, Balances Locks, [Sender Account]
+		/// # </weight>
+		#[weight = T::WeightInfo::merge_schedules(MaxLocksOf::<T>::get(), T::MaxVestingSchedules::get())]
+		pub fn merge_schedules(origin, schedule1_index: u32, schedule2_index: u32) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			if schedule1_index == schedule2_index {
+				return Ok(())
+			}
+			let mut schedules = Self::vesting(&who).ok_or(Error::<T>::NotVesting)?;
+			let (first, second) = (
+				schedule1_index.min(schedule2_index) as usize,
+				schedule1_index.max(schedule2_index) as usize,
+			);
+			ensure!(second < schedules.len(), Error::<T>::ScheduleIndexOutOfBounds);
 
-			Ok(())
+			let now = <frame_system::Module<T>>::block_number();
+			// Remove the later index first so that the earlier one stays valid.
+			let schedule2 = schedules.remove(second);
+			let schedule1 = schedules.remove(first);
+			if let Some(merged) = Self::merge_vesting_info(now, schedule1, schedule2) {
+				schedules.try_push(merged).map_err(|_| Error::<T>::AtMaxVestingSchedules)?;
+			}
+			Vesting::<T>::insert(&who, schedules);
+			Self::update_lock(who)
 		}
 	}
 }
@@ -312,10 +413,13 @@ decl_module! {
 impl<T: Config> Module<T> {
 	/// (Re)set or remove the module's currency lock on `who`'s account in accordance with their
 	/// current unvested amount.
+	///
+	/// Schedules that are fully vested are removed.
 	fn update_lock(who: T::AccountId) -> DispatchResult {
-		let vesting = Self::vesting(&who).ok_or(Error::<T>::NotVesting)?;
+		let mut schedules = Self::vesting(&who).ok_or(Error::<T>::NotVesting)?;
 		let now = <frame_system::Module<T>>::block_number();
-		let locked_now = vesting.locked_at::<T::BlockNumberToBalance>(now);
+		schedules.retain(|s| !s.locked_at::<T::BlockNumberToBalance>(now).is_zero());
+		let locked_now = Self::locked_at(&schedules, now);
 
 		if locked_now.is_zero() {
 			T::Currency::remove_lock(VESTING_ID, &who);
@@ -324,10 +428,103 @@ impl<T: Config> Module<T> {
 		} else {
 			let reasons = WithdrawReasons::TRANSFER | WithdrawReasons::RESERVE;
 			T::Currency::set_lock(VESTING_ID, &who, locked_now, reasons);
+			Vesting::<T>::insert(&who, schedules);
 			Self::deposit_event(RawEvent::VestingUpdated(who, locked_now));
 		}
 		Ok(())
 	}
+
+	/// The total amount locked by `schedules` at block `now`.
+	fn locked_at(
+		schedules: &[VestingInfo<BalanceOf<T>, T::BlockNumber>],
+		now: T::BlockNumber,
+	) -> BalanceOf<T> {
+		schedules.iter().fold(Zero::zero(), |total, schedule| {
+			total.saturating_add(schedule.locked_at::<T::BlockNumberToBalance>(now))
+		})
+	}
+
+	/// Merge two vesting schedules into one that unlocks what both still have locked at `now`.
+	///
+	/// Returns `None` if both schedules are fully vested.
+	fn merge_vesting_info(
+		now: T::BlockNumber,
+		schedule1: VestingInfo<BalanceOf<T>, T::BlockNumber>,
+		schedule2: VestingInfo<BalanceOf<T>, T::BlockNumber>,
+	) -> Option<VestingInfo<BalanceOf<T>, T::BlockNumber>> {
+		let locked = schedule1.locked_at::<T::BlockNumberToBalance>(now)
+			.saturating_add(schedule2.locked_at::<T::BlockNumberToBalance>(now));
+		if locked.is_zero() {
+			return None
+		}
+
+		let starting_block = now.max(schedule1.starting_block).max(schedule2.starting_block);
+		let ending_block = schedule1.ending_block_as_balance::<T::BlockNumberToBalance>()
+			.max(schedule2.ending_block_as_balance::<T::BlockNumberToBalance>());
+		let duration = ending_block
+			.saturating_sub(T::BlockNumberToBalance::convert(starting_block))
+			.max(One::one());
+		let per_block = (locked / duration).max(One::one());
+
+		Some(VestingInfo { locked, per_block, starting_block })
+	}
+
+	/// Transfer `schedule.locked` from `source` to `target` and add `schedule` to `target`.
+	fn do_vested_transfer(
+		source: T::AccountId,
+		target: T::AccountId,
+		schedule: VestingInfo<BalanceOf<T>, T::BlockNumber>,
+	) -> DispatchResult {
+		ensure!(schedule.locked >= T::MinVestedTransfer::get(), Error::<T>::AmountLow);
+		ensure!(!schedule.per_block.is_zero(), Error::<T>::InvalidScheduleParams);
+		Self::can_add_vesting_schedule(
+			&target,
+			schedule.locked,
+			schedule.per_block,
+			schedule.starting_block,
+		)?;
+
+		T::Currency::transfer(&source, &target, schedule.locked, ExistenceRequirement::AllowDeath)?;
+
+		Self::add_vesting_schedule(&target, schedule.locked, schedule.per_block, schedule.starting_block)
+			.expect("schedule was checked by `can_add_vesting_schedule`; q.e.d.");
+
+		Ok(())
+	}
+
+	/// Migrate the storage from a single vesting schedule per account to a list of them.
+	///
+	/// Returns the weight consumed.
+	pub fn migrate_to_v1() -> Weight {
+		Vesting::<T>::translate::<VestingInfo<BalanceOf<T>, T::BlockNumber>, _>(|_, schedule| {
+			VestingSchedules::<T>::try_from(sp_std::vec![schedule]).ok()
+		});
+		StorageVersion::put(Releases::V1);
+		T::BlockWeights::get().max_block
+	}
+
+	/// Checks to run before `migrate_to_v1`, in order to test the migration with try-runtime.
+	#[cfg(feature = "try-runtime")]
+	pub fn pre_migrate_to_v1() -> Result<(), &'static str> {
+		frame_support::ensure!(
+			StorageVersion::get() == Releases::V0,
+			"Vesting storage should be V0 before the migration",
+		);
+		Ok(())
+	}
+
+	/// Checks to run after `migrate_to_v1`, in order to test the migration with try-runtime.
+	#[cfg(feature = "try-runtime")]
+	pub fn post_migrate_to_v1() -> Result<(), &'static str> {
+		frame_support::ensure!(
+			StorageVersion::get() == Releases::V1,
+			"Vesting storage should be V1 after the migration",
+		);
+		for (_, schedules) in Vesting::<T>::iter() {
+			frame_support::ensure!(!schedules.is_empty(), "Migrated vesting schedules are empty");
+		}
+		Ok(())
+	}
 }
 
 impl<T: Config> VestingSchedule<T::AccountId> for Module<T> where
@@ -338,9 +535,9 @@ impl<T: Config> VestingSchedule<T::AccountId> for Module<T> where
 
 	/// Get the amount that is currently being vested and cannot be transferred out of this account.
 	fn vesting_balance(who: &T::AccountId) -> Option<BalanceOf<T>> {
-		if let Some(v) = Self::vesting(who) {
+		if let Some(schedules) = Self::vesting(who) {
 			let now = <frame_system::Module<T>>::block_number();
-			let locked_now = v.locked_at::<T::BlockNumberToBalance>(now);
+			let locked_now = Self::locked_at(&schedules, now);
 			Some(T::Currency::free_balance(who).min(locked_now))
 		} else {
 			None
@@ -349,7 +546,7 @@ impl<T: Config> VestingSchedule<T::AccountId> for Module<T> where
 
 	/// Adds a vesting schedule to a given account.
 	///
-	/// If there already exists a vesting schedule for the given account, an `Err` is returned
+	/// If the account already has `MaxVestingSchedules` vesting schedules, an `Err` is returned
 	/// and nothing is updated.
 	///
 	/// On success, a linearly reducing amount of funds will be locked. In order to realise any
@@ -364,25 +561,70 @@ impl<T: Config> VestingSchedule<T::AccountId> for Module<T> where
 		starting_block: T::BlockNumber
 	) -> DispatchResult {
 		if locked.is_zero() { return Ok(()) }
-		if Vesting::<T>::contains_key(who) {
-			Err(Error::<T>::ExistingVestingSchedule)?
-		}
 		let vesting_schedule = VestingInfo {
 			locked,
 			per_block,
 			starting_block
 		};
-		Vesting::<T>::insert(who, vesting_schedule);
+		let mut schedules = Self::vesting(who).unwrap_or_default();
+		schedules.try_push(vesting_schedule).map_err(|_| Error::<T>::AtMaxVestingSchedules)?;
+		Vesting::<T>::insert(who, schedules);
 		// it can't fail, but even if somehow it did, we don't really care.
 		let _ = Self::update_lock(who.clone());
 		Ok(())
 	}
 
-	/// Remove a vesting schedule for a given account.
-	fn remove_vesting_schedule(who: &T::AccountId) {
-		Vesting::<T>::remove(who);
+	/// Checks whether a vesting schedule can be added to a given account.
+	fn can_add_vesting_schedule(
+		who: &T::AccountId,
+		_locked: BalanceOf<T>,
+		_per_block: BalanceOf<T>,
+		_starting_block: T::BlockNumber,
+	) -> DispatchResult {
+		let count = Vesting::<T>::decode_len(who).unwrap_or(0);
+		ensure!(count < T::MaxVestingSchedules::get() as usize, Error::<T>::AtMaxVestingSchedules);
+		Ok(())
+	}
+
+	/// Remove the vesting schedule at `schedule_index` for a given account.
+	fn remove_vesting_schedule(who: &T::AccountId, schedule_index: u32) -> DispatchResult {
+		let mut schedules = Self::vesting(who).ok_or(Error::<T>::NotVesting)?;
+		ensure!(
+			(schedule_index as usize) < schedules.len(),
+			Error::<T>::ScheduleIndexOutOfBounds,
+		);
+		schedules.remove(schedule_index as usize);
+		Vesting::<T>::insert(who, schedules);
 		// it can't fail, but even if somehow it did, we don't really care.
 		let _ = Self::update_lock(who.clone());
+		Ok(())
+	}
+}
+
+/// Migrates the storage of the vesting pallet from `V0` to `V1`.
+///
+/// This is [`Module::migrate_to_v1`] as an `OnRuntimeUpgrade`, so that it can be included in
+/// the runtime upgrade of a runtime and its checks are run by try-runtime. The pallet runs it
+/// from its own `on_runtime_upgrade` as well, where it does nothing once the storage is `V1`.
+pub struct MigrateToV1<T>(PhantomData<T>);
+
+impl<T: Config> OnRuntimeUpgrade for MigrateToV1<T> {
+	fn on_runtime_upgrade() -> Weight {
+		if StorageVersion::get() == Releases::V0 {
+			Module::<T>::migrate_to_v1()
+		} else {
+			T::DbWeight::get().reads(1)
+		}
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn pre_upgrade() -> Result<(), &'static str> {
+		Module::<T>::pre_migrate_to_v1()
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn post_upgrade() -> Result<(), &'static str> {
+		Module::<T>::post_migrate_to_v1()
 	}
 }
 
@@ -448,6 +690,7 @@ mod tests {
 	}
 	parameter_types! {
 		pub const MinVestedTransfer: u64 = 256 * 2;
+		pub const MaxVestingSchedules: u32 = 3;
 		pub static ExistentialDeposit: u64 = 0;
 	}
 	impl Config for Test {
@@ -455,6 +698,7 @@ mod tests {
 		type Currency = Balances;
 		type BlockNumberToBalance = Identity;
 		type MinVestedTransfer = MinVestedTransfer;
+		type MaxVestingSchedules = MaxVestingSchedules;
 		type WeightInfo = ();
 	}
 	type System = frame_system::Module<Test>;
@@ -529,9 +773,9 @@ mod tests {
 					per_block: 64, // Vesting over 20 blocks
 					starting_block: 10,
 				};
-				assert_eq!(Vesting::vesting(&1), Some(user1_vesting_schedule)); // Account 1 has a vesting schedule
-				assert_eq!(Vesting::vesting(&2), Some(user2_vesting_schedule)); // Account 2 has a vesting schedule
-				assert_eq!(Vesting::vesting(&12), Some(user12_vesting_schedule)); // Account 12 has a vesting schedule
+				assert_eq!(Vesting::vesting(&1).map(|s| s.into_inner()), Some(vec![user1_vesting_schedule])); // Account 1 has a vesting schedule
+				assert_eq!(Vesting::vesting(&2).map(|s| s.into_inner()), Some(vec![user2_vesting_schedule])); // Account 2 has a vesting schedule
+				assert_eq!(Vesting::vesting(&12).map(|s| s.into_inner()), Some(vec![user12_vesting_schedule])); // Account 12 has a vesting schedule
 
 				// Account 1 has only 128 units vested from their illiquid 256 * 5 units at block 1
 				assert_eq!(Vesting::vesting_balance(&1), Some(128 * 9));
@@ -652,7 +896,7 @@ mod tests {
 					per_block: 64, // Vesting over 20 blocks
 					starting_block: 10,
 				};
-				assert_eq!(Vesting::vesting(&12), Some(user12_vesting_schedule));
+				assert_eq!(Vesting::vesting(&12).map(|s| s.into_inner()), Some(vec![user12_vesting_schedule]));
 
 				// Account 12 can still send liquid funds
 				assert_ok!(Balances::transfer(Some(12).into(), 3, 256 * 5));
@@ -679,7 +923,7 @@ mod tests {
 				};
 				assert_ok!(Vesting::vested_transfer(Some(3).into(), 4, new_vesting_schedule));
 				// Now account 4 should have vesting.
-				assert_eq!(Vesting::vesting(&4), Some(new_vesting_schedule));
+				assert_eq!(Vesting::vesting(&4).map(|s| s.into_inner()), Some(vec![new_vesting_schedule]));
 				// Ensure the transfer happened correctly.
 				let user3_free_balance_updated = Balances::free_balance(&3);
 				assert_eq!(user3_free_balance_updated, 256 * 25);
@@ -718,17 +962,17 @@ mod tests {
 					per_block: 256, // Vesting over 20 blocks
 					starting_block: 10,
 				};
-				assert_eq!(Vesting::vesting(&2), Some(user2_vesting_schedule));
+				assert_eq!(Vesting::vesting(&2).map(|s| s.into_inner()), Some(vec![user2_vesting_schedule]));
 
-				// The vesting schedule we will try to create, fails due to pre-existence of schedule.
+				// The vesting schedule we will try to create, fails because nothing is ever unlocked.
 				let new_vesting_schedule = VestingInfo {
 					locked: 256 * 5,
-					per_block: 64, // Vesting over 20 blocks
+					per_block: 0,
 					starting_block: 10,
 				};
 				assert_noop!(
 					Vesting::vested_transfer(Some(4).into(), 2, new_vesting_schedule),
-					Error::<Test>::ExistingVestingSchedule,
+					Error::<Test>::InvalidScheduleParams,
 				);
 
 				// Fails due to too low transfer amount.
@@ -769,7 +1013,7 @@ mod tests {
 				assert_noop!(Vesting::force_vested_transfer(Some(4).into(), 3, 4, new_vesting_schedule), BadOrigin);
 				assert_ok!(Vesting::force_vested_transfer(RawOrigin::Root.into(), 3, 4, new_vesting_schedule));
 				// Now account 4 should have vesting.
-				assert_eq!(Vesting::vesting(&4), Some(new_vesting_schedule));
+				assert_eq!(Vesting::vesting(&4).map(|s| s.into_inner()), Some(vec![new_vesting_schedule]));
 				// Ensure the transfer happened correctly.
 				let user3_free_balance_updated = Balances::free_balance(&3);
 				assert_eq!(user3_free_balance_updated, 256 * 25);
@@ -808,17 +1052,17 @@ mod tests {
 					per_block: 256, // Vesting over 20 blocks
 					starting_block: 10,
 				};
-				assert_eq!(Vesting::vesting(&2), Some(user2_vesting_schedule));
+				assert_eq!(Vesting::vesting(&2).map(|s| s.into_inner()), Some(vec![user2_vesting_schedule]));
 
-				// The vesting schedule we will try to create, fails due to pre-existence of schedule.
+				// The vesting schedule we will try to create, fails because nothing is ever unlocked.
 				let new_vesting_schedule = VestingInfo {
 					locked: 256 * 5,
-					per_block: 64, // Vesting over 20 blocks
+					per_block: 0,
 					starting_block: 10,
 				};
 				assert_noop!(
 					Vesting::force_vested_transfer(RawOrigin::Root.into(), 4, 2, new_vesting_schedule),
-					Error::<Test>::ExistingVestingSchedule,
+					Error::<Test>::InvalidScheduleParams,
 				);
 
 				// Fails due to too low transfer amount.
@@ -837,4 +1081,133 @@ mod tests {
 				assert_eq!(user4_free_balance, 256 * 40);
 			});
 	}
+
+	#[test]
+	fn vested_transfer_adds_schedules_up_to_max() {
+		ExtBuilder::default()
+			.existential_deposit(256)
+			.build()
+			.execute_with(|| {
+				let user2_vesting_schedule = VestingInfo {
+					locked: 256 * 20,
+					per_block: 256, // Vesting over 20 blocks
+					starting_block: 10,
+				};
+				let new_vesting_schedule = VestingInfo {
+					locked: 256 * 5,
+					per_block: 64, // Vesting over 20 blocks
+					starting_block: 10,
+				};
+				assert_ok!(Vesting::vested_transfer(Some(3).into(), 2, new_vesting_schedule));
+				assert_ok!(Vesting::vested_transfer(Some(3).into(), 2, new_vesting_schedule));
+				assert_eq!(
+					Vesting::vesting(&2).map(|s| s.into_inner()),
+					Some(vec![user2_vesting_schedule, new_vesting_schedule, new_vesting_schedule]),
+				);
+				// All schedules lock their funds.
+				assert_eq!(Vesting::vesting_balance(&2), Some(256 * 30));
+
+				// Account 2 reached `MaxVestingSchedules`.
+				assert_noop!(
+					Vesting::vested_transfer(Some(3).into(), 2, new_vesting_schedule),
+					Error::<Test>::AtMaxVestingSchedules,
+				);
+
+				System::set_block_number(20);
+				assert_eq!(Vesting::vesting_balance(&2), Some(256 * 10 + 2 * 64 * 10));
+
+				// Fully vested schedules are removed.
+				System::set_block_number(30);
+				assert_ok!(Vesting::vest(Some(2).into()));
+				assert_eq!(Vesting::vesting(&2), None);
+				assert_eq!(Vesting::vesting_balance(&2), None);
+			});
+	}
+
+	#[test]
+	fn merge_schedules_works() {
+		ExtBuilder::default()
+			.existential_deposit(256)
+			.build()
+			.execute_with(|| {
+				let user2_vesting_schedule = VestingInfo {
+					locked: 256 * 20,
+					per_block: 256, // Vesting over 20 blocks
+					starting_block: 10,
+				};
+				let new_vesting_schedule = VestingInfo {
+					locked: 256 * 5,
+					per_block: 128, // Vesting over 10 blocks
+					starting_block: 20,
+				};
+				assert_ok!(Vesting::vested_transfer(Some(3).into(), 2, new_vesting_schedule));
+
+				assert_noop!(Vesting::merge_schedules(Some(4).into(), 0, 1), Error::<Test>::NotVesting);
+				assert_noop!(
+					Vesting::merge_schedules(Some(2).into(), 0, 2),
+					Error::<Test>::ScheduleIndexOutOfBounds,
+				);
+				// Merging a schedule with itself does nothing.
+				assert_ok!(Vesting::merge_schedules(Some(2).into(), 1, 1));
+				assert_eq!(
+					Vesting::vesting(&2).map(|s| s.into_inner()),
+					Some(vec![user2_vesting_schedule, new_vesting_schedule]),
+				);
+
+				System::set_block_number(15);
+				let locked = Vesting::vesting_balance(&2);
+				assert_eq!(locked, Some(256 * 15 + 256 * 5));
+				assert_ok!(Vesting::merge_schedules(Some(2).into(), 1, 0));
+				// Both schedules end at block 30, the merged one starts once both started.
+				let merged_schedule = VestingInfo {
+					locked: 256 * 20,
+					per_block: 256 * 2,
+					starting_block: 20,
+				};
+				assert_eq!(Vesting::vesting(&2).map(|s| s.into_inner()), Some(vec![merged_schedule]));
+				assert_eq!(Vesting::vesting_balance(&2), locked);
+
+				System::set_block_number(30);
+				assert_eq!(Vesting::vesting_balance(&2), Some(0));
+			});
+	}
+
+	#[test]
+	fn migrate_to_v1_works() {
+		use frame_support::{Blake2_128Concat, StorageHasher};
+
+		sp_io::TestExternalities::default().execute_with(|| {
+			let schedule = VestingInfo::<u64, u64> {
+				locked: 256 * 5,
+				per_block: 64,
+				starting_block: 10,
+			};
+			frame_support::migration::put_storage_value(
+				b"Vesting",
+				b"Vesting",
+				&Blake2_128Concat::hash(&5u64.encode()),
+				schedule,
+			);
+			assert_eq!(StorageVersion::get(), Releases::V0);
+
+			Vesting::migrate_to_v1();
+
+			assert_eq!(StorageVersion::get(), Releases::V1);
+			assert_eq!(Vesting::vesting(&5).map(|s| s.into_inner()), Some(vec![schedule]));
+		});
+	}
+
+	#[test]
+	#[cfg(feature = "try-runtime")]
+	fn migration_to_v1_checks_work() {
+		sp_io::TestExternalities::default().execute_with(|| {
+			assert_ok!(MigrateToV1::<Test>::pre_upgrade());
+			assert!(MigrateToV1::<Test>::post_upgrade().is_err());
+
+			MigrateToV1::<Test>::on_runtime_upgrade();
+
+			assert_ok!(MigrateToV1::<Test>::post_upgrade());
+			assert!(MigrateToV1::<Test>::pre_upgrade().is_err());
+		});
+	}
 }
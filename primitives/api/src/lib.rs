@@ -671,8 +671,13 @@ decl_runtime_apis! {
 	}
 
 	/// The `Metadata` api trait that returns metadata for the runtime.
+	#[api_version(2)]
 	pub trait Metadata {
 		/// Returns the metadata of a runtime.
 		fn metadata() -> OpaqueMetadata;
+		/// Returns the metadata of a runtime in the given version.
+		///
+		/// Returns `None` if the runtime doesn't support this version of the metadata.
+		fn metadata_at_version(version: u32) -> Option<OpaqueMetadata>;
 	}
 }
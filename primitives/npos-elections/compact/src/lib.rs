@@ -234,6 +234,50 @@ fn struct_def(
 				return false
 			}
 		}
+
+		impl _npos::CompactSolution for #ident {
+			const LIMIT: usize = #count;
+			type Voter = #voter_type;
+			type Target = #target_type;
+			type Accuracy = #weight_type;
+
+			fn from_assignment<FV, FT, A>(
+				assignments: Vec<_npos::Assignment<A, #weight_type>>,
+				voter_index: FV,
+				target_index: FT,
+			) -> Result<Self, _npos::Error>
+				where
+					A: _npos::IdentifierT,
+					for<'r> FV: Fn(&'r A) -> Option<#voter_type>,
+					for<'r> FT: Fn(&'r A) -> Option<#target_type>,
+			{
+				#ident::from_assignment(assignments, voter_index, target_index)
+			}
+
+			fn into_assignment<A: _npos::IdentifierT>(
+				self,
+				voter_at: impl Fn(#voter_type) -> Option<A>,
+				target_at: impl Fn(#target_type) -> Option<A>,
+			) -> Result<Vec<_npos::Assignment<A, #weight_type>>, _npos::Error> {
+				#ident::into_assignment(self, voter_at, target_at)
+			}
+
+			fn voter_count(&self) -> usize {
+				#ident::len(self)
+			}
+
+			fn edge_count(&self) -> usize {
+				#ident::edge_count(self)
+			}
+
+			fn unique_targets(&self) -> Vec<#target_type> {
+				#ident::unique_targets(self)
+			}
+
+			fn remove_voter(&mut self, to_remove: #voter_type) -> bool {
+				#ident::remove_voter(self, to_remove)
+			}
+		}
 	))
 }
 
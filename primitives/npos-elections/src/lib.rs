@@ -76,6 +76,7 @@
 
 use sp_std::{
 	prelude::*, collections::btree_map::BTreeMap, fmt::Debug, cmp::Ordering, rc::Rc, cell::RefCell,
+	convert::{TryInto, TryFrom},
 };
 use sp_arithmetic::{
 	PerThing, Rational128, ThresholdOrd, InnerOf, Normalizable,
@@ -84,7 +85,6 @@ use sp_arithmetic::{
 
 #[cfg(feature = "std")]
 use serde::{Serialize, Deserialize};
-#[cfg(feature = "std")]
 use codec::{Encode, Decode};
 
 #[cfg(test)]
@@ -133,6 +133,59 @@ pub trait VotingLimit {
 	const LIMIT: usize;
 }
 
+/// An aggregator trait for the compact solution types generated by [`generate_solution_type`].
+///
+/// This allows a compact type to be used generically, e.g. as an associated type of a pallet's
+/// configuration. The generated compact type will implement this.
+pub trait CompactSolution: Sized {
+	/// The maximum number of votes that are allowed per voter.
+	const LIMIT: usize;
+
+	/// The type used to index voters.
+	type Voter: TryInto<usize> + TryFrom<usize> + Debug + Copy + Clone;
+
+	/// The type used to index targets.
+	type Target: TryInto<usize> + TryFrom<usize> + Debug + Copy + Clone;
+
+	/// The accuracy of the ratios of each vote.
+	type Accuracy: PerThing;
+
+	/// Build self from a list of assignments, given the functions to index voters and targets.
+	fn from_assignment<FV, FT, A>(
+		assignments: Vec<Assignment<A, Self::Accuracy>>,
+		voter_index: FV,
+		target_index: FT,
+	) -> Result<Self, Error>
+	where
+		A: IdentifierT,
+		for<'r> FV: Fn(&'r A) -> Option<Self::Voter>,
+		for<'r> FT: Fn(&'r A) -> Option<Self::Target>;
+
+	/// Convert self into a list of assignments, given the functions to look voters and targets up.
+	fn into_assignment<A: IdentifierT>(
+		self,
+		voter_at: impl Fn(Self::Voter) -> Option<A>,
+		target_at: impl Fn(Self::Target) -> Option<A>,
+	) -> Result<Vec<Assignment<A, Self::Accuracy>>, Error>;
+
+	/// Get the number of voters in this solution.
+	fn voter_count(&self) -> usize;
+
+	/// Get the total count of edges.
+	fn edge_count(&self) -> usize;
+
+	/// Get the sorted list of unique targets in this solution.
+	fn unique_targets(&self) -> Vec<Self::Target>;
+
+	/// Get the average edge count.
+	fn average_edge_count(&self) -> usize {
+		self.edge_count().checked_div(self.voter_count()).unwrap_or(0)
+	}
+
+	/// Remove a certain voter. Returns `true` if exactly one voter was removed.
+	fn remove_voter(&mut self, to_remove: Self::Voter) -> bool;
+}
+
 /// an aggregator trait for a generic type of a voter/target identifier. This usually maps to
 /// substrate's account id.
 pub trait IdentifierT: Clone + Eq + Default + Ord + Debug + codec::Codec {}
@@ -479,8 +532,8 @@ impl<AccountId> StakedAssignment<AccountId> {
 ///
 /// This, at the current version, resembles the `Exposure` defined in the Staking pallet, yet they
 /// do not necessarily have to be the same.
-#[derive(Default, Debug)]
-#[cfg_attr(feature = "std", derive(Serialize, Deserialize, Eq, PartialEq))]
+#[derive(Default, Debug, Clone, Eq, PartialEq, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 pub struct Support<AccountId> {
 	/// Total support.
 	pub total: ExtendedBalance,
@@ -491,6 +544,11 @@ pub struct Support<AccountId> {
 /// A linkage from a candidate and its [`Support`].
 pub type SupportMap<A> = BTreeMap<A, Support<A>>;
 
+/// A flat list of candidates and their [`Support`].
+///
+/// This is the same as [`SupportMap`], but more convenient to store and move around.
+pub type Supports<A> = Vec<(A, Support<A>)>;
+
 /// Build the support map from the given election result. It maps a flat structure like
 ///
 /// ```nocompile
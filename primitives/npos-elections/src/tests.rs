@@ -1525,4 +1525,24 @@ mod solution_type {
 			}
 		);
 	}
+
+	#[test]
+	fn compact_solution_trait_is_implemented() {
+		use crate::CompactSolution;
+
+		fn voter_count_of<C: CompactSolution>(compact: &C) -> usize {
+			compact.voter_count()
+		}
+
+		let compact = TestSolutionCompact {
+			votes1: vec![(0, 2), (1, 6)],
+			votes2: vec![(2, (0, TestAccuracy::from_percent(80)), 1)],
+			..Default::default()
+		};
+
+		assert_eq!(<TestSolutionCompact as CompactSolution>::LIMIT, 16);
+		assert_eq!(voter_count_of(&compact), 3);
+		assert_eq!(CompactSolution::edge_count(&compact), 4);
+		assert_eq!(CompactSolution::unique_targets(&compact), vec![0, 1, 2, 6]);
+	}
 }
@@ -411,6 +411,75 @@ pub enum DispatchError {
 		#[cfg_attr(feature = "std", serde(skip_deserializing))]
 		message: Option<&'static str>,
 	},
+	/// An error from the transactional storage layer.
+	Transactional(TransactionalError),
+	/// An error to do with tokens.
+	Token(TokenError),
+}
+
+/// Reason why a storage transaction could not be started.
+#[derive(Eq, PartialEq, Clone, Copy, Encode, Decode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum TransactionalError {
+	/// Too many transactional layers have been spawned.
+	LimitReached,
+}
+
+impl From<TransactionalError> for &'static str {
+	fn from(e: TransactionalError) -> &'static str {
+		match e {
+			TransactionalError::LimitReached => "Too many transactional layers have been spawned",
+		}
+	}
+}
+
+impl From<TransactionalError> for DispatchError {
+	fn from(e: TransactionalError) -> Self {
+		Self::Transactional(e)
+	}
+}
+
+/// Description of what went wrong when trying to complete an operation on a token.
+#[derive(Eq, PartialEq, Clone, Copy, Encode, Decode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum TokenError {
+	/// Funds are unavailable.
+	NoFunds,
+	/// Account that must exist would die.
+	WouldDie,
+	/// Account cannot exist with the funds that would be given.
+	BelowMinimum,
+	/// Account cannot be created.
+	CannotCreate,
+	/// The asset in question is unknown.
+	UnknownAsset,
+	/// Funds exist but are not spendable due to being frozen.
+	Frozen,
+	/// An underflow would occur.
+	Underflow,
+	/// An overflow would occur.
+	Overflow,
+}
+
+impl From<TokenError> for &'static str {
+	fn from(e: TokenError) -> &'static str {
+		match e {
+			TokenError::NoFunds => "Funds are unavailable",
+			TokenError::WouldDie => "Account that must exist would die",
+			TokenError::BelowMinimum => "Account cannot exist with the funds that would be given",
+			TokenError::CannotCreate => "Account cannot be created",
+			TokenError::UnknownAsset => "The asset in question is unknown",
+			TokenError::Frozen => "Funds exist but are not spendable due to being frozen",
+			TokenError::Underflow => "An underflow would occur",
+			TokenError::Overflow => "An overflow would occur",
+		}
+	}
+}
+
+impl From<TokenError> for DispatchError {
+	fn from(e: TokenError) -> Self {
+		Self::Token(e)
+	}
 }
 
 /// Result of a `Dispatchable` which contains the `DispatchResult` and additional information about
@@ -473,6 +542,8 @@ impl From<DispatchError> for &'static str {
 			DispatchError::CannotLookup => "Can not lookup",
 			DispatchError::BadOrigin => "Bad origin",
 			DispatchError::Module { message, .. } => message.unwrap_or("Unknown module error"),
+			DispatchError::Transactional(e) => e.into(),
+			DispatchError::Token(e) => e.into(),
 		}
 	}
 }
@@ -499,6 +570,8 @@ impl traits::Printable for DispatchError {
 					msg.print();
 				}
 			}
+			Self::Transactional(e) => <&'static str>::from(*e).print(),
+			Self::Token(e) => <&'static str>::from(*e).print(),
 		}
 	}
 }
@@ -327,6 +327,10 @@ impl Externalities for BasicExternalities {
 		unimplemented!("reset_read_write_count is not supported in Basic")
 	}
 
+	fn storage_layer_read_write_count(&self) -> Vec<(u32, u32)> {
+		unimplemented!("storage_layer_read_write_count is not supported in Basic")
+	}
+
 	fn get_whitelist(&self) -> Vec<TrackedStorageKey> {
 		unimplemented!("get_whitelist is not supported in Basic")
 	}
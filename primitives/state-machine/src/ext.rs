@@ -23,7 +23,7 @@ use crate::{
 };
 use hash_db::Hasher;
 use sp_core::{
-	storage::{well_known_keys::is_child_storage_key, ChildInfo, TrackedStorageKey},
+	storage::{well_known_keys::{self, is_child_storage_key}, ChildInfo, TrackedStorageKey},
 	hexdisplay::HexDisplay,
 };
 use sp_trie::{trie_types::Layout, empty_child_trie_root};
@@ -171,6 +171,24 @@ impl<'a, H, N, B> Ext<'a, H, N, B>
 		self.storage_transaction_cache.reset();
 	}
 
+	/// Tally a read of `key` in the storage layer stats.
+	///
+	/// The transaction level kept by the runtime is bookkeeping and not counted.
+	fn tally_read(&self, key: &[u8]) {
+		if key != well_known_keys::TRANSACTION_LEVEL {
+			self.overlay.storage_layer_stats().tally_read();
+		}
+	}
+
+	/// Tally a write of `key` in the storage layer stats.
+	///
+	/// The transaction level kept by the runtime is bookkeeping and not counted.
+	fn tally_write(&self, key: &[u8]) {
+		if key != well_known_keys::TRANSACTION_LEVEL {
+			self.overlay.storage_layer_stats().tally_write();
+		}
+	}
+
 	/// Read only accessor for the scheduled overlay changes.
 	#[cfg(feature = "std")]
 	pub fn get_offchain_storage_changes(&self) -> &OffchainOverlayedChanges {
@@ -220,6 +238,7 @@ where
 
 	fn storage(&self, key: &[u8]) -> Option<StorageValue> {
 		let _guard = guard();
+		self.tally_read(key);
 		let result = self.overlay.storage(key).map(|x| x.map(|x| x.to_vec())).unwrap_or_else(||
 			self.backend.storage(key).expect(EXT_NOT_ALLOWED_TO_FAIL));
 		trace!(target: "state", "{:04x}: Get {}={:?}",
@@ -232,6 +251,7 @@ where
 
 	fn storage_hash(&self, key: &[u8]) -> Option<Vec<u8>> {
 		let _guard = guard();
+		self.tally_read(key);
 		let result = self.overlay
 			.storage(key)
 			.map(|x| x.map(|x| H::hash(x)))
@@ -251,6 +271,7 @@ where
 		key: &[u8],
 	) -> Option<StorageValue> {
 		let _guard = guard();
+		self.overlay.storage_layer_stats().tally_read();
 		let result = self.overlay
 			.child_storage(child_info, key)
 			.map(|x| x.map(|x| x.to_vec()))
@@ -275,6 +296,7 @@ where
 		key: &[u8],
 	) -> Option<Vec<u8>> {
 		let _guard = guard();
+		self.overlay.storage_layer_stats().tally_read();
 		let result = self.overlay
 			.child_storage(child_info, key)
 			.map(|x| x.map(|x| H::hash(x)))
@@ -295,6 +317,7 @@ where
 
 	fn exists_storage(&self, key: &[u8]) -> bool {
 		let _guard = guard();
+		self.tally_read(key);
 		let result = match self.overlay.storage(key) {
 			Some(x) => x.is_some(),
 			_ => self.backend.exists_storage(key).expect(EXT_NOT_ALLOWED_TO_FAIL),
@@ -315,6 +338,7 @@ where
 		key: &[u8],
 	) -> bool {
 		let _guard = guard();
+		self.overlay.storage_layer_stats().tally_read();
 
 		let result = match self.overlay.child_storage(child_info, key) {
 			Some(x) => x.is_some(),
@@ -333,6 +357,7 @@ where
 	}
 
 	fn next_storage_key(&self, key: &[u8]) -> Option<StorageKey> {
+		self.overlay.storage_layer_stats().tally_read();
 		let next_backend_key = self.backend.next_storage_key(key).expect(EXT_NOT_ALLOWED_TO_FAIL);
 		let next_overlay_key_change = self.overlay.next_storage_key_change(key);
 
@@ -352,6 +377,7 @@ where
 		child_info: &ChildInfo,
 		key: &[u8],
 	) -> Option<StorageKey> {
+		self.overlay.storage_layer_stats().tally_read();
 		let next_backend_key = self.backend
 			.next_child_storage_key(child_info, key)
 			.expect(EXT_NOT_ALLOWED_TO_FAIL);
@@ -381,6 +407,7 @@ where
 			value.as_ref().map(HexDisplay::from)
 		);
 		let _guard = guard();
+		self.tally_write(&key);
 		if is_child_storage_key(&key) {
 			warn!(target: "trie", "Refuse to directly set child storage key");
 			return;
@@ -403,6 +430,7 @@ where
 			value.as_ref().map(HexDisplay::from)
 		);
 		let _guard = guard();
+		self.overlay.storage_layer_stats().tally_write();
 
 		self.mark_dirty();
 		self.overlay.set_child_storage(child_info, key, value);
@@ -418,6 +446,7 @@ where
 			HexDisplay::from(&child_info.storage_key()),
 		);
 		let _guard = guard();
+		self.overlay.storage_layer_stats().tally_write();
 		self.mark_dirty();
 		self.overlay.clear_child_storage(child_info);
 
@@ -454,6 +483,7 @@ where
 			HexDisplay::from(&prefix),
 		);
 		let _guard = guard();
+		self.overlay.storage_layer_stats().tally_write();
 		if is_child_storage_key(prefix) {
 			warn!(target: "trie", "Refuse to directly clear prefix that is part of child storage key");
 			return;
@@ -477,6 +507,7 @@ where
 			HexDisplay::from(&prefix),
 		);
 		let _guard = guard();
+		self.overlay.storage_layer_stats().tally_write();
 
 		self.mark_dirty();
 		self.overlay.clear_child_prefix(child_info, prefix);
@@ -497,6 +528,7 @@ where
 		);
 
 		let _guard = guard();
+		self.tally_write(&key);
 		self.mark_dirty();
 
 		let backend = &mut self.backend;
@@ -681,9 +713,14 @@ where
 	}
 
 	fn reset_read_write_count(&mut self) {
+		self.overlay.storage_layer_stats().reset();
 		self.backend.reset_read_write_count()
 	}
 
+	fn storage_layer_read_write_count(&self) -> Vec<(u32, u32)> {
+		self.overlay.storage_layer_stats().read_write_count()
+	}
+
 	fn get_whitelist(&self) -> Vec<TrackedStorageKey> {
 		self.backend.get_whitelist()
 	}
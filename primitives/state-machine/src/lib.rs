@@ -123,7 +123,7 @@ pub use crate::overlayed_changes::{
 pub use crate::backend::Backend;
 pub use crate::trie_backend_essence::{TrieBackendStorage, Storage};
 pub use crate::trie_backend::TrieBackend;
-pub use crate::stats::{UsageInfo, UsageUnit, StateMachineStats};
+pub use crate::stats::{UsageInfo, UsageUnit, StateMachineStats, StorageLayerStats};
 pub use error::{Error, ExecutionError};
 pub use crate::ext::Ext;
 
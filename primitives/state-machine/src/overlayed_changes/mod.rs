@@ -21,7 +21,7 @@ mod changeset;
 
 use crate::{
 	backend::Backend,
-	stats::StateMachineStats,
+	stats::{StateMachineStats, StorageLayerStats},
 };
 use sp_std::{vec::Vec, any::{TypeId, Any}, boxed::Box};
 use self::changeset::OverlayedChangeSet;
@@ -101,6 +101,8 @@ pub struct OverlayedChanges {
 	collect_extrinsics: bool,
 	/// Collect statistic on this execution.
 	stats: StateMachineStats,
+	/// Storage reads and writes done by the runtime in each transaction layer.
+	layer_stats: StorageLayerStats,
 }
 
 /// A storage changes structure that can be generated by the data collected in [`OverlayedChanges`].
@@ -372,6 +374,7 @@ impl OverlayedChanges {
 		for (_, (changeset, _)) in self.children.iter_mut() {
 			changeset.start_transaction();
 		}
+		self.layer_stats.set_transaction_depth(self.transaction_depth());
 	}
 
 	/// Rollback the last transaction started by `start_transaction`.
@@ -385,6 +388,7 @@ impl OverlayedChanges {
 				.expect("Top and children changesets are started in lockstep; qed");
 			!changeset.is_empty()
 		});
+		self.layer_stats.set_transaction_depth(self.transaction_depth());
 		Ok(())
 	}
 
@@ -398,6 +402,7 @@ impl OverlayedChanges {
 			changeset.commit_transaction()
 				.expect("Top and children changesets are started in lockstep; qed");
 		}
+		self.layer_stats.set_transaction_depth(self.transaction_depth());
 		Ok(())
 	}
 
@@ -424,9 +429,15 @@ impl OverlayedChanges {
 			changeset.exit_runtime()
 				.expect("Top and children changesets are entering runtime in lockstep; qed");
 		}
+		self.layer_stats.set_transaction_depth(self.transaction_depth());
 		Ok(())
 	}
 
+	/// The storage reads and writes done by the runtime in each open transaction layer.
+	pub fn storage_layer_stats(&self) -> &StorageLayerStats {
+		&self.layer_stats
+	}
+
 	/// Consume all changes (top + children) and return them.
 	///
 	/// After calling this function no more changes are contained in this changeset.
@@ -804,6 +815,44 @@ mod tests {
 		assert_eq!(&ext.storage_root()[..], &ROOT);
 	}
 
+	#[test]
+	fn storage_layer_stats_work() {
+		let backend = InMemoryBackend::<Blake2Hasher>::default();
+		let mut overlay = OverlayedChanges::default();
+		let mut offchain_overlay = Default::default();
+		let mut cache = StorageTransactionCache::default();
+		let mut ext = Ext::new(
+			&mut overlay,
+			&mut offchain_overlay,
+			&mut cache,
+			&backend,
+			crate::changes_trie::disabled_state::<_, u64>(),
+			None,
+		);
+
+		ext.storage(b"a");
+		ext.storage_start_transaction();
+		ext.set_storage(b"a".to_vec(), vec![1]);
+		ext.storage_start_transaction();
+		ext.storage(b"a");
+		ext.set_storage(b"b".to_vec(), vec![2]);
+		assert_eq!(ext.storage_layer_read_write_count(), vec![(1, 0), (0, 1), (1, 1)]);
+
+		// Closing a layer accounts its accesses to the parent, even when rolled back.
+		ext.storage_rollback_transaction().unwrap();
+		assert_eq!(ext.storage_layer_read_write_count(), vec![(1, 0), (1, 2)]);
+
+		ext.storage_commit_transaction().unwrap();
+		assert_eq!(ext.storage_layer_read_write_count(), vec![(2, 2)]);
+
+		// Accesses to the transaction level are bookkeeping and not counted.
+		let level_key = sp_core::storage::well_known_keys::TRANSACTION_LEVEL;
+		ext.storage(level_key);
+		ext.set_storage(level_key.to_vec(), vec![1]);
+		ext.clear_storage(level_key);
+		assert_eq!(ext.storage_layer_read_write_count(), vec![(2, 2)]);
+	}
+
 	#[test]
 	fn extrinsic_changes_are_collected() {
 		let mut overlay = OverlayedChanges::default();
@@ -197,6 +197,10 @@ impl<'a, H: Hasher, B: 'a + Backend<H>> Externalities for ReadOnlyExternalities<
 		unimplemented!("reset_read_write_count is not supported in ReadOnlyExternalities")
 	}
 
+	fn storage_layer_read_write_count(&self) -> Vec<(u32, u32)> {
+		unimplemented!("storage_layer_read_write_count is not supported in ReadOnlyExternalities")
+	}
+
 	fn get_whitelist(&self) -> Vec<TrackedStorageKey> {
 		unimplemented!("get_whitelist is not supported in ReadOnlyExternalities")
 	}
@@ -19,7 +19,7 @@
 
 #[cfg(feature = "std")]
 use std::time::{Instant, Duration};
-use sp_std::cell::RefCell;
+use sp_std::{cell::RefCell, vec::Vec};
 
 /// Measured count of operations and total bytes.
 #[derive(Clone, Debug, Default)]
@@ -129,3 +129,68 @@ impl StateMachineStats {
 		*self.bytes_writes_overlay.borrow_mut() += data_bytes;
 	}
 }
+
+/// Number of storage reads and writes done in each storage transaction layer.
+///
+/// The first layer holds the accesses done outside of any transaction. When a transaction is
+/// closed, the accesses done in it are accounted to its parent layer, whether the transaction
+/// was committed or rolled back: the accesses happened anyway.
+#[derive(Debug, Default, Clone)]
+pub struct StorageLayerStats {
+	/// `(reads, writes)` of each open layer, lazily initialized with the layer outside of any
+	/// transaction.
+	layers: RefCell<Vec<(u32, u32)>>,
+}
+
+impl StorageLayerStats {
+	fn with_current(&self, f: impl FnOnce(&mut (u32, u32))) {
+		let mut layers = self.layers.borrow_mut();
+		if layers.is_empty() {
+			layers.push(Default::default());
+		}
+		f(layers.last_mut().expect("pushed above if empty; qed"));
+	}
+
+	/// Tally one storage read in the current layer.
+	pub fn tally_read(&self) {
+		self.with_current(|(reads, _)| *reads = reads.saturating_add(1));
+	}
+
+	/// Tally one storage write in the current layer.
+	pub fn tally_write(&self) {
+		self.with_current(|(_, writes)| *writes = writes.saturating_add(1));
+	}
+
+	/// Set the number of open transactions, opening new empty layers or closing the deepest ones
+	/// as needed.
+	pub fn set_transaction_depth(&self, depth: usize) {
+		let mut layers = self.layers.borrow_mut();
+		if layers.is_empty() {
+			layers.push(Default::default());
+		}
+		while layers.len() > depth + 1 {
+			let (reads, writes) = layers.pop().expect("len > depth + 1 >= 1; qed");
+			let parent = layers.last_mut().expect("len >= depth + 1 >= 1; qed");
+			parent.0 = parent.0.saturating_add(reads);
+			parent.1 = parent.1.saturating_add(writes);
+		}
+		while layers.len() < depth + 1 {
+			layers.push(Default::default());
+		}
+	}
+
+	/// The `(reads, writes)` of each open layer, starting with the one outside of any
+	/// transaction.
+	pub fn read_write_count(&self) -> Vec<(u32, u32)> {
+		let mut layers = self.layers.borrow().clone();
+		if layers.is_empty() {
+			layers.push(Default::default());
+		}
+		layers
+	}
+
+	/// Reset the count of all the open layers to zero.
+	pub fn reset(&self) {
+		self.layers.borrow_mut().iter_mut().for_each(|layer| *layer = Default::default());
+	}
+}
@@ -166,6 +166,9 @@ pub mod well_known_keys {
 	/// Changes trie configuration is stored under this key.
 	pub const CHANGES_TRIE_CONFIG: &'static [u8] = b":changes_trie";
 
+	/// Number of active storage transaction layers (u32) is stored under this key.
+	pub const TRANSACTION_LEVEL: &'static [u8] = b":transaction_level:";
+
 	/// Prefix of child storage keys.
 	pub const CHILD_STORAGE_KEY_PREFIX: &'static [u8] = b":child_storage:";
 
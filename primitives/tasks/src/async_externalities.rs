@@ -184,6 +184,10 @@ impl Externalities for AsyncExternalities {
 		unimplemented!("reset_read_write_count is not supported in AsyncExternalities")
 	}
 
+	fn storage_layer_read_write_count(&self) -> Vec<(u32, u32)> {
+		unimplemented!("storage_layer_read_write_count is not supported in AsyncExternalities")
+	}
+
 	fn get_whitelist(&self) -> Vec<TrackedStorageKey> {
 		unimplemented!("get_whitelist is not supported in AsyncExternalities")
 	}
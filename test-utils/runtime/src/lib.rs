@@ -574,6 +574,10 @@ cfg_if! {
 				fn metadata() -> OpaqueMetadata {
 					unimplemented!()
 				}
+
+				fn metadata_at_version(_version: u32) -> Option<OpaqueMetadata> {
+					unimplemented!()
+				}
 			}
 
 			impl sp_transaction_pool::runtime_api::TaggedTransactionQueue<Block> for Runtime {
@@ -821,6 +825,10 @@ cfg_if! {
 				fn metadata() -> OpaqueMetadata {
 					unimplemented!()
 				}
+
+				fn metadata_at_version(_version: u32) -> Option<OpaqueMetadata> {
+					unimplemented!()
+				}
 			}
 
 			impl sp_transaction_pool::runtime_api::TaggedTransactionQueue<Block> for Runtime {
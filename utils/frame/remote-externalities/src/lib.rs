@@ -0,0 +1,528 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # Remote Externalities
+//!
+//! An equivalent of `sp_io::TestExternalities` that can load its state from a remote node, or
+//! from a state snapshot on disk.
+//!
+//! The state can either be:
+//!
+//! - read from a snapshot file, as exported by the `export-state` subcommand of a node, in
+//!   [`Mode::Offline`].
+//! - downloaded from a live node, using the paged `state_getKeysPaged` and `state_getStorage` RPC
+//!   calls, in [`Mode::Online`]. The downloaded state can be cached to disk, in the same format as
+//!   `export-state`, and then be loaded again in [`Mode::Offline`].
+//!
+//! In both modes, the state can be restricted to the storage of a list of pallets, identified by
+//! the `twox_128` hash of their name, which is the prefix of all their storage keys.
+//!
+//! ## Example
+//!
+//! ```ignore
+//! #[tokio::test]
+//! async fn reproduce_production_bug() {
+//! 	Builder::new()
+//! 		.mode(Mode::Online(OnlineConfig {
+//! 			uri: "http://localhost:9933".into(),
+//! 			cache_path: Some("staking.json".into()),
+//! 			..Default::default()
+//! 		}))
+//! 		.module("Staking")
+//! 		.build()
+//! 		.await
+//! 		.unwrap()
+//! 		.execute_with(|| {
+//! 			// interact with the state of the `Staking` pallet.
+//! 		});
+//! }
+//! ```
+
+use std::{collections::HashMap, fs, path::{Path, PathBuf}};
+use futures::compat::Future01CompatExt;
+use jsonrpc_core_client::{transports::http, RpcChannel};
+use log::*;
+use serde::{Deserialize, Serialize};
+use sc_rpc_api::{chain::ChainClient, state::StateClient};
+use sp_core::{
+	H256,
+	hashing::twox_128,
+	storage::{
+		ChildInfo, Storage, StorageChild, StorageData, StorageKey,
+		well_known_keys::{CODE, HEAP_PAGES},
+	},
+};
+
+/// The hash type of the blocks of the remote node.
+pub type Hash = H256;
+
+/// A storage key and its value.
+pub type KeyPair = (StorageKey, StorageData);
+
+const LOG_TARGET: &str = "remote-ext";
+const DEFAULT_URI: &str = "http://localhost:9933";
+const PAGE: u32 = 512;
+
+/// A chain client only used to query the finalized head: the other types are left opaque.
+type Chain = ChainClient<serde_json::Value, Hash, serde_json::Value, serde_json::Value>;
+
+/// The state snapshot format, which is a subset of the chain spec written by `export-state`.
+#[derive(Serialize, Deserialize)]
+struct Snapshot {
+	genesis: SnapshotGenesis,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+enum SnapshotGenesis {
+	Raw(RawState),
+}
+
+#[derive(Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawState {
+	top: HashMap<StorageKey, StorageData>,
+	#[serde(default)]
+	children_default: HashMap<StorageKey, HashMap<StorageKey, StorageData>>,
+}
+
+/// The mode in which the remote externalities are built.
+#[derive(Clone, Debug)]
+pub enum Mode {
+	/// Download the state from a live node.
+	Online(OnlineConfig),
+	/// Load the state from a snapshot on disk.
+	Offline(OfflineConfig),
+}
+
+impl Default for Mode {
+	fn default() -> Self {
+		Mode::Online(OnlineConfig::default())
+	}
+}
+
+/// Configuration of the offline execution.
+#[derive(Clone, Debug)]
+pub struct OfflineConfig {
+	/// The path to the state snapshot, as written by `export-state` or cached by a previous
+	/// online execution.
+	pub snapshot_path: PathBuf,
+}
+
+/// Configuration of the online execution.
+#[derive(Clone, Debug)]
+pub struct OnlineConfig {
+	/// The HTTP RPC endpoint of the node to download the state from.
+	pub uri: String,
+	/// The block hash at which to download the state. Defaults to the finalized head.
+	pub at: Option<Hash>,
+	/// If set, the downloaded state is cached to this path, from which it can later be loaded
+	/// in [`Mode::Offline`].
+	pub cache_path: Option<PathBuf>,
+}
+
+impl Default for OnlineConfig {
+	fn default() -> Self {
+		OnlineConfig { uri: DEFAULT_URI.to_owned(), at: None, cache_path: None }
+	}
+}
+
+/// Builder for remote-externalities.
+#[derive(Default)]
+pub struct Builder {
+	mode: Mode,
+	modules: Vec<String>,
+	inject: Vec<KeyPair>,
+}
+
+impl Builder {
+	/// Create a new builder, downloading the whole state of a node running at the default
+	/// location.
+	pub fn new() -> Self {
+		Default::default()
+	}
+
+	/// Configure the mode in which the state is loaded.
+	pub fn mode(mut self, mode: Mode) -> Self {
+		self.mode = mode;
+		self
+	}
+
+	/// Only load the storage of the given pallet. Can be called multiple times.
+	///
+	/// If never called, the whole state is loaded. The child storage, if any, is always loaded.
+	pub fn module(mut self, name: &str) -> Self {
+		self.modules.push(name.to_owned());
+		self
+	}
+
+	/// Inject the given key-value pairs into the state, after it has been loaded.
+	pub fn inject(mut self, injections: &[KeyPair]) -> Self {
+		self.inject.extend(injections.iter().cloned());
+		self
+	}
+
+	/// Build the test externalities.
+	pub async fn build(self) -> Result<sp_io::TestExternalities, &'static str> {
+		let prefixes = self.modules.iter()
+			.map(|name| StorageKey(twox_128(name.as_bytes()).to_vec()))
+			.collect::<Vec<_>>();
+
+		let state = match self.mode {
+			Mode::Offline(config) => {
+				let mut state = load_snapshot(&config.snapshot_path)?;
+				if !prefixes.is_empty() {
+					state.top.retain(|key, _| prefixes.iter().any(|p| key.0.starts_with(&p.0)));
+				}
+				state
+			},
+			Mode::Online(config) => {
+				let state = download_state(&config, &prefixes).await?;
+				match &config.cache_path {
+					Some(path) => save_snapshot(state, path)?,
+					None => state,
+				}
+			},
+		};
+
+		info!(target: LOG_TARGET, "building externalities with {} top keys", state.top.len());
+		let mut ext = into_externalities(state);
+		for (key, value) in self.inject {
+			ext.insert(key.0, value.0);
+		}
+		Ok(ext)
+	}
+}
+
+fn load_snapshot(path: &Path) -> Result<RawState, &'static str> {
+	info!(target: LOG_TARGET, "loading state snapshot from {:?}", path);
+	let file = fs::File::open(path).map_err(|e| {
+		error!(target: LOG_TARGET, "failed to open {:?}: {:?}", path, e);
+		"failed to open the state snapshot"
+	})?;
+	let snapshot: Snapshot = serde_json::from_reader(std::io::BufReader::new(file)).map_err(|e| {
+		error!(target: LOG_TARGET, "failed to parse {:?}: {:?}", path, e);
+		"failed to parse the state snapshot, it should be the raw output of `export-state`"
+	})?;
+	let SnapshotGenesis::Raw(state) = snapshot.genesis;
+	Ok(state)
+}
+
+fn save_snapshot(state: RawState, path: &Path) -> Result<RawState, &'static str> {
+	info!(target: LOG_TARGET, "caching state snapshot to {:?}", path);
+	let file = fs::File::create(path).map_err(|e| {
+		error!(target: LOG_TARGET, "failed to create {:?}: {:?}", path, e);
+		"failed to create the state snapshot"
+	})?;
+	let snapshot = Snapshot { genesis: SnapshotGenesis::Raw(state) };
+	serde_json::to_writer(std::io::BufWriter::new(file), &snapshot).map_err(|e| {
+		error!(target: LOG_TARGET, "failed to write {:?}: {:?}", path, e);
+		"failed to write the state snapshot"
+	})?;
+	let SnapshotGenesis::Raw(state) = snapshot.genesis;
+	Ok(state)
+}
+
+async fn download_state(
+	config: &OnlineConfig,
+	prefixes: &[StorageKey],
+) -> Result<RawState, &'static str> {
+	info!(target: LOG_TARGET, "downloading state from {}", config.uri);
+	let channel: RpcChannel = http::connect(&config.uri).compat().await.map_err(|e| {
+		error!(target: LOG_TARGET, "failed to connect to {}: {:?}", config.uri, e);
+		"failed to connect to the node"
+	})?;
+	let chain = Chain::new(channel.clone());
+	let state = StateClient::<Hash>::new(channel);
+
+	let at = match config.at {
+		Some(at) => at,
+		None => chain.finalized_head().compat().await.map_err(|e| {
+			error!(target: LOG_TARGET, "rpc finalized_head failed: {:?}", e);
+			"rpc finalized_head failed"
+		})?,
+	};
+	info!(target: LOG_TARGET, "downloading state at block {:?}", at);
+
+	let mut keys = Vec::new();
+	if prefixes.is_empty() {
+		keys.extend(get_keys_paged(&state, StorageKey(Vec::new()), at).await?);
+	} else {
+		for prefix in prefixes {
+			keys.extend(get_keys_paged(&state, prefix.clone(), at).await?);
+		}
+	}
+
+	let mut top = HashMap::with_capacity(keys.len());
+	for key in keys {
+		let value = state.storage(key.clone(), Some(at)).compat().await.map_err(|e| {
+			error!(target: LOG_TARGET, "rpc storage failed for key {:?}: {:?}", key, e);
+			"rpc storage failed"
+		})?;
+		// the key may have been removed in between, in which case it is not part of the state.
+		if let Some(value) = value {
+			top.insert(key, value);
+		}
+	}
+
+	Ok(RawState { top, children_default: Default::default() })
+}
+
+/// Get all the keys starting with `prefix` at block `at`, one page at a time.
+async fn get_keys_paged(
+	state: &StateClient<Hash>,
+	prefix: StorageKey,
+	at: Hash,
+) -> Result<Vec<StorageKey>, &'static str> {
+	let mut last_key: Option<StorageKey> = None;
+	let mut all_keys = Vec::new();
+	loop {
+		let page = state.storage_keys_paged(Some(prefix.clone()), PAGE, last_key.clone(), Some(at))
+			.compat()
+			.await
+			.map_err(|e| {
+				error!(target: LOG_TARGET, "rpc storage_keys_paged failed: {:?}", e);
+				"rpc storage_keys_paged failed"
+			})?;
+		let page_len = page.len();
+		all_keys.extend(page);
+
+		if page_len < PAGE as usize {
+			debug!(target: LOG_TARGET, "last page received: {}", page_len);
+			break;
+		}
+		last_key = all_keys.last().cloned();
+		debug!(target: LOG_TARGET, "new total = {}, full page received: {:?}", all_keys.len(), last_key);
+	}
+	Ok(all_keys)
+}
+
+fn into_externalities(state: RawState) -> sp_io::TestExternalities {
+	let RawState { top, children_default } = state;
+	let mut storage = Storage {
+		top: top.into_iter().map(|(k, v)| (k.0, v.0)).collect(),
+		children_default: children_default.into_iter().map(|(storage_key, data)| {
+			let child_info = ChildInfo::new_default(&storage_key.0);
+			(
+				child_info.prefixed_storage_key().into_inner(),
+				StorageChild {
+					data: data.into_iter().map(|(k, v)| (k.0, v.0)).collect(),
+					child_info,
+				},
+			)
+		}).collect(),
+	};
+
+	// `TestExternalities` overwrites the code and heap pages: make sure those of the state are
+	// kept.
+	let code = storage.top.remove(CODE).unwrap_or_default();
+	let heap_pages = storage.top.remove(HEAP_PAGES);
+	let mut ext = sp_io::TestExternalities::new_with_code(&code, storage);
+	if let Some(heap_pages) = heap_pages {
+		ext.insert(HEAP_PAGES.to_vec(), heap_pages);
+	}
+	ext
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::{collections::BTreeMap, sync::Arc};
+	use futures::executor::block_on;
+	use jsonrpc_core::{IoHandler, Params, Value};
+	use jsonrpc_http_server::{Server, ServerBuilder};
+
+	const FINALIZED: Hash = H256::repeat_byte(1);
+
+	fn key(module: &str, index: u32) -> Vec<u8> {
+		let mut key = twox_128(module.as_bytes()).to_vec();
+		key.extend(index.to_le_bytes().iter());
+		key
+	}
+
+	fn mock_state() -> BTreeMap<Vec<u8>, Vec<u8>> {
+		let mut state = BTreeMap::new();
+		// more than two pages of `Foo`.
+		for i in 0..(2 * PAGE + 10) {
+			state.insert(key("Foo", i), i.to_le_bytes().to_vec());
+		}
+		for i in 0..10u32 {
+			state.insert(key("Bar", i), i.to_le_bytes().to_vec());
+		}
+		state.insert(CODE.to_vec(), b"code".to_vec());
+		state
+	}
+
+	/// A node serving `state` at the `FINALIZED` block, over the few RPCs used by the builder.
+	fn mock_node(state: BTreeMap<Vec<u8>, Vec<u8>>) -> Server {
+		let state = Arc::new(state);
+		let mut io = IoHandler::new();
+
+		io.add_method("chain_getFinalizedHead", |_: Params| -> jsonrpc_core::Result<Value> {
+			Ok(serde_json::json!(FINALIZED))
+		});
+
+		let keys_state = state.clone();
+		io.add_method("state_getKeysPaged", move |params: Params| -> jsonrpc_core::Result<Value> {
+			let (prefix, count, start_key, at): (
+				Option<StorageKey>, u32, Option<StorageKey>, Option<Hash>,
+			) = params.parse()?;
+			assert_eq!(at, Some(FINALIZED));
+			let prefix = prefix.map(|p| p.0).unwrap_or_default();
+			let keys = keys_state.keys()
+				.filter(|k| k.starts_with(&prefix))
+				.filter(|k| start_key.as_ref().map_or(true, |s| **k > s.0))
+				.take(count as usize)
+				.map(|k| StorageKey(k.clone()))
+				.collect::<Vec<_>>();
+			Ok(serde_json::json!(keys))
+		});
+
+		io.add_method("state_getStorage", move |params: Params| -> jsonrpc_core::Result<Value> {
+			let (key, at): (StorageKey, Option<Hash>) = params.parse()?;
+			assert_eq!(at, Some(FINALIZED));
+			let value = state.get(&key.0).map(|v| StorageData(v.clone()));
+			Ok(serde_json::json!(value))
+		});
+
+		ServerBuilder::new(io)
+			.start_http(&"127.0.0.1:0".parse().unwrap())
+			.expect("mock node should start")
+	}
+
+	fn online(server: &Server, cache_path: Option<PathBuf>) -> Mode {
+		Mode::Online(OnlineConfig {
+			uri: format!("http://{}", server.address()),
+			at: None,
+			cache_path,
+		})
+	}
+
+	#[test]
+	fn online_downloads_the_whole_state() {
+		let server = mock_node(mock_state());
+
+		block_on(Builder::new().mode(online(&server, None)).build())
+			.unwrap()
+			.execute_with(|| {
+				for (key, value) in mock_state() {
+					assert_eq!(sp_io::storage::get(&key), Some(value));
+				}
+			});
+	}
+
+	#[test]
+	fn online_filters_by_module_and_caches_the_state() {
+		let server = mock_node(mock_state());
+		let dir = tempfile::tempdir().unwrap();
+		let cache_path = dir.path().join("snapshot.json");
+
+		block_on(
+			Builder::new()
+				.mode(online(&server, Some(cache_path.clone())))
+				.module("Foo")
+				.build()
+		)
+			.unwrap()
+			.execute_with(|| {
+				assert_eq!(sp_io::storage::get(&key("Foo", 2 * PAGE)), Some(vec![0, 4, 0, 0]));
+				assert!(sp_io::storage::get(&key("Bar", 0)).is_none());
+			});
+
+		// the cached snapshot can be loaded offline, without any node.
+		drop(server);
+		block_on(
+			Builder::new()
+				.mode(Mode::Offline(OfflineConfig { snapshot_path: cache_path }))
+				.build()
+		)
+			.unwrap()
+			.execute_with(|| {
+				for i in 0..(2 * PAGE + 10) {
+					assert_eq!(sp_io::storage::get(&key("Foo", i)), Some(i.to_le_bytes().to_vec()));
+				}
+				assert!(sp_io::storage::get(&key("Bar", 0)).is_none());
+			});
+	}
+
+	#[test]
+	fn offline_loads_export_state_output() {
+		let dir = tempfile::tempdir().unwrap();
+		let snapshot_path = dir.path().join("exported.json");
+		let child = ChildInfo::new_default(b"child");
+		let hex = |bytes: &[u8]| sp_core::bytes::to_hex(bytes, false);
+		let exported = serde_json::json!({
+			"name": "Development",
+			"id": "dev",
+			"chainType": "Development",
+			"bootNodes": [],
+			"telemetryEndpoints": null,
+			"protocolId": null,
+			"properties": null,
+			"consensusEngine": null,
+			"genesis": {
+				"raw": {
+					"top": {
+						"0x3a636f6465": "0x01020304",
+						hex(&key("Foo", 1)): "0x01",
+						hex(&key("Bar", 1)): "0x02",
+					},
+					"childrenDefault": {
+						"0x6368696c64": {
+							"0x01": "0x02",
+						},
+					},
+				},
+			},
+		});
+		fs::write(&snapshot_path, exported.to_string()).unwrap();
+
+		let injected = (StorageKey(key("Baz", 1)), StorageData(vec![3]));
+		block_on(
+			Builder::new()
+				.mode(Mode::Offline(OfflineConfig { snapshot_path }))
+				.module("Foo")
+				.inject(&[injected])
+				.build()
+		)
+			.unwrap()
+			.execute_with(|| {
+				assert_eq!(sp_io::storage::get(&key("Foo", 1)), Some(vec![1]));
+				assert!(sp_io::storage::get(&key("Bar", 1)).is_none());
+				assert_eq!(sp_io::storage::get(&key("Baz", 1)), Some(vec![3]));
+				assert_eq!(
+					sp_io::default_child_storage::get(child.storage_key(), &[1]),
+					Some(vec![2]),
+				);
+			});
+	}
+
+	#[test]
+	fn offline_rejects_non_raw_state() {
+		let dir = tempfile::tempdir().unwrap();
+		let snapshot_path = dir.path().join("not_raw.json");
+		fs::write(&snapshot_path, r#"{ "genesis": { "runtime": {} } }"#).unwrap();
+
+		assert!(
+			block_on(
+				Builder::new()
+					.mode(Mode::Offline(OfflineConfig { snapshot_path }))
+					.build()
+			).is_err()
+		);
+	}
+}
@@ -0,0 +1,194 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! `Structopt`-ready struct for `try-runtime`.
+//!
+//! The `try-runtime` subcommand executes the `TryRuntime_on_runtime_upgrade` runtime api of the
+//! runtime compiled into the node against real chain state. This runs all `OnRuntimeUpgrade`
+//! implementations of the runtime, surrounded by their `pre_upgrade` and `post_upgrade` checks,
+//! without ever touching a live chain.
+//!
+//! The state is loaded with `remote-externalities`, either from a state snapshot written by
+//! `export-state`, or downloaded from a live node.
+
+use std::{fmt::Debug, path::PathBuf};
+use codec::Decode;
+use remote_externalities::{Builder, Mode, OfflineConfig, OnlineConfig};
+use sc_cli::{CliConfiguration, ExecutionStrategy, Result, SharedParams, WasmExecutionMethod};
+use sc_executor::NativeExecutor;
+use sc_service::{Configuration, NativeExecutionDispatch};
+use sp_core::{Bytes, H256, storage::{StorageData, StorageKey, well_known_keys}};
+use sp_externalities::Extensions;
+use sp_runtime::{
+	RuntimeString,
+	traits::{Block as BlockT, NumberFor},
+};
+use sp_state_machine::StateMachine;
+
+/// Various commands to try out the new runtime, over configurable states.
+#[derive(Debug, structopt::StructOpt)]
+pub struct TryRuntimeCmd {
+	/// The state to execute the runtime upgrade against.
+	#[structopt(subcommand)]
+	pub state: State,
+
+	/// The pallets whose storage should be loaded. Defaults to the whole state.
+	#[structopt(long, use_delimiter = true)]
+	pub modules: Vec<String>,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+
+	/// The execution strategy that should be used.
+	#[structopt(
+		long = "execution",
+		value_name = "STRATEGY",
+		possible_values = &ExecutionStrategy::variants(),
+		case_insensitive = true,
+		default_value = "Native",
+	)]
+	pub execution: ExecutionStrategy,
+
+	/// Method for executing Wasm runtime code.
+	#[structopt(
+		long = "wasm-execution",
+		value_name = "METHOD",
+		possible_values = &WasmExecutionMethod::enabled_variants(),
+		case_insensitive = true,
+		default_value = "Interpreted",
+	)]
+	pub wasm_method: WasmExecutionMethod,
+}
+
+/// The source of the state to execute the runtime upgrade against.
+#[derive(Debug, structopt::StructOpt)]
+pub enum State {
+	/// Use a state snapshot, as written by `export-state` or cached by `live`.
+	Snap {
+		/// The path to the state snapshot.
+		#[structopt(long, value_name = "PATH", parse(from_os_str))]
+		snapshot_path: PathBuf,
+	},
+
+	/// Download the state of a live chain.
+	Live {
+		/// The HTTP RPC endpoint of the node to download the state from.
+		#[structopt(long, default_value = "http://localhost:9933")]
+		url: String,
+
+		/// The block hash at which to download the state. Defaults to the finalized head.
+		#[structopt(long, parse(try_from_str = parse_hash))]
+		block_at: Option<H256>,
+
+		/// If set, the downloaded state is cached to this path, to be used later with `snap`.
+		#[structopt(long, value_name = "PATH", parse(from_os_str))]
+		snapshot_path: Option<PathBuf>,
+	},
+}
+
+fn parse_hash(s: &str) -> std::result::Result<H256, String> {
+	let bytes: Bytes = s.parse().map_err(|e| format!("Invalid hex: {:?}", e))?;
+	if bytes.0.len() != 32 {
+		return Err("A block hash must be 32 bytes long".into());
+	}
+	Ok(H256::from_slice(&bytes.0))
+}
+
+impl TryRuntimeCmd {
+	/// Runs the runtime upgrade of the runtime compiled into the node against the chosen state.
+	pub fn run<B, ExecDispatch>(&self, config: Configuration) -> Result<()>
+	where
+		B: BlockT,
+		ExecDispatch: NativeExecutionDispatch + 'static,
+	{
+		// The runtime to test is the one of this node, and not the one found in the state.
+		let genesis_storage = config.chain_spec.build_storage()?;
+		let code = genesis_storage.top.get(well_known_keys::CODE)
+			.ok_or("The chain spec does not contain any runtime code")?;
+		let code = (StorageKey(well_known_keys::CODE.to_vec()), StorageData(code.clone()));
+
+		let mode = match &self.state {
+			State::Snap { snapshot_path } => Mode::Offline(OfflineConfig {
+				snapshot_path: snapshot_path.clone(),
+			}),
+			State::Live { url, block_at, snapshot_path } => Mode::Online(OnlineConfig {
+				uri: url.clone(),
+				at: *block_at,
+				cache_path: snapshot_path.clone(),
+			}),
+		};
+		let builder = self.modules.iter()
+			.fold(Builder::new().mode(mode), |builder, module| builder.module(module))
+			.inject(&[code]);
+		let ext = futures::executor::block_on(builder.build())?;
+
+		let backend = ext.commit_all();
+		let mut changes = Default::default();
+		let mut offchain_changes = Default::default();
+		let executor = NativeExecutor::<ExecDispatch>::new(
+			self.wasm_method.into(),
+			None,
+			config.max_runtime_instances,
+		);
+
+		let encoded_result = StateMachine::<_, _, NumberFor<B>, _>::new(
+			&backend,
+			None,
+			&mut changes,
+			&mut offchain_changes,
+			&executor,
+			"TryRuntime_on_runtime_upgrade",
+			&[],
+			Extensions::default(),
+			&sp_state_machine::backend::BackendRuntimeCode::new(&backend).runtime_code()?,
+			sp_core::testing::TaskExecutor::new(),
+		)
+		.execute(self.execution.into())
+		.map_err(|e| format!("Failed to execute 'TryRuntime_on_runtime_upgrade': {:?}", e))?;
+
+		let result = <std::result::Result<(u64, u64), RuntimeString>>::decode(
+			&mut &encoded_result[..]
+		).map_err(|e| format!("Failed to decode the runtime upgrade result: {:?}", e))?;
+
+		match result {
+			Ok((weight, total_weight)) => {
+				log::info!(
+					"try-runtime executed without errors. Consumed weight = {}, total weight = {} ({:.2}%)",
+					weight,
+					total_weight,
+					weight as f64 / total_weight as f64 * 100.0,
+				);
+				Ok(())
+			},
+			Err(error) => Err(format!("Runtime upgrade check failed: {}", error).into()),
+		}
+	}
+}
+
+impl CliConfiguration for TryRuntimeCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn chain_id(&self, _is_dev: bool) -> Result<String> {
+		Ok(match self.shared_params.chain {
+			Some(ref chain) => chain.clone(),
+			None => "dev".into(),
+		})
+	}
+}